@@ -0,0 +1,10 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use a bundled protoc unless one is given, so building the CLI needs no system install.
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["proto/token_ops.proto"], &["proto"])?;
+    Ok(())
+}
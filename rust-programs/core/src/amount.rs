@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+
+/// Render a raw token amount with the mint's decimals, without float rounding.
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let amount = amount as u128;
+    let divisor = 10u128.pow(decimals.min(38) as u32);
+    let fraction = format!("{:0width$}", amount % divisor, width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        (amount / divisor).to_string()
+    } else {
+        format!("{}.{}", amount / divisor, fraction)
+    }
+}
+
+/// Render a raw amount of a ScaledUiAmount mint, which holders see as
+/// `amount * multiplier` truncated to base units, as Token-2022 does.
+pub fn format_scaled_amount(amount: u64, decimals: u8, multiplier: f64) -> String {
+    format_amount((amount as f64 * multiplier).trunc() as u64, decimals)
+}
+
+/// Parse a human amount such as `12.5` into raw base units for the mint's decimals.
+pub fn parse_amount(value: &str, decimals: u8) -> Result<u64> {
+    let value = value.trim();
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+    if fraction.len() > decimals as usize {
+        return Err(anyhow!("Amount {} has more than {} decimal place(s)", value, decimals));
+    }
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(anyhow!("Invalid amount: '{}'", value));
+    }
+
+    let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    digits
+        .parse::<u64>()
+        .map_err(|e| anyhow!("Invalid amount '{}': {}", value, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_whole_and_fractional_amounts() {
+        assert_eq!(parse_amount("12.5", 2).unwrap(), 1_250);
+        assert_eq!(parse_amount("12", 2).unwrap(), 1_200);
+        assert_eq!(parse_amount(".5", 1).unwrap(), 5);
+        assert_eq!(parse_amount("5.", 0).unwrap(), 5);
+        assert_eq!(parse_amount(" 0.000000001 ", 9).unwrap(), 1);
+        assert_eq!(parse_amount("0", 9).unwrap(), 0);
+    }
+
+    #[test]
+    fn rejects_more_decimals_than_the_mint() {
+        assert_eq!(parse_amount("1.25", 2).unwrap(), 125);
+        assert!(parse_amount("1.255", 2).is_err());
+        assert!(parse_amount("1.5", 0).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_amounts() {
+        for value in ["", " ", ".", "-1", "1.2.3", "1e5", "abc", "1,5"] {
+            assert!(parse_amount(value, 6).is_err(), "accepted {:?}", value);
+        }
+    }
+
+    #[test]
+    fn parses_up_to_u64_max_and_no_further() {
+        assert_eq!(parse_amount("18446744073709551615", 0).unwrap(), u64::MAX);
+        assert!(parse_amount("18446744073709551616", 0).is_err());
+        assert_eq!(parse_amount("18446744073.709551615", 9).unwrap(), u64::MAX);
+        assert!(parse_amount("18446744073.709551616", 9).is_err());
+        assert!(parse_amount("18446744074", 9).is_err());
+        // Shifting by the decimals alone can overflow.
+        assert!(parse_amount("1", 20).is_err());
+        assert_eq!(parse_amount("0", 20).unwrap(), 0);
+    }
+
+    #[test]
+    fn formats_without_trailing_zeros() {
+        assert_eq!(format_amount(0, 0), "0");
+        assert_eq!(format_amount(0, 9), "0");
+        assert_eq!(format_amount(1, 9), "0.000000001");
+        assert_eq!(format_amount(1_500_000_000, 9), "1.5");
+        assert_eq!(format_amount(2_000_000_000, 9), "2");
+        assert_eq!(format_amount(u64::MAX, 0), "18446744073709551615");
+        assert_eq!(format_amount(u64::MAX, 19), "1.8446744073709551615");
+        assert_eq!(format_amount(u64::MAX, 20), "0.18446744073709551615");
+    }
+
+    #[test]
+    fn format_and_parse_round_trip() {
+        for (amount, decimals) in [(0, 6), (1, 6), (123_456_789, 6), (u64::MAX, 9), (u64::MAX, 0)] {
+            assert_eq!(parse_amount(&format_amount(amount, decimals), decimals).unwrap(), amount);
+        }
+    }
+}
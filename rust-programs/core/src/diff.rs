@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use crate::{amount::format_amount, report::HolderSnapshot};
+
+/// How one owner's holdings changed between two snapshots.
+#[derive(Debug, Clone, Serialize)]
+pub struct HolderChange {
+    pub owner: String,
+    pub status: String,
+    pub before: String,
+    pub after: String,
+    pub delta: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotDiff {
+    pub mint: String,
+    pub before_slot: u64,
+    pub after_slot: u64,
+    pub entered: usize,
+    pub exited: usize,
+    pub increased: usize,
+    pub decreased: usize,
+    pub unchanged: usize,
+    /// Entered, exited and changed owners, largest absolute change first.
+    pub changes: Vec<HolderChange>,
+}
+
+/// Compare two snapshots of the same mint by owner, summing balances across
+/// each owner's token accounts.
+pub fn diff_snapshots(before: &HolderSnapshot, after: &HolderSnapshot) -> Result<SnapshotDiff> {
+    if before.mint != after.mint {
+        return Err(anyhow!("Snapshots are for different mints ({} and {})", before.mint, after.mint));
+    }
+
+    let mut balances: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+    for holder in &before.holders {
+        balances.entry(holder.owner.as_str()).or_default().0 += holder.amount;
+    }
+    for holder in &after.holders {
+        balances.entry(holder.owner.as_str()).or_default().1 += holder.amount;
+    }
+
+    let decimals = after.decimals;
+    let mut diff = SnapshotDiff {
+        mint: after.mint.clone(),
+        before_slot: before.slot,
+        after_slot: after.slot,
+        entered: 0,
+        exited: 0,
+        increased: 0,
+        decreased: 0,
+        unchanged: 0,
+        changes: Vec::new(),
+    };
+
+    let mut changes: Vec<(i128, HolderChange)> = Vec::new();
+    for (owner, (old, new)) in balances {
+        let status = match (old, new) {
+            (0, _) => {
+                diff.entered += 1;
+                "entered"
+            }
+            (_, 0) => {
+                diff.exited += 1;
+                "exited"
+            }
+            (old, new) if new > old => {
+                diff.increased += 1;
+                "increased"
+            }
+            (old, new) if new < old => {
+                diff.decreased += 1;
+                "decreased"
+            }
+            _ => {
+                diff.unchanged += 1;
+                continue;
+            }
+        };
+
+        let delta = new as i128 - old as i128;
+        changes.push((
+            delta,
+            HolderChange {
+                owner: owner.to_string(),
+                status: status.to_string(),
+                before: format_amount(old, decimals),
+                after: format_amount(new, decimals),
+                delta: format!("{}{}", if delta < 0 { "-" } else { "+" }, format_amount(delta.unsigned_abs() as u64, decimals)),
+            },
+        ));
+    }
+
+    changes.sort_by_key(|(delta, _)| Reverse(delta.unsigned_abs()));
+    diff.changes = changes.into_iter().map(|(_, change)| change).collect();
+
+    Ok(diff)
+}
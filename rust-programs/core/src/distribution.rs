@@ -0,0 +1,134 @@
+//! Splitting a total amount in proportion to weights, in whole base units.
+
+use anyhow::{anyhow, Result};
+use std::str::FromStr;
+
+/// How fractional base units of a pro-rata split are resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Every share rounds down; the leftover units are not distributed.
+    Floor,
+    /// Shares round down and the leftover units go one each to the largest
+    /// fractional parts, so the shares add up to the total exactly.
+    LargestRemainder,
+}
+
+impl FromStr for Rounding {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "floor" => Ok(Rounding::Floor),
+            "largest-remainder" => Ok(Rounding::LargestRemainder),
+            other => Err(anyhow!("Unknown rounding '{}'; use floor or largest-remainder", other)),
+        }
+    }
+}
+
+/// Split `total` over `weights` in proportion, one share per weight.
+/// Ties between equal remainders go to the earlier weight, so callers get
+/// a reproducible split by passing weights in a stable order.
+pub fn pro_rata(weights: &[u64], total: u64, rounding: Rounding) -> Result<Vec<u64>> {
+    let weight_sum: u128 = weights.iter().map(|weight| *weight as u128).sum();
+    if weight_sum == 0 {
+        return Err(anyhow!("Nothing to split over: all weights are zero"));
+    }
+
+    let mut shares = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    for (index, weight) in weights.iter().enumerate() {
+        let scaled = total as u128 * *weight as u128;
+        // Each share is at most `total`, so it fits in u64.
+        shares.push((scaled / weight_sum) as u64);
+        remainders.push((scaled % weight_sum, index));
+    }
+
+    if rounding == Rounding::LargestRemainder {
+        let leftover = total - shares.iter().sum::<u64>();
+        remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for (_, index) in remainders.iter().take(leftover as usize) {
+            shares[*index] += 1;
+        }
+    }
+    Ok(shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOTH: [Rounding; 2] = [Rounding::Floor, Rounding::LargestRemainder];
+
+    #[test]
+    fn empty_or_zero_weights_are_an_error() {
+        for rounding in BOTH {
+            assert!(pro_rata(&[], 100, rounding).is_err());
+            assert!(pro_rata(&[0, 0], 100, rounding).is_err());
+        }
+    }
+
+    #[test]
+    fn single_holder_gets_everything() {
+        for rounding in BOTH {
+            assert_eq!(pro_rata(&[5], 7, rounding).unwrap(), vec![7]);
+            assert_eq!(pro_rata(&[0, 3, 0], 7, rounding).unwrap(), vec![0, 7, 0]);
+        }
+    }
+
+    #[test]
+    fn equal_weights_split_evenly() {
+        assert_eq!(pro_rata(&[4, 4, 4], 9, Rounding::Floor).unwrap(), vec![3, 3, 3]);
+        assert_eq!(pro_rata(&[4, 4, 4], 10, Rounding::Floor).unwrap(), vec![3, 3, 3]);
+        // Equal remainders go to the earlier weights.
+        assert_eq!(pro_rata(&[4, 4, 4], 10, Rounding::LargestRemainder).unwrap(), vec![4, 3, 3]);
+        assert_eq!(pro_rata(&[4, 4, 4], 11, Rounding::LargestRemainder).unwrap(), vec![4, 4, 3]);
+    }
+
+    #[test]
+    fn remainder_goes_to_the_largest_fractions() {
+        // Exact shares 3.33, 6.67.
+        assert_eq!(pro_rata(&[1, 2], 10, Rounding::Floor).unwrap(), vec![3, 6]);
+        assert_eq!(pro_rata(&[1, 2], 10, Rounding::LargestRemainder).unwrap(), vec![3, 7]);
+        // Exact shares 1.4, 1.4, 4.2: the one leftover unit goes to the first 0.4.
+        assert_eq!(pro_rata(&[1, 1, 3], 7, Rounding::LargestRemainder).unwrap(), vec![2, 1, 4]);
+    }
+
+    #[test]
+    fn largest_remainder_always_adds_up_to_the_total() {
+        let weights = [17, 1, 999, 3, 250, 0, 42];
+        for total in [0, 1, 2, 13, 1_000, 1_000_003] {
+            let floor = pro_rata(&weights, total, Rounding::Floor).unwrap();
+            let exact = pro_rata(&weights, total, Rounding::LargestRemainder).unwrap();
+            assert!(floor.iter().sum::<u64>() <= total);
+            assert_eq!(exact.iter().sum::<u64>(), total);
+            // No share moves by more than the one leftover unit, and zero weights get nothing.
+            for (floor, exact) in floor.iter().zip(&exact) {
+                assert!(*exact == *floor || *exact == *floor + 1);
+            }
+            assert_eq!(exact[5], 0);
+        }
+    }
+
+    #[test]
+    fn zero_total_gives_zero_shares() {
+        for rounding in BOTH {
+            assert_eq!(pro_rata(&[1, 2, 3], 0, rounding).unwrap(), vec![0, 0, 0]);
+        }
+    }
+
+    #[test]
+    fn does_not_overflow_at_u64_max() {
+        let shares = pro_rata(&[u64::MAX, u64::MAX], u64::MAX, Rounding::Floor).unwrap();
+        assert_eq!(shares, vec![u64::MAX / 2, u64::MAX / 2]);
+        let shares = pro_rata(&[u64::MAX, u64::MAX], u64::MAX, Rounding::LargestRemainder).unwrap();
+        assert_eq!(shares, vec![u64::MAX / 2 + 1, u64::MAX / 2]);
+        assert_eq!(pro_rata(&[u64::MAX, 1], u64::MAX, Rounding::LargestRemainder).unwrap().iter().sum::<u64>(), u64::MAX);
+    }
+
+    #[test]
+    fn parses_rounding_names() {
+        assert_eq!("floor".parse::<Rounding>().unwrap(), Rounding::Floor);
+        assert_eq!("largest-remainder".parse::<Rounding>().unwrap(), Rounding::LargestRemainder);
+        assert!("nearest".parse::<Rounding>().is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! Human-readable explanations of program errors found in transaction logs.
+
+use num_traits::FromPrimitive;
+use solana_program::{pubkey::Pubkey, system_instruction::SystemError, system_program};
+use spl_token_2022::error::TokenError;
+use std::str::FromStr;
+
+/// The innermost program failure reported in a transaction's logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramFailure {
+    pub program_id: Pubkey,
+    /// The custom error code, for `custom program error: 0x..` failures.
+    pub code: Option<u32>,
+    /// The failure text after `failed: `.
+    pub message: String,
+}
+
+/// Find the first `Program <id> failed: ...` line. Inner instructions fail
+/// before the programs that invoked them, so this is the failing callee.
+pub fn find_failure(logs: &[String]) -> Option<ProgramFailure> {
+    logs.iter().find_map(|line| {
+        let rest = line.strip_prefix("Program ")?;
+        let (program_id, message) = rest.split_once(" failed: ")?;
+        let code = message
+            .strip_prefix("custom program error: 0x")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok());
+        Some(ProgramFailure {
+            program_id: Pubkey::from_str(program_id).ok()?,
+            code,
+            message: message.to_string(),
+        })
+    })
+}
+
+/// Display name of the programs this tool calls most.
+pub fn program_name(program_id: &Pubkey) -> Option<&'static str> {
+    if *program_id == spl_token::id() {
+        Some("SPL Token")
+    } else if *program_id == spl_token_2022::id() {
+        Some("Token-2022")
+    } else if *program_id == spl_associated_token_account::id() {
+        Some("Associated Token Account")
+    } else if *program_id == system_program::id() {
+        Some("System")
+    } else if *program_id == spl_memo::id() {
+        Some("Memo")
+    } else {
+        None
+    }
+}
+
+/// What commonly causes a token program error, beyond its message.
+fn token_error_hint(error: &TokenError) -> Option<&'static str> {
+    match error {
+        TokenError::InsufficientFunds => Some("the source account holds less than the amount being moved"),
+        TokenError::MintMismatch => Some("a token account passed belongs to a different mint"),
+        TokenError::OwnerMismatch => Some("the signing wallet is not the owner or delegate of the token account"),
+        TokenError::FixedSupply => Some("the mint authority has been revoked, so no more tokens can be minted"),
+        TokenError::AlreadyInUse => Some("the account is already initialized"),
+        TokenError::UninitializedState => Some("the token account or mint does not exist yet"),
+        TokenError::NonNativeHasBalance => Some("only empty token accounts can be closed"),
+        TokenError::MintCannotFreeze => Some("the mint has no freeze authority"),
+        TokenError::AccountFrozen => Some("the account was frozen by the mint's freeze authority"),
+        TokenError::MintDecimalsMismatch => Some("the decimals given do not match the mint"),
+        _ => None,
+    }
+}
+
+/// Explain a custom error `code` of `program_id`, for the programs whose
+/// error enums are known.
+pub fn explain_custom_error(program_id: &Pubkey, code: u32) -> Option<String> {
+    if *program_id == spl_token::id() || *program_id == spl_token_2022::id() {
+        // SPL Token's errors are the first variants of Token-2022's.
+        let error = TokenError::from_u32(code)?;
+        return Some(match token_error_hint(&error) {
+            Some(hint) => format!("{} ({})", error, hint),
+            None => error.to_string(),
+        });
+    }
+    if *program_id == spl_associated_token_account::id() && code == 0 {
+        return Some("Associated token account owner does not match address derivation".to_string());
+    }
+    if *program_id == system_program::id() {
+        return SystemError::from_u32(code).map(|error| error.to_string());
+    }
+    None
+}
+
+/// One line describing a failure: the program and what went wrong.
+pub fn describe_failure(failure: &ProgramFailure) -> String {
+    let program = program_name(&failure.program_id)
+        .map(str::to_string)
+        .unwrap_or_else(|| failure.program_id.to_string());
+    let explanation = failure
+        .code
+        .and_then(|code| explain_custom_error(&failure.program_id, code))
+        .unwrap_or_else(|| failure.message.clone());
+    format!("{} program: {}", program, explanation)
+}
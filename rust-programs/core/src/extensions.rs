@@ -0,0 +1,167 @@
+use anyhow::{anyhow, Result};
+use bytemuck::Pod;
+use solana_program::pubkey::Pubkey;
+use spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState,
+        group_member_pointer::GroupMemberPointer,
+        group_pointer::GroupPointer,
+        interest_bearing_mint::InterestBearingConfig,
+        metadata_pointer::MetadataPointer,
+        mint_close_authority::MintCloseAuthority,
+        permanent_delegate::PermanentDelegate,
+        transfer_fee::{TransferFee, TransferFeeConfig},
+        transfer_hook::TransferHook,
+        ExtensionType,
+    },
+    state::AccountState,
+};
+use spl_pod::optional_keys::OptionalNonZeroPubkey;
+use spl_token_group_interface::state::{TokenGroup, TokenGroupMember};
+use spl_token_metadata_interface::state::TokenMetadata;
+use spl_type_length_value::variable_len_pack::VariableLenPack;
+
+use crate::{
+    pausable::PausableConfig,
+    scaled_ui_amount::ScaledUiAmountConfig,
+    tlv::{extension_name, mint_extensions, PAUSABLE, SCALED_UI_AMOUNT},
+};
+
+fn pod<T: Pod>(value: &[u8]) -> Result<&T> {
+    spl_pod::bytemuck::pod_from_bytes::<T>(value).map_err(|e| anyhow!("Failed to parse mint extension: {}", e))
+}
+
+/// Human-readable lines describing every extension set on a mint, given
+/// the raw mint account data.
+pub fn describe_extensions(data: &[u8]) -> Result<Vec<String>> {
+    let mut lines = Vec::new();
+
+    for (extension_type, value) in mint_extensions(data)? {
+        if extension_type == SCALED_UI_AMOUNT {
+            let config = ScaledUiAmountConfig::unpack(value)?;
+            lines.push("Scaled UI Amount".to_string());
+            lines.push(format!("  Authority: {}", config.authority.map(|key| key.to_string()).unwrap_or_else(|| "None".to_string())));
+            lines.push(format!("  Multiplier: {}", config.multiplier));
+            if config.new_multiplier != config.multiplier {
+                lines.push(format!(
+                    "  New Multiplier: {} (from {})",
+                    config.new_multiplier, config.new_multiplier_effective_timestamp
+                ));
+            }
+            continue;
+        }
+        if extension_type == PAUSABLE {
+            let config = PausableConfig::unpack(value)?;
+            lines.push("Pausable".to_string());
+            lines.push(format!("  Authority: {}", config.authority.map(|key| key.to_string()).unwrap_or_else(|| "None".to_string())));
+            lines.push(format!("  Paused: {}", if config.paused { "yes" } else { "no" }));
+            continue;
+        }
+        let Ok(known) = ExtensionType::try_from(extension_type) else {
+            lines.push(extension_name(extension_type));
+            continue;
+        };
+
+        match known {
+            ExtensionType::TransferFeeConfig => {
+                let config = pod::<TransferFeeConfig>(value)?;
+                lines.push("Transfer Fee Config".to_string());
+                lines.push(format!("  Config Authority: {}", show(config.transfer_fee_config_authority)));
+                lines.push(format!("  Withdraw Withheld Authority: {}", show(config.withdraw_withheld_authority)));
+                lines.push(format!("  Withheld Amount: {}", u64::from(config.withheld_amount)));
+                lines.push(format!("  Newer Fee: {}", show_fee(&config.newer_transfer_fee)));
+                lines.push(format!("  Older Fee: {}", show_fee(&config.older_transfer_fee)));
+            }
+            ExtensionType::InterestBearingConfig => {
+                let config = pod::<InterestBearingConfig>(value)?;
+                lines.push("Interest Bearing Config".to_string());
+                lines.push(format!("  Rate Authority: {}", show(config.rate_authority)));
+                lines.push(format!("  Current Rate: {} bps", i16::from(config.current_rate)));
+                lines.push(format!("  Pre-Update Average Rate: {} bps", i16::from(config.pre_update_average_rate)));
+                lines.push(format!("  Initialized At: {}", i64::from(config.initialization_timestamp)));
+                lines.push(format!("  Last Updated At: {}", i64::from(config.last_update_timestamp)));
+            }
+            ExtensionType::MetadataPointer => {
+                let pointer = pod::<MetadataPointer>(value)?;
+                lines.push("Metadata Pointer".to_string());
+                lines.push(format!("  Authority: {}", show(pointer.authority)));
+                lines.push(format!("  Metadata Address: {}", show(pointer.metadata_address)));
+            }
+            ExtensionType::TokenMetadata => {
+                let metadata = TokenMetadata::unpack_from_slice(value)?;
+                lines.push("Token Metadata".to_string());
+                lines.push(format!("  Name: {}", metadata.name));
+                lines.push(format!("  Symbol: {}", metadata.symbol));
+                lines.push(format!("  URI: {}", metadata.uri));
+                lines.push(format!("  Update Authority: {}", show(metadata.update_authority)));
+                for (key, value) in &metadata.additional_metadata {
+                    lines.push(format!("  {}: {}", key, value));
+                }
+            }
+            ExtensionType::GroupPointer => {
+                let pointer = pod::<GroupPointer>(value)?;
+                lines.push("Group Pointer".to_string());
+                lines.push(format!("  Authority: {}", show(pointer.authority)));
+                lines.push(format!("  Group Address: {}", show(pointer.group_address)));
+            }
+            ExtensionType::TokenGroup => {
+                let group = pod::<TokenGroup>(value)?;
+                lines.push("Token Group".to_string());
+                lines.push(format!("  Update Authority: {}", show(group.update_authority)));
+                lines.push(format!("  Size: {} of {} members", u32::from(group.size), u32::from(group.max_size)));
+            }
+            ExtensionType::GroupMemberPointer => {
+                let pointer = pod::<GroupMemberPointer>(value)?;
+                lines.push("Group Member Pointer".to_string());
+                lines.push(format!("  Authority: {}", show(pointer.authority)));
+                lines.push(format!("  Member Address: {}", show(pointer.member_address)));
+            }
+            ExtensionType::TokenGroupMember => {
+                let member = pod::<TokenGroupMember>(value)?;
+                lines.push("Token Group Member".to_string());
+                lines.push(format!("  Group: {}", member.group));
+                lines.push(format!("  Member Number: {}", u32::from(member.member_number)));
+            }
+            ExtensionType::PermanentDelegate => {
+                let delegate = pod::<PermanentDelegate>(value)?;
+                lines.push(format!("Permanent Delegate: {}", show(delegate.delegate)));
+            }
+            ExtensionType::TransferHook => {
+                let hook = pod::<TransferHook>(value)?;
+                lines.push("Transfer Hook".to_string());
+                lines.push(format!("  Authority: {}", show(hook.authority)));
+                lines.push(format!("  Program: {}", show(hook.program_id)));
+            }
+            ExtensionType::MintCloseAuthority => {
+                let close = pod::<MintCloseAuthority>(value)?;
+                lines.push(format!("Mint Close Authority: {}", show(close.close_authority)));
+            }
+            ExtensionType::DefaultAccountState => {
+                let default_state = pod::<DefaultAccountState>(value)?;
+                let account_state = AccountState::try_from(default_state.state)
+                    .map(|state| format!("{:?}", state))
+                    .unwrap_or_else(|_| format!("unknown ({})", default_state.state));
+                lines.push(format!("Default Account State: {}", account_state));
+            }
+            ExtensionType::NonTransferable => lines.push("Non-Transferable".to_string()),
+            other => lines.push(format!("{:?}", other)),
+        }
+    }
+
+    Ok(lines)
+}
+
+fn show(key: OptionalNonZeroPubkey) -> String {
+    Option::<Pubkey>::from(key)
+        .map(|key| key.to_string())
+        .unwrap_or_else(|| "None".to_string())
+}
+
+fn show_fee(fee: &TransferFee) -> String {
+    format!(
+        "{} bps, max {} (from epoch {})",
+        u16::from(fee.transfer_fee_basis_points),
+        u64::from(fee.maximum_fee),
+        u64::from(fee.epoch)
+    )
+}
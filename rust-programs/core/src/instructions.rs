@@ -0,0 +1,46 @@
+use anyhow::Result;
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_memo::build_memo;
+use spl_token_2022::instruction::transfer_checked;
+
+use crate::mint::MintInfo;
+
+/// Memo sent when the destination requires one and the caller gave none.
+pub const DEFAULT_TRANSFER_MEMO: &str = "token-ops transfer";
+
+/// An SPL Memo instruction signed by `signer`.
+pub fn memo(text: &str, signer: &Pubkey) -> Instruction {
+    build_memo(text.as_bytes(), &[signer])
+}
+
+/// Send `amount` from `owner`'s associated token account to `recipient`'s,
+/// creating the recipient's account first if needed (paid by `owner`).
+///
+/// A `memo` is placed right before the transfer, where Token-2022 looks for
+/// it when the destination requires memos.
+pub fn transfer_to_wallet(
+    mint_info: &MintInfo,
+    owner: &Pubkey,
+    recipient: &Pubkey,
+    amount: u64,
+    decimals: u8,
+    memo: Option<&str>,
+) -> Result<Vec<Instruction>> {
+    let mut instructions =
+        vec![create_associated_token_account_idempotent(owner, recipient, &mint_info.address, &mint_info.program_id)];
+    if let Some(memo) = memo {
+        instructions.push(self::memo(memo, owner));
+    }
+    instructions.push(transfer_checked(
+        &mint_info.program_id,
+        &mint_info.associated_token_address(owner),
+        &mint_info.address,
+        &mint_info.associated_token_address(recipient),
+        owner,
+        &[],
+        amount,
+        decimals,
+    )?);
+    Ok(instructions)
+}
@@ -0,0 +1,29 @@
+//! Account decoding, instruction building and report types shared by the
+//! `token-ops` CLI and the web dashboard.
+//!
+//! Nothing here performs I/O, spawns threads or talks to an RPC node, so the
+//! crate builds for `wasm32-unknown-unknown`. Callers fetch account data
+//! themselves and pass the bytes in. The `wasm` feature adds JavaScript
+//! bindings over the same functions, and `rpc` adds fetching helpers for
+//! native callers.
+//!
+//! The crate is not `no_std`: `solana-program` and the SPL crates it builds
+//! on require std, as do `anyhow` and `serde_json`. wasm32 provides std, so
+//! the dashboard build works without it.
+
+pub mod amount;
+pub mod diff;
+pub mod distribution;
+pub mod errors;
+pub mod extensions;
+pub mod instructions;
+pub mod merkle;
+pub mod mint;
+pub mod pausable;
+pub mod report;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+pub mod scaled_ui_amount;
+pub mod tlv;
+#[cfg(feature = "wasm")]
+pub mod wasm;
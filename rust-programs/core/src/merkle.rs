@@ -0,0 +1,133 @@
+//! Merkle trees over address lists, for claim programs and gated sales
+//! that check membership with a proof against a stored root.
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+/// Prefixes that keep a leaf from being passed off as an inner node.
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+pub fn leaf_hash(address: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, address.as_ref()]).to_bytes()
+}
+
+/// Pairs are hashed in sorted order, so a proof is just the sibling hashes
+/// from leaf to root with no left/right flags.
+fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, low, high]).to_bytes()
+}
+
+/// A SHA-256 Merkle tree with one leaf per address, in the given order.
+/// An odd node at the end of a level is carried up unchanged.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    pub fn new(addresses: &[Pubkey]) -> Self {
+        let mut levels = vec![addresses.iter().map(leaf_hash).collect::<Vec<_>>()];
+        while levels.last().map_or(0, Vec::len) > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node_hash(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    /// The root, or all zeroes for an empty list.
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().and_then(|level| level.first()).copied().unwrap_or_default()
+    }
+
+    /// Sibling hashes proving the leaf at `index`.
+    pub fn proof(&self, mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len().saturating_sub(1)] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+/// Check `proof` for `address` against `root`.
+pub fn verify(root: &[u8; 32], address: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(leaf_hash(address), |hash, sibling| node_hash(&hash, sibling));
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(count: usize) -> Vec<Pubkey> {
+        (0..count).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    #[test]
+    fn empty_list_has_zero_root() {
+        let tree = MerkleTree::new(&[]);
+        assert_eq!(tree.root(), [0; 32]);
+        assert!(tree.proof(0).is_empty());
+    }
+
+    #[test]
+    fn single_leaf_is_the_root() {
+        let address = Pubkey::new_unique();
+        let tree = MerkleTree::new(&[address]);
+        assert_eq!(tree.root(), leaf_hash(&address));
+        assert!(tree.proof(0).is_empty());
+        assert!(verify(&tree.root(), &address, &[]));
+    }
+
+    #[test]
+    fn every_leaf_proves_for_even_and_odd_sizes() {
+        for count in 2..=9 {
+            let addresses = addresses(count);
+            let tree = MerkleTree::new(&addresses);
+            for (index, address) in addresses.iter().enumerate() {
+                assert!(verify(&tree.root(), address, &tree.proof(index)), "leaf {} of {}", index, count);
+            }
+        }
+    }
+
+    #[test]
+    fn odd_last_leaf_is_carried_up() {
+        let addresses = addresses(3);
+        let tree = MerkleTree::new(&addresses);
+        assert_eq!(
+            tree.root(),
+            node_hash(&node_hash(&leaf_hash(&addresses[0]), &leaf_hash(&addresses[1])), &leaf_hash(&addresses[2]))
+        );
+        assert_eq!(tree.proof(2).len(), 1);
+    }
+
+    #[test]
+    fn proofs_do_not_verify_other_addresses() {
+        let addresses = addresses(5);
+        let tree = MerkleTree::new(&addresses);
+        assert!(!verify(&tree.root(), &addresses[1], &tree.proof(0)));
+        assert!(!verify(&tree.root(), &Pubkey::new_unique(), &tree.proof(0)));
+        assert!(!verify(&[0; 32], &addresses[0], &tree.proof(0)));
+    }
+
+    #[test]
+    fn inner_node_is_not_a_leaf() {
+        let addresses = addresses(2);
+        let tree = MerkleTree::new(&addresses);
+        let inner = node_hash(&leaf_hash(&addresses[0]), &leaf_hash(&addresses[1]));
+        assert_ne!(leaf_hash(&Pubkey::new_from_array(inner)), tree.root());
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use solana_program::pubkey::Pubkey;
+use spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    state::{Account, AccountState, Mint},
+};
+
+use crate::{
+    amount::{format_amount, format_scaled_amount},
+    extensions::describe_extensions,
+    pausable::PausableConfig,
+    scaled_ui_amount::ScaledUiAmountConfig,
+    tlv::{find_mint_extension, token_account_extensions, PAUSABLE, SCALED_UI_AMOUNT},
+};
+
+/// A mint account owned by either SPL Token or Token-2022.
+///
+/// Token-2022 keeps the base Mint layout and appends extensions, so both
+/// programs' accounts are parsed with `StateWithExtensions`.
+pub struct MintInfo {
+    pub address: Pubkey,
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+}
+
+impl MintInfo {
+    pub fn state(&self) -> Result<StateWithExtensions<'_, Mint>> {
+        StateWithExtensions::<Mint>::unpack(&self.data)
+            .map_err(|e| anyhow!("Failed to parse mint data: {}", e))
+    }
+
+    pub fn base(&self) -> Result<Mint> {
+        Ok(self.state()?.base)
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.program_id == spl_token_2022::id()
+    }
+
+    pub fn associated_token_address(&self, owner: &Pubkey) -> Pubkey {
+        spl_associated_token_account::get_associated_token_address_with_program_id(owner, &self.address, &self.program_id)
+    }
+
+    /// The mint's ScaledUiAmount configuration, if it has the extension.
+    pub fn scaled_ui_amount(&self) -> Result<Option<ScaledUiAmountConfig>> {
+        find_mint_extension(&self.data, SCALED_UI_AMOUNT)?
+            .map(ScaledUiAmountConfig::unpack)
+            .transpose()
+    }
+
+    /// The mint's Pausable configuration, if it has the extension.
+    pub fn pausable(&self) -> Result<Option<PausableConfig>> {
+        find_mint_extension(&self.data, PAUSABLE)?.map(PausableConfig::unpack).transpose()
+    }
+
+    /// Render a raw amount the way wallets show it: with the mint's decimals
+    /// and, for ScaledUiAmount mints, times the multiplier in force at
+    /// `unix_timestamp`.
+    pub fn ui_amount(&self, amount: u64, unix_timestamp: i64) -> Result<String> {
+        let decimals = self.base()?.decimals;
+        Ok(match self.scaled_ui_amount()? {
+            Some(config) => format_scaled_amount(amount, decimals, config.multiplier_at(unix_timestamp)),
+            None => format_amount(amount, decimals),
+        })
+    }
+
+    /// State new token accounts start in, from the DefaultAccountState extension.
+    pub fn default_account_state(&self) -> Result<Option<AccountState>> {
+        let state = self.state()?;
+        let Ok(extension) = state.get_extension::<DefaultAccountState>() else {
+            return Ok(None);
+        };
+
+        AccountState::try_from(extension.state)
+            .map(Some)
+            .map_err(|e| anyhow!("Invalid default account state: {}", e))
+    }
+}
+
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::id() || *program_id == spl_token_2022::id()
+}
+
+/// Whether a token account rejects transfers in that are not preceded by a
+/// memo (the Token-2022 MemoTransfer extension).
+pub fn requires_incoming_memo(data: &[u8]) -> Result<bool> {
+    let memo_transfer = ExtensionType::MemoTransfer as u16;
+    Ok(token_account_extensions(data)?
+        .into_iter()
+        .any(|(extension, value)| extension == memo_transfer && value.first().is_some_and(|flag| *flag != 0)))
+}
+
+/// Parse the base state of a token account owned by either token program.
+pub fn unpack_token_account(data: &[u8]) -> Result<Account> {
+    StateWithExtensions::<Account>::unpack(data)
+        .map(|state| state.base)
+        .map_err(|e| anyhow!("Failed to parse token account: {}", e))
+}
+
+/// What `verify` reports about a mint, in a form the CLI logs and the
+/// dashboard renders.
+#[derive(Debug, Clone, Serialize)]
+pub struct MintSummary {
+    pub address: String,
+    pub program: String,
+    pub supply: u64,
+    pub decimals: u8,
+    pub is_initialized: bool,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    /// Lines from `describe_extensions`; empty for SPL Token mints.
+    pub extensions: Vec<String>,
+}
+
+impl MintInfo {
+    pub fn summary(&self) -> Result<MintSummary> {
+        let state = self.state()?;
+        let extensions = if self.is_token_2022() { describe_extensions(&self.data)? } else { Vec::new() };
+        let key = |key: Option<Pubkey>| key.map(|key| key.to_string());
+
+        Ok(MintSummary {
+            address: self.address.to_string(),
+            program: if self.is_token_2022() { "Token-2022" } else { "SPL Token" }.to_string(),
+            supply: state.base.supply,
+            decimals: state.base.decimals,
+            is_initialized: state.base.is_initialized,
+            mint_authority: key(state.base.mint_authority.into()),
+            freeze_authority: key(state.base.freeze_authority.into()),
+            extensions,
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Result};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// Token-2022 instruction prefix for the Pausable extension.
+const PAUSABLE_EXTENSION: u8 = 44;
+const INITIALIZE: u8 = 0;
+const PAUSE: u8 = 1;
+const RESUME: u8 = 2;
+
+/// Bytes of the PausableConfig extension value.
+pub const CONFIG_LEN: usize = 32 + 1;
+
+/// The Pausable extension of a mint. While paused, Token-2022 rejects
+/// transfers, mints and burns of the token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PausableConfig {
+    pub authority: Option<Pubkey>,
+    pub paused: bool,
+}
+
+impl PausableConfig {
+    pub fn unpack(value: &[u8]) -> Result<Self> {
+        if value.len() != CONFIG_LEN {
+            return Err(anyhow!("Pausable extension has {} bytes, expected {}", value.len(), CONFIG_LEN));
+        }
+        let authority = Pubkey::try_from(&value[..32]).map_err(|e| anyhow!("Invalid pause authority: {}", e))?;
+
+        Ok(Self {
+            authority: (authority != Pubkey::default()).then_some(authority),
+            paused: value[32] != 0,
+        })
+    }
+}
+
+/// Bytes the PausableConfig TLV entry adds to a mint, including the 4-byte
+/// TLV header.
+pub fn tlv_len() -> usize {
+    4 + CONFIG_LEN
+}
+
+/// Bytes the (empty) PausableAccount TLV entry adds to each token account
+/// of a pausable mint.
+pub fn account_tlv_len() -> usize {
+    4
+}
+
+/// InitializePausableConfig, which must run before InitializeMint2.
+pub fn initialize(mint: &Pubkey, authority: &Pubkey) -> Instruction {
+    let mut data = vec![PAUSABLE_EXTENSION, INITIALIZE];
+    data.extend_from_slice(authority.as_ref());
+
+    Instruction {
+        program_id: spl_token_2022::id(),
+        accounts: vec![AccountMeta::new(*mint, false)],
+        data,
+    }
+}
+
+/// Pause or resume the mint, signed by the pause authority.
+pub fn set_paused(mint: &Pubkey, authority: &Pubkey, paused: bool) -> Instruction {
+    Instruction {
+        program_id: spl_token_2022::id(),
+        accounts: vec![AccountMeta::new(*mint, false), AccountMeta::new_readonly(*authority, true)],
+        data: vec![PAUSABLE_EXTENSION, if paused { PAUSE } else { RESUME }],
+    }
+}
@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One token account holding a non-zero balance of the snapshotted mint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HolderBalance {
+    pub account: String,
+    pub owner: String,
+    pub amount: u64,
+}
+
+/// One owner's balance of a mint, held in their associated token account.
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenBalance {
+    pub mint: String,
+    pub owner: String,
+    pub account: String,
+    pub amount: u64,
+    pub ui_amount: String,
+}
+
+/// A mint's supply as reported by getTokenSupply.
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenSupply {
+    pub mint: String,
+    pub slot: u64,
+    pub amount: u64,
+    pub decimals: u8,
+    pub ui_amount: String,
+}
+
+/// Point-in-time list of every funded token account for a mint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HolderSnapshot {
+    pub mint: String,
+    pub slot: u64,
+    pub supply: u64,
+    pub decimals: u8,
+    /// Sorted by balance, largest first.
+    pub holders: Vec<HolderBalance>,
+}
+
+/// Every funded token account of one owner, merged into a single holder.
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnerBalance {
+    pub owner: String,
+    pub amount: u64,
+    pub accounts: usize,
+    /// What the owner is when it is not a plain wallet, e.g. a known
+    /// liquidity pool, a lock program or an unlabelled PDA.
+    pub label: Option<String>,
+}
+
+/// Merge a snapshot's token accounts by owner, largest balance first.
+pub fn merge_by_owner(snapshot: &HolderSnapshot) -> Vec<OwnerBalance> {
+    let mut merged: HashMap<&str, OwnerBalance> = HashMap::new();
+    for holder in &snapshot.holders {
+        let entry = merged.entry(holder.owner.as_str()).or_insert_with(|| OwnerBalance {
+            owner: holder.owner.clone(),
+            amount: 0,
+            accounts: 0,
+            label: None,
+        });
+        entry.amount = entry.amount.saturating_add(holder.amount);
+        entry.accounts += 1;
+    }
+
+    let mut owners: Vec<OwnerBalance> = merged.into_values().collect();
+    owners.sort_by(|a, b| b.amount.cmp(&a.amount).then_with(|| a.owner.cmp(&b.owner)));
+    owners
+}
+
+/// Concentration metrics reported to exchanges and launchpads.
+#[derive(Debug, Clone, Serialize)]
+pub struct DistributionStats {
+    pub holders: usize,
+    pub top_1_pct: f64,
+    pub top_10_pct: f64,
+    pub top_100_pct: f64,
+    pub gini: f64,
+    pub median_balance: u64,
+}
+
+/// Compute concentration metrics from raw holder balances.
+pub fn distribution_stats(balances: &[u64], supply: u64) -> DistributionStats {
+    let mut sorted: Vec<u64> = balances.iter().copied().filter(|b| *b > 0).collect();
+    sorted.sort_unstable();
+
+    let n = sorted.len();
+    let total: u128 = sorted.iter().map(|b| *b as u128).sum();
+
+    let top_pct = |count: usize| {
+        if supply == 0 {
+            return 0.0;
+        }
+        let held: u128 = sorted.iter().rev().take(count).map(|b| *b as u128).sum();
+        held as f64 / supply as f64 * 100.0
+    };
+
+    let median_balance = match n {
+        0 => 0,
+        _ if n % 2 == 1 => sorted[n / 2],
+        _ => ((sorted[n / 2 - 1] as u128 + sorted[n / 2] as u128) / 2) as u64,
+    };
+
+    // Gini over ascending balances: G = 2 * sum(i * x_i) / (n * sum(x)) - (n + 1) / n
+    let gini = if n == 0 || total == 0 {
+        0.0
+    } else {
+        let weighted: f64 = sorted
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (i as f64 + 1.0) * *b as f64)
+            .sum();
+        2.0 * weighted / (n as f64 * total as f64) - (n as f64 + 1.0) / n as f64
+    };
+
+    DistributionStats {
+        holders: n,
+        top_1_pct: top_pct(1),
+        top_10_pct: top_pct(10),
+        top_100_pct: top_pct(100),
+        gini,
+        median_balance,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn empty_input_has_no_concentration() {
+        let stats = distribution_stats(&[], 0);
+        assert_eq!(stats.holders, 0);
+        assert_eq!(stats.median_balance, 0);
+        assert!(close(stats.gini, 0.0));
+        assert!(close(stats.top_1_pct, 0.0));
+        assert!(close(stats.top_100_pct, 0.0));
+    }
+
+    #[test]
+    fn zero_balances_are_not_holders() {
+        let stats = distribution_stats(&[0, 0, 0], 0);
+        assert_eq!(stats.holders, 0);
+        assert!(close(stats.gini, 0.0));
+    }
+
+    #[test]
+    fn single_holder() {
+        let stats = distribution_stats(&[0, 500, 0], 1_000);
+        assert_eq!(stats.holders, 1);
+        assert_eq!(stats.median_balance, 500);
+        assert!(close(stats.gini, 0.0));
+        assert!(close(stats.top_1_pct, 50.0));
+        assert!(close(stats.top_10_pct, 50.0));
+    }
+
+    #[test]
+    fn equal_balances_have_zero_gini() {
+        let stats = distribution_stats(&[7; 20], 140);
+        assert_eq!(stats.holders, 20);
+        assert_eq!(stats.median_balance, 7);
+        assert!(close(stats.gini, 0.0));
+        assert!(close(stats.top_1_pct, 5.0));
+        assert!(close(stats.top_10_pct, 50.0));
+        assert!(close(stats.top_100_pct, 100.0));
+    }
+
+    #[test]
+    fn gini_of_two_unequal_holders() {
+        // Mean absolute difference 2 over twice the mean 4.
+        let stats = distribution_stats(&[3, 1], 4);
+        assert!(close(stats.gini, 0.25));
+        assert!(close(stats.top_1_pct, 75.0));
+    }
+
+    #[test]
+    fn median_of_even_count_averages_the_middle_pair() {
+        assert_eq!(distribution_stats(&[4, 1, 3, 2], 10).median_balance, 2);
+        assert_eq!(distribution_stats(&[1, 2, 3], 6).median_balance, 2);
+        assert_eq!(distribution_stats(&[u64::MAX, u64::MAX], u64::MAX).median_balance, u64::MAX);
+    }
+
+    #[test]
+    fn top_shares_use_the_largest_balances() {
+        let mut balances = vec![1; 150];
+        balances[42] = 850;
+        let stats = distribution_stats(&balances, 1_000);
+        assert!(close(stats.top_1_pct, 85.0));
+        assert!(close(stats.top_10_pct, 85.9));
+        assert!(close(stats.top_100_pct, 94.9));
+    }
+
+    #[test]
+    fn zero_supply_reports_no_top_share() {
+        let stats = distribution_stats(&[10, 20], 0);
+        assert_eq!(stats.holders, 2);
+        assert!(close(stats.top_1_pct, 0.0));
+    }
+
+    #[test]
+    fn merge_by_owner_sums_accounts_of_one_owner() {
+        let holder = |account: &str, owner: &str, amount| HolderBalance {
+            account: account.to_string(),
+            owner: owner.to_string(),
+            amount,
+        };
+        let snapshot = HolderSnapshot {
+            mint: "mint".to_string(),
+            slot: 1,
+            supply: 60,
+            decimals: 0,
+            holders: vec![holder("a1", "alice", 10), holder("b1", "bob", 30), holder("a2", "alice", 20)],
+        };
+        let owners = merge_by_owner(&snapshot);
+        let alice = owners.iter().find(|owner| owner.owner == "alice").unwrap();
+        assert_eq!((alice.amount, alice.accounts), (30, 2));
+        assert_eq!(owners.len(), 2);
+    }
+}
@@ -0,0 +1,160 @@
+//! Fetching helpers built on the decoders, for native callers with an RPC
+//! client (the CLI and the Python module). Not available on wasm32.
+
+use anyhow::{anyhow, Result};
+use log::info;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use std::{
+    cmp::Reverse,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use spl_token::state::Account;
+
+use crate::{
+    instructions::DEFAULT_TRANSFER_MEMO,
+    mint::{is_token_program, requires_incoming_memo, unpack_token_account, MintInfo},
+    report::{HolderBalance, HolderSnapshot, TokenBalance, TokenSupply},
+};
+
+pub fn fetch_mint_info(client: &RpcClient, mint_pubkey: &Pubkey) -> Result<MintInfo> {
+    let account = client.get_account(mint_pubkey)
+        .map_err(|e| anyhow!("Failed to get mint account: {}", e))?;
+
+    if !is_token_program(&account.owner) {
+        return Err(anyhow!("Account is not owned by SPL Token or Token-2022"));
+    }
+
+    Ok(MintInfo {
+        address: *mint_pubkey,
+        program_id: account.owner,
+        data: account.data,
+    })
+}
+
+/// Balance of `owner`'s associated token account; zero when it does not exist.
+pub fn fetch_balance(client: &RpcClient, mint_pubkey: &Pubkey, owner: &Pubkey) -> Result<TokenBalance> {
+    let mint_info = fetch_mint_info(client, mint_pubkey)?;
+    let account = mint_info.associated_token_address(owner);
+
+    let amount = match client
+        .get_account_with_commitment(&account, client.commitment())
+        .map_err(|e| anyhow!("Failed to get token account: {}", e))?
+        .value
+    {
+        Some(data) => unpack_token_account(&data.data)?.amount,
+        None => 0,
+    };
+
+    Ok(TokenBalance {
+        mint: mint_pubkey.to_string(),
+        owner: owner.to_string(),
+        account: account.to_string(),
+        amount,
+        ui_amount: mint_info.ui_amount(amount, unix_now())?,
+    })
+}
+
+/// Supply and decimals in one getTokenSupply call, without fetching and
+/// unpacking the mint account.
+pub fn fetch_supply(client: &RpcClient, mint_pubkey: &Pubkey) -> Result<TokenSupply> {
+    let response = client
+        .get_token_supply_with_commitment(mint_pubkey, client.commitment())
+        .map_err(|e| anyhow!("Failed to get token supply: {}", e))?;
+    let supply = response.value;
+
+    Ok(TokenSupply {
+        mint: mint_pubkey.to_string(),
+        slot: response.context.slot,
+        amount: supply.amount.parse().map_err(|e| anyhow!("Invalid supply '{}': {}", supply.amount, e))?,
+        decimals: supply.decimals,
+        ui_amount: supply.ui_amount_string,
+    })
+}
+
+/// The memo to send with a transfer to `recipient`'s ATA: `memo` when
+/// given, else a default if the existing destination account requires memos.
+pub fn transfer_memo<'a>(
+    client: &RpcClient,
+    mint_info: &MintInfo,
+    recipient: &Pubkey,
+    memo: Option<&'a str>,
+) -> Result<Option<&'a str>> {
+    if memo.is_some() || !mint_info.is_token_2022() {
+        return Ok(memo);
+    }
+    let destination = mint_info.associated_token_address(recipient);
+    let account = client
+        .get_account_with_commitment(&destination, client.commitment())
+        .map_err(|e| anyhow!("Failed to get destination account: {}", e))?
+        .value;
+    match account {
+        Some(account) if requires_incoming_memo(&account.data)? => {
+            info!("Destination {} requires a memo; adding \"{}\"", destination, DEFAULT_TRANSFER_MEMO);
+            Ok(Some(DEFAULT_TRANSFER_MEMO))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Seconds since the Unix epoch, for picking a ScaledUiAmount multiplier.
+pub fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs() as i64).unwrap_or_default()
+}
+
+/// Funded token accounts of a mint, largest first. Works for SPL Token and
+/// Token-2022 mints; Token-2022 accounts vary in size with their
+/// extensions, so they are matched on the mint alone.
+pub fn fetch_holder_snapshot(client: &RpcClient, mint_pubkey: &Pubkey) -> Result<HolderSnapshot> {
+    info!("Taking holder snapshot for mint: {}", mint_pubkey);
+
+    let mint_info = fetch_mint_info(client, mint_pubkey)?;
+    let mint = mint_info.base()?;
+
+    let slot = client.get_slot()
+        .map_err(|e| anyhow!("Failed to get current slot: {}", e))?;
+
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, mint_pubkey.as_ref()))];
+    if !mint_info.is_token_2022() {
+        filters.push(RpcFilterType::DataSize(Account::LEN as u64));
+    }
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = client.get_program_accounts_with_config(&mint_info.program_id, config)
+        .map_err(|e| anyhow!("Failed to get token accounts for mint: {}", e))?;
+
+    let mut holders: Vec<HolderBalance> = accounts
+        .iter()
+        .filter_map(|(pubkey, account)| {
+            let token_account = unpack_token_account(&account.data).ok()?;
+            (token_account.mint == *mint_pubkey && token_account.amount > 0).then(|| HolderBalance {
+                account: pubkey.to_string(),
+                owner: token_account.owner.to_string(),
+                amount: token_account.amount,
+            })
+        })
+        .collect();
+    holders.sort_by_key(|holder| Reverse(holder.amount));
+
+    info!("Snapshot at slot {}: {} funded account(s) out of {}", slot, holders.len(), accounts.len());
+
+    Ok(HolderSnapshot {
+        mint: mint_pubkey.to_string(),
+        slot,
+        supply: mint.supply,
+        decimals: mint.decimals,
+        holders,
+    })
+}
@@ -0,0 +1,92 @@
+use anyhow::{anyhow, Result};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// Token-2022 instruction prefix for the ScaledUiAmount extension.
+const SCALED_UI_AMOUNT_EXTENSION: u8 = 43;
+const INITIALIZE: u8 = 0;
+const UPDATE_MULTIPLIER: u8 = 1;
+
+/// Bytes of the ScaledUiAmountConfig extension value.
+pub const CONFIG_LEN: usize = 32 + 8 + 8 + 8;
+
+/// The ScaledUiAmount extension of a mint: balances are shown as the raw
+/// amount times `multiplier`, which the authority can change at a future
+/// timestamp.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaledUiAmountConfig {
+    pub authority: Option<Pubkey>,
+    pub multiplier: f64,
+    pub new_multiplier_effective_timestamp: i64,
+    pub new_multiplier: f64,
+}
+
+impl ScaledUiAmountConfig {
+    pub fn unpack(value: &[u8]) -> Result<Self> {
+        if value.len() != CONFIG_LEN {
+            return Err(anyhow!("ScaledUiAmount extension has {} bytes, expected {}", value.len(), CONFIG_LEN));
+        }
+        let authority = Pubkey::try_from(&value[..32]).map_err(|e| anyhow!("Invalid scaled UI authority: {}", e))?;
+        let word = |offset: usize| <[u8; 8]>::try_from(&value[offset..offset + 8]).unwrap_or_default();
+
+        Ok(Self {
+            authority: (authority != Pubkey::default()).then_some(authority),
+            multiplier: f64::from_le_bytes(word(32)),
+            new_multiplier_effective_timestamp: i64::from_le_bytes(word(40)),
+            new_multiplier: f64::from_le_bytes(word(48)),
+        })
+    }
+
+    /// The multiplier in force at `unix_timestamp`.
+    pub fn multiplier_at(&self, unix_timestamp: i64) -> f64 {
+        if unix_timestamp >= self.new_multiplier_effective_timestamp {
+            self.new_multiplier
+        } else {
+            self.multiplier
+        }
+    }
+}
+
+/// Bytes the ScaledUiAmountConfig TLV entry adds to a mint, including the
+/// 4-byte TLV header.
+pub fn tlv_len() -> usize {
+    4 + CONFIG_LEN
+}
+
+fn check_multiplier(multiplier: f64) -> Result<()> {
+    if !multiplier.is_finite() || multiplier <= 0.0 {
+        return Err(anyhow!("UI multiplier must be a positive number, got {}", multiplier));
+    }
+    Ok(())
+}
+
+/// InitializeScaledUiAmountMint, which must run before InitializeMint2.
+pub fn initialize(mint: &Pubkey, authority: Option<&Pubkey>, multiplier: f64) -> Result<Instruction> {
+    check_multiplier(multiplier)?;
+    let mut data = vec![SCALED_UI_AMOUNT_EXTENSION, INITIALIZE];
+    data.extend_from_slice(authority.copied().unwrap_or_default().as_ref());
+    data.extend_from_slice(&multiplier.to_le_bytes());
+
+    Ok(Instruction {
+        program_id: spl_token_2022::id(),
+        accounts: vec![AccountMeta::new(*mint, false)],
+        data,
+    })
+}
+
+/// UpdateMultiplier: switch to `multiplier` from `effective_timestamp`, or
+/// immediately when that is in the past.
+pub fn update_multiplier(mint: &Pubkey, authority: &Pubkey, multiplier: f64, effective_timestamp: i64) -> Result<Instruction> {
+    check_multiplier(multiplier)?;
+    let mut data = vec![SCALED_UI_AMOUNT_EXTENSION, UPDATE_MULTIPLIER];
+    data.extend_from_slice(&multiplier.to_le_bytes());
+    data.extend_from_slice(&effective_timestamp.to_le_bytes());
+
+    Ok(Instruction {
+        program_id: spl_token_2022::id(),
+        accounts: vec![AccountMeta::new(*mint, false), AccountMeta::new_readonly(*authority, true)],
+        data,
+    })
+}
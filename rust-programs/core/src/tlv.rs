@@ -0,0 +1,81 @@
+use anyhow::{anyhow, Result};
+use solana_program::program_pack::Pack;
+use spl_token_2022::{
+    extension::ExtensionType,
+    state::{Account, Mint},
+};
+
+/// Extension type numbers the pinned spl-token-2022 release predates. Its
+/// own TLV readers reject accounts holding them, so these are read here.
+pub const SCALED_UI_AMOUNT: u16 = 25;
+pub const PAUSABLE: u16 = 26;
+pub const PAUSABLE_ACCOUNT: u16 = 27;
+
+/// Account type bytes Token-2022 writes after the padded base state.
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// The extension entries of a mint account as `(type, value)` pairs, in
+/// account order. Unlike `StateWithExtensions`, unknown types are kept
+/// rather than rejected. SPL Token mints have none.
+pub fn mint_extensions(data: &[u8]) -> Result<Vec<(u16, &[u8])>> {
+    if data.len() <= Mint::LEN {
+        return Ok(Vec::new());
+    }
+    // Mints are padded to the token account length so the two can be told
+    // apart, then tagged with the account type.
+    if data.len() <= Account::LEN || data[Account::LEN] != ACCOUNT_TYPE_MINT {
+        return Err(anyhow!("Account is not a Token-2022 mint with extensions"));
+    }
+    entries(data)
+}
+
+/// The extension entries of a token account, like `mint_extensions`.
+pub fn token_account_extensions(data: &[u8]) -> Result<Vec<(u16, &[u8])>> {
+    if data.len() <= Account::LEN {
+        return Ok(Vec::new());
+    }
+    if data[Account::LEN] != ACCOUNT_TYPE_ACCOUNT {
+        return Err(anyhow!("Account is not a Token-2022 token account"));
+    }
+    entries(data)
+}
+
+fn entries(data: &[u8]) -> Result<Vec<(u16, &[u8])>> {
+    let mut entries = Vec::new();
+    let mut offset = Account::LEN + 1;
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        // Uninitialized entries pad the end of the account.
+        if extension_type == 0 {
+            break;
+        }
+        let value = data
+            .get(offset + 4..offset + 4 + length)
+            .ok_or_else(|| anyhow!("Extension {} overruns the mint account", extension_type))?;
+        entries.push((extension_type, value));
+        offset += 4 + length;
+    }
+    Ok(entries)
+}
+
+/// The value of one extension entry, if the mint has it.
+pub fn find_mint_extension(data: &[u8], extension_type: u16) -> Result<Option<&[u8]>> {
+    Ok(mint_extensions(data)?
+        .into_iter()
+        .find(|(entry_type, _)| *entry_type == extension_type)
+        .map(|(_, value)| value))
+}
+
+/// Name of an extension type by number, including the ones newer than the
+/// pinned spl-token-2022 release.
+pub fn extension_name(extension_type: u16) -> String {
+    match ExtensionType::try_from(extension_type) {
+        Ok(known) => format!("{:?}", known),
+        Err(_) if extension_type == SCALED_UI_AMOUNT => "ScaledUiAmount".to_string(),
+        Err(_) if extension_type == PAUSABLE => "Pausable".to_string(),
+        Err(_) if extension_type == PAUSABLE_ACCOUNT => "PausableAccount".to_string(),
+        Err(_) => format!("Unknown({})", extension_type),
+    }
+}
@@ -0,0 +1,40 @@
+//! JavaScript bindings. Structured results are returned as JSON strings so
+//! the dashboard can `JSON.parse` them without extra glue.
+
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+use crate::{amount, mint::MintInfo, report};
+
+fn js_error(e: impl std::fmt::Display) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// Decode a mint account fetched by the caller into a `MintSummary` JSON string.
+#[wasm_bindgen(js_name = decodeMint)]
+pub fn decode_mint(address: &str, owner: &str, data: &[u8]) -> Result<String, JsError> {
+    let mint_info = MintInfo {
+        address: Pubkey::from_str(address).map_err(js_error)?,
+        program_id: Pubkey::from_str(owner).map_err(js_error)?,
+        data: data.to_vec(),
+    };
+    let summary = mint_info.summary().map_err(js_error)?;
+    serde_json::to_string(&summary).map_err(js_error)
+}
+
+#[wasm_bindgen(js_name = formatAmount)]
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    amount::format_amount(amount, decimals)
+}
+
+#[wasm_bindgen(js_name = parseAmount)]
+pub fn parse_amount(value: &str, decimals: u8) -> Result<u64, JsError> {
+    amount::parse_amount(value, decimals).map_err(js_error)
+}
+
+/// Concentration metrics for a list of raw balances, as a `DistributionStats` JSON string.
+#[wasm_bindgen(js_name = distributionStats)]
+pub fn distribution_stats(balances: Vec<u64>, supply: u64) -> Result<String, JsError> {
+    serde_json::to_string(&report::distribution_stats(&balances, supply)).map_err(js_error)
+}
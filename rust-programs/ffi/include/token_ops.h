@@ -0,0 +1,37 @@
+/*
+ * C interface to token-ops verification and balance checks.
+ *
+ * Every operation writes a heap-allocated JSON string to *out and returns a
+ * status code. On failure the JSON is {"error": "..."}. Release every string
+ * written to *out with token_ops_string_free. Calls are blocking and safe to
+ * make from multiple threads.
+ */
+#ifndef TOKEN_OPS_H
+#define TOKEN_OPS_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define TOKEN_OPS_OK 0
+#define TOKEN_OPS_INVALID_ARGUMENT 1
+#define TOKEN_OPS_FAILED 2
+#define TOKEN_OPS_PANIC 3
+
+/* {"address", "program", "supply", "decimals", "is_initialized",
+ *  "mint_authority", "freeze_authority", "extensions"} */
+int token_ops_verify(const char *rpc_url, const char *mint, char **out);
+
+/* {"mint", "owner", "account", "amount", "ui_amount"} */
+int token_ops_balance(const char *rpc_url, const char *mint, const char *owner, char **out);
+
+/* {"mint", "slot", "supply", "decimals", "holders": [{"account", "owner", "amount"}]} */
+int token_ops_snapshot(const char *rpc_url, const char *mint, char **out);
+
+void token_ops_string_free(char *value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TOKEN_OPS_H */
@@ -0,0 +1,142 @@
+//! C ABI over the core verification and balance logic; see
+//! `include/token_ops.h` for the contract.
+//!
+//! Every operation writes a JSON string to `*out` and returns a status code.
+//! On failure the JSON is `{"error": "..."}`. Strings returned through `out`
+//! are owned by the caller and must be released with `token_ops_string_free`.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{
+    ffi::{c_char, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr,
+    str::FromStr,
+};
+use token_ops_core::rpc;
+
+pub const TOKEN_OPS_OK: i32 = 0;
+/// A null pointer, non-UTF-8 string or malformed address was passed in.
+pub const TOKEN_OPS_INVALID_ARGUMENT: i32 = 1;
+/// The RPC request or decoding the account failed.
+pub const TOKEN_OPS_FAILED: i32 = 2;
+/// A Rust panic was caught at the boundary.
+pub const TOKEN_OPS_PANIC: i32 = 3;
+
+enum FfiError {
+    InvalidArgument(String),
+    Failed(anyhow::Error),
+}
+
+impl From<anyhow::Error> for FfiError {
+    fn from(e: anyhow::Error) -> Self {
+        FfiError::Failed(e)
+    }
+}
+
+/// # Safety
+/// `value` must be null or a valid NUL-terminated string.
+unsafe fn string_arg<'a>(value: *const c_char, name: &str) -> std::result::Result<&'a str, FfiError> {
+    if value.is_null() {
+        return Err(FfiError::InvalidArgument(format!("{} is null", name)));
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map_err(|_| FfiError::InvalidArgument(format!("{} is not valid UTF-8", name)))
+}
+
+fn pubkey_arg(value: &str, name: &str) -> std::result::Result<Pubkey, FfiError> {
+    Pubkey::from_str(value).map_err(|e| FfiError::InvalidArgument(format!("Invalid {} '{}': {}", name, value, e)))
+}
+
+fn client(rpc_url: &str) -> RpcClient {
+    RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| anyhow!("Failed to serialize result: {}", e))
+}
+
+/// Run `operation`, write its JSON (or the error JSON) to `out` and map the
+/// outcome to a status code.
+///
+/// # Safety
+/// `out` must be null or valid for writing one pointer.
+unsafe fn respond(out: *mut *mut c_char, operation: impl FnOnce() -> std::result::Result<String, FfiError>) -> i32 {
+    let (status, json) = match catch_unwind(AssertUnwindSafe(operation)) {
+        Ok(Ok(json)) => (TOKEN_OPS_OK, json),
+        Ok(Err(FfiError::InvalidArgument(message))) => (TOKEN_OPS_INVALID_ARGUMENT, error_json(&message)),
+        Ok(Err(FfiError::Failed(e))) => (TOKEN_OPS_FAILED, error_json(&e.to_string())),
+        Err(_) => (TOKEN_OPS_PANIC, error_json("internal error")),
+    };
+
+    if out.is_null() {
+        return TOKEN_OPS_INVALID_ARGUMENT;
+    }
+    // serde_json escapes control characters, so the JSON never contains NUL.
+    *out = CString::new(json).map(CString::into_raw).unwrap_or(ptr::null_mut());
+    status
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Verify a mint and report its program, supply, decimals, authorities and extensions.
+///
+/// # Safety
+/// `rpc_url` and `mint` must be NUL-terminated strings and `out` valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn token_ops_verify(rpc_url: *const c_char, mint: *const c_char, out: *mut *mut c_char) -> i32 {
+    respond(out, || {
+        let client = client(string_arg(rpc_url, "rpc_url")?);
+        let mint = pubkey_arg(string_arg(mint, "mint")?, "mint")?;
+        let summary = rpc::fetch_mint_info(&client, &mint)?.summary()?;
+        Ok(to_json(&summary)?)
+    })
+}
+
+/// Balance of `owner`'s associated token account for `mint`; zero when it does not exist.
+///
+/// # Safety
+/// `rpc_url`, `mint` and `owner` must be NUL-terminated strings and `out` valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn token_ops_balance(
+    rpc_url: *const c_char,
+    mint: *const c_char,
+    owner: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    respond(out, || {
+        let client = client(string_arg(rpc_url, "rpc_url")?);
+        let mint = pubkey_arg(string_arg(mint, "mint")?, "mint")?;
+        let owner = pubkey_arg(string_arg(owner, "owner")?, "owner")?;
+        Ok(to_json(&rpc::fetch_balance(&client, &mint, &owner)?)?)
+    })
+}
+
+/// Every funded token account of the mint, largest first.
+///
+/// # Safety
+/// `rpc_url` and `mint` must be NUL-terminated strings and `out` valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn token_ops_snapshot(rpc_url: *const c_char, mint: *const c_char, out: *mut *mut c_char) -> i32 {
+    respond(out, || {
+        let client = client(string_arg(rpc_url, "rpc_url")?);
+        let mint = pubkey_arg(string_arg(mint, "mint")?, "mint")?;
+        Ok(to_json(&rpc::fetch_holder_snapshot(&client, &mint)?)?)
+    })
+}
+
+/// Release a string returned through an `out` parameter. Null is ignored.
+///
+/// # Safety
+/// `value` must be null or a pointer returned by this library, freed only once.
+#[no_mangle]
+pub unsafe extern "C" fn token_ops_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
@@ -0,0 +1,109 @@
+//! Python bindings over the same core logic the `token-ops` CLI uses.
+//!
+//! ```python
+//! import token_ops
+//!
+//! ops = token_ops.TokenOps("https://api.devnet.solana.com")
+//! ops.verify(mint)                     # dict: program, supply, authorities, extensions
+//! ops.balance(mint, owner)             # dict: account, amount, ui_amount
+//! ops.snapshot(mint)                   # dict: slot, supply, holders
+//! ops.build_transfer(mint, owner, recipient, "12.5")  # base64 unsigned transaction
+//! ```
+//!
+//! Build with `maturin develop` from this directory.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use pyo3::{exceptions::PyRuntimeError, exceptions::PyValueError, prelude::*};
+use pythonize::pythonize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, transaction::Transaction};
+use std::str::FromStr;
+use token_ops_core::{amount, instructions::transfer_to_wallet, rpc};
+
+fn runtime_error(e: impl std::fmt::Display) -> PyErr {
+    PyRuntimeError::new_err(e.to_string())
+}
+
+fn pubkey(value: &str, name: &str) -> PyResult<Pubkey> {
+    Pubkey::from_str(value).map_err(|e| PyValueError::new_err(format!("Invalid {} '{}': {}", name, value, e)))
+}
+
+/// Read-only token operations against one RPC endpoint.
+#[pyclass]
+struct TokenOps {
+    client: RpcClient,
+}
+
+#[pymethods]
+impl TokenOps {
+    #[new]
+    #[pyo3(signature = (rpc_url, commitment = "confirmed"))]
+    fn new(rpc_url: &str, commitment: &str) -> PyResult<Self> {
+        let commitment = CommitmentConfig::from_str(commitment)
+            .map_err(|_| PyValueError::new_err(format!("Invalid commitment '{}'", commitment)))?;
+        Ok(Self { client: RpcClient::new_with_commitment(rpc_url.to_string(), commitment) })
+    }
+
+    /// Mint program, supply, decimals, authorities and extensions, as the CLI's `verify` reports them.
+    fn verify(&self, py: Python<'_>, mint: &str) -> PyResult<PyObject> {
+        let summary = rpc::fetch_mint_info(&self.client, &pubkey(mint, "mint")?)
+            .and_then(|mint_info| mint_info.summary())
+            .map_err(runtime_error)?;
+        pythonize(py, &summary).map(Bound::unbind).map_err(runtime_error)
+    }
+
+    /// Balance of `owner`'s associated token account; zero when it does not exist.
+    fn balance(&self, py: Python<'_>, mint: &str, owner: &str) -> PyResult<PyObject> {
+        let balance = rpc::fetch_balance(&self.client, &pubkey(mint, "mint")?, &pubkey(owner, "owner")?)
+            .map_err(runtime_error)?;
+        pythonize(py, &balance).map(Bound::unbind).map_err(runtime_error)
+    }
+
+    /// Every funded token account of the mint, largest first.
+    fn snapshot(&self, py: Python<'_>, mint: &str) -> PyResult<PyObject> {
+        let snapshot = rpc::fetch_holder_snapshot(&self.client, &pubkey(mint, "mint")?).map_err(runtime_error)?;
+        pythonize(py, &snapshot).map(Bound::unbind).map_err(runtime_error)
+    }
+
+    /// An unsigned transaction sending `amount` whole tokens from `owner`'s
+    /// associated account to `recipient`'s (created if needed), with a fresh
+    /// blockhash, as base64 for signing elsewhere. A memo is added when given
+    /// or when the recipient's account requires one.
+    #[pyo3(signature = (mint, owner, recipient, amount, memo = None))]
+    fn build_transfer(&self, mint: &str, owner: &str, recipient: &str, amount: &str, memo: Option<&str>) -> PyResult<String> {
+        let mint_info = rpc::fetch_mint_info(&self.client, &pubkey(mint, "mint")?).map_err(runtime_error)?;
+        let decimals = mint_info.base().map_err(runtime_error)?.decimals;
+        let owner = pubkey(owner, "owner")?;
+        let raw = amount::parse_amount(amount, decimals).map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let recipient = pubkey(recipient, "recipient")?;
+        let memo = rpc::transfer_memo(&self.client, &mint_info, &recipient, memo).map_err(runtime_error)?;
+        let instructions = transfer_to_wallet(&mint_info, &owner, &recipient, raw, decimals, memo)
+            .map_err(runtime_error)?;
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&owner));
+        transaction.message.recent_blockhash = self.client.get_latest_blockhash().map_err(runtime_error)?;
+
+        let bytes = bincode::serialize(&transaction).map_err(runtime_error)?;
+        Ok(STANDARD.encode(bytes))
+    }
+}
+
+/// Render a raw amount with the mint's decimals.
+#[pyfunction]
+fn format_amount(amount: u64, decimals: u8) -> String {
+    amount::format_amount(amount, decimals)
+}
+
+/// Parse a whole-token amount such as "12.5" into raw units.
+#[pyfunction]
+fn parse_amount(value: &str, decimals: u8) -> PyResult<u64> {
+    amount::parse_amount(value, decimals).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+#[pymodule]
+fn token_ops(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<TokenOps>()?;
+    module.add_function(wrap_pyfunction!(format_amount, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_amount, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_sdk::{
+    program_option::COption,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use spl_token_2022::{
+    extension::ExtensionType,
+    instruction::{initialize_account3, initialize_immutable_owner},
+};
+use std::str::FromStr;
+use token_ops_core::tlv::token_account_extensions;
+
+use crate::{
+    output::{write_output, OutputFormat},
+    token_program::token_account_len,
+    TokenOperations,
+};
+
+/// Estimated compute units for CreateAccount, InitializeImmutableOwner and InitializeAccount3.
+const CREATE_ACCOUNT_UNITS: u32 = 20_000;
+
+/// Security-relevant settings of one of the wallet's token accounts.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountAudit {
+    pub token_account: String,
+    pub mint: String,
+    pub program: String,
+    pub associated: bool,
+    pub immutable_owner: bool,
+    pub delegate: Option<String>,
+    pub close_authority: Option<String>,
+    pub issues: String,
+}
+
+impl TokenOperations {
+    /// Create a token account at a fresh address (not an ATA) for `owner`,
+    /// defaulting to the wallet. With `immutable_owner` the Token-2022
+    /// ImmutableOwner extension is added, so the owner can never be
+    /// reassigned.
+    pub fn create_token_account(&self, mint_address: &str, owner: Option<&str>, immutable_owner: bool) -> Result<()> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let owner = match owner {
+            Some(owner) => Pubkey::from_str(owner).map_err(|e| anyhow!("Invalid owner address: {}", e))?,
+            None => self.wallet.pubkey(),
+        };
+        let mint_info = self.fetch_mint_info(&mint)?;
+        if immutable_owner && !mint_info.is_token_2022() {
+            return Err(anyhow!("--immutable-owner requires a Token-2022 mint; SPL Token accounts cannot have it"));
+        }
+
+        let account = Keypair::new();
+        let space = token_account_len(&mint_info, immutable_owner)?;
+        let rent = self.client.get_minimum_balance_for_rent_exemption(space)
+            .map_err(|e| anyhow!("Failed to get rent exemption: {}", e))?;
+
+        let mut instructions = vec![system_instruction::create_account(
+            &self.wallet.pubkey(),
+            &account.pubkey(),
+            rent,
+            space as u64,
+            &mint_info.program_id,
+        )];
+        if immutable_owner {
+            instructions.push(initialize_immutable_owner(&mint_info.program_id, &account.pubkey())?);
+        }
+        instructions.push(initialize_account3(&mint_info.program_id, &account.pubkey(), &mint, &owner)?);
+
+        let signature = self
+            .send_instructions_with_signers(instructions, CREATE_ACCOUNT_UNITS, &[&account])
+            .map_err(|e| anyhow!("Failed to create token account: {}", e))?;
+        info!(
+            "Token account created! Address: {} (owner {}{})",
+            account.pubkey(),
+            owner,
+            if immutable_owner { ", immutable" } else { "" }
+        );
+        info!("Signature: {}", signature);
+        Ok(())
+    }
+
+    /// Flag the wallet's token accounts whose owner can be reassigned (no
+    /// ImmutableOwner), or that grant a delegate or a foreign close authority.
+    pub fn audit_accounts(&self, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let wallet = self.wallet.pubkey();
+        let immutable_owner_type = ExtensionType::ImmutableOwner as u16;
+        let mut audits = Vec::new();
+
+        for owned in self.owned_token_accounts(&wallet, None)? {
+            let account = &owned.account;
+            let is_token_2022 = owned.program_id == spl_token_2022::id();
+            let immutable_owner = is_token_2022
+                && token_account_extensions(&owned.data)?
+                    .iter()
+                    .any(|(extension, _)| *extension == immutable_owner_type);
+            let associated = owned.address
+                == spl_associated_token_account::get_associated_token_address_with_program_id(
+                    &wallet,
+                    &account.mint,
+                    &owned.program_id,
+                );
+
+            let mut issues = Vec::new();
+            if !immutable_owner {
+                issues.push(if is_token_2022 {
+                    "owner can be reassigned (no ImmutableOwner)"
+                } else {
+                    "owner can be reassigned (SPL Token accounts cannot be immutable)"
+                });
+            }
+            let delegate = Option::<Pubkey>::from(account.delegate);
+            if delegate.is_some() {
+                issues.push("delegate can move tokens");
+            }
+            let close_authority = match account.close_authority {
+                COption::Some(authority) if authority != wallet => Some(authority),
+                _ => None,
+            };
+            if close_authority.is_some() {
+                issues.push("another key can close the account");
+            }
+
+            audits.push(AccountAudit {
+                token_account: owned.address.to_string(),
+                mint: account.mint.to_string(),
+                program: if is_token_2022 { "Token-2022" } else { "SPL Token" }.to_string(),
+                associated,
+                immutable_owner,
+                delegate: delegate.map(|key| key.to_string()),
+                close_authority: close_authority.map(|key| key.to_string()),
+                issues: issues.join("; "),
+            });
+        }
+
+        let flagged = audits.iter().filter(|audit| !audit.issues.is_empty()).count();
+        info!("Audited {} token account(s) of {}; {} flagged", audits.len(), wallet, flagged);
+        for audit in audits.iter().filter(|audit| !audit.issues.is_empty()) {
+            warn!("  {} ({}): {}", audit.token_account, audit.mint, audit.issues);
+        }
+
+        write_output(output, &audits, &audits)
+    }
+}
@@ -0,0 +1,610 @@
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use serde::Serialize;
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::lamports_to_sol,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    system_program,
+};
+use spl_token_2022::{extension::StateWithExtensions, state::Mint};
+use std::{collections::HashMap, str::FromStr};
+use token_ops_core::instructions::transfer_to_wallet;
+
+use crate::{
+    fees::LAMPORTS_PER_SIGNATURE,
+    holders::{format_amount, parse_amount},
+    idempotency::{unix_now, SETTLE_SECONDS},
+    input::read_input,
+    ledger::AirdropLedger,
+    output::{write_output, OutputFormat},
+    prompt::confirm,
+    token_program::{is_token_program, token_account_len, unpack_token_account, MintInfo},
+    TokenOperations,
+};
+
+/// Accounts fetched per getMultipleAccounts call.
+const FETCH_CHUNK: usize = 100;
+
+/// Recipients sent per batcher run; the ledger is updated after each run so
+/// an interrupted airdrop loses at most this many records.
+const SEND_CHUNK: usize = 25;
+
+/// Estimated compute units for an idempotent ATA creation.
+const CREATE_ATA_UNITS: u32 = 40_000;
+
+/// Estimated compute units for a TransferChecked instruction.
+const TRANSFER_UNITS: u32 = 20_000;
+
+/// One line of a recipient file.
+#[derive(Debug, Clone)]
+pub struct Recipient {
+    pub line: usize,
+    pub wallet: Pubkey,
+    pub amount: u64,
+}
+
+/// A problem found in a recipient file. Errors block the distribution;
+/// warnings are worth a look but do not.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecipientIssue {
+    pub line: usize,
+    pub wallet: String,
+    pub severity: String,
+    pub problem: String,
+}
+
+impl RecipientIssue {
+    fn error(line: usize, wallet: &str, problem: String) -> Self {
+        Self { line, wallet: wallet.to_string(), severity: "error".to_string(), problem }
+    }
+
+    fn warning(line: usize, wallet: &str, problem: String) -> Self {
+        Self { line, wallet: wallet.to_string(), severity: "warning".to_string(), problem }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == "error"
+    }
+}
+
+/// Per-recipient outcome of an airdrop.
+#[derive(Debug, Clone, Serialize)]
+pub struct AirdropResult {
+    pub wallet: String,
+    pub token_account: String,
+    pub amount: String,
+    pub status: String,
+    pub signature: Option<String>,
+}
+
+/// An earlier attempt to pay a recipient that a rerun must not repeat.
+enum AttemptStatus {
+    Landed(Signature),
+    /// Not seen on chain yet, with the seconds until its blockhash expires.
+    Unsettled(Signature, i64),
+}
+
+impl AttemptStatus {
+    fn signature(&self) -> &Signature {
+        match self {
+            AttemptStatus::Landed(signature) | AttemptStatus::Unsettled(signature, _) => signature,
+        }
+    }
+}
+
+/// What an airdrop would cost, from `airdrop --estimate`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AirdropEstimate {
+    pub mint: String,
+    pub recipients: usize,
+    pub already_paid: usize,
+    pub amount: String,
+    pub token_balance: String,
+    pub transactions: u64,
+    pub atas_to_create: u64,
+    pub rent_sol: f64,
+    pub base_fee_sol: f64,
+    pub priority_fee_sol: f64,
+    pub total_sol: f64,
+    pub wallet_sol: f64,
+    pub sufficient_sol: bool,
+    pub sufficient_tokens: bool,
+}
+
+/// A checked recipient file: recipients still to pay (with their index in
+/// `results`) and one result row per recipient.
+struct PreparedAirdrop {
+    mint_info: MintInfo,
+    decimals: u8,
+    pending: Vec<(Recipient, usize)>,
+    results: Vec<AirdropResult>,
+    total: u64,
+    available: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecipientValidation {
+    pub mint: String,
+    pub recipients_file: String,
+    pub recipients: usize,
+    pub total: String,
+    pub available: String,
+    pub errors: usize,
+    pub warnings: usize,
+    pub issues: Vec<RecipientIssue>,
+}
+
+/// Parse a `wallet,amount` recipient file, amounts in whole tokens.
+///
+/// Blank lines, `#` comments and a leading header row are skipped. Lines that
+/// cannot be parsed are reported as issues rather than failing the read.
+pub fn read_recipients(path: &str, decimals: u8) -> Result<(Vec<Recipient>, Vec<RecipientIssue>)> {
+    let contents = read_input(path)?;
+
+    let mut recipients = Vec::new();
+    let mut issues = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split(',').map(str::trim);
+        let wallet = fields.next().unwrap_or("");
+        let amount = fields.next().unwrap_or("");
+
+        let wallet_pubkey = match Pubkey::from_str(wallet) {
+            Ok(pubkey) => pubkey,
+            Err(_) if recipients.is_empty() && issues.is_empty() && amount.parse::<f64>().is_err() => continue,
+            Err(e) => {
+                issues.push(RecipientIssue::error(line_number, wallet, format!("malformed address: {}", e)));
+                continue;
+            }
+        };
+
+        match parse_amount(amount, decimals) {
+            Ok(0) => issues.push(RecipientIssue::error(line_number, wallet, "amount is zero".to_string())),
+            Ok(amount) => recipients.push(Recipient { line: line_number, wallet: wallet_pubkey, amount }),
+            Err(e) if amount.is_empty() => {
+                issues.push(RecipientIssue::error(line_number, wallet, format!("missing amount: {}", e)))
+            }
+            Err(e) => issues.push(RecipientIssue::error(line_number, wallet, format!("invalid amount: {}", e))),
+        }
+    }
+
+    Ok((recipients, issues))
+}
+
+impl TokenOperations {
+    /// Check recipients for duplicates and for addresses that are not wallets.
+    pub fn check_recipients(&self, mint_info: &MintInfo, recipients: &[Recipient]) -> Result<Vec<RecipientIssue>> {
+        let mut issues = Vec::new();
+
+        let mut first_line: HashMap<Pubkey, usize> = HashMap::new();
+        for recipient in recipients {
+            match first_line.get(&recipient.wallet) {
+                Some(first) => issues.push(RecipientIssue::error(
+                    recipient.line,
+                    &recipient.wallet.to_string(),
+                    format!("duplicate of line {}", first),
+                )),
+                None => {
+                    first_line.insert(recipient.wallet, recipient.line);
+                }
+            }
+        }
+
+        for chunk in recipients.chunks(FETCH_CHUNK) {
+            let wallets: Vec<Pubkey> = chunk.iter().map(|recipient| recipient.wallet).collect();
+            let accounts = self.client.get_multiple_accounts(&wallets)
+                .map_err(|e| anyhow!("Failed to get recipient accounts: {}", e))?;
+
+            for (recipient, account) in chunk.iter().zip(accounts) {
+                let wallet = recipient.wallet.to_string();
+                let issue = match account {
+                    Some(account) if account.executable => {
+                        Some(RecipientIssue::error(recipient.line, &wallet, "address is a program".to_string()))
+                    }
+                    Some(account) if is_token_program(&account.owner) => {
+                        if recipient.wallet == mint_info.address {
+                            Some(RecipientIssue::error(recipient.line, &wallet, "address is the mint being sent".to_string()))
+                        } else if StateWithExtensions::<Mint>::unpack(&account.data).is_ok() {
+                            Some(RecipientIssue::error(recipient.line, &wallet, "address is a mint".to_string()))
+                        } else if unpack_token_account(&account.data).is_ok() {
+                            Some(RecipientIssue::error(
+                                recipient.line,
+                                &wallet,
+                                "address is a token account; its ATA would be unrecoverable".to_string(),
+                            ))
+                        } else {
+                            Some(RecipientIssue::error(recipient.line, &wallet, "address is owned by a token program".to_string()))
+                        }
+                    }
+                    Some(account) if account.owner != system_program::id() => Some(RecipientIssue::warning(
+                        recipient.line,
+                        &wallet,
+                        format!("address is a data account owned by program {}", account.owner),
+                    )),
+                    _ if !recipient.wallet.is_on_curve() => Some(RecipientIssue::warning(
+                        recipient.line,
+                        &wallet,
+                        "address is off-curve (a PDA); only its program can move the tokens".to_string(),
+                    )),
+                    _ => None,
+                };
+                issues.extend(issue);
+            }
+        }
+
+        issues.sort_by_key(|issue| issue.line);
+        Ok(issues)
+    }
+
+    /// Balance of the mint in the loaded wallet's ATA, the source of airdrops.
+    pub fn airdrop_source_balance(&self, mint_info: &MintInfo) -> Result<u64> {
+        let source = mint_info.associated_token_address(&self.wallet.pubkey());
+        let account = self.client
+            .get_account_with_commitment(&source, self.client.commitment())
+            .map_err(|e| anyhow!("Failed to get source token account: {}", e))?
+            .value;
+        match account {
+            Some(account) => Ok(unpack_token_account(&account.data)?.amount),
+            None => Ok(0),
+        }
+    }
+
+    /// Validate a recipient file against the mint and the wallet's balance.
+    /// Fails when any error-level issue is found.
+    pub fn validate_airdrop(
+        &self,
+        mint_address: &str,
+        recipients_file: &str,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+        let decimals = mint_info.base()?.decimals;
+
+        let (recipients, mut issues) = read_recipients(recipients_file, decimals)?;
+        issues.extend(self.check_recipients(&mint_info, &recipients)?);
+        issues.sort_by_key(|issue| issue.line);
+
+        let total = recipients
+            .iter()
+            .try_fold(0u64, |total, recipient| total.checked_add(recipient.amount))
+            .ok_or_else(|| anyhow!("Total amount overflows u64"))?;
+        let available = self.airdrop_source_balance(&mint_info)?;
+        if total > available {
+            issues.push(RecipientIssue::error(
+                0,
+                "",
+                format!(
+                    "total {} exceeds the wallet's balance of {}",
+                    format_amount(total, decimals),
+                    format_amount(available, decimals)
+                ),
+            ));
+        }
+
+        let report = RecipientValidation {
+            mint: mint_pubkey.to_string(),
+            recipients_file: recipients_file.to_string(),
+            recipients: recipients.len(),
+            total: format_amount(total, decimals),
+            available: format_amount(available, decimals),
+            errors: issues.iter().filter(|issue| issue.is_error()).count(),
+            warnings: issues.iter().filter(|issue| !issue.is_error()).count(),
+            issues,
+        };
+
+        info!("Validated {} recipient(s) from {}", report.recipients, recipients_file);
+        info!("  Total:     {}", report.total);
+        info!("  Available: {}", report.available);
+        for issue in &report.issues {
+            let location = if issue.line == 0 { "total".to_string() } else { format!("line {}", issue.line) };
+            if issue.is_error() {
+                error!("  {} {}: {}", location, issue.wallet, issue.problem);
+            } else {
+                warn!("  {} {}: {}", location, issue.wallet, issue.problem);
+            }
+        }
+        info!("{} error(s), {} warning(s)", report.errors, report.warnings);
+
+        write_output(output, &report, &report.issues)?;
+
+        if report.errors > 0 {
+            return Err(anyhow!("Recipient file has {} error(s)", report.errors));
+        }
+        Ok(())
+    }
+
+    /// Parse and check the recipient file, and split recipients into those
+    /// still to be paid in `campaign` and those the ledger already has.
+    fn prepare_airdrop(
+        &self,
+        mint_address: &str,
+        recipients_file: &str,
+        campaign: &str,
+        ledger: &AirdropLedger,
+    ) -> Result<PreparedAirdrop> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+        let decimals = mint_info.base()?.decimals;
+
+        let (recipients, mut issues) = read_recipients(recipients_file, decimals)?;
+        issues.extend(self.check_recipients(&mint_info, &recipients)?);
+        let errors = issues.iter().filter(|issue| issue.is_error()).count();
+        if errors > 0 {
+            return Err(anyhow!(
+                "Recipient file has {} error(s); run validate-airdrop for details",
+                errors
+            ));
+        }
+
+        if let Some(mint) = ledger.campaign_mint(campaign)? {
+            if mint != mint_pubkey.to_string() {
+                return Err(anyhow!("Campaign '{}' already paid out mint {}", campaign, mint));
+            }
+        }
+
+        let mut results = Vec::with_capacity(recipients.len());
+        let mut pending = Vec::new();
+        for recipient in recipients {
+            let mut result = AirdropResult {
+                wallet: recipient.wallet.to_string(),
+                token_account: mint_info.associated_token_address(&recipient.wallet).to_string(),
+                amount: format_amount(recipient.amount, decimals),
+                status: "pending".to_string(),
+                signature: None,
+            };
+            if let Some(signature) = ledger.sent(campaign, &recipient.wallet)? {
+                result.status = "skipped: already sent in this campaign".to_string();
+                result.signature = Some(signature);
+            } else if let Some(attempt) = self.resolve_attempt(ledger, campaign, &mint_info, &recipient)? {
+                result.status = match attempt {
+                    AttemptStatus::Landed(_) => "skipped: already sent in this campaign".to_string(),
+                    AttemptStatus::Unsettled(_, wait) => format!("skipped: earlier send may still land; rerun in {}s", wait),
+                };
+                result.signature = Some(attempt.signature().to_string());
+            } else {
+                pending.push((recipient, results.len()));
+            }
+            results.push(result);
+        }
+
+        let total = pending
+            .iter()
+            .try_fold(0u64, |total, (recipient, _)| total.checked_add(recipient.amount))
+            .ok_or_else(|| anyhow!("Total amount overflows u64"))?;
+        let available = self.airdrop_source_balance(&mint_info)?;
+
+        Ok(PreparedAirdrop { mint_info, decimals, pending, results, total, available })
+    }
+
+    /// Settle an earlier attempt to pay `recipient` from its status on
+    /// chain, like an idempotency key: a landed send is moved to the sends,
+    /// and one that failed or whose blockhash has expired is forgotten so
+    /// the recipient can be paid again. `None` means nothing is in flight.
+    fn resolve_attempt(
+        &self,
+        ledger: &AirdropLedger,
+        campaign: &str,
+        mint_info: &MintInfo,
+        recipient: &Recipient,
+    ) -> Result<Option<AttemptStatus>> {
+        let Some(attempt) = ledger.attempt(campaign, &recipient.wallet)? else {
+            return Ok(None);
+        };
+        let signature = Signature::from_str(&attempt.signature)
+            .map_err(|e| anyhow!("Invalid signature in airdrop ledger: {}", e))?;
+        let status = self.client.get_signature_statuses_with_history(&[signature])
+            .map_err(|e| anyhow!("Failed to get status of {}: {}", signature, e))?
+            .value
+            .into_iter()
+            .next()
+            .flatten();
+
+        match status {
+            Some(status) if status.err.is_some() => {
+                warn!("Earlier send to {} failed on chain; sending again", recipient.wallet);
+            }
+            Some(status) if status.satisfies_commitment(self.client.commitment()) => {
+                info!("Earlier send to {} landed in {}", recipient.wallet, signature);
+                ledger.record(campaign, &recipient.wallet, &mint_info.address, attempt.amount, &signature)?;
+                return Ok(Some(AttemptStatus::Landed(signature)));
+            }
+            _ => {
+                let elapsed = unix_now() - attempt.attempted_at;
+                if elapsed < SETTLE_SECONDS {
+                    return Ok(Some(AttemptStatus::Unsettled(signature, SETTLE_SECONDS - elapsed)));
+                }
+                warn!("Earlier send to {} never landed; sending again", recipient.wallet);
+            }
+        }
+        ledger.clear_attempt(campaign, &recipient.wallet)?;
+        Ok(None)
+    }
+
+    /// Transfer tokens from the wallet's ATA to every recipient in the file,
+    /// creating ATAs as needed. Sends are recorded in the ledger under
+    /// `campaign`, and recipients already paid in that campaign are skipped.
+    /// Every transaction's signature is recorded before it is submitted, so
+    /// a rerun checks the chain before paying those recipients again.
+    pub fn airdrop(
+        &self,
+        mint_address: &str,
+        recipients_file: &str,
+        campaign: &str,
+        ledger_path: &str,
+        assume_yes: bool,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let ledger = AirdropLedger::open(ledger_path)?;
+        let PreparedAirdrop { mint_info, decimals, pending, mut results, total, available } =
+            self.prepare_airdrop(mint_address, recipients_file, campaign, &ledger)?;
+
+        if total > available {
+            return Err(anyhow!(
+                "Airdrop needs {} but the wallet holds {}",
+                format_amount(total, decimals),
+                format_amount(available, decimals)
+            ));
+        }
+
+        confirm(
+            &[
+                format!("Airdrop {} of {}", format_amount(total, decimals), mint_info.address),
+                format!("Campaign:   {}", campaign),
+                format!("Recipients: {}", pending.len()),
+                format!("Skipped:    {} (already paid)", results.len() - pending.len()),
+            ],
+            assume_yes,
+        )?;
+
+        if self.relayer.is_some() {
+            warn!("Relayed sends are only recorded once they confirm; an interrupted run may pay a recipient twice");
+        }
+
+        for chunk in pending.chunks(SEND_CHUNK) {
+            // Batcher items are numbered in push order, one per recipient of the chunk.
+            let record_attempt = |items: &[usize], signature: &Signature| -> Result<()> {
+                for (recipient, _) in items.iter().filter_map(|item| chunk.get(*item)) {
+                    ledger.record_attempt(campaign, &recipient.wallet, &mint_info.address, recipient.amount, signature)?;
+                }
+                Ok(())
+            };
+            let mut batcher = self.batcher().with_before_send(Some(&record_attempt));
+            for (recipient, _) in chunk {
+                let instructions = self.airdrop_instructions(&mint_info, decimals, recipient)?;
+                batcher.push(instructions, CREATE_ATA_UNITS + TRANSFER_UNITS)?;
+            }
+
+            for batch in batcher.finish() {
+                for item in &batch.items {
+                    let Some((recipient, result_index)) = chunk.get(*item) else {
+                        continue;
+                    };
+                    let result = &mut results[*result_index];
+                    match &batch.signature {
+                        Ok(signature) => {
+                            ledger.record(campaign, &recipient.wallet, &mint_info.address, recipient.amount, signature)?;
+                            result.status = "sent".to_string();
+                            result.signature = Some(signature.to_string());
+                        }
+                        Err(e) => result.status = format!("failed: {}", e),
+                    }
+                }
+            }
+        }
+
+        let sent = results.iter().filter(|result| result.status == "sent").count();
+        let failed = results.iter().filter(|result| result.status.starts_with("failed")).count();
+        for result in results.iter().filter(|result| result.status.starts_with("failed")) {
+            warn!("  {}: {}", result.wallet, result.status);
+        }
+        info!(
+            "Airdrop complete: {} sent, {} skipped, {} failed",
+            sent,
+            results.len() - sent - failed,
+            failed
+        );
+
+        write_output(output, &results, &results)?;
+
+        if failed > 0 {
+            return Err(anyhow!("{} recipient(s) were not paid; rerun to retry them", failed));
+        }
+        Ok(())
+    }
+
+    /// Report the transactions, ATA creations and SOL an airdrop would need,
+    /// without sending anything.
+    pub fn estimate_airdrop(
+        &self,
+        mint_address: &str,
+        recipients_file: &str,
+        campaign: &str,
+        ledger_path: &str,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let ledger = AirdropLedger::open(ledger_path)?;
+        let PreparedAirdrop { mint_info, decimals, pending, results, total, available } =
+            self.prepare_airdrop(mint_address, recipients_file, campaign, &ledger)?;
+
+        let mut batcher = self.batcher().with_dry_run(true);
+        for (recipient, _) in &pending {
+            batcher.push(self.airdrop_instructions(&mint_info, decimals, recipient)?, CREATE_ATA_UNITS + TRANSFER_UNITS)?;
+        }
+        let transactions = batcher.finish().len() as u64;
+
+        let destinations: Vec<Pubkey> = pending
+            .iter()
+            .map(|(recipient, _)| mint_info.associated_token_address(&recipient.wallet))
+            .collect();
+        let mut atas_to_create = 0u64;
+        for chunk in destinations.chunks(FETCH_CHUNK) {
+            let accounts = self.client.get_multiple_accounts(chunk)
+                .map_err(|e| anyhow!("Failed to get recipient token accounts: {}", e))?;
+            atas_to_create += accounts.iter().filter(|account| account.is_none()).count() as u64;
+        }
+
+        let ata_rent = self.client.get_minimum_balance_for_rent_exemption(token_account_len(&mint_info, true)?)
+            .map_err(|e| anyhow!("Failed to get rent exemption: {}", e))?;
+        let rent_lamports = ata_rent * atas_to_create;
+        let base_fee_lamports = LAMPORTS_PER_SIGNATURE * transactions;
+        // An automatic fee is estimated at its cap.
+        let priority_fee_lamports = self
+            .compute_unit_price
+            .or(self.auto_priority_fee.map(|auto| auto.max))
+            .map(|price| (price as u128 * self.compute_unit_limit as u128).div_ceil(1_000_000) as u64 * transactions)
+            .unwrap_or(0);
+        let total_lamports = rent_lamports + base_fee_lamports + priority_fee_lamports;
+        let wallet_lamports = self.client.get_balance(&self.wallet.pubkey())
+            .map_err(|e| anyhow!("Failed to get wallet balance: {}", e))?;
+
+        let estimate = AirdropEstimate {
+            mint: mint_info.address.to_string(),
+            recipients: pending.len(),
+            already_paid: results.len() - pending.len(),
+            amount: format_amount(total, decimals),
+            token_balance: format_amount(available, decimals),
+            transactions,
+            atas_to_create,
+            rent_sol: lamports_to_sol(rent_lamports),
+            base_fee_sol: lamports_to_sol(base_fee_lamports),
+            priority_fee_sol: lamports_to_sol(priority_fee_lamports),
+            total_sol: lamports_to_sol(total_lamports),
+            wallet_sol: lamports_to_sol(wallet_lamports),
+            sufficient_sol: wallet_lamports >= total_lamports,
+            sufficient_tokens: available >= total,
+        };
+
+        info!("Airdrop estimate (nothing sent):");
+        info!("  Recipients:     {} ({} already paid)", estimate.recipients, estimate.already_paid);
+        info!("  Amount:         {} (wallet holds {})", estimate.amount, estimate.token_balance);
+        info!("  Transactions:   {}", estimate.transactions);
+        info!("  ATAs to create: {}", estimate.atas_to_create);
+        info!("  Rent:           {} SOL", estimate.rent_sol);
+        info!("  Base fees:      {} SOL", estimate.base_fee_sol);
+        info!("  Priority fees:  {} SOL", estimate.priority_fee_sol);
+        info!("  Total:          {} SOL (wallet holds {} SOL)", estimate.total_sol, estimate.wallet_sol);
+        if !estimate.sufficient_sol {
+            warn!("Wallet needs {} more SOL", lamports_to_sol(total_lamports - wallet_lamports));
+        }
+        if !estimate.sufficient_tokens {
+            warn!("Wallet needs {} more tokens", format_amount(total - available, decimals));
+        }
+
+        write_output(output, &estimate, std::slice::from_ref(&estimate))
+    }
+
+    /// Create the recipient's ATA if needed and transfer from the wallet's ATA.
+    fn airdrop_instructions(&self, mint_info: &MintInfo, decimals: u8, recipient: &Recipient) -> Result<Vec<Instruction>> {
+        transfer_to_wallet(mint_info, &self.wallet.pubkey(), &recipient.wallet, recipient.amount, decimals, None)
+    }
+}
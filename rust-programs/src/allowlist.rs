@@ -0,0 +1,118 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Serialize;
+use solana_sdk::{hash::Hash, pubkey::Pubkey};
+use std::str::FromStr;
+use token_ops_core::merkle::MerkleTree;
+
+use crate::{
+    holders::{format_amount, merge_by_owner, parse_amount},
+    index::Index,
+    output::{write_output, OutputFormat},
+    TokenOperations,
+};
+
+/// One allowlisted wallet.
+#[derive(Debug, Clone, Serialize)]
+pub struct AllowlistEntry {
+    pub address: String,
+    pub balance: String,
+    pub raw_amount: u64,
+    /// Base58 sibling hashes from the leaf up, when a Merkle root is computed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub proof: Vec<String>,
+}
+
+/// One allowlisted wallet as a CSV row; the address list claim tools import.
+#[derive(Debug, Clone, Serialize)]
+pub struct AllowlistRow {
+    pub address: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Allowlist {
+    pub mint: String,
+    pub slot: u64,
+    pub min_amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merkle_root: Option<String>,
+    pub entries: Vec<AllowlistEntry>,
+}
+
+impl TokenOperations {
+    /// Export the wallets holding at least `min_amount` of a mint, merged
+    /// across their token accounts and sorted by address. Only on-curve
+    /// owners are listed, since pools, escrows and other PDAs cannot claim.
+    ///
+    /// With `at_slot` the balances come from the latest indexed snapshot at
+    /// or before that slot, otherwise from a fresh snapshot. With `merkle`
+    /// the report also carries the root of a SHA-256 tree over the sorted
+    /// addresses and each entry's proof (see `token_ops_core::merkle`).
+    pub fn export_allowlist(
+        &self,
+        mint_address: &str,
+        min_amount: &str,
+        at_slot: Option<u64>,
+        index_path: &str,
+        merkle: bool,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let snapshot = match at_slot {
+            Some(slot) => Index::open(index_path)?.snapshot_at(mint_address, slot)?.ok_or_else(|| {
+                anyhow!("No snapshot of {} at or before slot {} in {}; take one with index-snapshot", mint, slot, index_path)
+            })?,
+            None => self.fetch_holder_snapshot(&mint)?,
+        };
+        let threshold = parse_amount(min_amount, snapshot.decimals)?;
+
+        let owners = merge_by_owner(&snapshot);
+        let eligible: Vec<_> = owners.iter().filter(|owner| owner.amount >= threshold).collect();
+        let mut wallets: Vec<(Pubkey, u64)> = eligible
+            .iter()
+            .filter_map(|owner| Some((Pubkey::from_str(&owner.owner).ok()?, owner.amount)))
+            .filter(|(owner, _)| owner.is_on_curve())
+            .collect();
+        wallets.sort_by_key(|(owner, _)| owner.to_string());
+
+        let tree = merkle.then(|| MerkleTree::new(&wallets.iter().map(|(owner, _)| *owner).collect::<Vec<_>>()));
+        let entries: Vec<AllowlistEntry> = wallets
+            .iter()
+            .enumerate()
+            .map(|(i, (owner, amount))| AllowlistEntry {
+                address: owner.to_string(),
+                balance: format_amount(*amount, snapshot.decimals),
+                raw_amount: *amount,
+                proof: tree
+                    .as_ref()
+                    .map(|tree| tree.proof(i).into_iter().map(|hash| Hash::new_from_array(hash).to_string()).collect())
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        info!(
+            "Allowlist for {} at slot {}: {} wallet(s) hold at least {}",
+            mint,
+            snapshot.slot,
+            entries.len(),
+            format_amount(threshold, snapshot.decimals)
+        );
+        if eligible.len() > entries.len() {
+            info!("  Skipped {} program-owned holder(s)", eligible.len() - entries.len());
+        }
+        let merkle_root = tree.map(|tree| Hash::new_from_array(tree.root()).to_string());
+        if let Some(root) = &merkle_root {
+            info!("  Merkle root: {}", root);
+        }
+
+        let rows: Vec<AllowlistRow> = entries.iter().map(|entry| AllowlistRow { address: entry.address.clone() }).collect();
+        let allowlist = Allowlist {
+            mint: mint.to_string(),
+            slot: snapshot.slot,
+            min_amount: format_amount(threshold, snapshot.decimals),
+            merkle_root,
+            entries,
+        };
+        write_output(output, &allowlist, &rows)
+    }
+}
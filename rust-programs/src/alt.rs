@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use solana_sdk::{
+    address_lookup_table::{
+        instruction::{close_lookup_table, create_lookup_table, deactivate_lookup_table, extend_lookup_table},
+        state::AddressLookupTable,
+    },
+    address_lookup_table_account::AddressLookupTableAccount,
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::Signer,
+    system_program,
+};
+use std::str::FromStr;
+
+use crate::{batcher::TxBatcher, input::read_addresses, outputs, prompt::confirm_typed, TokenOperations};
+
+/// Addresses appended per ExtendLookupTable instruction; keeps each
+/// extension comfortably inside a single transaction.
+const EXTEND_CHUNK_SIZE: usize = 20;
+
+/// Compute units budgeted for one lookup table instruction.
+const LOOKUP_TABLE_UNITS: u32 = 10_000;
+
+impl TokenOperations {
+    /// Fetch lookup tables so batch operations can compile v0 transactions against them.
+    pub fn load_lookup_tables(&mut self, table_addresses: &[String]) -> Result<()> {
+        for address in table_addresses {
+            let key = Pubkey::from_str(address)
+                .map_err(|e| anyhow!("Invalid lookup table address: {}", e))?;
+            let account = self.client.get_account(&key)
+                .map_err(|e| anyhow!("Failed to get lookup table {}: {}", key, e))?;
+            let table = AddressLookupTable::deserialize(&account.data)
+                .map_err(|e| anyhow!("Failed to parse lookup table {}: {}", key, e))?;
+
+            info!("Loaded lookup table {} with {} address(es)", key, table.addresses.len());
+            self.lookup_tables.push(AddressLookupTableAccount {
+                key,
+                addresses: table.addresses.to_vec(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn alt_create(&self) -> Result<()> {
+        info!("Creating address lookup table with authority: {}", self.wallet.pubkey());
+
+        let recent_slot = self.client.get_slot_with_commitment(CommitmentConfig::finalized())
+            .map_err(|e| anyhow!("Failed to get recent slot: {}", e))?;
+
+        let (instruction, table_address) = create_lookup_table(
+            self.wallet.pubkey(),
+            self.wallet.pubkey(),
+            recent_slot,
+        );
+
+        let signature = self.send_lookup_table_instructions(vec![vec![instruction]])?;
+        info!("Lookup table created: {}", table_address);
+        outputs::record("lookup_table", &table_address.to_string());
+        info!("  Signature: {}", signature);
+
+        Ok(())
+    }
+
+    pub fn alt_extend(&self, table_address: &str, addresses_file: &str, mint_address: Option<&str>) -> Result<()> {
+        let table_pubkey = Pubkey::from_str(table_address)
+            .map_err(|e| anyhow!("Invalid lookup table address: {}", e))?;
+
+        let mut addresses = read_addresses(addresses_file)?;
+
+        // With a mint, also store the recipients' ATAs and the programs an
+        // airdrop touches so v0 transactions can reference them by index.
+        if let Some(mint_address) = mint_address {
+            let mint_pubkey = Pubkey::from_str(mint_address)
+                .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+            let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+
+            let atas: Vec<Pubkey> = addresses
+                .iter()
+                .map(|owner| mint_info.associated_token_address(owner))
+                .collect();
+            addresses.extend(atas);
+            addresses.extend([
+                mint_pubkey,
+                mint_info.program_id,
+                spl_associated_token_account::id(),
+                system_program::id(),
+            ]);
+        }
+
+        // Skip anything the table already holds.
+        let existing = self.client.get_account(&table_pubkey)
+            .map_err(|e| anyhow!("Failed to get lookup table: {}", e))?;
+        let table = AddressLookupTable::deserialize(&existing.data)
+            .map_err(|e| anyhow!("Failed to parse lookup table: {}", e))?;
+
+        let mut seen: std::collections::HashSet<Pubkey> = table.addresses.iter().copied().collect();
+        addresses.retain(|address| seen.insert(*address));
+
+        if addresses.is_empty() {
+            info!("All addresses are already present in lookup table {}", table_pubkey);
+            return Ok(());
+        }
+
+        info!("Extending lookup table {} with {} address(es)", table_pubkey, addresses.len());
+
+        let groups = addresses
+            .chunks(EXTEND_CHUNK_SIZE)
+            .map(|chunk| {
+                vec![extend_lookup_table(
+                    table_pubkey,
+                    self.wallet.pubkey(),
+                    Some(self.wallet.pubkey()),
+                    chunk.to_vec(),
+                )]
+            })
+            .collect();
+
+        self.send_lookup_table_instructions(groups)?;
+        info!("Lookup table now holds {} address(es)", seen.len());
+
+        Ok(())
+    }
+
+    pub fn alt_deactivate(&self, table_address: &str) -> Result<()> {
+        let table_pubkey = Pubkey::from_str(table_address)
+            .map_err(|e| anyhow!("Invalid lookup table address: {}", e))?;
+
+        info!("Deactivating lookup table: {}", table_pubkey);
+
+        let instruction = deactivate_lookup_table(table_pubkey, self.wallet.pubkey());
+        let signature = self.send_lookup_table_instructions(vec![vec![instruction]])?;
+
+        info!("Lookup table deactivated! Signature: {}", signature);
+        info!("It can be closed once the deactivation slot leaves the slot hashes (about 512 slots)");
+
+        Ok(())
+    }
+
+    pub fn alt_close(&self, table_address: &str, assume_yes: bool) -> Result<()> {
+        let table_pubkey = Pubkey::from_str(table_address)
+            .map_err(|e| anyhow!("Invalid lookup table address: {}", e))?;
+
+        let account = self.client.get_account(&table_pubkey)
+            .map_err(|e| anyhow!("Failed to get lookup table: {}", e))?;
+        let table = AddressLookupTable::deserialize(&account.data)
+            .map_err(|e| anyhow!("Failed to parse lookup table: {}", e))?;
+
+        if table.meta.deactivation_slot == u64::MAX {
+            return Err(anyhow!("Lookup table is still active; run alt-deactivate first"));
+        }
+
+        info!("Closing lookup table {} ({} lamports reclaimable)", table_pubkey, account.lamports);
+        confirm_typed(
+            &[
+                "CLOSE LOOKUP TABLE (irreversible)".to_string(),
+                format!("Table:     {} ({} address(es))", table_pubkey, table.addresses.len()),
+                format!("Reclaimed: {} lamports to {}", account.lamports, self.wallet.pubkey()),
+                "Transactions compiled against this table will no longer resolve.".to_string(),
+            ],
+            table_address,
+            assume_yes,
+        )?;
+
+        let instruction = close_lookup_table(table_pubkey, self.wallet.pubkey(), self.wallet.pubkey());
+        match self.send_lookup_table_instructions(vec![vec![instruction]]) {
+            Ok(signature) => {
+                info!("Lookup table closed! Signature: {}", signature);
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Close failed; the table may still be cooling down after deactivation");
+                Err(e)
+            }
+        }
+    }
+
+    /// Lookup table management always uses legacy transactions, so the
+    /// batcher is built without the tables loaded for other operations.
+    fn send_lookup_table_instructions(&self, groups: Vec<Vec<solana_sdk::instruction::Instruction>>) -> Result<String> {
+        let mut batcher = TxBatcher::new(&self.client, &self.wallet)
+            .with_compute_unit_limit(self.compute_unit_limit)
+            .with_compute_unit_price(self.compute_unit_price)
+            .with_auto_priority_fee(self.auto_priority_fee);
+
+        for group in groups {
+            batcher.push(group, LOOKUP_TABLE_UNITS)?;
+        }
+
+        let mut last_signature = None;
+        for result in batcher.finish() {
+            match result.signature {
+                Ok(signature) => last_signature = Some(signature),
+                Err(e) => return Err(anyhow!("Lookup table transaction failed: {}", e)),
+            }
+        }
+
+        last_signature
+            .map(|signature| signature.to_string())
+            .ok_or_else(|| anyhow!("No transaction was submitted"))
+    }
+}
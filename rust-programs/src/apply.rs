@@ -0,0 +1,482 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    instruction::{mint_to_checked, set_authority, AuthorityType},
+    state::Mint,
+};
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
+use std::{fs, path::Path, str::FromStr};
+use token_ops_core::tlv::{extension_name, mint_extensions};
+
+use crate::{
+    holders::{format_amount, parse_amount},
+    mint::{load_or_generate_mint_keypair, MintConfig, MintExtension},
+    output::{write_output, OutputFormat},
+    outputs,
+    prompt::confirm,
+    read_keypair, TokenOperations,
+};
+
+/// Estimated compute units for one UpdateField instruction.
+const UPDATE_FIELD_UNITS: u32 = 15_000;
+
+/// Estimated compute units for creating the wallet's token account and minting to it.
+const MINT_SUPPLY_UNITS: u32 = 40_000;
+
+/// Estimated compute units for one SetAuthority instruction.
+const SET_AUTHORITY_UNITS: u32 = 5_000;
+
+/// Desired state of a mint, read from a YAML spec file.
+///
+/// ```yaml
+/// mint_keypair: mint.json
+/// token_2022: true
+/// decimals: 6
+/// extensions: ["transfer-fee=50:1000"]
+/// metadata: { name: Example, symbol: EXM, uri: "https://example.com/exm.json" }
+/// supply: "1000000"
+/// mint_authority: none
+/// freeze_authority: wallet
+/// ```
+///
+/// Authorities are `wallet`, `none` or an address; omitted fields are left
+/// as they are. Program, decimals and extensions are fixed at creation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenSpec {
+    /// Keypair file of the mint, generated on first apply.
+    pub mint_keypair: String,
+    #[serde(default)]
+    pub token_2022: bool,
+    pub decimals: u8,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    pub metadata: Option<SpecMetadata>,
+    /// Total supply in tokens.
+    pub supply: Option<String>,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpecMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+impl TokenSpec {
+    pub fn load(path: &str) -> Result<Self> {
+        let data = fs::read_to_string(path).map_err(|e| anyhow!("Failed to read spec {}: {}", path, e))?;
+        serde_yaml::from_str(&data).map_err(|e| anyhow!("Failed to parse spec {}: {}", path, e))
+    }
+
+    fn mint_config(&self) -> Result<MintConfig> {
+        let config = MintConfig::new(self.decimals, self.token_2022, &self.extensions)?;
+        match &self.metadata {
+            Some(metadata) => config.with_metadata(&metadata.name, &metadata.symbol, &metadata.uri),
+            None => Ok(config),
+        }
+    }
+}
+
+/// The mint fields a spec manages, as they are on chain or as a newly
+/// created mint would start out.
+#[derive(Debug, Clone)]
+struct MintState {
+    exists: bool,
+    supply: u64,
+    mint_authority: Option<Pubkey>,
+    freeze_authority: Option<Pubkey>,
+    metadata: Option<TokenMetadata>,
+    data_len: usize,
+    lamports: u64,
+}
+
+/// One way the chain differs from the spec.
+#[derive(Debug, Clone, Serialize)]
+pub struct Drift {
+    pub field: String,
+    pub on_chain: String,
+    pub spec: String,
+    /// Whether `apply` can bring this field in line; fields fixed at creation
+    /// or held by another authority need manual action.
+    pub fixable: bool,
+}
+
+/// Drift between a spec and the chain, as written by `plan`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DriftReport {
+    pub spec: String,
+    /// None until the mint keypair has been generated by a first apply.
+    pub mint: Option<String>,
+    pub in_sync: bool,
+    pub drift: Vec<Drift>,
+}
+
+/// One transaction needed to bring the mint in line with its spec.
+struct Change {
+    summary: String,
+    instructions: Vec<Instruction>,
+    units: u32,
+}
+
+/// Every divergence from a spec and the transactions, in sending order,
+/// that resolve the fixable ones.
+struct TokenPlan {
+    create: Option<MintConfig>,
+    changes: Vec<Change>,
+    drift: Vec<Drift>,
+}
+
+impl TokenPlan {
+    fn drift(&mut self, field: &str, on_chain: impl ToString, spec: impl ToString, fixable: bool) {
+        self.drift.push(Drift {
+            field: field.to_string(),
+            on_chain: on_chain.to_string(),
+            spec: spec.to_string(),
+            fixable,
+        });
+    }
+}
+
+fn parse_authority(value: &str, wallet: &Pubkey, field: &str) -> Result<Option<Pubkey>> {
+    match value {
+        "wallet" => Ok(Some(*wallet)),
+        "none" => Ok(None),
+        address => Pubkey::from_str(address)
+            .map(Some)
+            .map_err(|e| anyhow!("Invalid {} '{}': {}", field, address, e)),
+    }
+}
+
+fn show(key: Option<Pubkey>) -> String {
+    key.map(|key| key.to_string()).unwrap_or_else(|| "none".to_string())
+}
+
+impl TokenOperations {
+    /// Bring a mint to the state declared in a spec file, sending only the
+    /// transactions needed. Applying an already matching spec does nothing.
+    pub fn apply_spec(&self, spec_path: &str, assume_yes: bool) -> Result<()> {
+        self.apply_token_spec(&TokenSpec::load(spec_path)?, spec_path, assume_yes)
+    }
+
+    /// Apply an in-memory spec; `spec_path` only labels it in messages.
+    pub fn apply_token_spec(&self, spec: &TokenSpec, spec_path: &str, assume_yes: bool) -> Result<()> {
+        let mint_keypair = load_or_generate_mint_keypair(&spec.mint_keypair, None, false)?;
+        let mint = mint_keypair.pubkey();
+        let plan = self.plan_spec(spec, &mint)?;
+
+        let blocked: Vec<String> = plan
+            .drift
+            .iter()
+            .filter(|drift| !drift.fixable)
+            .map(|drift| format!("{}: on chain {}, spec {}", drift.field, drift.on_chain, drift.spec))
+            .collect();
+        if !blocked.is_empty() {
+            return Err(anyhow!("{} cannot be brought in line with {}: {}", mint, spec_path, blocked.join("; ")));
+        }
+        if plan.create.is_none() && plan.changes.is_empty() {
+            info!("Mint {} already matches {}; nothing to do", mint, spec_path);
+            return Ok(());
+        }
+
+        let mut summary = vec![format!("Apply {} to mint {}:", spec_path, mint)];
+        if plan.create.is_some() {
+            summary.push("- create mint".to_string());
+        }
+        summary.extend(plan.changes.iter().map(|change| format!("- {}", change.summary)));
+        confirm(&summary, assume_yes)?;
+
+        if let Some(config) = &plan.create {
+            let signature = self.create_mint_account(&mint_keypair, config)?;
+            info!("Mint created! Address: {}", mint);
+            info!("Signature: {}", signature);
+        }
+        outputs::record("mint", &mint.to_string());
+
+        for change in plan.changes {
+            let signature = self
+                .send_instructions(change.instructions, change.units)
+                .map_err(|e| anyhow!("Failed to {}: {}", change.summary, e))?;
+            info!("{}: {}", change.summary, signature);
+        }
+
+        info!("Mint {} now matches {}", mint, spec_path);
+        Ok(())
+    }
+
+    /// Report every divergence between a spec and the chain without sending
+    /// anything. Fails when there is any drift, so it can gate a release.
+    pub fn plan_token_spec(&self, spec_path: &str, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let spec = TokenSpec::load(spec_path)?;
+
+        // Without a keypair file the mint was never created; plan against a
+        // fresh address, which cannot exist yet.
+        let mint = if Path::new(&spec.mint_keypair).exists() {
+            Some(read_keypair(&spec.mint_keypair)?.pubkey())
+        } else {
+            None
+        };
+        let plan = self.plan_spec(&spec, &mint.unwrap_or_else(|| Keypair::new().pubkey()))?;
+
+        let report = DriftReport {
+            spec: spec_path.to_string(),
+            mint: mint.map(|mint| mint.to_string()),
+            in_sync: plan.drift.is_empty(),
+            drift: plan.drift,
+        };
+
+        match &report.mint {
+            Some(mint) => info!("Drift between {} and mint {}:", spec_path, mint),
+            None => info!("Drift between {} and a mint not yet created:", spec_path),
+        }
+        if report.in_sync {
+            info!("  none, the mint matches the spec");
+        }
+        for drift in &report.drift {
+            info!(
+                "  {}: on chain {}, spec {}{}",
+                drift.field,
+                drift.on_chain,
+                drift.spec,
+                if drift.fixable { "" } else { " (apply cannot fix this)" }
+            );
+        }
+        write_output(output, &report, &report.drift)?;
+
+        if !report.in_sync {
+            return Err(anyhow!("{} field(s) differ from {}", report.drift.len(), spec_path));
+        }
+        Ok(())
+    }
+
+    /// Compare a spec with the chain and work out the changes to apply.
+    fn plan_spec(&self, spec: &TokenSpec, mint: &Pubkey) -> Result<TokenPlan> {
+        let config = spec.mint_config()?;
+        let program_id = config.program_id();
+        let wallet = self.wallet.pubkey();
+        let mut plan = TokenPlan { create: None, changes: Vec::new(), drift: Vec::new() };
+
+        let account = self.client
+            .get_account_with_commitment(mint, self.client.commitment())
+            .map_err(|e| anyhow!("Failed to get mint account: {}", e))?
+            .value;
+
+        let current = match &account {
+            Some(account) => {
+                let program = |id: &Pubkey| match *id {
+                    id if id == spl_token_2022::id() => "Token-2022".to_string(),
+                    id if id == spl_token::id() => "SPL Token".to_string(),
+                    id => id.to_string(),
+                };
+                if account.owner != program_id {
+                    plan.drift("program", program(&account.owner), program(&program_id), false);
+                    return Ok(plan);
+                }
+                let state = StateWithExtensions::<Mint>::unpack(&account.data)
+                    .map_err(|e| anyhow!("Failed to parse mint data: {}", e))?;
+                if state.base.decimals != spec.decimals {
+                    plan.drift("decimals", state.base.decimals, spec.decimals, false);
+                }
+                if let Some((actual, expected)) = extension_drift(&account.data, &config)? {
+                    plan.drift("extensions", actual, expected, false);
+                }
+
+                MintState {
+                    exists: true,
+                    supply: state.base.supply,
+                    mint_authority: state.base.mint_authority.into(),
+                    freeze_authority: state.base.freeze_authority.into(),
+                    metadata: state.get_variable_len_extension::<TokenMetadata>().ok(),
+                    data_len: account.data.len(),
+                    lamports: account.lamports,
+                }
+            }
+            // A new mint starts with the wallet as both authorities and any
+            // metadata written during creation.
+            None => {
+                plan.drift("mint", "missing", "exists", true);
+                plan.create = Some(config);
+                MintState {
+                    exists: false,
+                    supply: 0,
+                    mint_authority: Some(wallet),
+                    freeze_authority: Some(wallet),
+                    metadata: None,
+                    data_len: 0,
+                    lamports: 0,
+                }
+            }
+        };
+
+        if current.exists {
+            if let Some(metadata) = &spec.metadata {
+                self.plan_metadata(&mut plan, mint, &program_id, &current, metadata)?;
+            }
+        }
+
+        if let Some(supply) = &spec.supply {
+            let desired = parse_amount(supply, spec.decimals)?;
+            let on_chain = format_amount(current.supply, spec.decimals);
+            if current.supply > desired {
+                // Burning supply back down is not something apply decides on.
+                plan.drift("supply", on_chain, format_amount(desired, spec.decimals), false);
+            } else if current.supply < desired {
+                let fixable = current.mint_authority == Some(wallet);
+                if current.exists {
+                    plan.drift("supply", &on_chain, format_amount(desired, spec.decimals), fixable);
+                }
+                if fixable {
+                    let amount = desired - current.supply;
+                    let ata = spl_associated_token_account::get_associated_token_address_with_program_id(&wallet, mint, &program_id);
+                    plan.changes.push(Change {
+                        summary: format!(
+                            "mint {} to the wallet (supply {} -> {})",
+                            format_amount(amount, spec.decimals),
+                            on_chain,
+                            format_amount(desired, spec.decimals)
+                        ),
+                        instructions: vec![
+                            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                                &wallet,
+                                &wallet,
+                                mint,
+                                &program_id,
+                            ),
+                            mint_to_checked(&program_id, mint, &ata, &wallet, &[], amount, spec.decimals)?,
+                        ],
+                        units: MINT_SUPPLY_UNITS,
+                    });
+                }
+            }
+        }
+
+        // The mint authority goes last so supply is minted before it is handed off.
+        let authorities = [
+            ("freeze_authority", AuthorityType::FreezeAccount, &spec.freeze_authority, current.freeze_authority),
+            ("mint_authority", AuthorityType::MintTokens, &spec.mint_authority, current.mint_authority),
+        ];
+        for (field, authority_type, desired, current_authority) in authorities {
+            let Some(desired) = desired else {
+                continue;
+            };
+            let desired = parse_authority(desired, &wallet, field)?;
+            if desired == current_authority {
+                continue;
+            }
+
+            let fixable = current_authority == Some(wallet);
+            if current.exists {
+                plan.drift(field, show(current_authority), show(desired), fixable);
+            }
+            if fixable {
+                plan.changes.push(Change {
+                    summary: format!("set {} {} -> {}", field, show(current_authority), show(desired)),
+                    instructions: vec![set_authority(&program_id, mint, desired.as_ref(), authority_type, &wallet, &[])?],
+                    units: SET_AUTHORITY_UNITS,
+                });
+            }
+        }
+
+        Ok(plan)
+    }
+
+    /// UpdateField changes for the embedded metadata, topping up rent first
+    /// when the fields grow.
+    fn plan_metadata(
+        &self,
+        plan: &mut TokenPlan,
+        mint: &Pubkey,
+        program_id: &Pubkey,
+        current: &MintState,
+        desired: &SpecMetadata,
+    ) -> Result<()> {
+        let Some(metadata) = &current.metadata else {
+            // TokenMetadata can only be initialized while creating the mint here.
+            plan.drift("metadata", "none", format!("{} ({})", desired.name, desired.symbol), false);
+            return Ok(());
+        };
+
+        let fields = [
+            ("name", Field::Name, &metadata.name, &desired.name),
+            ("symbol", Field::Symbol, &metadata.symbol, &desired.symbol),
+            ("uri", Field::Uri, &metadata.uri, &desired.uri),
+        ];
+        let outdated: Vec<_> = fields.into_iter().filter(|(_, _, old, new)| old != new).collect();
+        if outdated.is_empty() {
+            return Ok(());
+        }
+
+        let wallet = self.wallet.pubkey();
+        let update_authority = Option::<Pubkey>::from(metadata.update_authority);
+        let fixable = update_authority == Some(wallet);
+        for (name, _, old, new) in &outdated {
+            plan.drift(&format!("metadata.{}", name), old, new, fixable);
+        }
+        if !fixable {
+            plan.drift("metadata.update_authority", show(update_authority), show(Some(wallet)), false);
+            return Ok(());
+        }
+
+        let growth: isize = outdated.iter().map(|(_, _, old, new)| new.len() as isize - old.len() as isize).sum();
+        let new_len = (current.data_len as isize + growth.max(0)) as usize;
+        let rent = self.client.get_minimum_balance_for_rent_exemption(new_len)
+            .map_err(|e| anyhow!("Failed to get rent exemption: {}", e))?;
+        if rent > current.lamports {
+            plan.changes.push(Change {
+                summary: format!("fund {} lamports of rent for the larger metadata", rent - current.lamports),
+                instructions: vec![system_instruction::transfer(&wallet, mint, rent - current.lamports)],
+                units: SET_AUTHORITY_UNITS,
+            });
+        }
+
+        for (name, field, old, new) in outdated {
+            plan.changes.push(Change {
+                summary: format!("update metadata {} '{}' -> '{}'", name, old, new),
+                instructions: vec![spl_token_metadata_interface::instruction::update_field(
+                    program_id,
+                    mint,
+                    &wallet,
+                    field,
+                    new.clone(),
+                )],
+                units: UPDATE_FIELD_UNITS,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Extensions cannot be added or removed after creation, so the mint's set
+/// must match the spec's exactly. Returns both sets when they differ.
+fn extension_drift(data: &[u8], config: &MintConfig) -> Result<Option<(String, String)>> {
+    // Written after InitializeMint2 rather than requested as extensions.
+    let initialized_later = [ExtensionType::TokenMetadata, ExtensionType::TokenGroup, ExtensionType::TokenGroupMember]
+        .map(|extension| extension as u16);
+    let mut actual: Vec<u16> = mint_extensions(data)?
+        .into_iter()
+        .map(|(extension, _)| extension)
+        .filter(|extension| !initialized_later.contains(extension))
+        .collect();
+    let mut expected: Vec<u16> = config.extensions.iter().map(MintExtension::type_id).collect();
+
+    actual.sort_unstable();
+    expected.sort_unstable();
+    if actual == expected {
+        return Ok(None);
+    }
+    let names = |extensions: &[u16]| {
+        format!("[{}]", extensions.iter().map(|extension| extension_name(*extension)).collect::<Vec<_>>().join(", "))
+    };
+    Ok(Some((names(&actual), names(&expected))))
+}
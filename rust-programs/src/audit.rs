@@ -0,0 +1,233 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_sdk::signature::Signature;
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, OnceLock,
+    },
+};
+
+use crate::providers::redact_url;
+
+/// Audit file used on mainnet when `--audit-log` is not given.
+pub const DEFAULT_AUDIT_LOG: &str = "token-ops-audit.jsonl";
+
+/// `prev_hash` of the first entry.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Parameters whose values are secrets and never written to the trail.
+const SECRET_PARAMETERS: &[&str] = &["rpc-basic-auth", "rpc-header", "webhook-secret", "geyser-token"];
+
+/// Parameters holding URLs that may carry API keys.
+const URL_PARAMETERS: &[&str] =
+    &["rpc-url", "cross-check-rpc", "reference-rpc", "webhook", "fee-relayer", "otlp-endpoint", "geyser-endpoint"];
+
+/// One audited transaction, without its own hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub sequence: u64,
+    pub timestamp: String,
+    /// The wallet that signed.
+    pub operator: String,
+    /// The OS account that ran the tool.
+    pub user: Option<String>,
+    pub operation: String,
+    pub parameters: BTreeMap<String, Vec<String>>,
+    /// The transaction's signature, when it landed.
+    pub signatures: Vec<String>,
+    /// 1 when the transaction failed or was not confirmed.
+    pub failed_transactions: usize,
+    /// "ok" or "error: <message>".
+    pub result: String,
+    pub prev_hash: String,
+}
+
+/// One line of the audit file: the record and the SHA-256 chaining it to
+/// the previous line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    #[serde(flatten)]
+    pub record: AuditRecord,
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// Hex SHA-256 of the record's JSON, which includes `prev_hash`, so
+    /// editing, removing or reordering any line breaks every later hash.
+    fn hash(&self) -> Result<String> {
+        let json = serde_json::to_vec(self).map_err(|e| anyhow!("Failed to encode audit record: {}", e))?;
+        Ok(Sha256::digest(json).iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+}
+
+/// The run being audited.
+struct AuditLog {
+    path: String,
+    operator: String,
+    operation: String,
+    parameters: BTreeMap<String, Vec<String>>,
+}
+
+static AUDIT_LOG: OnceLock<AuditLog> = OnceLock::new();
+
+/// Sequence number and hash the next entry chains to, read from the file
+/// once and then kept, so concurrent sends append in order.
+static CHAIN_HEAD: Mutex<Option<(u64, String)>> = Mutex::new(None);
+
+/// Set when an entry could not be written, so the run still fails.
+static WRITE_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Parameters with secrets dropped and URLs reduced to their host.
+fn redacted_parameters(parameters: &BTreeMap<String, Vec<String>>) -> BTreeMap<String, Vec<String>> {
+    parameters
+        .iter()
+        .map(|(name, values)| {
+            let values = if SECRET_PARAMETERS.contains(&name.as_str()) {
+                vec!["***".to_string()]
+            } else if URL_PARAMETERS.contains(&name.as_str()) {
+                values.iter().map(|value| redact_url(value)).collect()
+            } else {
+                values.clone()
+            };
+            (name.clone(), values)
+        })
+        .collect()
+}
+
+/// Audit this run to `path`. Checked up front, so an unwritable trail stops
+/// the operation before it changes anything.
+pub fn configure(path: &str, operator: &str, operation: &str, parameters: &BTreeMap<String, Vec<String>>) -> Result<()> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| anyhow!("Cannot open audit log {}: {}", path, e))?;
+    info!("Auditing to {}", path);
+
+    AUDIT_LOG
+        .set(AuditLog {
+            path: path.to_string(),
+            operator: operator.to_string(),
+            operation: operation.to_string(),
+            parameters: redacted_parameters(parameters),
+        })
+        .map_err(|_| anyhow!("Audit log is already configured"))
+}
+
+/// The last entry's sequence number and hash.
+fn chain_head(path: &str) -> Result<(u64, String)> {
+    let contents = fs::read_to_string(path).map_err(|e| anyhow!("Failed to read audit log {}: {}", path, e))?;
+    match contents.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => {
+            let entry: AuditEntry = serde_json::from_str(line)
+                .map_err(|e| anyhow!("The last line of audit log {} is not an audit entry: {}", path, e))?;
+            Ok((entry.record.sequence + 1, entry.hash))
+        }
+        None => Ok((0, GENESIS_HASH.to_string())),
+    }
+}
+
+/// Append an entry for a submitted transaction as soon as its outcome is
+/// known: its signature when it landed, or the error. Written per
+/// transaction rather than per run, so servers stopped by a signal and runs
+/// that exit early leave a complete trail. Runs that only read state leave
+/// no entry.
+pub fn record_transaction(outcome: std::result::Result<&Signature, &str>) {
+    let Some(log) = AUDIT_LOG.get() else {
+        return;
+    };
+    let (signatures, result) = match outcome {
+        Ok(signature) => (vec![signature.to_string()], "ok".to_string()),
+        Err(e) => (Vec::new(), format!("error: {}", e)),
+    };
+    if let Err(e) = append_entry(log, signatures, result) {
+        WRITE_FAILED.store(true, Ordering::SeqCst);
+        error!("A transaction was sent but its audit entry was not written: {}", e);
+    }
+}
+
+/// Fail the run if any of its audit entries could not be written.
+pub fn finish() -> Result<()> {
+    if WRITE_FAILED.load(Ordering::SeqCst) {
+        if let Some(log) = AUDIT_LOG.get() {
+            return Err(anyhow!("Audit log {} is missing entries for transactions this run sent", log.path));
+        }
+    }
+    Ok(())
+}
+
+fn append_entry(log: &AuditLog, signatures: Vec<String>, result: String) -> Result<()> {
+    let mut head = CHAIN_HEAD.lock().map_err(|_| anyhow!("Audit chain head is unavailable"))?;
+    let (sequence, prev_hash) = match head.take() {
+        Some(head) => head,
+        None => chain_head(&log.path)?,
+    };
+    let record = AuditRecord {
+        sequence,
+        timestamp: Utc::now().to_rfc3339(),
+        operator: log.operator.clone(),
+        user: env::var("USER").or_else(|_| env::var("USERNAME")).ok(),
+        operation: log.operation.clone(),
+        parameters: log.parameters.clone(),
+        failed_transactions: usize::from(signatures.is_empty()),
+        signatures,
+        result,
+        prev_hash,
+    };
+    let hash = record.hash()?;
+    let entry = AuditEntry { hash: hash.clone(), record };
+    let line = serde_json::to_string(&entry).map_err(|e| anyhow!("Failed to encode audit entry: {}", e))?;
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(&log.path)
+        .map_err(|e| anyhow!("Cannot open audit log {}: {}", log.path, e))?;
+    // On failure the head stays unset and is re-read, in case part of the line was written.
+    writeln!(file, "{}", line).map_err(|e| anyhow!("Failed to write audit log {}: {}", log.path, e))?;
+    *head = Some((sequence + 1, hash));
+    Ok(())
+}
+
+/// Check every entry's hash and its link to the previous one, failing at
+/// the first line that was edited, removed or reordered. Dropping the
+/// newest entries only shows against a head hash kept elsewhere, so the
+/// head is logged for the operator to record.
+pub fn verify_audit_log(path: &str) -> Result<()> {
+    let contents = fs::read_to_string(path).map_err(|e| anyhow!("Failed to read audit log {}: {}", path, e))?;
+    let mut prev_hash = GENESIS_HASH.to_string();
+    let mut entries = 0u64;
+
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_number = index + 1;
+        let entry: AuditEntry = serde_json::from_str(line)
+            .map_err(|e| anyhow!("{}:{}: not an audit entry: {}", path, line_number, e))?;
+        if entry.record.sequence != entries {
+            return Err(anyhow!(
+                "{}:{}: sequence {} where {} was expected; entries were removed or reordered",
+                path, line_number, entry.record.sequence, entries
+            ));
+        }
+        if entry.record.prev_hash != prev_hash {
+            return Err(anyhow!("{}:{}: does not chain to the previous entry", path, line_number));
+        }
+        if entry.record.hash()? != entry.hash {
+            return Err(anyhow!("{}:{}: hash mismatch; the entry was modified", path, line_number));
+        }
+        prev_hash = entry.hash;
+        entries += 1;
+    }
+
+    info!("Audit log {} is intact: {} entries, head {}", path, entries, prev_hash);
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::info;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashSet, str::FromStr};
+
+use crate::{
+    input::read_addresses,
+    output::{write_output, OutputFormat},
+    token_program::unpack_token_account,
+    TokenOperations,
+};
+
+/// Accounts fetched per getMultipleAccounts call.
+const FETCH_CHUNK: usize = 100;
+
+/// One wallet's ATA balance.
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletBalance {
+    pub wallet: String,
+    pub token_account: String,
+    pub balance: String,
+    pub raw_amount: u64,
+    pub state: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkBalanceReport {
+    pub mint: String,
+    pub slot: u64,
+    pub wallets: usize,
+    pub with_balance: usize,
+    pub without_account: usize,
+    pub total: String,
+    pub balances: Vec<WalletBalance>,
+}
+
+impl TokenOperations {
+    /// Print a mint's supply from one getTokenSupply call; cheap enough to
+    /// run in a polling loop.
+    pub fn supply(&self, mint_address: &str, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let supply = token_ops_core::rpc::fetch_supply(&self.client, &mint_pubkey)?;
+
+        info!("Supply: {} (raw {}, {} decimals, slot {})", supply.ui_amount, supply.amount, supply.decimals, supply.slot);
+        write_output(output, &supply, std::slice::from_ref(&supply))
+    }
+
+    /// Look up the ATA balance of every wallet in `addresses_file`.
+    pub fn bulk_balance(
+        &self,
+        mint_address: &str,
+        addresses_file: &str,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+        let now = Utc::now().timestamp();
+
+        let mut wallets = read_addresses(addresses_file)?;
+        let mut seen = HashSet::new();
+        wallets.retain(|wallet| seen.insert(*wallet));
+        info!("Fetching balances of {} wallet(s) from {}", wallets.len(), addresses_file);
+
+        let token_accounts: Vec<Pubkey> = wallets
+            .iter()
+            .map(|wallet| mint_info.associated_token_address(wallet))
+            .collect();
+
+        let slot = self.client.get_slot().map_err(|e| anyhow!("Failed to get slot: {}", e))?;
+        let mut balances = Vec::with_capacity(wallets.len());
+        let mut total: u64 = 0;
+
+        for (wallets_chunk, accounts_chunk) in wallets.chunks(FETCH_CHUNK).zip(token_accounts.chunks(FETCH_CHUNK)) {
+            let accounts = self.client.get_multiple_accounts(accounts_chunk)
+                .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
+
+            for ((wallet, token_account), account) in wallets_chunk.iter().zip(accounts_chunk).zip(accounts) {
+                let state = account
+                    .filter(|account| account.owner == mint_info.program_id)
+                    .and_then(|account| unpack_token_account(&account.data).ok());
+
+                let (amount, state) = match state {
+                    Some(account) => (account.amount, format!("{:?}", account.state).to_lowercase()),
+                    None => (0, "no account".to_string()),
+                };
+                total += amount;
+
+                balances.push(WalletBalance {
+                    wallet: wallet.to_string(),
+                    token_account: token_account.to_string(),
+                    balance: mint_info.ui_amount(amount, now)?,
+                    raw_amount: amount,
+                    state,
+                });
+            }
+        }
+
+        let report = BulkBalanceReport {
+            mint: mint_pubkey.to_string(),
+            slot,
+            wallets: balances.len(),
+            with_balance: balances.iter().filter(|balance| balance.raw_amount > 0).count(),
+            without_account: balances.iter().filter(|balance| balance.state == "no account").count(),
+            total: mint_info.ui_amount(total, now)?,
+            balances,
+        };
+
+        info!("Balances at slot {}:", report.slot);
+        info!("  Wallets:         {}", report.wallets);
+        info!("  With a balance:  {}", report.with_balance);
+        info!("  Without an ATA:  {}", report.without_account);
+        info!("  Total:           {}", report.total);
+        if output.is_none() {
+            for balance in &report.balances {
+                info!("  {}: {}", balance.wallet, balance.balance);
+            }
+        }
+
+        write_output(output, &report, &report.balances)
+    }
+}
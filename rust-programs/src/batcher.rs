@@ -0,0 +1,464 @@
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use opentelemetry::KeyValue;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount,
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    packet::PACKET_DATA_SIZE,
+    signature::{Keypair, Signature, Signer},
+    transaction::{Transaction, VersionedTransaction},
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    audit,
+    hooks::Hooks,
+    outputs,
+    priority::AutoPriorityFee,
+    profile::{self, TransactionProfile},
+    receipts::Receipts,
+    relayer::FeeRelayer,
+    simulation,
+    telemetry::in_span,
+};
+
+/// Compute units requested per transaction when no explicit limit is configured.
+pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// Hard per-transaction compute ceiling enforced by the runtime.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// How long a confirmed transaction may take to reach finalized.
+const FINALIZE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Delay between finalization status polls.
+const FINALIZE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Called with a transaction's item indices and signature once it is
+/// signed and before it is submitted; an error stops the send.
+pub type BeforeSend<'a> = &'a dyn Fn(&[usize], &Signature) -> Result<()>;
+
+/// Outcome of one submitted transaction.
+#[derive(Debug)]
+pub struct BatchResult {
+    /// Indices (in push order) of the instruction groups carried by this transaction.
+    pub items: Vec<usize>,
+    pub signature: std::result::Result<Signature, String>,
+}
+
+/// Shared transaction packer for batch operations.
+///
+/// Instructions are queued in atomic groups together with an estimate of the
+/// compute units they consume. Groups are packed into as few transactions as
+/// possible while keeping each one under the 1232-byte packet limit and the
+/// configured compute budget. Every full transaction is signed and submitted
+/// before packing continues, and a failed send does not stop the batch.
+///
+/// When lookup tables are supplied, v0 transactions are compiled against them
+/// so accounts present in a table cost one byte instead of 32.
+pub struct TxBatcher<'a> {
+    client: &'a RpcClient,
+    payer: &'a Keypair,
+    signers: Vec<&'a Keypair>,
+    compute_unit_limit: u32,
+    compute_unit_price: Option<u64>,
+    auto_priority_fee: Option<AutoPriorityFee>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    dry_run: bool,
+    wait_finalized: bool,
+    receipts: Option<&'a Receipts>,
+    hooks: Option<&'a Hooks>,
+    relayer: Option<&'a FeeRelayer>,
+    before_send: Option<BeforeSend<'a>>,
+    pending: Vec<Instruction>,
+    pending_items: Vec<usize>,
+    pending_units: u32,
+    next_item: usize,
+    results: Vec<BatchResult>,
+}
+
+impl<'a> TxBatcher<'a> {
+    pub fn new(client: &'a RpcClient, payer: &'a Keypair) -> Self {
+        Self {
+            client,
+            payer,
+            signers: Vec::new(),
+            compute_unit_limit: DEFAULT_COMPUTE_UNIT_LIMIT,
+            compute_unit_price: None,
+            auto_priority_fee: None,
+            lookup_tables: Vec::new(),
+            dry_run: false,
+            wait_finalized: false,
+            receipts: None,
+            hooks: None,
+            relayer: None,
+            before_send: None,
+            pending: Vec::new(),
+            pending_items: Vec::new(),
+            pending_units: 0,
+            next_item: 0,
+            results: Vec::new(),
+        }
+    }
+
+    /// Additional keypairs that sign every transaction, e.g. a new account
+    /// being created. Only use this when each transaction requires them all.
+    pub fn with_signers(mut self, signers: &[&'a Keypair]) -> Self {
+        self.signers = signers.to_vec();
+        self
+    }
+
+    pub fn with_compute_unit_limit(mut self, limit: u32) -> Self {
+        self.compute_unit_limit = limit.min(MAX_COMPUTE_UNIT_LIMIT);
+        self
+    }
+
+    pub fn with_compute_unit_price(mut self, micro_lamports: Option<u64>) -> Self {
+        self.compute_unit_price = micro_lamports;
+        self
+    }
+
+    /// Price every transaction from recent prioritization fees instead of
+    /// a fixed compute unit price.
+    pub fn with_auto_priority_fee(mut self, auto: Option<AutoPriorityFee>) -> Self {
+        self.auto_priority_fee = auto;
+        self
+    }
+
+    pub fn with_lookup_tables(mut self, lookup_tables: Vec<AddressLookupTableAccount>) -> Self {
+        self.lookup_tables = lookup_tables;
+        self
+    }
+
+    /// Pack transactions without signing or sending them; every result then
+    /// carries a "not sent" error. Used to count transactions up front.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Only report a transaction as sent once it is finalized, failing it if
+    /// it is dropped (e.g. on a minority fork) after confirmation.
+    pub fn with_wait_finalized(mut self, wait_finalized: bool) -> Self {
+        self.wait_finalized = wait_finalized;
+        self
+    }
+
+    /// Write a receipt for every transaction that lands.
+    pub fn with_receipts(mut self, receipts: Option<&'a Receipts>) -> Self {
+        self.receipts = receipts;
+        self
+    }
+
+    /// Inject the hooks' instructions into every transaction and run their
+    /// per-transaction actions after each one lands.
+    pub fn with_hooks(mut self, hooks: Option<&'a Hooks>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Let a fee relayer pay for every transaction: its fee transfer goes
+    /// first and it co-signs as fee payer before sending.
+    pub fn with_relayer(mut self, relayer: Option<&'a FeeRelayer>) -> Self {
+        self.relayer = relayer;
+        self
+    }
+
+    /// Run `before_send` on every signed transaction before submitting it,
+    /// e.g. to record its signature. A relayed transaction's signature is
+    /// only known once the relayer signs it, so it is skipped there.
+    pub fn with_before_send(mut self, before_send: Option<BeforeSend<'a>>) -> Self {
+        self.before_send = before_send;
+        self
+    }
+
+    /// Queue an atomic group of instructions, submitting the pending
+    /// transaction first if the group would not fit alongside it.
+    /// Returns the index of the group for matching against results.
+    pub fn push(&mut self, instructions: Vec<Instruction>, units: u32) -> Result<usize> {
+        if self.hook_units() + units > self.compute_unit_limit {
+            return Err(anyhow!(
+                "Instruction group needs {} compute units but the budget is {}",
+                self.hook_units() + units,
+                self.compute_unit_limit
+            ));
+        }
+        if !self.fits_packet(&instructions)? {
+            return Err(anyhow!("Instruction group does not fit in a single transaction"));
+        }
+
+        if !self.pending.is_empty() {
+            let mut candidate = self.pending.clone();
+            candidate.extend(instructions.iter().cloned());
+
+            if self.hook_units() + self.pending_units + units > self.compute_unit_limit || !self.fits_packet(&candidate)? {
+                self.flush();
+            }
+        }
+
+        let item = self.next_item;
+        self.next_item += 1;
+
+        self.pending.extend(instructions);
+        self.pending_items.push(item);
+        self.pending_units += units;
+
+        Ok(item)
+    }
+
+    /// Submit whatever is still pending and return one result per transaction sent.
+    pub fn finish(mut self) -> Vec<BatchResult> {
+        self.flush();
+        self.results
+    }
+
+    fn budget_instructions(&self, compute_unit_price: Option<u64>) -> Vec<Instruction> {
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(self.compute_unit_limit)];
+        if let Some(price) = compute_unit_price {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+        }
+        instructions
+    }
+
+    fn hook_units(&self) -> u32 {
+        self.hooks.map(Hooks::instruction_units).unwrap_or(0)
+    }
+
+    /// The full instruction list of a transaction carrying `instructions`.
+    fn transaction_instructions(&self, instructions: &[Instruction], compute_unit_price: Option<u64>) -> Vec<Instruction> {
+        // Relayers expect their fee transfer to be the first instruction.
+        let mut all: Vec<Instruction> = self.relayer.map(|relayer| relayer.fee_instruction().clone()).into_iter().collect();
+        all.extend(self.budget_instructions(compute_unit_price));
+        match self.hooks {
+            Some(hooks) => all.extend(hooks.wrap(instructions)),
+            None => all.extend(instructions.iter().cloned()),
+        }
+        all
+    }
+
+    fn fits_packet(&self, instructions: &[Instruction]) -> Result<bool> {
+        // An automatic fee is priced at send time; the cap serializes to the same size.
+        let price = self.compute_unit_price.or(self.auto_priority_fee.map(|auto| auto.max));
+        let all = self.transaction_instructions(instructions, price);
+
+        // Placeholder signatures serialize to the same size as real ones.
+        let transaction = self.build(&all, Hash::default(), false)?;
+        let size = bincode::serialized_size(&transaction)
+            .map_err(|e| anyhow!("Failed to measure transaction size: {}", e))?;
+
+        Ok(size as usize <= PACKET_DATA_SIZE)
+    }
+
+    fn build(&self, instructions: &[Instruction], recent_blockhash: Hash, sign: bool) -> Result<VersionedTransaction> {
+        let payer = self.payer.pubkey();
+        let mut signers = vec![self.payer];
+        signers.extend(&self.signers);
+
+        if let Some(relayer) = self.relayer {
+            let mut transaction = Transaction::new_with_payer(instructions, Some(&relayer.fee_payer));
+            if sign {
+                transaction
+                    .try_partial_sign(signers.as_slice(), recent_blockhash)
+                    .map_err(|e| anyhow!("Failed to sign transaction: {}", e))?;
+            }
+            return Ok(transaction.into());
+        }
+
+        if self.lookup_tables.is_empty() {
+            let transaction = if sign {
+                Transaction::new_signed_with_payer(instructions, Some(&payer), signers.as_slice(), recent_blockhash)
+            } else {
+                Transaction::new_with_payer(instructions, Some(&payer))
+            };
+            return Ok(transaction.into());
+        }
+
+        let message = v0::Message::try_compile(&payer, instructions, &self.lookup_tables, recent_blockhash)
+            .map_err(|e| anyhow!("Failed to compile v0 message: {}", e))?;
+        let message = VersionedMessage::V0(message);
+
+        if sign {
+            VersionedTransaction::try_new(message, signers.as_slice())
+                .map_err(|e| anyhow!("Failed to sign transaction: {}", e))
+        } else {
+            let signatures = vec![Signature::default(); message.header().num_required_signatures as usize];
+            Ok(VersionedTransaction { signatures, message })
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let instructions = std::mem::take(&mut self.pending);
+        let items = std::mem::take(&mut self.pending_items);
+        self.pending_units = 0;
+
+        if self.dry_run {
+            self.results.push(BatchResult { items, signature: Err("not sent (dry run)".to_string()) });
+            return;
+        }
+
+        let attributes = vec![
+            KeyValue::new("batch.groups", items.len() as i64),
+            KeyValue::new("batch.instructions", instructions.len() as i64),
+        ];
+        let signature = in_span("tx.batch", attributes, || self.send(&instructions, &items));
+        match &signature {
+            Ok(signature) => info!(
+                "Batch of {} group(s) {}: {}",
+                items.len(),
+                if self.wait_finalized { "finalized" } else { "confirmed" },
+                signature
+            ),
+            Err(e) => error!("Batch of {} group(s) failed: {}", items.len(), e),
+        }
+
+        self.results.push(BatchResult { items, signature });
+    }
+
+    fn send(&self, instructions: &[Instruction], items: &[usize]) -> std::result::Result<Signature, String> {
+        let (transaction, recent_blockhash, last_valid_block_height, compute_unit_price) = in_span("tx.build", vec![], || {
+            let compute_unit_price = match self.auto_priority_fee {
+                Some(auto) => Some(auto.price(self.client, instructions).map_err(|e| e.to_string())?),
+                None => self.compute_unit_price,
+            };
+            let instructions = self.transaction_instructions(instructions, compute_unit_price);
+            let (recent_blockhash, last_valid_block_height) = self
+                .client
+                .get_latest_blockhash_with_commitment(self.client.commitment())
+                .map_err(|e| e.to_string())?;
+            let transaction = self
+                .build(&instructions, recent_blockhash, true)
+                .map_err(|e| e.to_string())?;
+            Ok::<_, String>((transaction, recent_blockhash, last_valid_block_height, compute_unit_price))
+        })?;
+
+        if let (Some(before_send), None) = (self.before_send, self.relayer) {
+            before_send(items, &transaction.signatures[0]).map_err(|e| e.to_string())?;
+        }
+
+        // Measured before sending, since a relayed transaction is handed over.
+        let profiled = profile::enabled().then(|| TransactionProfile {
+            signature: transaction.signatures[0].to_string(),
+            landed: false,
+            size: bincode::serialized_size(&transaction).unwrap_or_default() as usize,
+            signatures: transaction.signatures.len(),
+            compute_unit_limit: self.compute_unit_limit,
+            compute_unit_price,
+            units_simulated: simulation::simulated_units(self.client, &transaction),
+            units_consumed: None,
+            fee: None,
+        });
+
+        // A relayed transaction only becomes complete once the relayer signs it.
+        let pending = match (self.receipts, self.relayer) {
+            (Some(receipts), None) => match receipts.save_pending(&transaction, last_valid_block_height) {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    warn!("Failed to save signed transaction for rebroadcast: {}", e);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        let sent = match self.relayer {
+            Some(relayer) => in_span("tx.relay", vec![], || self.relay(relayer, transaction)),
+            None => in_span(
+                "tx.send_and_confirm",
+                vec![KeyValue::new("tx.signature", transaction.signatures[0].to_string())],
+                || {
+                    self.client
+                        .send_and_confirm_transaction(&transaction)
+                        .map_err(|e| simulation::describe_send_error(self.client, &transaction, e))
+                },
+            ),
+        };
+        audit::record_transaction(sent.as_ref().map_err(String::as_str));
+        if let (Err(_), Some(path)) = (&sent, &pending) {
+            warn!("Signed transaction kept at {} for rebroadcast", path.display());
+        }
+        if let Some(mut profiled) = profiled {
+            if let Ok(signature) = &sent {
+                let (fee, units_consumed) = profile::landed_cost(self.client, signature);
+                profiled.signature = signature.to_string();
+                profiled.landed = true;
+                profiled.fee = fee;
+                profiled.units_consumed = units_consumed;
+            }
+            profile::record(profiled);
+        }
+        let signature = sent?;
+
+        if self.wait_finalized {
+            in_span("tx.wait_finalized", vec![], || self.wait_for_finalization(&signature, &recent_blockhash))?;
+        }
+        outputs::record("signature", &signature.to_string());
+        if let Some(hooks) = self.hooks {
+            hooks.run_after_transaction(&signature);
+        }
+        if let Some(receipts) = self.receipts {
+            match receipts.write(self.client, &signature) {
+                Ok(path) => info!("Receipt written to {}", path.display()),
+                Err(e) => warn!("Failed to write receipt for {}: {}", signature, e),
+            }
+            receipts.clear_pending(&signature);
+        }
+        Ok(signature)
+    }
+
+    /// Submit a partially signed legacy transaction to the fee relayer and
+    /// wait for it to confirm.
+    fn relay(&self, relayer: &FeeRelayer, transaction: VersionedTransaction) -> std::result::Result<Signature, String> {
+        let transaction = transaction
+            .into_legacy_transaction()
+            .ok_or_else(|| "Fee relayers only accept legacy transactions".to_string())?;
+        let signature = relayer.submit(&transaction)?;
+        self.client
+            .poll_for_signature_with_commitment(&signature, self.client.commitment())
+            .map_err(|e| format!("{} was relayed but not confirmed: {}", signature, e))?;
+        Ok(signature)
+    }
+
+    /// Poll a confirmed transaction until it is finalized. Once its blockhash
+    /// has expired, a transaction that is no longer even confirmed was
+    /// dropped and can never finalize.
+    fn wait_for_finalization(&self, signature: &Signature, recent_blockhash: &Hash) -> std::result::Result<(), String> {
+        info!("Waiting for {} to finalize...", signature);
+        let started = Instant::now();
+
+        loop {
+            match self.client.get_signature_status_with_commitment(signature, CommitmentConfig::finalized()) {
+                Ok(Some(Ok(()))) => return Ok(()),
+                Ok(Some(Err(e))) => return Err(format!("{} failed at finalization: {}", signature, e)),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to get status of {}: {}", signature, e),
+            }
+
+            let expired = !self.client
+                .is_blockhash_valid(recent_blockhash, CommitmentConfig::processed())
+                .unwrap_or(true);
+            if expired {
+                let confirmed = self.client.get_signature_status_with_commitment(signature, CommitmentConfig::confirmed());
+                if let Ok(None) = confirmed {
+                    return Err(format!("{} was confirmed but dropped before finalization (fork)", signature));
+                }
+            }
+
+            if started.elapsed() > FINALIZE_TIMEOUT {
+                return Err(format!("{} was not finalized within {}s", signature, FINALIZE_TIMEOUT.as_secs()));
+            }
+            thread::sleep(FINALIZE_POLL_INTERVAL);
+        }
+    }
+}
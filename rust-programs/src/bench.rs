@@ -0,0 +1,142 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Serialize;
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use std::time::Instant;
+
+use crate::{
+    output::{write_output, OutputFormat},
+    providers::redact_url,
+    telemetry::rpc_client,
+};
+
+/// Latency and error rate of one RPC method on one endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct MethodBenchmark {
+    pub url: String,
+    pub method: String,
+    pub samples: usize,
+    pub errors: usize,
+    pub error_rate: f64,
+    pub min_ms: Option<f64>,
+    pub p50_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    pub mean_ms: Option<f64>,
+}
+
+/// Time getAccountInfo (on `account`), getLatestBlockhash and
+/// simulateTransaction `samples` times against each endpoint and print a
+/// comparison table. The simulated transaction is an unfunded self-transfer,
+/// so nothing is signed with the wallet and nothing is sent.
+pub fn rpc_bench(
+    urls: &[String],
+    account: &Pubkey,
+    samples: usize,
+    output: Option<(&str, OutputFormat)>,
+) -> Result<()> {
+    if samples == 0 {
+        return Err(anyhow!("--samples must be at least 1"));
+    }
+
+    let mut results = Vec::new();
+    for url in urls {
+        info!("Benchmarking {} ({} samples per method)...", redact_url(url), samples);
+        let client = rpc_client(url, CommitmentConfig::confirmed());
+        let probe = Keypair::new();
+
+        results.push(measure(url, "getAccountInfo", samples, || {
+            client.get_account_with_commitment(account, client.commitment()).map(drop).map_err(|e| e.to_string())
+        }));
+        results.push(measure(url, "getLatestBlockhash", samples, || {
+            client.get_latest_blockhash().map(drop).map_err(|e| e.to_string())
+        }));
+        results.push(measure(url, "simulateTransaction", samples, || simulate(&client, &probe)));
+    }
+
+    info!(
+        "{:<40} {:<20} {:>8} {:>8} {:>8} {:>8} {:>8}",
+        "endpoint", "method", "p50 ms", "p95 ms", "max ms", "mean ms", "errors"
+    );
+    for result in &results {
+        info!(
+            "{:<40} {:<20} {:>8} {:>8} {:>8} {:>8} {:>5}/{}",
+            result.url,
+            result.method,
+            show(result.p50_ms),
+            show(result.p95_ms),
+            show(result.max_ms),
+            show(result.mean_ms),
+            result.errors,
+            result.samples
+        );
+    }
+
+    write_output(output, &results, &results)
+}
+
+fn measure(
+    url: &str,
+    method: &str,
+    samples: usize,
+    mut call: impl FnMut() -> std::result::Result<(), String>,
+) -> MethodBenchmark {
+    let mut latencies = Vec::with_capacity(samples);
+    let mut errors = 0;
+    for _ in 0..samples {
+        let started = Instant::now();
+        match call() {
+            Ok(()) => latencies.push(started.elapsed().as_secs_f64() * 1000.0),
+            Err(_) => errors += 1,
+        }
+    }
+    latencies.sort_by(f64::total_cmp);
+
+    let percentile = |p: f64| -> Option<f64> {
+        if latencies.is_empty() {
+            return None;
+        }
+        let index = ((latencies.len() - 1) as f64 * p).round() as usize;
+        Some(latencies[index])
+    };
+
+    MethodBenchmark {
+        url: redact_url(url),
+        method: method.to_string(),
+        samples,
+        errors,
+        error_rate: errors as f64 / samples as f64,
+        min_ms: latencies.first().copied(),
+        p50_ms: percentile(0.5),
+        p95_ms: percentile(0.95),
+        max_ms: latencies.last().copied(),
+        mean_ms: (!latencies.is_empty()).then(|| latencies.iter().sum::<f64>() / latencies.len() as f64),
+    }
+}
+
+/// Simulate a zero-lamport self-transfer from a throwaway keypair. The
+/// simulation itself fails (the account does not exist); only the RPC
+/// round trip is measured.
+fn simulate(client: &RpcClient, probe: &Keypair) -> std::result::Result<(), String> {
+    let instruction = system_instruction::transfer(&probe.pubkey(), &probe.pubkey(), 0);
+    let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&probe.pubkey()), &[probe], Hash::default());
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(client.commitment()),
+        ..RpcSimulateTransactionConfig::default()
+    };
+    client.simulate_transaction_with_config(&transaction, config).map(drop).map_err(|e| e.to_string())
+}
+
+fn show(value: Option<f64>) -> String {
+    value.map(|value| format!("{:.1}", value)).unwrap_or_else(|| "-".to_string())
+}
@@ -0,0 +1,124 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use crate::output::write_json;
+
+/// Directory checkpoints are written to when `--checkpoint-dir` is not given.
+pub const DEFAULT_CHECKPOINT_DIR: &str = "token-ops-checkpoints";
+
+/// Exit status of a process stopped by a signal it did not handle.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Set by the first interrupt while a scan is running.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Scans currently able to stop at a checkpoint.
+static ACTIVE_SCANS: AtomicUsize = AtomicUsize::new(0);
+
+/// Handle SIGINT, SIGTERM and SIGHUP. While a scan runs, the first signal
+/// asks it to stop at its next page and save a checkpoint; a second signal,
+/// or one arriving when no scan runs, exits at once as before.
+pub fn install_interrupt_handler() -> Result<()> {
+    ctrlc::set_handler(|| {
+        if ACTIVE_SCANS.load(Ordering::SeqCst) > 0 && !INTERRUPTED.swap(true, Ordering::SeqCst) {
+            warn!("Interrupted; saving a checkpoint after the current page (interrupt again to exit now)");
+        } else {
+            process::exit(INTERRUPTED_EXIT_CODE);
+        }
+    })
+    .map_err(|e| anyhow!("Failed to install interrupt handler: {}", e))
+}
+
+/// Where scans save their progress, and whether to continue from it.
+#[derive(Debug, Clone)]
+pub struct Checkpoints {
+    dir: PathBuf,
+    resume: bool,
+}
+
+impl Checkpoints {
+    pub fn new(dir: &str, resume: bool) -> Self {
+        Self { dir: PathBuf::from(dir), resume }
+    }
+
+    /// Start the scan identified by `key`, e.g. `signatures-<address>`.
+    pub fn scan(&self, key: &str) -> Scan<'_> {
+        ACTIVE_SCANS.fetch_add(1, Ordering::SeqCst);
+        Scan { checkpoints: self, path: self.dir.join(format!("{}.json", key)) }
+    }
+}
+
+/// A running scan. Its state is saved when it stops early and removed
+/// when it completes.
+pub struct Scan<'a> {
+    checkpoints: &'a Checkpoints,
+    path: PathBuf,
+}
+
+impl Scan<'_> {
+    /// The state saved by an earlier, interrupted run of this scan, when
+    /// `--resume` was given. Without it a leftover checkpoint is ignored
+    /// and replaced.
+    pub fn resume<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        if !self.checkpoints.resume {
+            warn!("Ignoring checkpoint {}; pass --resume to continue from it", self.path.display());
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&self.path)
+            .map_err(|e| anyhow!("Failed to read checkpoint {}: {}", self.path.display(), e))?;
+        let state = serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Failed to parse checkpoint {}: {}", self.path.display(), e))?;
+        info!("Resuming from checkpoint {}", self.path.display());
+        Ok(Some(state))
+    }
+
+    /// Save `state` and stop with an error if an interrupt arrived.
+    /// Called between pages, once `state` covers everything processed.
+    pub fn checkpoint<T: Serialize>(&self, state: &T) -> Result<()> {
+        if !INTERRUPTED.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.save(state)?;
+        Err(anyhow!("Interrupted; progress saved to {}, rerun with --resume to continue", self.path.display()))
+    }
+
+    /// Save `state` after `error` stopped the scan, so a rerun with
+    /// `--resume` continues past the pages already fetched.
+    pub fn fail<T: Serialize>(&self, state: &T, error: anyhow::Error) -> anyhow::Error {
+        match self.save(state) {
+            Ok(()) => anyhow!("{} (progress saved to {}, rerun with --resume to continue)", error, self.path.display()),
+            Err(e) => anyhow!("{} (and saving a checkpoint failed: {})", error, e),
+        }
+    }
+
+    /// Remove the checkpoint of a completed scan.
+    pub fn finish(self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)
+                .map_err(|e| anyhow!("Failed to remove checkpoint {}: {}", self.path.display(), e))?;
+        }
+        Ok(())
+    }
+
+    fn save<T: Serialize>(&self, state: &T) -> Result<()> {
+        let dir: &Path = &self.checkpoints.dir;
+        fs::create_dir_all(dir).map_err(|e| anyhow!("Failed to create checkpoint directory {}: {}", dir.display(), e))?;
+        write_json(&self.path.to_string_lossy(), state)
+    }
+}
+
+impl Drop for Scan<'_> {
+    fn drop(&mut self) {
+        ACTIVE_SCANS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
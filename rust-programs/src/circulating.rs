@@ -0,0 +1,130 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::{info, warn};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashSet, str::FromStr};
+
+use crate::{
+    input::read_addresses,
+    output::{write_output, OutputFormat},
+    token_program::unpack_token_account,
+    TokenOperations,
+};
+
+/// Excluded addresses fetched per getMultipleAccounts call.
+const FETCH_CHUNK: usize = 100;
+
+/// Balance of one excluded address, summed over its token accounts.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExcludedBalance {
+    pub address: String,
+    pub token_accounts: usize,
+    pub balance: String,
+    pub raw_amount: u64,
+}
+
+/// Supply figures in the shape aggregators ask for: plain decimal strings
+/// for total, circulating and locked supply.
+#[derive(Debug, Clone, Serialize)]
+pub struct CirculatingSupply {
+    pub mint: String,
+    pub decimals: u8,
+    pub generated_at: String,
+    pub total_supply: String,
+    pub circulating_supply: String,
+    pub locked_supply: String,
+    pub excluded: Vec<ExcludedBalance>,
+}
+
+impl TokenOperations {
+    /// Total supply minus everything held by the addresses in
+    /// `exclude_file` (treasury, vesting, burn and locker wallets, or their
+    /// token accounts directly).
+    pub fn circulating_supply(
+        &self,
+        mint_address: &str,
+        exclude_file: &str,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+        let base = mint_info.base()?;
+        let now = Utc::now();
+
+        let mut addresses = read_addresses(exclude_file)?;
+        let mut seen = HashSet::new();
+        addresses.retain(|address| seen.insert(*address));
+        info!("Excluding {} address(es) from {}", addresses.len(), exclude_file);
+
+        // A token account listed directly and again through its owner wallet
+        // must only be subtracted once.
+        let mut counted = HashSet::new();
+        let mut excluded = Vec::with_capacity(addresses.len());
+        for chunk in addresses.chunks(FETCH_CHUNK) {
+            let accounts = self.client.get_multiple_accounts(chunk)
+                .map_err(|e| anyhow!("Failed to get excluded accounts: {}", e))?;
+
+            for (address, account) in chunk.iter().zip(accounts) {
+                let direct = account
+                    .filter(|account| account.owner == mint_info.program_id)
+                    .and_then(|account| unpack_token_account(&account.data).ok())
+                    .filter(|token_account| token_account.mint == mint_pubkey);
+
+                let holdings: Vec<(Pubkey, u64)> = match direct {
+                    Some(token_account) => vec![(*address, token_account.amount)],
+                    None => self
+                        .owned_token_accounts(address, Some(&mint_pubkey))?
+                        .into_iter()
+                        .map(|owned| (owned.address, owned.account.amount))
+                        .collect(),
+                };
+
+                let mut raw_amount = 0u64;
+                let mut token_accounts = 0;
+                for (token_account, amount) in holdings {
+                    if counted.insert(token_account) {
+                        raw_amount = raw_amount.saturating_add(amount);
+                        token_accounts += 1;
+                    }
+                }
+                if token_accounts == 0 {
+                    warn!("{} holds no {} tokens", address, mint_pubkey);
+                }
+
+                excluded.push(ExcludedBalance {
+                    address: address.to_string(),
+                    token_accounts,
+                    balance: mint_info.ui_amount(raw_amount, now.timestamp())?,
+                    raw_amount,
+                });
+            }
+        }
+
+        let locked: u64 = excluded.iter().map(|balance| balance.raw_amount).sum();
+        if locked > base.supply {
+            warn!("Excluded balances exceed the total supply; is the exclusion list for another mint?");
+        }
+        let circulating = base.supply.saturating_sub(locked);
+
+        let report = CirculatingSupply {
+            mint: mint_pubkey.to_string(),
+            decimals: base.decimals,
+            generated_at: now.to_rfc3339(),
+            total_supply: mint_info.ui_amount(base.supply, now.timestamp())?,
+            circulating_supply: mint_info.ui_amount(circulating, now.timestamp())?,
+            locked_supply: mint_info.ui_amount(locked, now.timestamp())?,
+            excluded,
+        };
+
+        info!("Total supply: {}", report.total_supply);
+        info!("Circulating supply: {}", report.circulating_supply);
+        info!("Locked supply: {}", report.locked_supply);
+        for balance in report.excluded.iter().filter(|balance| balance.raw_amount > 0) {
+            info!("  {}: {}", balance.address, balance.balance);
+        }
+
+        write_output(output, &report, &report.excluded)
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use spl_token_2022::{
+    extension::{permanent_delegate::PermanentDelegate, BaseStateWithExtensions},
+    instruction::{burn_checked, transfer_checked},
+};
+use std::str::FromStr;
+use token_ops_core::{instructions::memo, rpc::transfer_memo};
+
+use crate::{
+    holders::{format_amount, parse_amount},
+    prompt::confirm_typed,
+    token_program::{is_token_program, unpack_token_account},
+    TokenOperations,
+};
+
+/// Estimated compute units for an idempotent ATA creation.
+const CREATE_ATA_UNITS: u32 = 40_000;
+
+/// Estimated compute units for a TransferChecked or BurnChecked instruction.
+const CLAWBACK_UNITS: u32 = 20_000;
+
+impl TokenOperations {
+    /// Move (or burn) tokens out of a holder's account using the Token-2022
+    /// permanent delegate authority held by the loaded wallet.
+    ///
+    /// `from` may be a token account of the mint or a wallet, in which case
+    /// its ATA is used. Transferred tokens go to `to`'s ATA, or the loaded
+    /// wallet's ATA when no destination is given.
+    pub fn clawback(
+        &self,
+        mint_address: &str,
+        from: &str,
+        amount: &str,
+        to: Option<&str>,
+        burn: bool,
+        assume_yes: bool,
+    ) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+
+        let delegate = mint_info
+            .state()?
+            .get_extension::<PermanentDelegate>()
+            .ok()
+            .and_then(|extension| Option::<Pubkey>::from(extension.delegate));
+        if delegate != Some(self.wallet.pubkey()) {
+            return Err(anyhow!("Loaded wallet is not the permanent delegate of this mint"));
+        }
+        let decimals = mint_info.base()?.decimals;
+
+        let from_pubkey = Pubkey::from_str(from)
+            .map_err(|e| anyhow!("Invalid source address: {}", e))?;
+        let source = match self.client
+            .get_account_with_commitment(&from_pubkey, self.client.commitment())
+            .map_err(|e| anyhow!("Failed to get source account: {}", e))?
+            .value
+        {
+            Some(account) if is_token_program(&account.owner) => from_pubkey,
+            _ => mint_info.associated_token_address(&from_pubkey),
+        };
+
+        let source_account = self.client.get_account(&source)
+            .map_err(|e| anyhow!("Failed to get source token account {}: {}", source, e))?;
+        let source_state = unpack_token_account(&source_account.data)?;
+        if source_state.mint != mint_pubkey {
+            return Err(anyhow!("Token account {} does not hold this mint", source));
+        }
+
+        let amount = if amount == "all" {
+            source_state.amount
+        } else {
+            parse_amount(amount, decimals)?
+        };
+        if amount == 0 || amount > source_state.amount {
+            return Err(anyhow!(
+                "Cannot claw back {} from an account holding {}",
+                format_amount(amount, decimals),
+                format_amount(source_state.amount, decimals)
+            ));
+        }
+
+        let mut instructions = Vec::new();
+        let mut units = CLAWBACK_UNITS;
+        let destination_owner = match to {
+            Some(to) => Pubkey::from_str(to).map_err(|e| anyhow!("Invalid destination address: {}", e))?,
+            None => self.wallet.pubkey(),
+        };
+        let destination = mint_info.associated_token_address(&destination_owner);
+
+        let mut summary = vec![
+            format!("Mint:            {}", mint_pubkey),
+            format!("Source owner:    {}", source_state.owner),
+            format!("Source account:  {}", source),
+            format!("Amount:          {}", format_amount(amount, decimals)),
+        ];
+
+        if burn {
+            summary.insert(0, "CLAWBACK (BURN) using permanent delegate authority".to_string());
+            instructions.push(burn_checked(
+                &mint_info.program_id,
+                &source,
+                &mint_pubkey,
+                &self.wallet.pubkey(),
+                &[],
+                amount,
+                decimals,
+            )?);
+        } else {
+            summary.insert(0, "CLAWBACK (TRANSFER) using permanent delegate authority".to_string());
+            summary.push(format!("Destination:     {} (owner {})", destination, destination_owner));
+            instructions.push(
+                spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    &self.wallet.pubkey(),
+                    &destination_owner,
+                    &mint_pubkey,
+                    &mint_info.program_id,
+                ),
+            );
+            units += CREATE_ATA_UNITS;
+            if let Some(text) = transfer_memo(&self.client, &mint_info, &destination_owner, None)? {
+                instructions.push(memo(text, &self.wallet.pubkey()));
+            }
+            instructions.push(transfer_checked(
+                &mint_info.program_id,
+                &source,
+                &mint_pubkey,
+                &destination,
+                &self.wallet.pubkey(),
+                &[],
+                amount,
+                decimals,
+            )?);
+        }
+
+        confirm_typed(&summary, mint_address, assume_yes)?;
+
+        match self.send_instructions(instructions, units) {
+            Ok(signature) => {
+                info!("Clawback completed! Signature: {}", signature);
+                Ok(())
+            }
+            Err(e) => Err(anyhow!("Clawback failed: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{cmp::Reverse, collections::BTreeMap, str::FromStr};
+
+use crate::{
+    holders::{format_amount, merge_by_owner},
+    output::{write_output, OutputFormat},
+    TokenOperations,
+};
+
+/// Wallets listed per group in the log; the output file has all of them.
+const LISTED_HOLDERS: usize = 10;
+
+/// One owner's balances of both mints.
+#[derive(Debug, Clone, Serialize)]
+pub struct HolderComparison {
+    pub owner: String,
+    /// "both", "only_a" or "only_b".
+    pub status: &'static str,
+    pub balance_a: String,
+    pub balance_b: String,
+    pub raw_amount_a: u64,
+    pub raw_amount_b: u64,
+}
+
+/// Holder count and balance of one group on one mint.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupTotals {
+    pub holders: usize,
+    pub balance_a: String,
+    pub balance_b: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HolderOverlap {
+    pub mint_a: String,
+    pub mint_b: String,
+    pub slot_a: u64,
+    pub slot_b: u64,
+    pub both: GroupTotals,
+    pub only_a: GroupTotals,
+    pub only_b: GroupTotals,
+    pub holders: Vec<HolderComparison>,
+}
+
+impl TokenOperations {
+    /// Compare the holders of two mints by owner wallet: who holds both,
+    /// who holds only one, and how much of each mint every group holds.
+    pub fn compare_holders(&self, mint_a: &str, mint_b: &str, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let mint_a = Pubkey::from_str(mint_a).map_err(|e| anyhow!("Invalid --mint-a address: {}", e))?;
+        let mint_b = Pubkey::from_str(mint_b).map_err(|e| anyhow!("Invalid --mint-b address: {}", e))?;
+        if mint_a == mint_b {
+            return Err(anyhow!("--mint-a and --mint-b are the same mint"));
+        }
+        let snapshot_a = self.fetch_holder_snapshot(&mint_a)?;
+        let snapshot_b = self.fetch_holder_snapshot(&mint_b)?;
+
+        let mut amounts: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+        for owner in merge_by_owner(&snapshot_a) {
+            amounts.entry(owner.owner).or_default().0 = owner.amount;
+        }
+        for owner in merge_by_owner(&snapshot_b) {
+            amounts.entry(owner.owner).or_default().1 = owner.amount;
+        }
+
+        let mut holders: Vec<HolderComparison> = amounts
+            .into_iter()
+            .map(|(owner, (a, b))| HolderComparison {
+                owner,
+                status: match (a > 0, b > 0) {
+                    (true, true) => "both",
+                    (true, false) => "only_a",
+                    _ => "only_b",
+                },
+                balance_a: format_amount(a, snapshot_a.decimals),
+                balance_b: format_amount(b, snapshot_b.decimals),
+                raw_amount_a: a,
+                raw_amount_b: b,
+            })
+            .collect();
+        holders.sort_by_key(|holder| Reverse((holder.raw_amount_a, holder.raw_amount_b)));
+
+        let totals = |status: &str| {
+            let group: Vec<&HolderComparison> = holders.iter().filter(|holder| holder.status == status).collect();
+            GroupTotals {
+                holders: group.len(),
+                balance_a: format_amount(group.iter().map(|holder| holder.raw_amount_a).sum(), snapshot_a.decimals),
+                balance_b: format_amount(group.iter().map(|holder| holder.raw_amount_b).sum(), snapshot_b.decimals),
+            }
+        };
+        let (both, only_a, only_b) = (totals("both"), totals("only_a"), totals("only_b"));
+
+        info!("Holders of {} (A) and {} (B):", mint_a, mint_b);
+        info!("  Both:   {} wallet(s) holding {} A and {} B", both.holders, both.balance_a, both.balance_b);
+        info!("  Only A: {} wallet(s) holding {} A", only_a.holders, only_a.balance_a);
+        info!("  Only B: {} wallet(s) holding {} B", only_b.holders, only_b.balance_b);
+        for holder in holders.iter().filter(|holder| holder.status == "both").take(LISTED_HOLDERS) {
+            info!("    {} {} A, {} B", holder.owner, holder.balance_a, holder.balance_b);
+        }
+
+        let overlap = HolderOverlap {
+            mint_a: mint_a.to_string(),
+            mint_b: mint_b.to_string(),
+            slot_a: snapshot_a.slot,
+            slot_b: snapshot_b.slot,
+            both,
+            only_a,
+            only_b,
+            holders,
+        };
+        write_output(output, &overlap, &overlap.holders)
+    }
+}
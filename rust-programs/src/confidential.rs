@@ -0,0 +1,301 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signer,
+    signer::EncodableKey,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::{
+    extension::{
+        confidential_transfer::{
+            account_info::{ApplyPendingBalanceAccountInfo, WithdrawAccountInfo},
+            instruction::{apply_pending_balance, approve_account, configure_account, deposit, withdraw, PubkeyValidityData},
+            ConfidentialTransferAccount, ConfidentialTransferMint,
+        },
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    instruction::reallocate,
+    proof::ProofLocation,
+    solana_zk_token_sdk::encryption::{auth_encryption::AeKey, elgamal::ElGamalKeypair},
+    state::Account,
+};
+use std::{fs, path::Path, str::FromStr};
+
+use crate::{
+    holders::{format_amount, parse_amount},
+    token_program::MintInfo,
+    TokenOperations,
+};
+
+/// Default directory for per-account ElGamal and AES keys.
+pub const DEFAULT_KEYS_DIR: &str = "confidential-keys";
+
+/// Pending-balance credits an account accepts before it must apply them.
+const MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER: u64 = 65_536;
+
+/// Estimated compute units for reallocating and configuring an account,
+/// including the pubkey validity proof.
+const CONFIGURE_UNITS: u32 = 150_000;
+
+/// Estimated compute units for a Deposit, ApplyPendingBalance or ApproveAccount instruction.
+const BALANCE_UNITS: u32 = 30_000;
+
+/// Estimated compute units for a Withdraw with its equality and range proofs.
+const WITHDRAW_UNITS: u32 = 300_000;
+
+/// The ElGamal keypair that encrypts an account's balances and the AES key
+/// that lets the owner decrypt its available balance cheaply.
+struct ConfidentialKeys {
+    elgamal: ElGamalKeypair,
+    aes: AeKey,
+}
+
+fn key_paths(keys_dir: &str, account: &Pubkey) -> (String, String) {
+    let dir = Path::new(keys_dir);
+    (
+        dir.join(format!("{}.elgamal.json", account)).display().to_string(),
+        dir.join(format!("{}.aes.json", account)).display().to_string(),
+    )
+}
+
+impl TokenOperations {
+    /// Keys stored for `account` in `keys_dir`, or else the ones derived from
+    /// the wallet and the account address, the same derivation the
+    /// `spl-token` CLI uses.
+    fn confidential_keys(&self, account: &Pubkey, keys_dir: &str) -> Result<ConfidentialKeys> {
+        let (elgamal_path, aes_path) = key_paths(keys_dir, account);
+        if Path::new(&elgamal_path).exists() && Path::new(&aes_path).exists() {
+            return Ok(ConfidentialKeys {
+                elgamal: ElGamalKeypair::read_from_file(&elgamal_path)
+                    .map_err(|e| anyhow!("Failed to read {}: {}", elgamal_path, e))?,
+                aes: AeKey::read_from_file(&aes_path).map_err(|e| anyhow!("Failed to read {}: {}", aes_path, e))?,
+            });
+        }
+
+        Ok(ConfidentialKeys {
+            elgamal: ElGamalKeypair::new_from_signer(&self.wallet, &account.to_bytes())
+                .map_err(|e| anyhow!("Failed to derive ElGamal key: {}", e))?,
+            aes: AeKey::new_from_signer(&self.wallet, &account.to_bytes())
+                .map_err(|e| anyhow!("Failed to derive AES key: {}", e))?,
+        })
+    }
+
+    /// A confidential-transfer mint and the wallet's ATA of it.
+    fn confidential_mint(&self, mint_address: &str) -> Result<(MintInfo, Pubkey)> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+        if mint_info.state()?.get_extension::<ConfidentialTransferMint>().is_err() {
+            return Err(anyhow!("Mint {} does not have the confidential transfer extension", mint_pubkey));
+        }
+        let account = mint_info.associated_token_address(&self.wallet.pubkey());
+        Ok((mint_info, account))
+    }
+
+    /// The confidential state of a configured token account.
+    fn confidential_state(&self, account: &Pubkey) -> Result<(u64, ConfidentialTransferAccount)> {
+        let data = self.client.get_account(account)
+            .map_err(|e| anyhow!("Failed to get token account {}: {}", account, e))?
+            .data;
+        let state = StateWithExtensions::<Account>::unpack(&data)
+            .map_err(|e| anyhow!("Failed to parse token account: {}", e))?;
+        let extension = *state
+            .get_extension::<ConfidentialTransferAccount>()
+            .map_err(|_| anyhow!("{} is not configured for confidential transfers; run confidential-configure", account))?;
+        Ok((state.base.amount, extension))
+    }
+
+    /// Make the wallet's ATA of a confidential-transfer mint ready to hold a
+    /// confidential balance: create it if needed, grow it for the extension,
+    /// store its ElGamal and AES keys in `keys_dir` and configure it with the
+    /// ElGamal public key and a validity proof.
+    pub fn confidential_configure(&self, mint_address: &str, keys_dir: &str) -> Result<()> {
+        let (mint_info, account) = self.confidential_mint(mint_address)?;
+        let owner = self.wallet.pubkey();
+        let auto_approve = bool::from(mint_info.state()?.get_extension::<ConfidentialTransferMint>()?.auto_approve_new_accounts);
+
+        let keys = self.confidential_keys(&account, keys_dir)?;
+        let (elgamal_path, aes_path) = key_paths(keys_dir, &account);
+        fs::create_dir_all(keys_dir).map_err(|e| anyhow!("Failed to create {}: {}", keys_dir, e))?;
+        if !Path::new(&elgamal_path).exists() {
+            keys.elgamal.write_to_file(&elgamal_path).map_err(|e| anyhow!("Failed to write {}: {}", elgamal_path, e))?;
+        }
+        if !Path::new(&aes_path).exists() {
+            keys.aes.write_to_file(&aes_path).map_err(|e| anyhow!("Failed to write {}: {}", aes_path, e))?;
+        }
+        info!("Keys for {} stored in {}", account, keys_dir);
+        info!("  ElGamal public key: {}", keys.elgamal.pubkey());
+
+        let proof_data = PubkeyValidityData::new(&keys.elgamal)
+            .map_err(|e| anyhow!("Failed to generate pubkey validity proof: {}", e))?;
+        let mut instructions = vec![
+            create_associated_token_account_idempotent(&owner, &owner, &mint_info.address, &mint_info.program_id),
+            reallocate(&mint_info.program_id, &account, &owner, &owner, &[], &[ExtensionType::ConfidentialTransferAccount])?,
+        ];
+        instructions.extend(configure_account(
+            &mint_info.program_id,
+            &account,
+            &mint_info.address,
+            keys.aes.encrypt(0),
+            MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER,
+            &owner,
+            &[],
+            ProofLocation::InstructionOffset(1.try_into()?, &proof_data),
+        )?);
+
+        let signature = self
+            .send_instructions(instructions, CONFIGURE_UNITS)
+            .map_err(|e| anyhow!("Failed to configure {}: {}", account, e))?;
+        info!("Configured {} for confidential transfers! Signature: {}", account, signature);
+        if !auto_approve {
+            warn!("The mint does not auto-approve accounts; its authority must run confidential-approve --token-account {}", account);
+        }
+        Ok(())
+    }
+
+    /// Approve a configured account on a mint that does not auto-approve;
+    /// the loaded wallet must be the mint's confidential transfer authority.
+    pub fn confidential_approve(&self, mint_address: &str, token_account: &str) -> Result<()> {
+        let (mint_info, _) = self.confidential_mint(mint_address)?;
+        let account = Pubkey::from_str(token_account)
+            .map_err(|e| anyhow!("Invalid token account: {}", e))?;
+
+        let instruction = approve_account(&mint_info.program_id, &account, &mint_info.address, &self.wallet.pubkey(), &[])?;
+        let signature = self
+            .send_instructions(vec![instruction], BALANCE_UNITS)
+            .map_err(|e| anyhow!("Failed to approve {}: {}", account, e))?;
+        info!("Approved {} for confidential transfers! Signature: {}", account, signature);
+        Ok(())
+    }
+
+    /// Move `amount` of the public balance into the pending confidential balance.
+    pub fn confidential_deposit(&self, mint_address: &str, amount: &str) -> Result<()> {
+        let (mint_info, account) = self.confidential_mint(mint_address)?;
+        let decimals = mint_info.base()?.decimals;
+        let raw = parse_amount(amount, decimals)?;
+        if raw == 0 {
+            return Err(anyhow!("Amount must be greater than zero"));
+        }
+        let (public, _) = self.confidential_state(&account)?;
+        if raw > public {
+            return Err(anyhow!("Public balance is only {}", format_amount(public, decimals)));
+        }
+
+        let instruction = deposit(
+            &mint_info.program_id,
+            &account,
+            &mint_info.address,
+            raw,
+            decimals,
+            &self.wallet.pubkey(),
+            &[],
+        )?;
+        let signature = self
+            .send_instructions(vec![instruction], BALANCE_UNITS)
+            .map_err(|e| anyhow!("Failed to deposit: {}", e))?;
+        info!("Deposited {} into the pending confidential balance! Signature: {}", format_amount(raw, decimals), signature);
+        info!("Run confidential-apply to make it available");
+        Ok(())
+    }
+
+    /// Fold the pending confidential balance into the available one.
+    pub fn confidential_apply(&self, mint_address: &str, keys_dir: &str) -> Result<()> {
+        let (mint_info, account) = self.confidential_mint(mint_address)?;
+        let keys = self.confidential_keys(&account, keys_dir)?;
+        let (_, extension) = self.confidential_state(&account)?;
+
+        let account_info = ApplyPendingBalanceAccountInfo::new(&extension);
+        let counter = account_info.pending_balance_credit_counter();
+        if counter == 0 {
+            info!("No pending balance to apply");
+            return Ok(());
+        }
+        let new_balance = account_info
+            .new_decryptable_available_balance(keys.elgamal.secret(), &keys.aes)
+            .map_err(|e| anyhow!("Failed to decrypt the pending balance; are these the account's keys? {}", e))?;
+
+        let instruction = apply_pending_balance(
+            &mint_info.program_id,
+            &account,
+            counter,
+            new_balance,
+            &self.wallet.pubkey(),
+            &[],
+        )?;
+        let signature = self
+            .send_instructions(vec![instruction], BALANCE_UNITS)
+            .map_err(|e| anyhow!("Failed to apply pending balance: {}", e))?;
+        info!("Applied {} pending credit(s)! Signature: {}", counter, signature);
+        Ok(())
+    }
+
+    /// Move `amount` of the available confidential balance back to the public balance.
+    pub fn confidential_withdraw(&self, mint_address: &str, amount: &str, keys_dir: &str) -> Result<()> {
+        let (mint_info, account) = self.confidential_mint(mint_address)?;
+        let decimals = mint_info.base()?.decimals;
+        let raw = parse_amount(amount, decimals)?;
+        if raw == 0 {
+            return Err(anyhow!("Amount must be greater than zero"));
+        }
+        let keys = self.confidential_keys(&account, keys_dir)?;
+        let (_, extension) = self.confidential_state(&account)?;
+
+        let account_info = WithdrawAccountInfo::new(&extension);
+        let proof_data = account_info
+            .generate_proof_data(raw, &keys.elgamal, &keys.aes)
+            .map_err(|e| anyhow!("Failed to generate withdraw proof; is the available balance large enough? {}", e))?;
+        let new_balance = account_info
+            .new_decryptable_available_balance(raw, &keys.aes)
+            .map_err(|e| anyhow!("Failed to compute the new available balance: {}", e))?;
+
+        let instructions = withdraw(
+            &mint_info.program_id,
+            &account,
+            &mint_info.address,
+            raw,
+            decimals,
+            new_balance,
+            &self.wallet.pubkey(),
+            &[],
+            ProofLocation::InstructionOffset(1.try_into()?, &proof_data),
+        )?;
+        let signature = self
+            .send_instructions(instructions, WITHDRAW_UNITS)
+            .map_err(|e| anyhow!("Failed to withdraw: {}", e))?;
+        info!("Withdrew {} to the public balance! Signature: {}", format_amount(raw, decimals), signature);
+        Ok(())
+    }
+
+    /// Show the public balance and decrypt the pending and available
+    /// confidential balances of the wallet's ATA.
+    pub fn confidential_balance(&self, mint_address: &str, keys_dir: &str) -> Result<()> {
+        let (mint_info, account) = self.confidential_mint(mint_address)?;
+        let decimals = mint_info.base()?.decimals;
+        let keys = self.confidential_keys(&account, keys_dir)?;
+        let (public, extension) = self.confidential_state(&account)?;
+
+        let decrypt_pending = || -> Option<u64> {
+            let lo = keys.elgamal.secret().decrypt_u32(&extension.pending_balance_lo.try_into().ok()?)?;
+            let hi = keys.elgamal.secret().decrypt_u32(&extension.pending_balance_hi.try_into().ok()?)?;
+            Some(lo + (hi << 16))
+        };
+        let available = extension
+            .decryptable_available_balance
+            .try_into()
+            .ok()
+            .and_then(|ciphertext| keys.aes.decrypt(&ciphertext));
+        let show = |amount: Option<u64>| amount.map(|amount| format_amount(amount, decimals)).unwrap_or_else(|| "undecryptable".to_string());
+
+        info!("Confidential balances of {}:", account);
+        info!("  Approved: {}", bool::from(extension.approved));
+        info!("  Public: {}", format_amount(public, decimals));
+        info!(
+            "  Pending: {} ({} credit(s))",
+            show(decrypt_pending()),
+            u64::from(extension.pending_balance_credit_counter)
+        );
+        info!("  Available: {}", show(available));
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{native_token::lamports_to_sol, program_option::COption, program_pack::Pack, pubkey::Pubkey, signature::Signer};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    instruction::{close_account, transfer_checked},
+    state::{Account, AccountState, Mint},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+use token_ops_core::{
+    instructions::{memo, DEFAULT_TRANSFER_MEMO},
+    mint::requires_incoming_memo,
+};
+
+use crate::{
+    output::{write_output, OutputFormat},
+    TokenOperations,
+};
+
+/// Byte offset of the `owner` field in the base token account layout.
+const ACCOUNT_OWNER_OFFSET: usize = 32;
+
+/// Estimated compute units for an idempotent ATA creation.
+const CREATE_ATA_UNITS: u32 = 40_000;
+
+/// Estimated compute units for a TransferChecked instruction.
+const TRANSFER_UNITS: u32 = 20_000;
+
+/// Estimated compute units for a CloseAccount instruction.
+const CLOSE_UNITS: u32 = 5_000;
+
+/// A token account held by a wallet, with the program that owns it.
+#[derive(Debug, Clone)]
+pub struct OwnedTokenAccount {
+    pub address: Pubkey,
+    pub program_id: Pubkey,
+    pub lamports: u64,
+    pub account: Account,
+    pub data: Vec<u8>,
+}
+
+/// Per-account outcome of a consolidation.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsolidateResult {
+    pub mint: String,
+    pub token_account: String,
+    pub amount: u64,
+    pub status: String,
+    pub signature: Option<String>,
+}
+
+impl TokenOperations {
+    /// Every SPL Token and Token-2022 account owned by `owner`, optionally
+    /// restricted to one mint.
+    pub fn owned_token_accounts(&self, owner: &Pubkey, mint: Option<&Pubkey>) -> Result<Vec<OwnedTokenAccount>> {
+        let mut owned = Vec::new();
+
+        for program_id in [spl_token::id(), spl_token_2022::id()] {
+            let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(ACCOUNT_OWNER_OFFSET, owner.as_ref()))];
+            if let Some(mint) = mint {
+                filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, mint.as_ref())));
+            }
+            if program_id == spl_token::id() {
+                filters.push(RpcFilterType::DataSize(spl_token::state::Account::LEN as u64));
+            }
+
+            let config = RpcProgramAccountsConfig {
+                filters: Some(filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            };
+
+            let accounts = self.client.get_program_accounts_with_config(&program_id, config)
+                .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
+
+            // Token-2022 mints are also 165+ bytes, so skip anything that is not an account.
+            owned.extend(accounts.into_iter().filter_map(|(address, account)| {
+                let state = StateWithExtensions::<Account>::unpack(&account.data).ok()?;
+                Some(OwnedTokenAccount {
+                    address,
+                    program_id,
+                    lamports: account.lamports,
+                    account: state.base,
+                    data: account.data,
+                })
+            }));
+        }
+
+        Ok(owned)
+    }
+
+    /// Move balances from non-ATA token accounts into the wallet's ATA for
+    /// each mint and close the emptied accounts to reclaim their rent.
+    pub fn consolidate(&self, mint_address: Option<&str>, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let mint_filter = mint_address
+            .map(|mint| Pubkey::from_str(mint).map_err(|e| anyhow!("Invalid mint address: {}", e)))
+            .transpose()?;
+        let owner = self.wallet.pubkey();
+
+        let mut by_mint: BTreeMap<Pubkey, Vec<OwnedTokenAccount>> = BTreeMap::new();
+        for owned in self.owned_token_accounts(&owner, mint_filter.as_ref())? {
+            by_mint.entry(owned.account.mint).or_default().push(owned);
+        }
+
+        let mut results: Vec<ConsolidateResult> = Vec::new();
+        // batcher item index -> (result index, lamports reclaimed if closed)
+        let mut queued: HashMap<usize, (usize, u64)> = HashMap::new();
+        let mut batcher = self.batcher();
+
+        for (mint, accounts) in &by_mint {
+            let program_id = accounts[0].program_id;
+            let ata = spl_associated_token_account::get_associated_token_address_with_program_id(&owner, mint, &program_id);
+            let auxiliary: Vec<&OwnedTokenAccount> = accounts.iter().filter(|owned| owned.address != ata).collect();
+            if auxiliary.is_empty() {
+                continue;
+            }
+
+            let mint_account = self.client.get_account(mint)
+                .map_err(|e| anyhow!("Failed to get mint account {}: {}", mint, e))?;
+            let decimals = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+                .map_err(|e| anyhow!("Failed to parse mint {}: {}", mint, e))?
+                .base
+                .decimals;
+
+            info!("Mint {}: consolidating {} auxiliary account(s) into {}", mint, auxiliary.len(), ata);
+            let existing_ata = accounts.iter().find(|owned| owned.address == ata);
+            let mut ata_ready = existing_ata.is_some();
+            let needs_memo = match existing_ata {
+                Some(owned) => requires_incoming_memo(&owned.data)?,
+                None => false,
+            };
+
+            for owned in auxiliary {
+                let mut result = ConsolidateResult {
+                    mint: mint.to_string(),
+                    token_account: owned.address.to_string(),
+                    amount: owned.account.amount,
+                    status: String::new(),
+                    signature: None,
+                };
+
+                if owned.account.state == AccountState::Frozen {
+                    result.status = "skipped: frozen".to_string();
+                    results.push(result);
+                    continue;
+                }
+
+                let mut instructions = Vec::new();
+                let mut units = 0;
+
+                if owned.account.amount > 0 {
+                    if !ata_ready {
+                        instructions.push(
+                            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                                &owner, &owner, mint, &program_id,
+                            ),
+                        );
+                        units += CREATE_ATA_UNITS;
+                        ata_ready = true;
+                    }
+                    if needs_memo {
+                        instructions.push(memo(DEFAULT_TRANSFER_MEMO, &owner));
+                    }
+                    instructions.push(transfer_checked(
+                        &program_id,
+                        &owned.address,
+                        mint,
+                        &ata,
+                        &owner,
+                        &[],
+                        owned.account.amount,
+                        decimals,
+                    )?);
+                    units += TRANSFER_UNITS;
+                }
+
+                let closable = match owned.account.close_authority {
+                    COption::Some(authority) => authority == owner,
+                    COption::None => true,
+                };
+                let reclaimed = if closable {
+                    instructions.push(close_account(&program_id, &owned.address, &owner, &owner, &[])?);
+                    units += CLOSE_UNITS;
+                    owned.lamports
+                } else {
+                    0
+                };
+
+                let item = batcher.push(instructions, units)?;
+                queued.insert(item, (results.len(), reclaimed));
+                results.push(result);
+            }
+        }
+
+        if results.is_empty() {
+            info!("No auxiliary token accounts to consolidate");
+            return Ok(());
+        }
+
+        let mut reclaimed_lamports = 0;
+        for batch in batcher.finish() {
+            for item in &batch.items {
+                let Some((result_index, reclaimed)) = queued.get(item) else {
+                    continue;
+                };
+                let result = &mut results[*result_index];
+                match &batch.signature {
+                    Ok(signature) => {
+                        result.status = if *reclaimed > 0 { "closed" } else { "emptied" }.to_string();
+                        result.signature = Some(signature.to_string());
+                        reclaimed_lamports += reclaimed;
+                    }
+                    Err(e) => result.status = format!("failed: {}", e),
+                }
+            }
+        }
+
+        let failed = results.iter().filter(|r| r.status.starts_with("failed")).count();
+        for result in results.iter().filter(|r| r.status != "closed") {
+            warn!("  {} ({}): {}", result.token_account, result.mint, result.status);
+        }
+        info!(
+            "Consolidated {} account(s), reclaimed {} SOL of rent, {} failed",
+            results.iter().filter(|r| r.signature.is_some()).count(),
+            lamports_to_sol(reclaimed_lamports),
+            failed
+        );
+
+        write_output(output, &results, &results)?;
+
+        if failed > 0 {
+            return Err(anyhow!("{} account(s) could not be consolidated", failed));
+        }
+        Ok(())
+    }
+}
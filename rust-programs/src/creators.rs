@@ -0,0 +1,244 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use mpl_token_metadata::{
+    accounts::Metadata,
+    instructions::{SignMetadataBuilder, UpdateMetadataAccountV2Builder},
+    types::{Creator, DataV2},
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use std::str::FromStr;
+
+use crate::{
+    metadata::{trim_padding, UPDATE_METADATA_UNITS},
+    prompt::confirm,
+    TokenOperations,
+};
+
+/// The Token Metadata program's limit on the creators array.
+const MAX_CREATORS: usize = 5;
+
+/// Estimated compute units for a SignMetadata instruction.
+const SIGN_METADATA_UNITS: u32 = 20_000;
+
+/// Royalties are basis points of the sale price.
+const MAX_SELLER_FEE_BASIS_POINTS: u16 = 10_000;
+
+/// Parse `--creator PUBKEY:SHARE` values; shares are whole percentages
+/// that must add up to 100.
+fn parse_creators(values: &[String]) -> Result<Vec<(Pubkey, u8)>> {
+    if values.is_empty() {
+        return Err(anyhow!("No creators given; pass --creator PUBKEY:SHARE"));
+    }
+    if values.len() > MAX_CREATORS {
+        return Err(anyhow!("At most {} creators are allowed, got {}", MAX_CREATORS, values.len()));
+    }
+
+    let mut creators: Vec<(Pubkey, u8)> = Vec::new();
+    for value in values {
+        let (address, share) = value
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid creator '{}'; expected PUBKEY:SHARE", value))?;
+        let address = Pubkey::from_str(address).map_err(|e| anyhow!("Invalid creator address '{}': {}", address, e))?;
+        let share: u8 = share.parse().map_err(|e| anyhow!("Invalid share in '{}': {}", value, e))?;
+        if creators.iter().any(|(existing, _)| *existing == address) {
+            return Err(anyhow!("Creator {} is listed twice", address));
+        }
+        creators.push((address, share));
+    }
+
+    let total: u32 = creators.iter().map(|(_, share)| *share as u32).sum();
+    if total != 100 {
+        return Err(anyhow!("Creator shares add up to {}, not 100", total));
+    }
+    Ok(creators)
+}
+
+fn creator_line(creator: &Creator) -> String {
+    format!(
+        "  Creator {}: {}%{}",
+        creator.address,
+        creator.share,
+        if creator.verified { " (verified)" } else { " (unverified)" }
+    )
+}
+
+/// The royalty and creators of `metadata`, one line each.
+fn royalty_lines(metadata: &Metadata) -> Vec<String> {
+    let mut lines = vec![format!(
+        "  Royalty: {} bps ({:.2}%)",
+        metadata.seller_fee_basis_points,
+        metadata.seller_fee_basis_points as f64 / 100.0
+    )];
+    match &metadata.creators {
+        Some(creators) if !creators.is_empty() => lines.extend(creators.iter().map(creator_line)),
+        _ => lines.push("  Creators: none".to_string()),
+    }
+    lines
+}
+
+/// The metadata's current data with `creators` and the royalty replaced.
+fn updated_data(metadata: &Metadata, creators: Option<Vec<Creator>>, seller_fee_basis_points: u16) -> DataV2 {
+    DataV2 {
+        name: trim_padding(&metadata.name).to_string(),
+        symbol: trim_padding(&metadata.symbol).to_string(),
+        uri: trim_padding(&metadata.uri).to_string(),
+        seller_fee_basis_points,
+        creators,
+        collection: metadata.collection.clone(),
+        uses: metadata.uses.clone(),
+    }
+}
+
+impl TokenOperations {
+    /// Metadata of a mint the loaded wallet may update.
+    fn updatable_metadata(&self, mint_pubkey: &Pubkey) -> Result<(Pubkey, Metadata)> {
+        let (metadata_address, metadata) = self
+            .fetch_metadata(mint_pubkey)?
+            .ok_or_else(|| anyhow!("Mint {} has no Metaplex metadata account", mint_pubkey))?;
+        if !metadata.is_mutable {
+            return Err(anyhow!("Metadata {} is immutable; creators and royalties can no longer change", metadata_address));
+        }
+        if metadata.update_authority != self.wallet.pubkey() {
+            return Err(anyhow!(
+                "Loaded wallet is not the update authority of this metadata; {} is",
+                metadata.update_authority
+            ));
+        }
+        Ok((metadata_address, metadata))
+    }
+
+    fn update_metadata_data(&self, mint_pubkey: &Pubkey, metadata_address: Pubkey, data: DataV2) -> Result<Metadata> {
+        let instruction = UpdateMetadataAccountV2Builder::new()
+            .metadata(metadata_address)
+            .update_authority(self.wallet.pubkey())
+            .data(data)
+            .instruction();
+        let signature = self
+            .send_instructions(vec![instruction], UPDATE_METADATA_UNITS)
+            .map_err(|e| anyhow!("Failed to update metadata: {}", e))?;
+        info!("Metadata updated! Signature: {}", signature);
+
+        let (_, after) = self
+            .fetch_metadata(mint_pubkey)?
+            .ok_or_else(|| anyhow!("Metadata account {} disappeared after the update", metadata_address))?;
+        Ok(after)
+    }
+
+    /// Replace the creators array. Creators already verified stay verified
+    /// and the wallet verifies itself as update authority; every other
+    /// creator starts unverified and must run `sign-metadata` themselves.
+    pub fn set_creators(&self, mint_address: &str, creators: &[String], assume_yes: bool) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let creators = parse_creators(creators)?;
+        let (metadata_address, metadata) = self.updatable_metadata(&mint_pubkey)?;
+
+        let wallet = self.wallet.pubkey();
+        let previous = metadata.creators.clone().unwrap_or_default();
+        let new_creators: Vec<Creator> = creators
+            .iter()
+            .map(|(address, share)| Creator {
+                address: *address,
+                verified: *address == wallet
+                    || previous.iter().any(|creator| creator.address == *address && creator.verified),
+                share: *share,
+            })
+            .collect();
+
+        let mut lines = vec![format!("Replace the creators of {}", mint_pubkey), "Current:".to_string()];
+        lines.extend(royalty_lines(&metadata));
+        lines.push("New:".to_string());
+        lines.extend(new_creators.iter().map(creator_line));
+        confirm(&lines, assume_yes)?;
+
+        let data = updated_data(&metadata, Some(new_creators), metadata.seller_fee_basis_points);
+        let after = self.update_metadata_data(&mint_pubkey, metadata_address, data)?;
+        let on_chain: Vec<(Pubkey, u8)> = after
+            .creators
+            .iter()
+            .flatten()
+            .map(|creator| (creator.address, creator.share))
+            .collect();
+        if on_chain != creators {
+            return Err(anyhow!("Creators on-chain do not match the requested ones after the update"));
+        }
+        info!("Verified on-chain:");
+        for line in royalty_lines(&after) {
+            info!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Set the royalty (seller_fee_basis_points) of a mint's metadata.
+    pub fn set_royalty(&self, mint_address: &str, basis_points: u16, assume_yes: bool) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        if basis_points > MAX_SELLER_FEE_BASIS_POINTS {
+            return Err(anyhow!("Royalty {} bps exceeds {} (100%)", basis_points, MAX_SELLER_FEE_BASIS_POINTS));
+        }
+        let (metadata_address, metadata) = self.updatable_metadata(&mint_pubkey)?;
+        if metadata.seller_fee_basis_points == basis_points {
+            info!("Royalty is already {} bps; nothing to do", basis_points);
+            return Ok(());
+        }
+
+        confirm(
+            &[format!(
+                "Royalty of {}: {} bps -> {} bps ({:.2}%)",
+                mint_pubkey,
+                metadata.seller_fee_basis_points,
+                basis_points,
+                basis_points as f64 / 100.0
+            )],
+            assume_yes,
+        )?;
+
+        let data = updated_data(&metadata, metadata.creators.clone(), basis_points);
+        let after = self.update_metadata_data(&mint_pubkey, metadata_address, data)?;
+        if after.seller_fee_basis_points != basis_points {
+            return Err(anyhow!("Royalty is {} bps after the update, not {}", after.seller_fee_basis_points, basis_points));
+        }
+        info!("Verified on-chain: royalty is now {} bps", basis_points);
+        Ok(())
+    }
+
+    /// Verify the loaded wallet as a creator of a mint's metadata.
+    pub fn sign_metadata(&self, mint_address: &str) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let (metadata_address, metadata) = self
+            .fetch_metadata(&mint_pubkey)?
+            .ok_or_else(|| anyhow!("Mint {} has no Metaplex metadata account", mint_pubkey))?;
+
+        let wallet = self.wallet.pubkey();
+        let creator = metadata
+            .creators
+            .iter()
+            .flatten()
+            .find(|creator| creator.address == wallet)
+            .ok_or_else(|| anyhow!("Loaded wallet {} is not a creator of {}", wallet, mint_pubkey))?;
+        if creator.verified {
+            info!("{} is already a verified creator of {}", wallet, mint_pubkey);
+            return Ok(());
+        }
+
+        let instruction = SignMetadataBuilder::new()
+            .creator(wallet)
+            .metadata(metadata_address)
+            .instruction();
+        let signature = self
+            .send_instructions(vec![instruction], SIGN_METADATA_UNITS)
+            .map_err(|e| anyhow!("Failed to sign metadata: {}", e))?;
+        info!("Metadata signed! Signature: {}", signature);
+
+        let (_, after) = self
+            .fetch_metadata(&mint_pubkey)?
+            .ok_or_else(|| anyhow!("Metadata account {} disappeared after signing", metadata_address))?;
+        let verified = after.creators.iter().flatten().any(|creator| creator.address == wallet && creator.verified);
+        if !verified {
+            return Err(anyhow!("{} is still unverified after signing", wallet));
+        }
+        info!("Verified on-chain: {} is a verified creator", wallet);
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcAccountInfoConfig};
+use solana_sdk::{commitment_config::CommitmentConfig, program_option::COption, pubkey::Pubkey};
+use spl_token_2022::{extension::StateWithExtensions, state::Mint};
+use std::thread;
+
+use crate::{providers::redact_url, telemetry::rpc_client, TokenOperations};
+
+/// Reads per endpoint before a disagreement is reported; endpoints that
+/// answered at different slots get another chance to converge.
+const CROSS_CHECK_ATTEMPTS: usize = 3;
+
+/// The mint fields compared across endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MintView {
+    supply: u64,
+    decimals: u8,
+    mint_authority: COption<Pubkey>,
+    freeze_authority: COption<Pubkey>,
+}
+
+/// One endpoint's answer: the slot it read at and what it saw.
+type EndpointRead = std::result::Result<(u64, Option<MintView>), String>;
+
+impl TokenOperations {
+    /// Use additional RPC endpoints to confirm critical reads.
+    pub fn with_cross_check(mut self, urls: &[String]) -> Self {
+        self.cross_check = urls
+            .iter()
+            .map(|url| rpc_client(url, CommitmentConfig::finalized()))
+            .collect();
+        self
+    }
+
+    /// Read the mint's supply and authorities from the primary and every
+    /// cross-check endpoint at the same finalized slot, failing if any
+    /// endpoint disagrees or cannot serve that slot. A no-op without
+    /// `--cross-check-rpc`.
+    pub fn cross_check_mint(&self, mint: &Pubkey) -> Result<()> {
+        if self.cross_check.is_empty() {
+            return Ok(());
+        }
+
+        let primary = rpc_client(&self.client.url(), CommitmentConfig::finalized());
+        let endpoints: Vec<&RpcClient> = std::iter::once(&primary).chain(&self.cross_check).collect();
+
+        for attempt in 1..=CROSS_CHECK_ATTEMPTS {
+            let slots: Vec<std::result::Result<u64, String>> = fan_out(&endpoints, |client| {
+                client.get_slot().map_err(|e| e.to_string())
+            });
+            let target = slots.iter().filter_map(|slot| slot.as_ref().ok()).copied().max()
+                .ok_or_else(|| anyhow!("No RPC endpoint answered getSlot"))?;
+
+            let reads: Vec<EndpointRead> = fan_out(&endpoints, |client| read_mint(client, mint, target));
+
+            let mut failures = Vec::new();
+            let mut views = Vec::new();
+            for (client, read) in endpoints.iter().zip(&reads) {
+                match read {
+                    Ok((slot, view)) => views.push((redact_url(&client.url()), *slot, view.clone())),
+                    Err(e) => failures.push(format!("{}: {}", redact_url(&client.url()), e)),
+                }
+            }
+            if !failures.is_empty() {
+                return Err(anyhow!("Cross-check failed at slot {}:\n  {}", target, failures.join("\n  ")));
+            }
+
+            let (_, _, reference) = &views[0];
+            if views.iter().all(|(_, _, view)| view == reference) {
+                info!("  Cross-checked against {} endpoint(s) at slot {}+", endpoints.len(), target);
+                return Ok(());
+            }
+
+            let same_slot = views.iter().all(|(_, slot, _)| *slot == views[0].1);
+            let report: Vec<String> = views
+                .iter()
+                .map(|(url, slot, view)| format!("{} (slot {}): {}", url, slot, describe(view.as_ref())))
+                .collect();
+            if same_slot || attempt == CROSS_CHECK_ATTEMPTS {
+                return Err(anyhow!("RPC endpoints disagree about {}:\n  {}", mint, report.join("\n  ")));
+            }
+            warn!("Endpoints answered at different slots and disagree; retrying ({}/{})", attempt, CROSS_CHECK_ATTEMPTS);
+        }
+
+        unreachable!("the last attempt always returns")
+    }
+}
+
+/// Run `read` against every endpoint concurrently, in endpoint order.
+fn fan_out<T: Send>(endpoints: &[&RpcClient], read: impl Fn(&RpcClient) -> T + Sync) -> Vec<T> {
+    let read = &read;
+    thread::scope(|scope| {
+        let handles: Vec<_> = endpoints.iter().map(|client| scope.spawn(move || read(client))).collect();
+        handles.into_iter().map(|handle| handle.join().expect("cross-check thread panicked")).collect()
+    })
+}
+
+fn read_mint(client: &RpcClient, mint: &Pubkey, min_slot: u64) -> EndpointRead {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(CommitmentConfig::finalized()),
+        min_context_slot: Some(min_slot),
+        ..RpcAccountInfoConfig::default()
+    };
+    let response = client.get_account_with_config(mint, config).map_err(|e| e.to_string())?;
+
+    let view = match response.value {
+        Some(account) => {
+            let state = StateWithExtensions::<Mint>::unpack(&account.data).map_err(|e| format!("not a mint: {}", e))?;
+            Some(MintView {
+                supply: state.base.supply,
+                decimals: state.base.decimals,
+                mint_authority: state.base.mint_authority,
+                freeze_authority: state.base.freeze_authority,
+            })
+        }
+        None => None,
+    };
+    Ok((response.context.slot, view))
+}
+
+fn describe(view: Option<&MintView>) -> String {
+    match view {
+        Some(view) => format!(
+            "supply {}, decimals {}, mint authority {:?}, freeze authority {:?}",
+            view.supply, view.decimals, view.mint_authority, view.freeze_authority
+        ),
+        None => "account not found".to_string(),
+    }
+}
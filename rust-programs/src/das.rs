@@ -0,0 +1,418 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::{cmp::Reverse, str::FromStr, time::Duration};
+
+use crate::{
+    checkpoint::Scan,
+    holders::{HolderBalance, HolderSnapshot},
+    output::{write_output, OutputFormat},
+    providers::redact_url,
+    rpc_headers,
+    TokenOperations,
+};
+
+/// Largest page the DAS methods return.
+const PAGE_LIMIT: usize = 1000;
+
+/// DAS request timeout; large pages take a few seconds.
+const DAS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// JSON-RPC "method not found".
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Where holder snapshots come from, chosen with `--backend`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// getProgramAccounts on any RPC node.
+    Rpc,
+    /// DAS methods, failing if the node does not support them.
+    Das,
+    /// DAS when the node supports it, otherwise getProgramAccounts.
+    Auto,
+}
+
+impl FromStr for Backend {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "rpc" => Ok(Backend::Rpc),
+            "das" => Ok(Backend::Das),
+            "auto" => Ok(Backend::Auto),
+            other => Err(anyhow!("Unknown backend '{}'; use rpc, das or auto", other)),
+        }
+    }
+}
+
+/// Client for the Digital Asset Standard read API served by providers such as Helius.
+#[derive(Debug)]
+pub struct DasClient {
+    http: reqwest::blocking::Client,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// Progress of a getTokenAccounts walk, saved when it stops early.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TokenAccountPages {
+    cursor: Option<String>,
+    holders: Vec<HolderBalance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenAccountsPage {
+    #[serde(default)]
+    token_accounts: Vec<DasTokenAccount>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DasTokenAccount {
+    address: String,
+    owner: String,
+    amount: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetsPage {
+    #[serde(default)]
+    items: Vec<DasAsset>,
+}
+
+/// The parts of a DAS asset this tool reports.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DasAsset {
+    pub id: String,
+    pub interface: String,
+    pub content: AssetContent,
+    pub authorities: Vec<AssetAuthority>,
+    pub grouping: Vec<AssetGroup>,
+    pub royalty: AssetRoyalty,
+    pub creators: Vec<AssetCreator>,
+    pub ownership: AssetOwnership,
+    pub compression: AssetCompression,
+    pub mutable: bool,
+    pub burnt: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetContent {
+    pub json_uri: String,
+    pub metadata: AssetMetadata,
+    pub links: AssetLinks,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetLinks {
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetAuthority {
+    pub address: String,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetGroup {
+    pub group_key: String,
+    pub group_value: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetRoyalty {
+    pub basis_points: u64,
+    pub primary_sale_happened: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetCreator {
+    pub address: String,
+    pub share: u64,
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetOwnership {
+    pub owner: String,
+    pub frozen: bool,
+    pub delegated: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssetCompression {
+    pub compressed: bool,
+}
+
+impl DasAsset {
+    fn collection(&self) -> Option<&str> {
+        self.grouping
+            .iter()
+            .find(|group| group.group_key == "collection")
+            .map(|group| group.group_value.as_str())
+    }
+}
+
+/// One asset as a flat CSV row.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetRow {
+    pub id: String,
+    pub name: String,
+    pub symbol: String,
+    pub owner: String,
+    pub collection: String,
+    pub uri: String,
+    pub royalty_bps: u64,
+    pub compressed: bool,
+    pub burnt: bool,
+}
+
+impl From<&DasAsset> for AssetRow {
+    fn from(asset: &DasAsset) -> Self {
+        Self {
+            id: asset.id.clone(),
+            name: asset.content.metadata.name.clone(),
+            symbol: asset.content.metadata.symbol.clone(),
+            owner: asset.ownership.owner.clone(),
+            collection: asset.collection().unwrap_or_default().to_string(),
+            uri: asset.content.json_uri.clone(),
+            royalty_bps: asset.royalty.basis_points,
+            compressed: asset.compression.compressed,
+            burnt: asset.burnt,
+        }
+    }
+}
+
+impl DasClient {
+    pub fn new(url: &str) -> Result<Self> {
+        let http = reqwest::blocking::Client::builder()
+            .default_headers(rpc_headers::header_map())
+            .timeout(DAS_TIMEOUT)
+            .build()
+            .map_err(|e| anyhow!("Failed to create HTTP client: {}", e))?;
+        Ok(Self { http, url: url.to_string() })
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        self.try_call(method, params)?
+            .map_err(|e| anyhow!("{} failed: {} ({})", method, e.message, e.code))
+    }
+
+    fn try_call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<std::result::Result<T, RpcError>> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: RpcResponse<T> = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .and_then(|response| response.json())
+            .map_err(|e| anyhow!("{} request failed: {}", method, e))?;
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Ok(Err(error)),
+            (Some(result), None) => Ok(Ok(result)),
+            (None, None) => Err(anyhow!("{} returned neither a result nor an error", method)),
+        }
+    }
+
+    /// Whether the node serves DAS methods.
+    pub fn supported(&self) -> bool {
+        let probe = json!({ "mint": spl_token::native_mint::id().to_string(), "limit": 1 });
+        match self.try_call::<TokenAccountsPage>("getTokenAccounts", probe) {
+            Ok(Ok(_)) => true,
+            Ok(Err(error)) if error.code == METHOD_NOT_FOUND => false,
+            Ok(Err(error)) => {
+                warn!("DAS probe failed: {}", error.message);
+                false
+            }
+            Err(e) => {
+                warn!("DAS probe failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Every funded token account of a mint, via cursor-paginated getTokenAccounts,
+    /// saving progress to `scan` between pages.
+    pub fn token_accounts(&self, mint: &Pubkey, scan: &Scan) -> Result<Vec<HolderBalance>> {
+        let mut pages: TokenAccountPages = scan.resume()?.unwrap_or_default();
+
+        loop {
+            let mut params = json!({ "mint": mint.to_string(), "limit": PAGE_LIMIT });
+            if let Some(cursor) = &pages.cursor {
+                params["cursor"] = json!(cursor);
+            }
+            let page: TokenAccountsPage = self.call("getTokenAccounts", params).map_err(|e| scan.fail(&pages, e))?;
+            let count = page.token_accounts.len();
+
+            pages.holders.extend(page.token_accounts.into_iter().filter(|account| account.amount > 0).map(|account| {
+                HolderBalance { account: account.address, owner: account.owner, amount: account.amount }
+            }));
+
+            pages.cursor = page.cursor;
+            if count < PAGE_LIMIT || pages.cursor.is_none() {
+                return Ok(pages.holders);
+            }
+            scan.checkpoint(&pages)?;
+        }
+    }
+
+    pub fn get_asset(&self, id: &Pubkey) -> Result<DasAsset> {
+        self.call("getAsset", json!({ "id": id.to_string() }))
+    }
+
+    /// Every asset matching the searchAssets `criteria`, page by page.
+    pub fn search_assets(&self, criteria: Value) -> Result<Vec<DasAsset>> {
+        let mut assets = Vec::new();
+
+        for page in 1.. {
+            let mut params = criteria.clone();
+            params["page"] = json!(page);
+            params["limit"] = json!(PAGE_LIMIT);
+            let result: AssetsPage = self.call("searchAssets", params)?;
+            let count = result.items.len();
+            assets.extend(result.items);
+            if count < PAGE_LIMIT {
+                break;
+            }
+        }
+
+        Ok(assets)
+    }
+}
+
+impl TokenOperations {
+    /// Use DAS for holder snapshots according to `--backend`.
+    pub fn with_backend(mut self, backend: Backend) -> Result<Self> {
+        if backend == Backend::Rpc {
+            return Ok(self);
+        }
+
+        let das = DasClient::new(&self.client.url())?;
+        match (backend, das.supported()) {
+            (_, true) => {
+                info!("Using the DAS API for holder snapshots");
+                self.das = Some(das);
+            }
+            (Backend::Das, false) => {
+                return Err(anyhow!("{} does not support the DAS API", redact_url(&self.client.url())))
+            }
+            _ => info!("RPC does not support the DAS API; using getProgramAccounts"),
+        }
+        Ok(self)
+    }
+
+    /// A DAS client for the configured RPC, whether or not `--backend` enabled it.
+    fn das_client(&self) -> Result<DasClient> {
+        DasClient::new(&self.client.url())
+    }
+
+    /// Holder snapshot built from getTokenAccounts. Unlike the
+    /// getProgramAccounts path this covers Token-2022 mints too.
+    pub fn das_holder_snapshot(&self, das: &DasClient, mint_pubkey: &Pubkey) -> Result<HolderSnapshot> {
+        info!("Taking holder snapshot for mint {} via DAS", mint_pubkey);
+
+        let mint = self.fetch_mint_info(mint_pubkey)?.base()?;
+        let slot = self.client.get_slot()
+            .map_err(|e| anyhow!("Failed to get current slot: {}", e))?;
+
+        let scan = self.checkpoints.scan(&format!("das-holders-{}", mint_pubkey));
+        let mut holders = das.token_accounts(mint_pubkey, &scan)?;
+        scan.finish()?;
+        holders.sort_by_key(|holder| Reverse(holder.amount));
+        info!("Snapshot at slot {}: {} funded account(s)", slot, holders.len());
+
+        Ok(HolderSnapshot {
+            mint: mint_pubkey.to_string(),
+            slot,
+            supply: mint.supply,
+            decimals: mint.decimals,
+            holders,
+        })
+    }
+
+    /// Show one asset (fungible or NFT) as DAS describes it.
+    pub fn asset_info(&self, id: &str, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let id = Pubkey::from_str(id).map_err(|e| anyhow!("Invalid asset address: {}", e))?;
+        let asset = self.das_client()?.get_asset(&id)?;
+
+        info!("Asset {} ({})", asset.id, asset.interface);
+        info!("  Name: {}", asset.content.metadata.name);
+        info!("  Symbol: {}", asset.content.metadata.symbol);
+        info!("  URI: {}", asset.content.json_uri);
+        if let Some(image) = &asset.content.links.image {
+            info!("  Image: {}", image);
+        }
+        info!("  Owner: {}", asset.ownership.owner);
+        if let Some(collection) = asset.collection() {
+            info!("  Collection: {}", collection);
+        }
+        for authority in &asset.authorities {
+            info!("  Authority: {} ({})", authority.address, authority.scopes.join(", "));
+        }
+        info!("  Royalty: {} bps", asset.royalty.basis_points);
+        for creator in &asset.creators {
+            info!("  Creator: {} {}%{}", creator.address, creator.share, if creator.verified { " (verified)" } else { "" });
+        }
+        info!("  Mutable: {}, compressed: {}, burnt: {}", asset.mutable, asset.compression.compressed, asset.burnt);
+
+        write_output(output, &asset, &[AssetRow::from(&asset)])
+    }
+
+    /// List the assets held by `owner` and/or in `collection` via searchAssets.
+    pub fn search_assets(&self, owner: Option<&str>, collection: Option<&str>, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let mut criteria = json!({});
+        if let Some(owner) = owner {
+            Pubkey::from_str(owner).map_err(|e| anyhow!("Invalid owner address: {}", e))?;
+            criteria["ownerAddress"] = json!(owner);
+        }
+        if let Some(collection) = collection {
+            Pubkey::from_str(collection).map_err(|e| anyhow!("Invalid collection address: {}", e))?;
+            criteria["grouping"] = json!(["collection", collection]);
+        }
+        if owner.is_none() && collection.is_none() {
+            return Err(anyhow!("Pass --owner and/or --collection to search assets"));
+        }
+
+        let assets = self.das_client()?.search_assets(criteria)?;
+        let rows: Vec<AssetRow> = assets.iter().map(AssetRow::from).collect();
+
+        info!("Found {} asset(s):", rows.len());
+        for row in &rows {
+            info!("  {} {} ({}) owner {}", row.id, row.name, row.symbol, row.owner);
+        }
+
+        write_output(output, &assets, &rows)
+    }
+}
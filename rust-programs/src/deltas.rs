@@ -0,0 +1,199 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::UiTransactionTokenBalance;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+    str::FromStr,
+    thread,
+    time::Duration,
+};
+
+use crate::{history::HistoricalTransaction, holders::format_amount, TokenOperations};
+
+/// A token account being watched and the newest signature already seen for it.
+struct WatchedAccount {
+    wallet: Pubkey,
+    mint: Pubkey,
+    cursor: Option<String>,
+}
+
+/// One balance change of a watched token account, printed as an NDJSON line.
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceChange {
+    pub wallet: String,
+    pub mint: String,
+    pub token_account: String,
+    pub old: String,
+    pub new: String,
+    /// Signed, e.g. "-1.5".
+    pub delta: String,
+    pub raw_old: u64,
+    pub raw_new: u64,
+    pub raw_delta: i128,
+    pub decimals: u8,
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+/// The raw amount and decimals of `account_index` in a token balance list.
+fn token_balance(balances: &[UiTransactionTokenBalance], account_index: usize) -> Option<(u64, u8)> {
+    balances
+        .iter()
+        .find(|balance| balance.account_index as usize == account_index)
+        .and_then(|balance| Some((balance.ui_token_amount.amount.parse().ok()?, balance.ui_token_amount.decimals)))
+}
+
+/// How `token_account` changed in a transaction, if it did. An account the
+/// transaction created or closed counts as a zero balance on that side.
+fn balance_change(transaction: &HistoricalTransaction, token_account: &Pubkey, watched: &WatchedAccount) -> Option<BalanceChange> {
+    let index = transaction.account_keys.iter().position(|key| key == token_account)?;
+    let pre = token_balance(&transaction.pre_token_balances(), index);
+    let post = token_balance(&transaction.post_token_balances(), index);
+    let decimals = post.or(pre)?.1;
+    let (raw_old, raw_new) = (pre.map_or(0, |(amount, _)| amount), post.map_or(0, |(amount, _)| amount));
+    if raw_old == raw_new {
+        return None;
+    }
+
+    let raw_delta = raw_new as i128 - raw_old as i128;
+    let sign = if raw_delta < 0 { "-" } else { "+" };
+    Some(BalanceChange {
+        wallet: watched.wallet.to_string(),
+        mint: watched.mint.to_string(),
+        token_account: token_account.to_string(),
+        old: format_amount(raw_old, decimals),
+        new: format_amount(raw_new, decimals),
+        delta: format!("{}{}", sign, format_amount(raw_new.abs_diff(raw_old), decimals)),
+        raw_old,
+        raw_new,
+        raw_delta,
+        decimals,
+        signature: transaction.signature.clone(),
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+    })
+}
+
+impl TokenOperations {
+    /// Follow the token accounts of `wallets`, limited to `mints` unless
+    /// empty, and print one NDJSON line on stdout per balance change with
+    /// the transaction that caused it. Logs go to stderr, so the output can
+    /// be piped into other tools. Runs until interrupted or stdout closes.
+    ///
+    /// Every poll reads each watched account's new signatures, so the cost
+    /// grows with the number of accounts. The wallets' own signatures are
+    /// read too, to pick up token accounts created after the start.
+    pub fn watch_balances(&self, wallets: &[Pubkey], mints: &[Pubkey], interval: u64) -> Result<()> {
+        if wallets.is_empty() {
+            return Err(anyhow!("Nothing to watch; pass --owner or --addresses-file"));
+        }
+        let wanted = |mint: &Pubkey| mints.is_empty() || mints.contains(mint);
+
+        let mut watched: BTreeMap<Pubkey, WatchedAccount> = BTreeMap::new();
+        let mut wallet_cursors: HashMap<Pubkey, Option<String>> = HashMap::new();
+        for wallet in wallets {
+            for owned in self.owned_token_accounts(wallet, None)? {
+                if !wanted(&owned.account.mint) {
+                    continue;
+                }
+                let newest = self.signatures_since(&owned.address, None, Some(1))?.into_iter().next();
+                watched.insert(
+                    owned.address,
+                    WatchedAccount { wallet: *wallet, mint: owned.account.mint, cursor: newest.map(|status| status.signature) },
+                );
+            }
+            let newest = self.signatures_since(wallet, None, Some(1))?.into_iter().next();
+            wallet_cursors.insert(*wallet, newest.map(|status| status.signature));
+        }
+        info!("Watching {} token account(s) of {} wallet(s) every {}s", watched.len(), wallets.len(), interval);
+
+        let mut stdout = io::stdout();
+        loop {
+            thread::sleep(Duration::from_secs(interval));
+            // Transactions touching several watched accounts are fetched once per poll.
+            let mut fetched: HashMap<String, HistoricalTransaction> = HashMap::new();
+
+            for wallet in wallets {
+                let cursor = wallet_cursors.get(wallet).cloned().flatten();
+                let signatures = match self.signatures_since(wallet, cursor.as_deref(), None) {
+                    Ok(signatures) => signatures,
+                    Err(e) => {
+                        warn!("Poll of {} failed, retrying: {}", wallet, e);
+                        continue;
+                    }
+                };
+                for status in signatures.iter().rev() {
+                    if status.err.is_none() {
+                        let transaction = match self.fetch_cached(&mut fetched, &status.signature) {
+                            Ok(transaction) => transaction,
+                            Err(e) => {
+                                warn!("Failed to fetch {}, retrying next poll: {}", status.signature, e);
+                                break;
+                            }
+                        };
+                        for balance in transaction.post_token_balances() {
+                            let owner = Option::<String>::from(balance.owner);
+                            let Some(account) = transaction.account_keys.get(balance.account_index as usize) else {
+                                continue;
+                            };
+                            let Ok(mint) = Pubkey::from_str(&balance.mint) else {
+                                continue;
+                            };
+                            if owner != Some(wallet.to_string()) || !wanted(&mint) || watched.contains_key(account) {
+                                continue;
+                            }
+                            info!("Watching new token account {} of {}", account, wallet);
+                            // New, so its whole history is read on this poll.
+                            watched.insert(*account, WatchedAccount { wallet: *wallet, mint, cursor: None });
+                        }
+                    }
+                    wallet_cursors.insert(*wallet, Some(status.signature.clone()));
+                }
+            }
+
+            for (address, account) in watched.iter_mut() {
+                let signatures = match self.signatures_since(address, account.cursor.as_deref(), None) {
+                    Ok(signatures) => signatures,
+                    Err(e) => {
+                        warn!("Poll of {} failed, retrying: {}", address, e);
+                        continue;
+                    }
+                };
+
+                // Oldest first, and the cursor only moves past fetched transactions.
+                for status in signatures.iter().rev() {
+                    if status.err.is_none() {
+                        let transaction = match self.fetch_cached(&mut fetched, &status.signature) {
+                            Ok(transaction) => transaction,
+                            Err(e) => {
+                                warn!("Failed to fetch {}, retrying next poll: {}", status.signature, e);
+                                break;
+                            }
+                        };
+                        if let Some(change) = balance_change(transaction, address, account) {
+                            writeln!(stdout, "{}", serde_json::to_string(&change)?)
+                                .map_err(|e| anyhow!("Failed to write to stdout: {}", e))?;
+                        }
+                    }
+                    account.cursor = Some(status.signature.clone());
+                }
+            }
+        }
+    }
+
+    fn fetch_cached<'a>(
+        &self,
+        fetched: &'a mut HashMap<String, HistoricalTransaction>,
+        signature: &str,
+    ) -> Result<&'a HistoricalTransaction> {
+        if !fetched.contains_key(signature) {
+            let transaction = self.fetch_transaction(signature)?;
+            fetched.insert(signature.to_string(), transaction);
+        }
+        Ok(&fetched[signature])
+    }
+}
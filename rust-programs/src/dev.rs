@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Serialize;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    native_token::sol_to_lamports,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_token_2022::instruction::mint_to_checked;
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    holders::parse_amount,
+    mint::MintConfig,
+    output::write_json,
+    read_keypair,
+    telemetry::rpc_client,
+    write_keypair, TokenOperations,
+};
+
+/// RPC endpoint of a default `solana-test-validator`.
+pub const LOCALNET_URL: &str = "http://127.0.0.1:8899";
+
+/// Ledger directory used by the bootstrapped validator.
+const LEDGER_DIR: &str = "test-ledger";
+
+/// How long to wait for the validator to answer getHealth.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// SOL airdropped to the throwaway wallet.
+const AIRDROP_SOL: f64 = 100.0;
+
+/// Tokens minted to the wallet for each fixture mint.
+const FIXTURE_SUPPLY: &str = "1000000";
+
+/// Estimated compute units for an idempotent ATA creation plus MintToChecked.
+const FUND_UNITS: u32 = 60_000;
+
+/// A test mint created by the bootstrap.
+#[derive(Debug, Clone, Serialize)]
+pub struct FixtureMint {
+    pub name: String,
+    pub symbol: String,
+    pub address: String,
+    pub program_id: String,
+    pub decimals: u8,
+    pub supply: String,
+}
+
+/// Addresses written to the fixtures file.
+#[derive(Debug, Clone, Serialize)]
+pub struct Fixtures {
+    pub rpc_url: String,
+    pub wallet: String,
+    pub wallet_path: String,
+    pub validator_pid: Option<u32>,
+    pub ledger: String,
+    pub mints: Vec<FixtureMint>,
+}
+
+/// Start a local validator (unless one is already running), fund a
+/// throwaway wallet at `wallet_path` and create fixture mints, writing
+/// everything to `fixtures_path`. The validator is left running.
+pub fn bootstrap(wallet_path: &str, fixtures_path: &str) -> Result<()> {
+    let client = rpc_client(LOCALNET_URL, CommitmentConfig::confirmed());
+
+    let validator_pid = if client.get_health().is_ok() {
+        info!("Reusing the validator already running at {}", LOCALNET_URL);
+        None
+    } else {
+        info!("Starting solana-test-validator (ledger {})", LEDGER_DIR);
+        let child = Command::new("solana-test-validator")
+            .args(["--reset", "--quiet", "--ledger", LEDGER_DIR])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| anyhow!("Failed to start solana-test-validator (is the Solana CLI installed?): {}", e))?;
+
+        let started = Instant::now();
+        while client.get_health().is_err() {
+            if started.elapsed() > STARTUP_TIMEOUT {
+                return Err(anyhow!("Validator did not become healthy within {}s", STARTUP_TIMEOUT.as_secs()));
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        info!("Validator running (pid {})", child.id());
+        Some(child.id())
+    };
+
+    let wallet = if Path::new(wallet_path).exists() {
+        read_keypair(wallet_path)?
+    } else {
+        let wallet = Keypair::new();
+        write_keypair(wallet_path, &wallet)?;
+        info!("Generated throwaway wallet {} at {}", wallet.pubkey(), wallet_path);
+        wallet
+    };
+
+    info!("Airdropping {} SOL to {}", AIRDROP_SOL, wallet.pubkey());
+    let signature = client.request_airdrop(&wallet.pubkey(), sol_to_lamports(AIRDROP_SOL))
+        .map_err(|e| anyhow!("Airdrop failed: {}", e))?;
+    while !client.confirm_transaction(&signature).map_err(|e| anyhow!("Failed to confirm airdrop: {}", e))? {
+        thread::sleep(Duration::from_millis(250));
+    }
+
+    let token_ops = TokenOperations::new(LOCALNET_URL, wallet_path)?;
+    let fixtures = [
+        ("Legacy Test Token", "LTEST", MintConfig::new(6, false, &[])?),
+        ("Test Token", "TEST", MintConfig::new(9, true, &[])?),
+        ("Test Fee Token", "TFEE", MintConfig::new(9, true, &["transfer-fee=100:10".to_string()])?),
+    ];
+
+    let mut mints = Vec::new();
+    for (name, symbol, config) in fixtures {
+        // The local validator has no Metaplex program, so only Token-2022
+        // mints carry metadata (stored in the mint itself).
+        let config = if config.token_2022 {
+            config.with_metadata(name, symbol, &format!("https://example.com/{}.json", symbol.to_lowercase()))?
+        } else {
+            config
+        };
+        let mint = token_ops.create_fixture_mint(&config)?;
+        info!("Created {} ({}) at {}", name, symbol, mint);
+
+        mints.push(FixtureMint {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            address: mint.to_string(),
+            program_id: config.program_id().to_string(),
+            decimals: config.decimals,
+            supply: FIXTURE_SUPPLY.to_string(),
+        });
+    }
+
+    let fixtures = Fixtures {
+        rpc_url: LOCALNET_URL.to_string(),
+        wallet: wallet.pubkey().to_string(),
+        wallet_path: wallet_path.to_string(),
+        validator_pid,
+        ledger: LEDGER_DIR.to_string(),
+        mints,
+    };
+    write_json(fixtures_path, &fixtures)?;
+    info!("Fixtures written to {}", fixtures_path);
+    if let Some(pid) = validator_pid {
+        info!("Stop the validator with: kill {}", pid);
+    }
+
+    Ok(())
+}
+
+impl TokenOperations {
+    /// Create a mint and mint the fixture supply to the wallet's ATA.
+    fn create_fixture_mint(&self, config: &MintConfig) -> Result<Pubkey> {
+        let mint_keypair = Keypair::new();
+        let mint = mint_keypair.pubkey();
+        self.create_mint_account(&mint_keypair, config)?;
+
+        let program_id = config.program_id();
+        let wallet = self.wallet.pubkey();
+        let ata = spl_associated_token_account::get_associated_token_address_with_program_id(&wallet, &mint, &program_id);
+        let instructions = vec![
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &wallet, &wallet, &mint, &program_id,
+            ),
+            mint_to_checked(
+                &program_id,
+                &mint,
+                &ata,
+                &wallet,
+                &[],
+                parse_amount(FIXTURE_SUPPLY, config.decimals)?,
+                config.decimals,
+            )?,
+        ];
+        self.send_instructions(instructions, FUND_UNITS)?;
+
+        Ok(mint)
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use std::fs;
+use token_ops_core::diff::diff_snapshots;
+
+use crate::{
+    holders::HolderSnapshot,
+    index::Index,
+    output::{write_output, OutputFormat},
+};
+
+/// Number of changes printed to the log; the output file has all of them.
+const LISTED_CHANGES: usize = 20;
+
+/// Load one side of a diff: a snapshot JSON file, or a slot number resolved
+/// to the latest indexed snapshot at or before it.
+fn load_snapshot(source: &str, mint: Option<&str>, index_path: &str) -> Result<HolderSnapshot> {
+    if let Ok(slot) = source.parse::<u64>() {
+        let mint = mint.ok_or_else(|| anyhow!("--mint-address is required to diff indexed snapshots by slot"))?;
+        return Index::open(index_path)?
+            .snapshot_at(mint, slot)?
+            .ok_or_else(|| anyhow!("No snapshot of {} at or before slot {} in {}", mint, slot, index_path));
+    }
+
+    let data = fs::read(source).map_err(|e| anyhow!("Failed to read {}: {}", source, e))?;
+    serde_json::from_slice(&data).map_err(|e| anyhow!("Failed to parse snapshot {}: {}", source, e))
+}
+
+/// Diff two holder snapshots given as files or slots.
+pub fn snapshot_diff(
+    before: &str,
+    after: &str,
+    mint: Option<&str>,
+    index_path: &str,
+    output: Option<(&str, OutputFormat)>,
+) -> Result<()> {
+    let before = load_snapshot(before, mint, index_path)?;
+    let after = load_snapshot(after, mint, index_path)?;
+    let diff = diff_snapshots(&before, &after)?;
+
+    info!("Holder changes for {} between slot {} and {}:", diff.mint, diff.before_slot, diff.after_slot);
+    info!(
+        "  {} entered, {} exited, {} increased, {} decreased, {} unchanged",
+        diff.entered, diff.exited, diff.increased, diff.decreased, diff.unchanged
+    );
+    for change in diff.changes.iter().take(LISTED_CHANGES) {
+        info!("  {} {}: {} -> {} ({})", change.status, change.owner, change.before, change.after, change.delta);
+    }
+    if diff.changes.len() > LISTED_CHANGES {
+        info!("  ... and {} more", diff.changes.len() - LISTED_CHANGES);
+    }
+
+    write_output(output, &diff, &diff.changes)
+}
@@ -0,0 +1,198 @@
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use mpl_token_metadata::{
+    accounts::{EditionMarker, MasterEdition, Metadata},
+    instructions::MintNewEditionFromMasterEditionViaTokenBuilder,
+    types::MintNewEditionFromMasterEditionViaTokenArgs,
+};
+use serde::Serialize;
+use solana_sdk::{
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+use spl_token::instruction::{initialize_mint2, mint_to};
+use std::str::FromStr;
+
+use crate::{
+    metadata::trim_padding,
+    output::{write_output, OutputFormat},
+    outputs,
+    prompt::confirm,
+    TokenOperations,
+};
+
+/// Editions tracked by one edition marker account.
+pub const EDITION_MARKER_BIT_SIZE: u64 = 248;
+
+/// Estimated compute units for creating the edition mint and its ATA,
+/// minting the token and MintNewEditionFromMasterEditionViaToken.
+const PRINT_EDITION_UNITS: u32 = 150_000;
+
+/// One edition printed from a master edition.
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintedEdition {
+    pub edition: u64,
+    pub mint: String,
+    pub recipient: String,
+    pub signature: String,
+}
+
+impl TokenOperations {
+    fn fetch_master_edition(&self, master_mint: &Pubkey) -> Result<(Pubkey, MasterEdition)> {
+        let (address, _) = MasterEdition::find_pda(master_mint);
+        let account = self.client
+            .get_account_with_commitment(&address, self.client.commitment())
+            .map_err(|e| anyhow!("Failed to get master edition account: {}", e))?
+            .value
+            .ok_or_else(|| anyhow!("{} has no master edition; only master edition NFTs can be printed", master_mint))?;
+        if account.owner != mpl_token_metadata::ID {
+            return Err(anyhow!("Master edition account {} is not owned by the Token Metadata program", address));
+        }
+        let master_edition = MasterEdition::from_bytes(&account.data)
+            .map_err(|e| anyhow!("Failed to parse master edition {}: {}", address, e))?;
+        Ok((address, master_edition))
+    }
+
+    /// Print `count` numbered editions of a master edition NFT held by the
+    /// loaded wallet and send each to `to`'s ATA.
+    ///
+    /// Every edition gets a new mint keypair, so each is its own transaction
+    /// numbered after the master's current supply. The edition marker PDA
+    /// that records which numbers are taken is derived per edition, as one
+    /// marker covers 248 editions. A failed edition stops the run; rerunning
+    /// continues from the new supply.
+    pub fn print_editions(
+        &self,
+        master_address: &str,
+        to: &str,
+        count: u64,
+        assume_yes: bool,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let master_mint = Pubkey::from_str(master_address)
+            .map_err(|e| anyhow!("Invalid master mint address: {}", e))?;
+        let recipient = Pubkey::from_str(to).map_err(|e| anyhow!("Invalid destination address: {}", e))?;
+        if count == 0 {
+            return Err(anyhow!("--count must be at least 1"));
+        }
+
+        let (master_edition_address, master_edition) = self.fetch_master_edition(&master_mint)?;
+        let (master_metadata_address, metadata) = self
+            .fetch_metadata(&master_mint)?
+            .ok_or_else(|| anyhow!("Mint {} has no Metaplex metadata account", master_mint))?;
+
+        let wallet = self.wallet.pubkey();
+        let master_token_account = get_associated_token_address(&wallet, &master_mint);
+        let holds_master = self.client
+            .get_token_account_balance(&master_token_account)
+            .map(|balance| balance.amount == "1")
+            .unwrap_or(false);
+        if !holds_master {
+            return Err(anyhow!("Loaded wallet does not hold the master edition token in {}", master_token_account));
+        }
+
+        let first = master_edition.supply + 1;
+        let last = master_edition.supply + count;
+        if let Some(max_supply) = master_edition.max_supply {
+            if last > max_supply {
+                return Err(anyhow!(
+                    "Master edition has printed {} of at most {}; {} more would exceed it",
+                    master_edition.supply,
+                    max_supply,
+                    count
+                ));
+            }
+        }
+
+        confirm(
+            &[
+                format!("Print {} edition(s) of {} ({})", count, master_mint, trim_padding(&metadata.name)),
+                format!(
+                    "Editions:  #{} to #{}{}",
+                    first,
+                    last,
+                    master_edition.max_supply.map(|max| format!(" of {}", max)).unwrap_or_default()
+                ),
+                format!("Recipient: {}", recipient),
+            ],
+            assume_yes,
+        )?;
+
+        let rent = self.client.get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+            .map_err(|e| anyhow!("Failed to get rent exemption: {}", e))?;
+
+        let mut printed = Vec::new();
+        for edition in first..=last {
+            let new_mint = Keypair::new();
+            let new_mint_pubkey = new_mint.pubkey();
+            let (new_metadata, _) = Metadata::find_pda(&new_mint_pubkey);
+            let (new_edition, _) = MasterEdition::find_pda(&new_mint_pubkey);
+            let (edition_marker, _) = EditionMarker::find_pda(&master_mint, &(edition / EDITION_MARKER_BIT_SIZE).to_string());
+
+            let instructions = vec![
+                system_instruction::create_account(
+                    &wallet,
+                    &new_mint_pubkey,
+                    rent,
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                initialize_mint2(&spl_token::id(), &new_mint_pubkey, &wallet, Some(&wallet), 0)?,
+                create_associated_token_account_idempotent(&wallet, &recipient, &new_mint_pubkey, &spl_token::id()),
+                mint_to(
+                    &spl_token::id(),
+                    &new_mint_pubkey,
+                    &get_associated_token_address(&recipient, &new_mint_pubkey),
+                    &wallet,
+                    &[],
+                    1,
+                )?,
+                MintNewEditionFromMasterEditionViaTokenBuilder::new()
+                    .new_metadata(new_metadata)
+                    .new_edition(new_edition)
+                    .master_edition(master_edition_address)
+                    .new_mint(new_mint_pubkey)
+                    .edition_mark_pda(edition_marker)
+                    .new_mint_authority(wallet)
+                    .payer(wallet)
+                    .token_account_owner(wallet)
+                    .token_account(master_token_account)
+                    .new_metadata_update_authority(wallet)
+                    .metadata(master_metadata_address)
+                    .mint_new_edition_from_master_edition_via_token_args(MintNewEditionFromMasterEditionViaTokenArgs { edition })
+                    .instruction(),
+            ];
+
+            match self.send_instructions_with_signers(instructions, PRINT_EDITION_UNITS, &[&new_mint]) {
+                Ok(signature) => {
+                    info!("Edition #{} printed: {} -> {} ({})", edition, new_mint_pubkey, recipient, signature);
+                    printed.push(PrintedEdition {
+                        edition,
+                        mint: new_mint_pubkey.to_string(),
+                        recipient: recipient.to_string(),
+                        signature: signature.to_string(),
+                    });
+                }
+                Err(e) => {
+                    error!("Edition #{} failed: {}", edition, e);
+                    break;
+                }
+            }
+        }
+
+        info!("Printed {} of {} edition(s)", printed.len(), count);
+        if let Some(last) = printed.last() {
+            outputs::record("edition_mint", &last.mint);
+        }
+        write_output(output, &printed, &printed)?;
+        if printed.len() as u64 != count {
+            return Err(anyhow!("Stopped after {} of {} edition(s)", printed.len(), count));
+        }
+        Ok(())
+    }
+}
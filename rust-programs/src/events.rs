@@ -0,0 +1,373 @@
+use anyhow::{anyhow, Result};
+use base58::FromBase58;
+use log::{info, warn};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{UiInnerInstructions, UiInstruction};
+use spl_token_2022::instruction::TokenInstruction;
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use crate::{history::HistoricalTransaction, index::Index, token_program::is_token_program, TokenOperations};
+
+/// Tables owned by the events module, created alongside the index schema.
+pub const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS events (
+        signature TEXT NOT NULL,
+        position TEXT NOT NULL,
+        mint TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        source TEXT,
+        destination TEXT,
+        source_owner TEXT,
+        destination_owner TEXT,
+        authority TEXT,
+        amount INTEGER NOT NULL,
+        slot INTEGER NOT NULL,
+        block_time INTEGER,
+        PRIMARY KEY (signature, position)
+    );
+    CREATE INDEX IF NOT EXISTS events_mint_time ON events (mint, block_time);
+    CREATE TABLE IF NOT EXISTS event_cursors (
+        address TEXT NOT NULL,
+        mint TEXT NOT NULL,
+        last_signature TEXT NOT NULL,
+        PRIMARY KEY (address, mint)
+    );
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum EventKind {
+    Transfer,
+    MintTo,
+    Burn,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Transfer => "transfer",
+            EventKind::MintTo => "mint_to",
+            EventKind::Burn => "burn",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "transfer" => Some(EventKind::Transfer),
+            "mint_to" => Some(EventKind::MintTo),
+            "burn" => Some(EventKind::Burn),
+            _ => None,
+        }
+    }
+}
+
+/// A single balance-moving token instruction. `position` is the outer
+/// instruction index, with `.n` appended for inner (CPI) instructions.
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenEvent {
+    pub signature: String,
+    pub position: String,
+    pub kind: EventKind,
+    pub source: Option<String>,
+    pub destination: Option<String>,
+    pub source_owner: Option<String>,
+    pub destination_owner: Option<String>,
+    pub authority: Option<String>,
+    pub amount: u64,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+}
+
+/// Decode every Transfer, MintTo and Burn (including the checked variants
+/// and CPI calls) touching `mint` in a transaction.
+pub fn extract_events(transaction: &HistoricalTransaction, mint: &Pubkey) -> Vec<TokenEvent> {
+    let mint_str = mint.to_string();
+
+    // Token balances are the only place the mint and owner of a plain
+    // Transfer's accounts are recorded.
+    let mut balances: HashMap<u8, (String, Option<String>)> = HashMap::new();
+    for balance in transaction.pre_token_balances().into_iter().chain(transaction.post_token_balances()) {
+        let owner = Option::<String>::from(balance.owner);
+        balances.insert(balance.account_index, (balance.mint, owner));
+    }
+
+    let key = |index: Option<&u8>| -> Option<String> {
+        index.and_then(|i| transaction.account_keys.get(*i as usize)).map(|k| k.to_string())
+    };
+    let mint_of = |index: Option<&u8>| index.and_then(|i| balances.get(i)).map(|(m, _)| m.as_str());
+    let owner_of = |index: Option<&u8>| index.and_then(|i| balances.get(i)).and_then(|(_, o)| o.clone());
+
+    let mut instructions: Vec<(String, u8, Vec<u8>, Vec<u8>)> = transaction
+        .decoded
+        .message
+        .instructions()
+        .iter()
+        .enumerate()
+        .map(|(i, ix)| (i.to_string(), ix.program_id_index, ix.accounts.clone(), ix.data.clone()))
+        .collect();
+
+    let inner = transaction
+        .transaction
+        .transaction
+        .meta
+        .as_ref()
+        .and_then(|meta| Option::<Vec<UiInnerInstructions>>::from(meta.inner_instructions.clone()))
+        .unwrap_or_default();
+    for group in inner {
+        for (n, instruction) in group.instructions.into_iter().enumerate() {
+            if let UiInstruction::Compiled(compiled) = instruction {
+                if let Ok(data) = compiled.data.from_base58() {
+                    instructions.push((format!("{}.{}", group.index, n), compiled.program_id_index, compiled.accounts, data));
+                }
+            }
+        }
+    }
+
+    let mut events = Vec::new();
+    for (position, program_index, accounts, data) in instructions {
+        // Token-2022's instruction set is a superset of SPL Token's, so it decodes both.
+        if !transaction.account_keys.get(program_index as usize).is_some_and(is_token_program) {
+            continue;
+        }
+        let Ok(instruction) = TokenInstruction::unpack(&data) else {
+            continue;
+        };
+
+        // Plain Transfer is deprecated for Token-2022 mints but still sent, and must be indexed.
+        #[allow(deprecated)]
+        let (kind, amount, source, destination, authority, instruction_mint) = match instruction {
+            TokenInstruction::Transfer { amount } => {
+                (EventKind::Transfer, amount, accounts.first(), accounts.get(1), accounts.get(2), mint_of(accounts.first()))
+            }
+            TokenInstruction::TransferChecked { amount, .. } => {
+                (EventKind::Transfer, amount, accounts.first(), accounts.get(2), accounts.get(3), mint_of(accounts.first()))
+            }
+            TokenInstruction::MintTo { amount } | TokenInstruction::MintToChecked { amount, .. } => {
+                (EventKind::MintTo, amount, None, accounts.get(1), accounts.get(2), mint_of(accounts.get(1)))
+            }
+            TokenInstruction::Burn { amount } | TokenInstruction::BurnChecked { amount, .. } => {
+                (EventKind::Burn, amount, accounts.first(), None, accounts.get(2), mint_of(accounts.first()))
+            }
+            _ => continue,
+        };
+
+        if instruction_mint != Some(mint_str.as_str()) {
+            continue;
+        }
+
+        events.push(TokenEvent {
+            signature: transaction.signature.clone(),
+            position,
+            kind,
+            source: key(source),
+            destination: key(destination),
+            source_owner: owner_of(source),
+            destination_owner: owner_of(destination),
+            authority: key(authority),
+            amount,
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+        });
+    }
+
+    events
+}
+
+impl Index {
+    pub fn record_events(&mut self, mint: &str, events: &[TokenEvent]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO events
+                 (signature, position, mint, kind, source, destination, source_owner, destination_owner, authority, amount, slot, block_time)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            )?;
+            for event in events {
+                inserted += insert.execute(params![
+                    event.signature,
+                    event.position,
+                    mint,
+                    event.kind.as_str(),
+                    event.source,
+                    event.destination,
+                    event.source_owner,
+                    event.destination_owner,
+                    event.authority,
+                    event.amount,
+                    event.slot,
+                    event.block_time,
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(inserted)
+    }
+
+    /// Indexed events for a mint with a block time at or after `since`, oldest first.
+    pub fn events(&self, mint: &str, since: Option<i64>) -> Result<Vec<TokenEvent>> {
+        let mut statement = self.conn.prepare(
+            "SELECT signature, position, kind, source, destination, source_owner, destination_owner, authority, amount, slot, block_time
+             FROM events WHERE mint = ?1 AND (?2 IS NULL OR block_time >= ?2)
+             ORDER BY slot, signature, position",
+        )?;
+
+        let rows = statement.query_map(params![mint, since], |row| {
+            let kind: String = row.get(2)?;
+            Ok(TokenEvent {
+                signature: row.get(0)?,
+                position: row.get(1)?,
+                kind: EventKind::parse(&kind).unwrap_or(EventKind::Transfer),
+                source: row.get(3)?,
+                destination: row.get(4)?,
+                source_owner: row.get(5)?,
+                destination_owner: row.get(6)?,
+                authority: row.get(7)?,
+                amount: row.get(8)?,
+                slot: row.get(9)?,
+                block_time: row.get(10)?,
+            })
+        })?;
+
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Whether index-events has walked the mint's own signatures.
+    pub fn events_indexed(&self, mint: &str) -> Result<bool> {
+        Ok(self.event_cursor(mint, mint)?.is_some())
+    }
+
+    pub(crate) fn event_cursor(&self, address: &str, mint: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT last_signature FROM event_cursors WHERE address = ?1 AND mint = ?2",
+                params![address, mint],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub(crate) fn set_event_cursor(&self, address: &str, mint: &str, signature: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO event_cursors (address, mint, last_signature) VALUES (?1, ?2, ?3)",
+            params![address, mint, signature],
+        )?;
+        Ok(())
+    }
+}
+
+/// Progress of index-events, saved when it stops early.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct EventsProgress {
+    /// New transactions, oldest first.
+    pub(crate) pending: Vec<(String, u64)>,
+    processed: usize,
+    stored: usize,
+    /// Newest signature of each walked address, stored once all are processed.
+    pub(crate) cursors: Vec<(String, String)>,
+}
+
+/// Plain transfers are only found through tracked accounts, so say when there are none.
+pub(crate) fn warn_if_untracked(index: &Index, mint_address: &str) -> Result<()> {
+    if index.tracked_accounts(mint_address)?.is_empty() {
+        warn!("No token accounts indexed for {}; plain transfers will be missed until index-snapshot is run", mint_address);
+    }
+    Ok(())
+}
+
+impl TokenOperations {
+    /// Walk new history for a mint and store its transfer, mint and burn events.
+    ///
+    /// The mint's own signatures cover minting, burning and checked transfers;
+    /// plain transfers are only visible through the token accounts involved,
+    /// so accounts already tracked by the holder index are walked as well.
+    /// An interrupted run saves a checkpoint and continues with `--resume`.
+    pub fn index_events(&self, mint_address: &str, index_path: &str) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+
+        let mut index = Index::open(index_path)?;
+        warn_if_untracked(&index, mint_address)?;
+        let scan = self.checkpoints.scan(&format!("index-events-{}", mint_address));
+        let mut progress = match scan.resume()? {
+            Some(progress) => progress,
+            None => self.pending_events(&index, mint_address)?,
+        };
+
+        info!("Processing {} new transaction(s) for mint {}", progress.pending.len() - progress.processed, mint_address);
+
+        while progress.processed < progress.pending.len() {
+            let signature = progress.pending[progress.processed].0.clone();
+            let transaction = self.fetch_transaction(&signature).map_err(|e| scan.fail(&progress, e))?;
+            if !transaction.failed() {
+                let events = extract_events(&transaction, &mint_pubkey);
+                progress.stored += index.record_events(mint_address, &events)?;
+            }
+            progress.processed += 1;
+            scan.checkpoint(&progress)?;
+        }
+
+        // Cursors only advance once every transaction has been stored.
+        for (address, signature) in &progress.cursors {
+            index.set_event_cursor(address, mint_address, signature)?;
+        }
+        scan.finish()?;
+
+        info!("Stored {} new event(s) for mint {}", progress.stored, mint_address);
+        Ok(())
+    }
+
+    /// Point the event cursor of the mint and of each tracked account
+    /// that has none at its newest transaction, so walks from here on skip
+    /// the history before it.
+    pub(crate) fn seed_event_cursors(&self, index: &Index, mint_address: &str) -> Result<()> {
+        let mut addresses = vec![mint_address.to_string()];
+        addresses.extend(index.tracked_accounts(mint_address)?);
+        for address in &addresses {
+            if index.event_cursor(address, mint_address)?.is_some() {
+                continue;
+            }
+            let pubkey = Pubkey::from_str(address)
+                .map_err(|e| anyhow!("Invalid address in index: {}", e))?;
+            if let Some(newest) = self.signatures_since(&pubkey, None, Some(1))?.first() {
+                index.set_event_cursor(address, mint_address, &newest.signature)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Successful transactions since each walked address's cursor.
+    pub(crate) fn pending_events(&self, index: &Index, mint_address: &str) -> Result<EventsProgress> {
+        let mut addresses = vec![mint_address.to_string()];
+        addresses.extend(index.tracked_accounts(mint_address)?);
+
+        // signature -> slot, deduplicated across every walked address
+        let mut pending: BTreeMap<String, u64> = BTreeMap::new();
+        let mut cursors = Vec::new();
+
+        for address in &addresses {
+            let pubkey = Pubkey::from_str(address)
+                .map_err(|e| anyhow!("Invalid address in index: {}", e))?;
+            let cursor = index.event_cursor(address, mint_address)?;
+            let signatures = self.signatures_since(&pubkey, cursor.as_deref(), None)?;
+
+            if let Some(newest) = signatures.first() {
+                cursors.push((address.clone(), newest.signature.clone()));
+            }
+            for status in signatures.into_iter().filter(|status| status.err.is_none()) {
+                pending.insert(status.signature, status.slot);
+            }
+        }
+
+        let mut ordered: Vec<(String, u64)> = pending.into_iter().collect();
+        ordered.sort_by_key(|(_, slot)| *slot);
+
+        Ok(EventsProgress { pending: ordered, cursors, ..EventsProgress::default() })
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Deserialize;
+use serde_json::json;
+use solana_sdk::{program_option::COption, pubkey::Pubkey, signature::Signer};
+use spl_token_2022::instruction::{mint_to_checked, transfer_checked};
+use std::{
+    collections::HashMap,
+    io::Read,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+    holders::{format_amount, parse_amount},
+    token_program::MintInfo,
+    TokenOperations,
+};
+
+/// Estimated compute units for an idempotent ATA creation.
+const CREATE_ATA_UNITS: u32 = 40_000;
+
+/// Estimated compute units for a MintToChecked or TransferChecked instruction.
+const DRIP_UNITS: u32 = 20_000;
+
+/// Largest request body accepted, in bytes.
+const MAX_BODY_BYTES: u64 = 4_096;
+
+#[derive(Debug, Deserialize)]
+struct DripRequest {
+    wallet: String,
+}
+
+/// How the faucet funds requests: by minting, or from the wallet's own balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DripSource {
+    Mint,
+    Transfer,
+}
+
+struct Faucet<'a> {
+    ops: &'a TokenOperations,
+    mint_info: MintInfo,
+    decimals: u8,
+    amount: u64,
+    source: DripSource,
+    cooldown: Duration,
+    /// Wallet address or client IP -> time of the last successful drip.
+    last_drip: HashMap<String, Instant>,
+}
+
+impl TokenOperations {
+    /// Run an HTTP faucet that sends `amount` tokens to any wallet that asks,
+    /// at most once per `cooldown` per wallet and per client IP.
+    ///
+    /// `POST /drip` with `{"wallet": "<address>"}` requests tokens and
+    /// `GET /` describes the faucet. Tokens are minted when the loaded wallet
+    /// is the mint authority, otherwise transferred from its ATA.
+    pub fn serve_faucet(&self, mint_address: &str, listen: &str, amount: &str, cooldown: &str) -> Result<()> {
+        if self.is_mainnet()? {
+            return Err(anyhow!("Refusing to run a faucet against mainnet-beta"));
+        }
+
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+        let mint = mint_info.base()?;
+
+        let source = if mint.mint_authority == COption::Some(self.wallet.pubkey()) {
+            DripSource::Mint
+        } else {
+            DripSource::Transfer
+        };
+
+        let cooldown = crate::report::parse_duration(cooldown)?;
+        if cooldown < 0 {
+            return Err(anyhow!("Cooldown cannot be negative"));
+        }
+
+        let mut faucet = Faucet {
+            ops: self,
+            amount: parse_amount(amount, mint.decimals)?,
+            decimals: mint.decimals,
+            mint_info,
+            source,
+            cooldown: Duration::from_secs(cooldown as u64),
+            last_drip: HashMap::new(),
+        };
+        if faucet.amount == 0 {
+            return Err(anyhow!("Faucet amount must be greater than zero"));
+        }
+
+        let server = Server::http(listen)
+            .map_err(|e| anyhow!("Failed to listen on {}: {}", listen, e))?;
+
+        info!("Faucet listening on http://{}", listen);
+        info!("Dripping {} tokens per request ({:?}), cooldown {}s",
+            format_amount(faucet.amount, faucet.decimals), faucet.source, faucet.cooldown.as_secs());
+
+        for request in server.incoming_requests() {
+            faucet.handle(request);
+        }
+
+        Ok(())
+    }
+}
+
+impl Faucet<'_> {
+    fn handle(&mut self, mut request: Request) {
+        let (status, body) = match (request.method(), request.url()) {
+            (Method::Get, "/") => (200, self.describe()),
+            (Method::Post, "/drip") => self.drip(&mut request),
+            _ => (404, json!({ "error": "Not found" })),
+        };
+
+        let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+            .expect("static header is valid");
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(header);
+
+        if let Err(e) = request.respond(response) {
+            warn!("Failed to send faucet response: {}", e);
+        }
+    }
+
+    fn describe(&self) -> serde_json::Value {
+        json!({
+            "mint": self.mint_info.address.to_string(),
+            "amount": format_amount(self.amount, self.decimals),
+            "cooldown_seconds": self.cooldown.as_secs(),
+        })
+    }
+
+    fn drip(&mut self, request: &mut Request) -> (u16, serde_json::Value) {
+        let mut body = String::new();
+        if request.as_reader().take(MAX_BODY_BYTES).read_to_string(&mut body).is_err() {
+            return (400, json!({ "error": "Invalid request body" }));
+        }
+
+        let wallet = match serde_json::from_str::<DripRequest>(&body)
+            .ok()
+            .and_then(|drip| Pubkey::from_str(drip.wallet.trim()).ok())
+        {
+            Some(wallet) => wallet,
+            None => return (400, json!({ "error": "Expected {\"wallet\": \"<address>\"}" })),
+        };
+
+        let mut keys = vec![wallet.to_string()];
+        if let Some(addr) = request.remote_addr() {
+            keys.push(addr.ip().to_string());
+        }
+
+        if let Some(wait) = keys.iter().filter_map(|key| self.remaining_cooldown(key)).max() {
+            info!("Rate limited drip to {} ({}s remaining)", wallet, wait.as_secs());
+            return (429, json!({ "error": "Rate limited", "retry_after_seconds": wait.as_secs() }));
+        }
+
+        match self.send(&wallet) {
+            Ok(signature) => {
+                let now = Instant::now();
+                for key in keys {
+                    self.last_drip.insert(key, now);
+                }
+                info!("Sent {} tokens to {}: {}", format_amount(self.amount, self.decimals), wallet, signature);
+                (200, json!({
+                    "wallet": wallet.to_string(),
+                    "amount": format_amount(self.amount, self.decimals),
+                    "signature": signature.to_string(),
+                }))
+            }
+            Err(e) => {
+                warn!("Drip to {} failed: {}", wallet, e);
+                (500, json!({ "error": format!("Transaction failed: {}", e) }))
+            }
+        }
+    }
+
+    fn remaining_cooldown(&self, key: &str) -> Option<Duration> {
+        let elapsed = self.last_drip.get(key)?.elapsed();
+        self.cooldown.checked_sub(elapsed).filter(|wait| !wait.is_zero())
+    }
+
+    fn send(&self, wallet: &Pubkey) -> Result<solana_sdk::signature::Signature> {
+        let payer = self.ops.wallet.pubkey();
+        let mint = &self.mint_info.address;
+        let program_id = &self.mint_info.program_id;
+        let destination = self.mint_info.associated_token_address(wallet);
+
+        let mut instructions = vec![
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &payer,
+                wallet,
+                mint,
+                program_id,
+            ),
+        ];
+
+        instructions.push(match self.source {
+            DripSource::Mint => mint_to_checked(
+                program_id,
+                mint,
+                &destination,
+                &payer,
+                &[],
+                self.amount,
+                self.decimals,
+            )?,
+            DripSource::Transfer => transfer_checked(
+                program_id,
+                &self.mint_info.associated_token_address(&payer),
+                mint,
+                &destination,
+                &payer,
+                &[],
+                self.amount,
+                self.decimals,
+            )?,
+        });
+
+        self.ops.send_instructions(instructions, CREATE_ATA_UNITS + DRIP_UNITS)
+    }
+}
@@ -0,0 +1,220 @@
+use anyhow::Result;
+use chrono::Utc;
+use log::{info, warn};
+use serde::Serialize;
+use solana_sdk::{
+    address_lookup_table, compute_budget, native_token::lamports_to_sol, pubkey::Pubkey, signature::Signer,
+    system_program,
+};
+use spl_token_2022::instruction::TokenInstruction;
+use std::collections::BTreeMap;
+
+use crate::{
+    history::HistoricalTransaction,
+    output::{write_output, OutputFormat},
+    report::{format_date, parse_since},
+    token_program::is_token_program,
+    TokenOperations,
+};
+
+/// Fee charged per signature before any priority fee.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Fees paid by the wallet, aggregated over one day or one operation type.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeeRow {
+    pub group: String,
+    pub key: String,
+    pub transactions: usize,
+    pub failed: usize,
+    pub base_fee_sol: f64,
+    pub priority_fee_sol: f64,
+    pub rent_sol: f64,
+    pub total_sol: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeReport {
+    pub wallet: String,
+    pub since: String,
+    pub until: Option<String>,
+    pub totals: FeeRow,
+    pub days: Vec<FeeRow>,
+    pub operations: Vec<FeeRow>,
+}
+
+/// Lamports one transaction cost its fee payer.
+#[derive(Debug, Clone, Copy, Default)]
+struct FeeSpend {
+    base: u64,
+    priority: u64,
+    rent: u64,
+    failed: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Totals {
+    transactions: usize,
+    failed: usize,
+    base: u64,
+    priority: u64,
+    rent: u64,
+}
+
+impl Totals {
+    fn add(&mut self, spend: &FeeSpend) {
+        self.transactions += 1;
+        self.failed += usize::from(spend.failed);
+        self.base += spend.base;
+        self.priority += spend.priority;
+        self.rent += spend.rent;
+    }
+
+    fn row(&self, group: &str, key: &str) -> FeeRow {
+        FeeRow {
+            group: group.to_string(),
+            key: key.to_string(),
+            transactions: self.transactions,
+            failed: self.failed,
+            base_fee_sol: lamports_to_sol(self.base),
+            priority_fee_sol: lamports_to_sol(self.priority),
+            rent_sol: lamports_to_sol(self.rent),
+            total_sol: lamports_to_sol(self.base + self.priority + self.rent),
+        }
+    }
+}
+
+impl TokenOperations {
+    /// Summarize fees and account-creation rent paid by the wallet between
+    /// `since` and `until` (default now), per day and per operation type.
+    pub fn fee_report(&self, since: &str, until: Option<&str>, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let wallet = self.wallet.pubkey();
+        let now = Utc::now();
+        let since = parse_since(since, now)?;
+        let until = until.map(|until| parse_since(until, now)).transpose()?;
+
+        let mut signatures = self.signatures_in_window(&wallet, since)?;
+        if let Some(until) = until {
+            signatures.retain(|status| status.block_time.is_some_and(|time| time < until));
+        }
+        info!("Scanning {} transaction(s) since {}", signatures.len(), format_date(since));
+
+        let mut totals = Totals::default();
+        let mut days: BTreeMap<String, Totals> = BTreeMap::new();
+        let mut operations: BTreeMap<String, Totals> = BTreeMap::new();
+
+        for status in &signatures {
+            let transaction = match self.fetch_transaction(&status.signature) {
+                Ok(transaction) => transaction,
+                Err(e) => {
+                    warn!("Skipping {}: {}", status.signature, e);
+                    continue;
+                }
+            };
+            // Only count transactions the wallet paid for.
+            if transaction.account_keys.first() != Some(&wallet) {
+                continue;
+            }
+            let Some(spend) = fee_spend(&transaction) else {
+                continue;
+            };
+
+            let day = transaction.block_time.map(format_date).unwrap_or_else(|| "unknown".to_string());
+            totals.add(&spend);
+            days.entry(day).or_default().add(&spend);
+            operations.entry(operation_type(&transaction)).or_default().add(&spend);
+        }
+
+        let report = FeeReport {
+            wallet: wallet.to_string(),
+            since: format_date(since),
+            until: until.map(format_date),
+            totals: totals.row("total", "all"),
+            days: days.iter().map(|(day, t)| t.row("day", day)).collect(),
+            operations: operations.iter().map(|(op, t)| t.row("operation", op)).collect(),
+        };
+
+        info!("Fees paid by {} since {}:", report.wallet, report.since);
+        for row in std::iter::once(&report.totals).chain(&report.days).chain(&report.operations) {
+            info!(
+                "  {:<10} {:<32} {:>5} tx ({} failed)  base {:.6}  priority {:.6}  rent {:.6}  total {:.6} SOL",
+                row.group, row.key, row.transactions, row.failed,
+                row.base_fee_sol, row.priority_fee_sol, row.rent_sol, row.total_sol
+            );
+        }
+
+        let rows: Vec<FeeRow> = std::iter::once(report.totals.clone())
+            .chain(report.days.iter().cloned())
+            .chain(report.operations.iter().cloned())
+            .collect();
+        write_output(output, &report, &rows)
+    }
+}
+
+/// Split the fee into base and priority parts, and count lamports moved
+/// into accounts that did not exist before the transaction as rent.
+fn fee_spend(transaction: &HistoricalTransaction) -> Option<FeeSpend> {
+    let meta = transaction.transaction.transaction.meta.as_ref()?;
+    let base = LAMPORTS_PER_SIGNATURE * transaction.decoded.signatures.len() as u64;
+
+    let rent = meta
+        .pre_balances
+        .iter()
+        .zip(&meta.post_balances)
+        .skip(1)
+        .filter(|(pre, post)| **pre == 0 && **post > 0)
+        .map(|(_, post)| *post)
+        .sum();
+
+    Some(FeeSpend {
+        base: base.min(meta.fee),
+        priority: meta.fee.saturating_sub(base),
+        // Failed transactions create nothing.
+        rent: if meta.err.is_some() { 0 } else { rent },
+        failed: meta.err.is_some(),
+    })
+}
+
+/// Label a transaction by its first instruction that is not a compute budget setting.
+fn operation_type(transaction: &HistoricalTransaction) -> String {
+    let message = &transaction.decoded.message;
+    let keys = message.static_account_keys();
+
+    for instruction in message.instructions() {
+        let Some(program_id) = keys.get(instruction.program_id_index as usize) else {
+            continue;
+        };
+        if *program_id == compute_budget::id() {
+            continue;
+        }
+        return program_label(program_id, &instruction.data);
+    }
+
+    "none".to_string()
+}
+
+fn program_label(program_id: &Pubkey, data: &[u8]) -> String {
+    if is_token_program(program_id) {
+        return match TokenInstruction::unpack(data) {
+            Ok(instruction) => {
+                let name = format!("{:?}", instruction);
+                let end = name.find([' ', '{', '(']).unwrap_or(name.len());
+                format!("token:{}", &name[..end])
+            }
+            Err(_) => "token:unknown".to_string(),
+        };
+    }
+    if *program_id == spl_associated_token_account::id() {
+        return "create-associated-token-account".to_string();
+    }
+    if *program_id == system_program::id() {
+        return "system".to_string();
+    }
+    if *program_id == address_lookup_table::program::id() {
+        return "address-lookup-table".to_string();
+    }
+    if *program_id == mpl_token_metadata::ID {
+        return "token-metadata".to_string();
+    }
+    program_id.to_string()
+}
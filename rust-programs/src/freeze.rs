@@ -0,0 +1,168 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_sdk::{program_option::COption, pubkey::Pubkey, signature::Signer};
+use spl_token_2022::{
+    instruction::{freeze_account, thaw_account},
+    state::AccountState,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use crate::{
+    input::read_addresses,
+    output::{write_output, OutputFormat},
+    token_program::unpack_token_account,
+    TokenOperations,
+};
+
+/// Estimated compute units for one FreezeAccount or ThawAccount instruction.
+const FREEZE_UNITS: u32 = 5_000;
+
+/// Accounts fetched per getMultipleAccounts call.
+const FETCH_CHUNK: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreezeAction {
+    Freeze,
+    Thaw,
+}
+
+/// Per-wallet outcome of a batch freeze or thaw.
+#[derive(Debug, Clone, Serialize)]
+pub struct FreezeResult {
+    pub wallet: String,
+    pub token_account: String,
+    pub status: String,
+    pub signature: Option<String>,
+}
+
+impl TokenOperations {
+    /// Freeze or thaw the ATA of every wallet in `addresses_file`, packing the
+    /// instructions into as few transactions as possible.
+    pub fn freeze_list(
+        &self,
+        mint_address: &str,
+        addresses_file: &str,
+        action: FreezeAction,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+
+        if mint_info.base()?.freeze_authority != COption::Some(self.wallet.pubkey()) {
+            return Err(anyhow!("Loaded wallet is not the freeze authority of this mint"));
+        }
+
+        let mut wallets = read_addresses(addresses_file)?;
+        let mut seen = HashSet::new();
+        wallets.retain(|wallet| seen.insert(*wallet));
+        info!("{} {} wallet(s) from {}", verb(action), wallets.len(), addresses_file);
+
+        let token_accounts: Vec<Pubkey> = wallets
+            .iter()
+            .map(|wallet| mint_info.associated_token_address(wallet))
+            .collect();
+
+        let mut results: Vec<FreezeResult> = Vec::with_capacity(wallets.len());
+        // batcher item index -> result index
+        let mut queued: HashMap<usize, usize> = HashMap::new();
+        let mut batcher = self.batcher();
+
+        for (wallets_chunk, accounts_chunk) in wallets.chunks(FETCH_CHUNK).zip(token_accounts.chunks(FETCH_CHUNK)) {
+            let accounts = self.client.get_multiple_accounts(accounts_chunk)
+                .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
+
+            for ((wallet, token_account), account) in wallets_chunk.iter().zip(accounts_chunk).zip(accounts) {
+                let mut result = FreezeResult {
+                    wallet: wallet.to_string(),
+                    token_account: token_account.to_string(),
+                    status: String::new(),
+                    signature: None,
+                };
+
+                let state = account
+                    .filter(|account| account.owner == mint_info.program_id)
+                    .and_then(|account| unpack_token_account(&account.data).ok())
+                    .map(|account| account.state);
+
+                match (state, action) {
+                    (None, _) => result.status = "skipped: no token account".to_string(),
+                    (Some(AccountState::Frozen), FreezeAction::Freeze) => result.status = "skipped: already frozen".to_string(),
+                    (Some(AccountState::Initialized), FreezeAction::Thaw) => result.status = "skipped: not frozen".to_string(),
+                    (Some(_), _) => {
+                        let instruction = match action {
+                            FreezeAction::Freeze => freeze_account(
+                                &mint_info.program_id,
+                                token_account,
+                                &mint_pubkey,
+                                &self.wallet.pubkey(),
+                                &[],
+                            )?,
+                            FreezeAction::Thaw => thaw_account(
+                                &mint_info.program_id,
+                                token_account,
+                                &mint_pubkey,
+                                &self.wallet.pubkey(),
+                                &[],
+                            )?,
+                        };
+                        let item = batcher.push(vec![instruction], FREEZE_UNITS)?;
+                        queued.insert(item, results.len());
+                    }
+                }
+
+                results.push(result);
+            }
+        }
+
+        for batch in batcher.finish() {
+            for item in &batch.items {
+                let Some(result_index) = queued.get(item) else {
+                    continue;
+                };
+                let result = &mut results[*result_index];
+                match &batch.signature {
+                    Ok(signature) => {
+                        result.status = past_tense(action).to_string();
+                        result.signature = Some(signature.to_string());
+                    }
+                    Err(e) => result.status = format!("failed: {}", e),
+                }
+            }
+        }
+
+        let done = results.iter().filter(|r| r.status == past_tense(action)).count();
+        let failed = results.iter().filter(|r| r.status.starts_with("failed")).count();
+        let skipped = results.len() - done - failed;
+
+        for result in results.iter().filter(|r| r.status != past_tense(action)) {
+            warn!("  {} ({}): {}", result.wallet, result.token_account, result.status);
+        }
+        info!("{} account(s) {}, {} skipped, {} failed", done, past_tense(action), skipped, failed);
+
+        write_output(output, &results, &results)?;
+
+        if failed > 0 {
+            return Err(anyhow!("{} account(s) could not be updated", failed));
+        }
+        Ok(())
+    }
+}
+
+fn verb(action: FreezeAction) -> &'static str {
+    match action {
+        FreezeAction::Freeze => "Freezing",
+        FreezeAction::Thaw => "Thawing",
+    }
+}
+
+fn past_tense(action: FreezeAction) -> &'static str {
+    match action {
+        FreezeAction::Freeze => "frozen",
+        FreezeAction::Thaw => "thawed",
+    }
+}
@@ -0,0 +1,76 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use crate::{
+    holders::{format_amount, parse_amount},
+    TokenOperations,
+};
+
+/// Result of a token gate check, printed to stdout as one JSON line.
+#[derive(Debug, Clone, Serialize)]
+pub struct GateResult {
+    pub mint: String,
+    pub owner: String,
+    pub passed: bool,
+    pub balance: String,
+    pub min_amount: String,
+    pub raw_balance: u64,
+    pub accounts: usize,
+    pub slot: u64,
+}
+
+/// Error `run` ends with when the gate did not pass, once teardown is done;
+/// `main` turns it into exit status 1 without reporting it as a failure.
+#[derive(Debug)]
+pub struct GateCheckFailed;
+
+impl std::fmt::Display for GateCheckFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Token gate check failed")
+    }
+}
+
+impl std::error::Error for GateCheckFailed {}
+
+impl TokenOperations {
+    /// Check whether `owner` holds at least `min_amount` of the mint across
+    /// all of their token accounts. Returns whether the gate passed.
+    pub fn gate_check(&self, mint_address: &str, owner: &str, min_amount: &str) -> Result<bool> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let owner = Pubkey::from_str(owner).map_err(|e| anyhow!("Invalid owner address: {}", e))?;
+
+        let decimals = self.fetch_mint_info(&mint)?.base()?.decimals;
+        let threshold = parse_amount(min_amount, decimals)?;
+        let slot = self.client.get_slot().map_err(|e| anyhow!("Failed to get slot: {}", e))?;
+
+        let accounts = self.owned_token_accounts(&owner, Some(&mint))?;
+        let balance: u64 = accounts.iter().map(|owned| owned.account.amount).sum();
+        let passed = balance >= threshold;
+
+        info!(
+            "Gate {} for {}: holds {} across {} account(s), needs {}",
+            if passed { "passed" } else { "failed" },
+            owner,
+            format_amount(balance, decimals),
+            accounts.len(),
+            format_amount(threshold, decimals)
+        );
+
+        let result = GateResult {
+            mint: mint.to_string(),
+            owner: owner.to_string(),
+            passed,
+            balance: format_amount(balance, decimals),
+            min_amount: format_amount(threshold, decimals),
+            raw_balance: balance,
+            accounts: accounts.len(),
+            slot,
+        };
+        println!("{}", serde_json::to_string(&result)?);
+
+        Ok(passed)
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use log::{info, warn};
+use mpl_token_metadata::accounts::Metadata;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::{collections::HashMap, time::Duration};
+use yellowstone_grpc_client::GeyserGrpcClient;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts,
+    SubscribeUpdateAccountInfo,
+};
+
+use crate::{
+    monitor::{compare, log_authorities, parse_authorities, Alerts, Authorities},
+    TokenOperations,
+};
+
+/// Delay before reconnecting a dropped stream, doubled up to `MAX_RECONNECT_DELAY`.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// A Yellowstone (Geyser gRPC) endpoint, from `--geyser-endpoint` and `--geyser-token`.
+#[derive(Debug, Clone)]
+pub struct GeyserConfig {
+    pub endpoint: String,
+    pub x_token: Option<String>,
+}
+
+/// Latest mint and metadata accounts of every monitored mint.
+struct AccountCache {
+    /// Metadata PDA to the mint it describes.
+    metadata_of: HashMap<Pubkey, Pubkey>,
+    mints: HashMap<Pubkey, Option<Account>>,
+    metadata: HashMap<Pubkey, Option<Account>>,
+}
+
+impl AccountCache {
+    fn authorities(&self, mint: &Pubkey) -> Authorities {
+        let metadata_address = Metadata::find_pda(mint).0;
+        parse_authorities(
+            self.mints.get(mint).and_then(Option::as_ref),
+            self.metadata.get(&metadata_address).and_then(Option::as_ref),
+        )
+    }
+
+    /// Store an update and return the mint it affects, if it is monitored.
+    fn update(&mut self, info: SubscribeUpdateAccountInfo) -> Option<Pubkey> {
+        let address = Pubkey::try_from(info.pubkey.as_slice()).ok()?;
+        let account = (info.lamports > 0).then(|| Account {
+            lamports: info.lamports,
+            data: info.data,
+            owner: Pubkey::try_from(info.owner.as_slice()).unwrap_or_default(),
+            executable: info.executable,
+            rent_epoch: info.rent_epoch,
+        });
+
+        if let Some(mint) = self.mints.get_mut(&address) {
+            *mint = account;
+            return Some(address);
+        }
+        let mint = *self.metadata_of.get(&address)?;
+        self.metadata.insert(address, account);
+        Some(mint)
+    }
+}
+
+impl TokenOperations {
+    /// Read every mint and metadata account over RPC.
+    fn fetch_account_cache(&self, mints: &[Pubkey]) -> Result<AccountCache> {
+        let metadata_of: HashMap<Pubkey, Pubkey> = mints.iter().map(|mint| (Metadata::find_pda(mint).0, *mint)).collect();
+        let mut cache = AccountCache { metadata_of, mints: HashMap::new(), metadata: HashMap::new() };
+
+        for chunk in mints.chunks(50) {
+            let mut addresses = chunk.to_vec();
+            addresses.extend(chunk.iter().map(|mint| Metadata::find_pda(mint).0));
+            let accounts = self.client.get_multiple_accounts(&addresses)
+                .map_err(|e| anyhow!("Failed to get mint accounts: {}", e))?;
+            let (mint_accounts, metadata_accounts) = accounts.split_at(chunk.len());
+
+            for ((mint, mint_account), metadata_account) in chunk.iter().zip(mint_accounts).zip(metadata_accounts) {
+                cache.mints.insert(*mint, mint_account.clone());
+                cache.metadata.insert(Metadata::find_pda(mint).0, metadata_account.clone());
+            }
+        }
+        Ok(cache)
+    }
+
+    /// Stream account updates for the mints and their metadata from a
+    /// Yellowstone endpoint. After every reconnect the accounts are re-read
+    /// over RPC first, so changes made while disconnected still alert.
+    pub(crate) fn monitor_authorities_geyser(&self, mints: &[Pubkey], geyser: &GeyserConfig, alerts: &Alerts) -> Result<()> {
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| anyhow!("Failed to start async runtime: {}", e))?;
+
+        let mut cache = self.fetch_account_cache(mints)?;
+        let mut known: HashMap<Pubkey, Authorities> = mints.iter().map(|mint| (*mint, cache.authorities(mint))).collect();
+        info!("Streaming authorities of {} mint(s) from {}", mints.len(), geyser.endpoint);
+        log_authorities(&known);
+
+        let mut delay = RECONNECT_DELAY;
+        let mut first = true;
+        loop {
+            if !first {
+                std::thread::sleep(delay);
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+
+                // Catch up on anything that changed while the stream was down.
+                match self.fetch_account_cache(mints) {
+                    Ok(current) => {
+                        cache = current;
+                        let slot = self.client.get_slot().unwrap_or_default();
+                        for mint in mints {
+                            let new = cache.authorities(mint);
+                            if let Some(old) = known.get(mint) {
+                                alerts.send_all(compare(mint, old, &new, slot));
+                            }
+                            known.insert(*mint, new);
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to resync before reconnecting: {}", e);
+                        continue;
+                    }
+                }
+            }
+            first = false;
+
+            let result = runtime.block_on(self.stream_account_updates(&mut cache, &mut known, geyser, alerts, &mut delay));
+            match result {
+                Ok(()) => warn!("Geyser stream ended; reconnecting"),
+                Err(e) => warn!("Geyser stream failed, reconnecting: {}", e),
+            }
+        }
+    }
+
+    async fn stream_account_updates(
+        &self,
+        cache: &mut AccountCache,
+        known: &mut HashMap<Pubkey, Authorities>,
+        geyser: &GeyserConfig,
+        alerts: &Alerts<'_>,
+        delay: &mut Duration,
+    ) -> Result<()> {
+        let mut client = GeyserGrpcClient::build_from_shared(geyser.endpoint.clone())
+            .and_then(|builder| builder.x_token(geyser.x_token.clone()))
+            .map_err(|e| anyhow!("Invalid Geyser endpoint {}: {}", geyser.endpoint, e))?
+            .connect()
+            .await
+            .map_err(|e| anyhow!("Failed to connect to {}: {}", geyser.endpoint, e))?;
+
+        let mut addresses: Vec<String> = cache.mints.keys().map(Pubkey::to_string).collect();
+        addresses.extend(cache.metadata_of.keys().map(Pubkey::to_string));
+        let request = SubscribeRequest {
+            accounts: HashMap::from([(
+                "monitored".to_string(),
+                SubscribeRequestFilterAccounts { account: addresses, owner: Vec::new(), filters: Vec::new() },
+            )]),
+            commitment: Some(CommitmentLevel::Confirmed as i32),
+            ..SubscribeRequest::default()
+        };
+        let (_sink, mut stream) = client
+            .subscribe_with_request(Some(request))
+            .await
+            .map_err(|e| anyhow!("Failed to subscribe: {}", e))?;
+        info!("Subscribed to account updates");
+
+        while let Some(message) = stream.next().await {
+            let message = message.map_err(|e| anyhow!("Stream error: {}", e))?;
+            *delay = RECONNECT_DELAY;
+
+            let Some(UpdateOneof::Account(update)) = message.update_oneof else {
+                continue;
+            };
+            let Some(info) = update.account else {
+                continue;
+            };
+            let Some(mint) = cache.update(info) else {
+                continue;
+            };
+
+            let new = cache.authorities(&mint);
+            if let Some(old) = known.get(&mint) {
+                alerts.send_all(compare(&mint, old, &new, update.slot));
+            }
+            known.insert(mint, new);
+        }
+        Ok(())
+    }
+}
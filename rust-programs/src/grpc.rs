@@ -0,0 +1,194 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use std::{pin::Pin, str::FromStr, sync::Arc};
+use token_ops_core::{instructions::transfer_to_wallet, rpc};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tonic::{transport::Server, Request, Response, Status};
+
+use crate::{holders::parse_amount, TokenOperations};
+
+pub mod proto {
+    tonic::include_proto!("tokenops.v1");
+}
+
+use proto::{
+    snapshot_event::Event,
+    token_ops_server::{TokenOps, TokenOpsServer},
+    transfer_event::Stage,
+    GetBalanceRequest, HolderBatch, SnapshotEvent, SnapshotHeader, SnapshotRequest, SubmitTransferRequest,
+    TransferEvent, VerifyTokenRequest,
+};
+
+/// Holders sent per Snapshot stream message.
+const HOLDERS_PER_MESSAGE: usize = 500;
+
+/// Estimated compute units for an idempotent ATA creation plus TransferChecked.
+const TRANSFER_UNITS: u32 = 60_000;
+
+type EventStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+struct Service {
+    ops: Arc<TokenOperations>,
+}
+
+#[allow(clippy::result_large_err)]
+fn pubkey(value: &str, name: &str) -> Result<Pubkey, Status> {
+    Pubkey::from_str(value).map_err(|e| Status::invalid_argument(format!("Invalid {} '{}': {}", name, value, e)))
+}
+
+/// Run blocking RPC work off the async executor.
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T, Status> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?
+        .map_err(|e| Status::unavailable(e.to_string()))
+}
+
+#[tonic::async_trait]
+impl TokenOps for Service {
+    type SnapshotStream = EventStream<SnapshotEvent>;
+    type SubmitTransferStream = EventStream<TransferEvent>;
+
+    async fn verify_token(&self, request: Request<VerifyTokenRequest>) -> Result<Response<proto::MintSummary>, Status> {
+        let mint = pubkey(&request.into_inner().mint, "mint")?;
+        let ops = self.ops.clone();
+        let summary = blocking(move || ops.fetch_mint_info(&mint)?.summary()).await?;
+
+        Ok(Response::new(proto::MintSummary {
+            address: summary.address,
+            program: summary.program,
+            supply: summary.supply,
+            decimals: summary.decimals as u32,
+            is_initialized: summary.is_initialized,
+            mint_authority: summary.mint_authority,
+            freeze_authority: summary.freeze_authority,
+            extensions: summary.extensions,
+        }))
+    }
+
+    async fn get_balance(&self, request: Request<GetBalanceRequest>) -> Result<Response<proto::TokenBalance>, Status> {
+        let request = request.into_inner();
+        let mint = pubkey(&request.mint, "mint")?;
+        let owner = pubkey(&request.owner, "owner")?;
+        let ops = self.ops.clone();
+        let balance = blocking(move || rpc::fetch_balance(&ops.client, &mint, &owner)).await?;
+
+        Ok(Response::new(proto::TokenBalance {
+            mint: balance.mint,
+            owner: balance.owner,
+            account: balance.account,
+            amount: balance.amount,
+            ui_amount: balance.ui_amount,
+        }))
+    }
+
+    async fn snapshot(&self, request: Request<SnapshotRequest>) -> Result<Response<Self::SnapshotStream>, Status> {
+        let mint = pubkey(&request.into_inner().mint, "mint")?;
+        let ops = self.ops.clone();
+        let snapshot = blocking(move || ops.fetch_holder_snapshot(&mint)).await?;
+
+        let (sender, receiver) = mpsc::channel(4);
+        tokio::spawn(async move {
+            let header = SnapshotHeader {
+                mint: snapshot.mint.clone(),
+                slot: snapshot.slot,
+                supply: snapshot.supply,
+                decimals: snapshot.decimals as u32,
+                holders: snapshot.holders.len() as u64,
+            };
+            if sender.send(Ok(SnapshotEvent { event: Some(Event::Header(header)) })).await.is_err() {
+                return;
+            }
+            for chunk in snapshot.holders.chunks(HOLDERS_PER_MESSAGE) {
+                let holders = chunk
+                    .iter()
+                    .map(|holder| proto::HolderBalance {
+                        account: holder.account.clone(),
+                        owner: holder.owner.clone(),
+                        amount: holder.amount,
+                    })
+                    .collect();
+                let event = SnapshotEvent { event: Some(Event::Holders(HolderBatch { holders })) };
+                if sender.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+
+    async fn submit_transfer(
+        &self,
+        request: Request<SubmitTransferRequest>,
+    ) -> Result<Response<Self::SubmitTransferStream>, Status> {
+        let request = request.into_inner();
+        let mint = pubkey(&request.mint, "mint")?;
+        let recipient = pubkey(&request.recipient, "recipient")?;
+        let ops = self.ops.clone();
+        let amount = request.amount;
+        let memo = request.memo;
+
+        let (sender, receiver) = mpsc::channel(4);
+        tokio::spawn(async move {
+            let event = |stage: Stage, signature: Option<String>, error: Option<String>| TransferEvent {
+                stage: stage as i32,
+                signature,
+                error,
+            };
+            if sender.send(Ok(event(Stage::Sending, None, None))).await.is_err() {
+                return;
+            }
+
+            let wait_finalized = ops.wait_finalized;
+            let result = blocking(move || {
+                let mint_info = ops.fetch_mint_info(&mint)?;
+                let decimals = mint_info.base()?.decimals;
+                let raw = parse_amount(&amount, decimals)?;
+                if raw == 0 {
+                    return Err(anyhow!("Amount must be greater than zero"));
+                }
+                info!("gRPC transfer of {} {} to {}", amount, mint, recipient);
+                let memo = rpc::transfer_memo(&ops.client, &mint_info, &recipient, memo.as_deref())?;
+                let instructions = transfer_to_wallet(&mint_info, &ops.wallet.pubkey(), &recipient, raw, decimals, memo)?;
+                ops.send_instructions(instructions, TRANSFER_UNITS)
+            })
+            .await;
+
+            let last = match result {
+                Ok(signature) => {
+                    let stage = if wait_finalized { Stage::Finalized } else { Stage::Confirmed };
+                    event(stage, Some(signature.to_string()), None)
+                }
+                Err(status) => {
+                    warn!("gRPC transfer failed: {}", status.message());
+                    event(Stage::Failed, None, Some(status.message().to_string()))
+                }
+            };
+            let _ = sender.send(Ok(last)).await;
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+}
+
+/// Serve VerifyToken, GetBalance, Snapshot and SubmitTransfer over gRPC
+/// (see `proto/token_ops.proto`) until interrupted. SubmitTransfer spends
+/// from the loaded wallet, so bind to an address only trusted callers reach.
+pub fn serve_grpc(ops: TokenOperations, listen: &str) -> Result<()> {
+    let address = listen.parse().map_err(|e| anyhow!("Invalid listen address {}: {}", listen, e))?;
+    info!("gRPC server listening on {}", listen);
+    warn!("SubmitTransfer sends tokens from {}; do not expose this port publicly", ops.wallet.pubkey());
+
+    let service = Service { ops: Arc::new(ops) };
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| anyhow!("Failed to start async runtime: {}", e))?;
+    runtime.block_on(async {
+        Server::builder()
+            .add_service(TokenOpsServer::new(service))
+            .serve(address)
+            .await
+            .map_err(|e| anyhow!("gRPC server failed: {}", e))
+    })
+}
@@ -0,0 +1,153 @@
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::{
+    output::{write_output, OutputFormat},
+    providers::redact_url,
+    telemetry::rpc_client,
+};
+
+/// Blocks a fresh blockhash stays valid for (MAX_PROCESSING_AGE).
+const BLOCKHASH_VALIDITY: u64 = 150;
+
+/// A blockhash with fewer blocks of validity left than this is stale.
+const MIN_BLOCKHASH_REMAINING: u64 = 100;
+
+/// Health of one RPC endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointHealth {
+    pub url: String,
+    pub healthy: bool,
+    pub get_health: String,
+    pub version: Option<String>,
+    pub slot: Option<u64>,
+    pub slots_behind: Option<u64>,
+    pub blockhash_remaining_blocks: Option<u64>,
+    pub problems: Vec<String>,
+}
+
+/// Check each endpoint's getHealth, version, slot against `reference` (or
+/// the highest slot seen) and blockhash freshness. Fails when any endpoint
+/// is unhealthy or more than `max_slot_lag` slots behind.
+pub fn rpc_health(
+    urls: &[String],
+    reference: Option<&str>,
+    max_slot_lag: u64,
+    output: Option<(&str, OutputFormat)>,
+) -> Result<()> {
+    let mut results: Vec<EndpointHealth> = urls.iter().map(|url| check_endpoint(url)).collect();
+
+    let reference_slot = match reference {
+        Some(url) => Some(
+            rpc_client(url, CommitmentConfig::processed())
+                .get_slot()
+                .map_err(|e| anyhow!("Failed to get slot from reference {}: {}", redact_url(url), e))?,
+        ),
+        None => results.iter().filter_map(|result| result.slot).max(),
+    };
+
+    for result in &mut results {
+        if let (Some(slot), Some(reference_slot)) = (result.slot, reference_slot) {
+            let behind = reference_slot.saturating_sub(slot);
+            result.slots_behind = Some(behind);
+            if behind > max_slot_lag {
+                result.problems.push(format!("{} slots behind (max {})", behind, max_slot_lag));
+            }
+        }
+        result.healthy = result.problems.is_empty();
+    }
+
+    for result in &results {
+        let summary = format!(
+            "{}: getHealth {}, version {}, slot {}, behind {}, blockhash valid for {} more blocks",
+            result.url,
+            result.get_health,
+            result.version.as_deref().unwrap_or("?"),
+            show(result.slot),
+            show(result.slots_behind),
+            show(result.blockhash_remaining_blocks)
+        );
+        if result.healthy {
+            info!("OK   {}", summary);
+        } else {
+            error!("FAIL {}", summary);
+            for problem in &result.problems {
+                error!("       {}", problem);
+            }
+        }
+    }
+
+    write_output(output, &results, &results)?;
+
+    let unhealthy = results.iter().filter(|result| !result.healthy).count();
+    if unhealthy > 0 {
+        return Err(anyhow!("{} of {} endpoint(s) failed the health check", unhealthy, results.len()));
+    }
+    Ok(())
+}
+
+fn check_endpoint(url: &str) -> EndpointHealth {
+    let client = rpc_client(url, CommitmentConfig::processed());
+    let mut health = EndpointHealth {
+        url: redact_url(url),
+        healthy: false,
+        get_health: "ok".to_string(),
+        version: None,
+        slot: None,
+        slots_behind: None,
+        blockhash_remaining_blocks: None,
+        problems: Vec::new(),
+    };
+
+    if let Err(e) = client.get_health() {
+        health.get_health = "failed".to_string();
+        health.problems.push(format!("getHealth: {}", e));
+    }
+
+    match client.get_version() {
+        Ok(version) => health.version = Some(version.solana_core),
+        Err(e) => health.problems.push(format!("getVersion: {}", e)),
+    }
+
+    match client.get_slot() {
+        Ok(slot) => health.slot = Some(slot),
+        Err(e) => health.problems.push(format!("getSlot: {}", e)),
+    }
+
+    match blockhash_remaining(&client) {
+        Ok(remaining) => {
+            health.blockhash_remaining_blocks = Some(remaining);
+            if remaining < MIN_BLOCKHASH_REMAINING {
+                health.problems.push(format!(
+                    "latest blockhash is stale: valid for {} of {} blocks",
+                    remaining, BLOCKHASH_VALIDITY
+                ));
+            }
+        }
+        Err(e) => health.problems.push(e.to_string()),
+    }
+
+    if !health.problems.is_empty() {
+        warn!("{} reported {} problem(s)", redact_url(url), health.problems.len());
+    }
+    health
+}
+
+/// Blocks until the endpoint's latest blockhash expires; a fresh one has ~150.
+fn blockhash_remaining(client: &RpcClient) -> Result<u64> {
+    let commitment = CommitmentConfig::confirmed();
+    let (_, last_valid_block_height) = client
+        .get_latest_blockhash_with_commitment(commitment)
+        .map_err(|e| anyhow!("getLatestBlockhash: {}", e))?;
+    let block_height = client
+        .get_block_height_with_commitment(commitment)
+        .map_err(|e| anyhow!("getBlockHeight: {}", e))?;
+    Ok(last_valid_block_height.saturating_sub(block_height))
+}
+
+fn show(value: Option<u64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_else(|| "?".to_string())
+}
@@ -0,0 +1,228 @@
+use anyhow::{anyhow, Result};
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiLoadedAddresses, UiTransactionEncoding,
+    UiTransactionTokenBalance,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::TokenOperations;
+
+/// Maximum page size accepted by getSignaturesForAddress.
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+/// Progress of a paged signature walk, saved when it stops early.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SignatureWalk {
+    signatures: Vec<RpcConfirmedTransactionStatusWithSignature>,
+}
+
+impl SignatureWalk {
+    /// Where the next page starts: just past the oldest signature so far.
+    fn before(&self) -> Result<Option<Signature>> {
+        self.signatures
+            .last()
+            .map(|status| Signature::from_str(&status.signature))
+            .transpose()
+            .map_err(|e| anyhow!("Invalid signature from RPC: {}", e))
+    }
+}
+
+/// A fetched transaction with its full account key list resolved, including
+/// addresses loaded from lookup tables.
+pub struct HistoricalTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub account_keys: Vec<Pubkey>,
+    pub decoded: VersionedTransaction,
+    pub transaction: EncodedConfirmedTransactionWithStatusMeta,
+}
+
+impl HistoricalTransaction {
+    pub fn pre_token_balances(&self) -> Vec<UiTransactionTokenBalance> {
+        self.transaction
+            .transaction
+            .meta
+            .as_ref()
+            .and_then(|meta| Option::<Vec<_>>::from(meta.pre_token_balances.clone()))
+            .unwrap_or_default()
+    }
+
+    pub fn post_token_balances(&self) -> Vec<UiTransactionTokenBalance> {
+        self.transaction
+            .transaction
+            .meta
+            .as_ref()
+            .and_then(|meta| Option::<Vec<_>>::from(meta.post_token_balances.clone()))
+            .unwrap_or_default()
+    }
+
+    pub fn failed(&self) -> bool {
+        self.transaction
+            .transaction
+            .meta
+            .as_ref()
+            .is_some_and(|meta| meta.err.is_some())
+    }
+}
+
+impl TokenOperations {
+    /// Signatures involving `address`, newest first, stopping at `until`
+    /// (exclusive) or after `limit` entries. Walks without a limit can be
+    /// long, so they save a checkpoint when interrupted or when a page fails.
+    pub fn signatures_since(
+        &self,
+        address: &Pubkey,
+        until: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let scan = match limit {
+            Some(_) => None,
+            None => Some(self.checkpoints.scan(&format!("signatures-{}-{}", address, until.unwrap_or("all")))),
+        };
+        let until = until
+            .map(Signature::from_str)
+            .transpose()
+            .map_err(|e| anyhow!("Invalid signature cursor: {}", e))?;
+
+        let mut walk = match &scan {
+            Some(scan) => scan.resume()?.unwrap_or_default(),
+            None => SignatureWalk::default(),
+        };
+
+        loop {
+            let page_size = match limit {
+                Some(limit) => SIGNATURE_PAGE_SIZE.min(limit - walk.signatures.len()),
+                None => SIGNATURE_PAGE_SIZE,
+            };
+
+            let page = self.client.get_signatures_for_address_with_config(
+                address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before: walk.before()?,
+                    until,
+                    limit: Some(page_size),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            ).map_err(|e| anyhow!("Failed to get signatures for {}: {}", address, e));
+            let page = match (page, &scan) {
+                (Err(e), Some(scan)) => return Err(scan.fail(&walk, e)),
+                (page, _) => page?,
+            };
+
+            let page_len = page.len();
+            walk.signatures.extend(page);
+
+            let reached_limit = limit.is_some_and(|limit| walk.signatures.len() >= limit);
+            if page_len < page_size || reached_limit {
+                break;
+            }
+            if let Some(scan) = &scan {
+                scan.checkpoint(&walk)?;
+            }
+        }
+
+        if let Some(scan) = scan {
+            scan.finish()?;
+        }
+        Ok(walk.signatures)
+    }
+
+    /// Signatures involving `address` with a block time at or after `since`,
+    /// newest first. Checkpointed like `signatures_since`.
+    pub fn signatures_in_window(
+        &self,
+        address: &Pubkey,
+        since: i64,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let scan = self.checkpoints.scan(&format!("signatures-window-{}", address));
+        let mut walk: SignatureWalk = scan.resume()?.unwrap_or_default();
+
+        loop {
+            let page = self.client.get_signatures_for_address_with_config(
+                address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before: walk.before()?,
+                    until: None,
+                    limit: Some(SIGNATURE_PAGE_SIZE),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            ).map_err(|e| scan.fail(&walk, anyhow!("Failed to get signatures for {}: {}", address, e)))?;
+
+            let page_len = page.len();
+            let reached_start = page.iter().any(|status| status.block_time.is_some_and(|time| time < since));
+            walk.signatures.extend(page);
+
+            if page_len < SIGNATURE_PAGE_SIZE || reached_start {
+                break;
+            }
+            scan.checkpoint(&walk)?;
+        }
+
+        // A resumed walk may have started from an earlier `since`.
+        let mut signatures = walk.signatures;
+        if let Some(end) = signatures.iter().position(|status| status.block_time.is_some_and(|time| time < since)) {
+            signatures.truncate(end);
+        }
+        scan.finish()?;
+        Ok(signatures)
+    }
+
+    pub fn fetch_transaction(&self, signature: &str) -> Result<HistoricalTransaction> {
+        fetch_transaction(&self.client, signature)
+    }
+}
+
+/// Fetch a confirmed transaction and resolve its account keys.
+pub fn fetch_transaction(client: &RpcClient, signature: &str) -> Result<HistoricalTransaction> {
+    let parsed = Signature::from_str(signature)
+        .map_err(|e| anyhow!("Invalid signature: {}", e))?;
+
+    let transaction = client.get_transaction_with_config(
+        &parsed,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        },
+    ).map_err(|e| anyhow!("Failed to get transaction {}: {}", signature, e))?;
+
+    let decoded = transaction
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("Failed to decode transaction {}", signature))?;
+
+    let mut account_keys = decoded.message.static_account_keys().to_vec();
+    if let Some(loaded) = transaction
+        .transaction
+        .meta
+        .as_ref()
+        .and_then(|meta| Option::<UiLoadedAddresses>::from(meta.loaded_addresses.clone()))
+    {
+        for address in loaded.writable.iter().chain(loaded.readonly.iter()) {
+            account_keys.push(
+                Pubkey::from_str(address).map_err(|e| anyhow!("Invalid loaded address: {}", e))?,
+            );
+        }
+    }
+
+    Ok(HistoricalTransaction {
+        signature: signature.to_string(),
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        account_keys,
+        decoded,
+        transaction,
+    })
+}
@@ -0,0 +1,141 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub use token_ops_core::{
+    amount::{format_amount, parse_amount},
+    report::{distribution_stats, merge_by_owner, HolderBalance, HolderSnapshot, OwnerBalance},
+};
+
+use crate::{
+    output::{write_output, OutputFormat},
+    token_program::is_token_program,
+    TokenOperations,
+};
+
+/// Owner accounts fetched per getMultipleAccounts call.
+const FETCH_CHUNK: usize = 100;
+
+/// Programs and well-known authorities whose token accounts hold tokens on
+/// behalf of others rather than for a single wallet.
+const KNOWN_OWNERS: &[(&str, &str)] = &[
+    ("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8", "Raydium AMM v4 pool"),
+    ("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1", "Raydium AMM v4 pool"),
+    ("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK", "Raydium CLMM pool"),
+    ("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C", "Raydium CPMM pool"),
+    ("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc", "Orca Whirlpool"),
+    ("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo", "Meteora DLMM pool"),
+    ("Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB", "Meteora pool"),
+    ("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P", "Pump.fun bonding curve"),
+    ("strmRqUCoQUgGUan5YhzUZa6KqdzwX5L6FpUxfmKg5m", "Streamflow escrow"),
+    ("LocpQgucEQHbqNABEYvBvwoxCPsSbG91A1QaQhQQqjn", "Jupiter Lock escrow"),
+    ("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf", "Squads multisig"),
+];
+
+fn known_owner(address: &Pubkey) -> Option<&'static str> {
+    let address = address.to_string();
+    KNOWN_OWNERS.iter().find(|(known, _)| *known == address).map(|(_, label)| *label)
+}
+
+impl TokenOperations {
+    pub fn fetch_holder_snapshot(&self, mint_pubkey: &Pubkey) -> Result<HolderSnapshot> {
+        match &self.das {
+            Some(das) => self.das_holder_snapshot(das, mint_pubkey),
+            None => token_ops_core::rpc::fetch_holder_snapshot(&self.client, mint_pubkey),
+        }
+    }
+
+    /// Merge a snapshot's token accounts by owner wallet and label owners
+    /// that are not wallets: known pools and escrows by address or owning
+    /// program, other program-owned accounts, nested token accounts and PDAs.
+    pub fn resolve_owners(&self, snapshot: &HolderSnapshot) -> Result<Vec<OwnerBalance>> {
+        let mut owners = merge_by_owner(snapshot);
+
+        // Wallets are on-curve keys, so only off-curve owners need a lookup.
+        let suspects: Vec<(usize, Pubkey)> = owners
+            .iter()
+            .enumerate()
+            .filter_map(|(i, owner)| Some((i, Pubkey::from_str(&owner.owner).ok()?)))
+            .filter(|(_, owner)| known_owner(owner).is_some() || !owner.is_on_curve())
+            .collect();
+
+        for chunk in suspects.chunks(FETCH_CHUNK) {
+            let addresses: Vec<Pubkey> = chunk.iter().map(|(_, owner)| *owner).collect();
+            let accounts = self.client.get_multiple_accounts(&addresses)
+                .map_err(|e| anyhow!("Failed to get owner accounts: {}", e))?;
+
+            for ((i, owner), account) in chunk.iter().zip(accounts) {
+                let label = match (known_owner(owner), account) {
+                    (Some(label), _) => label.to_string(),
+                    (None, Some(account)) => match known_owner(&account.owner) {
+                        Some(label) => label.to_string(),
+                        None if is_token_program(&account.owner) => "nested in a token account".to_string(),
+                        None => format!("program-owned ({})", account.owner),
+                    },
+                    (None, None) => "PDA".to_string(),
+                };
+                owners[*i].label = Some(label);
+            }
+        }
+
+        Ok(owners)
+    }
+
+    pub fn report_distribution(&self, mint_pubkey: &Pubkey) -> Result<()> {
+        let snapshot = self.fetch_holder_snapshot(mint_pubkey)?;
+        let owners = self.resolve_owners(&snapshot)?;
+        let balances: Vec<u64> = owners.iter().map(|h| h.amount).collect();
+        let stats = distribution_stats(&balances, snapshot.supply);
+
+        info!("Holder distribution (slot {}):", snapshot.slot);
+        info!("  Holders: {} owner(s) across {} account(s)", stats.holders, snapshot.holders.len());
+        info!("  Top 1 share: {:.2}%", stats.top_1_pct);
+        info!("  Top 10 share: {:.2}%", stats.top_10_pct);
+        info!("  Top 100 share: {:.2}%", stats.top_100_pct);
+        info!("  Gini coefficient: {:.4}", stats.gini);
+        info!("  Median balance: {}", format_amount(stats.median_balance, snapshot.decimals));
+
+        let labelled: Vec<&OwnerBalance> = owners.iter().filter(|owner| owner.label.is_some()).take(10).collect();
+        if !labelled.is_empty() {
+            info!("  Largest non-wallet holders:");
+            for owner in labelled {
+                info!(
+                    "    {} {} ({})",
+                    owner.owner,
+                    format_amount(owner.amount, snapshot.decimals),
+                    owner.label.as_deref().unwrap_or_default()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// List the largest holders of a mint by owner wallet, with every token
+    /// account of an owner merged and non-wallet owners labelled.
+    pub fn top_holders(&self, mint_address: &str, limit: usize, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let snapshot = self.fetch_holder_snapshot(&mint_pubkey)?;
+        let mut owners = self.resolve_owners(&snapshot)?;
+        let total = owners.len();
+        owners.truncate(limit);
+
+        info!("Top {} of {} holder(s) at slot {}:", owners.len(), total, snapshot.slot);
+        for (rank, owner) in owners.iter().enumerate() {
+            let share = if snapshot.supply == 0 { 0.0 } else { owner.amount as f64 / snapshot.supply as f64 * 100.0 };
+            info!(
+                "  {:>3}. {} {} ({:.2}%, {} account(s)){}",
+                rank + 1,
+                owner.owner,
+                format_amount(owner.amount, snapshot.decimals),
+                share,
+                owner.accounts,
+                owner.label.as_ref().map(|label| format!(" [{}]", label)).unwrap_or_default()
+            );
+        }
+
+        write_output(output, &owners, &owners)
+    }
+}
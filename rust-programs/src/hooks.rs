@@ -0,0 +1,252 @@
+use anyhow::{anyhow, Result};
+use base58::FromBase58;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signature,
+};
+use std::{fs, process::Command, str::FromStr, sync::Mutex, time::Duration};
+
+/// Webhook request timeout.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where an injected instruction goes relative to the operation's own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Position {
+    Before,
+    #[default]
+    After,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AccountSpec {
+    /// An address, or `$wallet` / `$mint` for the loaded wallet and `--mint-address`.
+    pubkey: String,
+    #[serde(default)]
+    is_signer: bool,
+    #[serde(default)]
+    is_writable: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct InstructionSpec {
+    program_id: String,
+    #[serde(default)]
+    accounts: Vec<AccountSpec>,
+    /// Base58-encoded instruction data.
+    #[serde(default)]
+    data: String,
+    #[serde(default)]
+    position: Position,
+    /// Compute units the instruction needs, reserved in every transaction.
+    #[serde(default)]
+    units: u32,
+}
+
+/// A post- or pre-operation action: a shell command or a webhook POST.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Action {
+    Command { command: String },
+    Webhook { webhook: String },
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct HooksFile {
+    #[serde(default)]
+    instructions: Vec<InstructionSpec>,
+    #[serde(default)]
+    before: Vec<Action>,
+    #[serde(default)]
+    after_transaction: Vec<Action>,
+    #[serde(default)]
+    after: Vec<Action>,
+}
+
+/// Payload posted to webhook actions; commands get the same fields as
+/// `TOKEN_OPS_*` environment variables.
+#[derive(Debug, Clone, Serialize)]
+struct HookEvent<'a> {
+    event: &'a str,
+    operation: &'a str,
+    mint: Option<&'a str>,
+    signature: Option<String>,
+    signatures: Vec<String>,
+}
+
+/// Hooks loaded from `--hooks`: instructions injected into every transaction
+/// sent, and actions run before the operation, after each confirmed
+/// transaction and after the operation succeeds.
+///
+/// ```json
+/// {
+///   "instructions": [{"program_id": "...", "accounts": [{"pubkey": "$wallet", "is_signer": true}],
+///                     "data": "3Bxs4h24hBtQy9rw", "position": "after", "units": 5000}],
+///   "before": [{"command": "./preflight.sh"}],
+///   "after_transaction": [{"webhook": "https://example.com/tx"}],
+///   "after": [{"command": "notify-send \"$TOKEN_OPS_OPERATION done\""}]
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Hooks {
+    operation: String,
+    mint: Option<String>,
+    instructions_before: Vec<Instruction>,
+    instructions_after: Vec<Instruction>,
+    instruction_units: u32,
+    before: Vec<Action>,
+    after_transaction: Vec<Action>,
+    after: Vec<Action>,
+    signatures: Mutex<Vec<String>>,
+}
+
+impl Hooks {
+    pub fn load(path: &str, operation: &str, mint: Option<&str>, wallet: &Pubkey) -> Result<Self> {
+        let data = fs::read_to_string(path).map_err(|e| anyhow!("Failed to read hooks file {}: {}", path, e))?;
+        let file: HooksFile = serde_json::from_str(&data).map_err(|e| anyhow!("Failed to parse hooks file {}: {}", path, e))?;
+
+        let resolve = |value: &str| -> Result<Pubkey> {
+            match value {
+                "$wallet" => Ok(*wallet),
+                "$mint" => {
+                    let mint = mint.ok_or_else(|| anyhow!("Hook uses $mint but no --mint-address was given"))?;
+                    Pubkey::from_str(mint).map_err(|e| anyhow!("Invalid mint address: {}", e))
+                }
+                address => Pubkey::from_str(address).map_err(|e| anyhow!("Invalid address '{}' in hooks file: {}", address, e)),
+            }
+        };
+
+        let mut hooks = Hooks {
+            operation: operation.to_string(),
+            mint: mint.map(str::to_string),
+            instructions_before: Vec::new(),
+            instructions_after: Vec::new(),
+            instruction_units: 0,
+            before: file.before,
+            after_transaction: file.after_transaction,
+            after: file.after,
+            signatures: Mutex::new(Vec::new()),
+        };
+
+        for spec in file.instructions {
+            let accounts = spec
+                .accounts
+                .iter()
+                .map(|account| {
+                    let pubkey = resolve(&account.pubkey)?;
+                    if account.is_signer && pubkey != *wallet {
+                        return Err(anyhow!("Hook instruction signer {} is not the loaded wallet", pubkey));
+                    }
+                    Ok(if account.is_writable {
+                        AccountMeta::new(pubkey, account.is_signer)
+                    } else {
+                        AccountMeta::new_readonly(pubkey, account.is_signer)
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let data = spec
+                .data
+                .from_base58()
+                .map_err(|e| anyhow!("Invalid base58 instruction data in hooks file: {:?}", e))?;
+
+            let instruction = Instruction { program_id: resolve(&spec.program_id)?, accounts, data };
+            hooks.instruction_units += spec.units;
+            match spec.position {
+                Position::Before => hooks.instructions_before.push(instruction),
+                Position::After => hooks.instructions_after.push(instruction),
+            }
+        }
+
+        info!(
+            "Loaded hooks from {}: {} injected instruction(s), {} before, {} per-transaction and {} after action(s)",
+            path,
+            hooks.instructions_before.len() + hooks.instructions_after.len(),
+            hooks.before.len(),
+            hooks.after_transaction.len(),
+            hooks.after.len()
+        );
+        Ok(hooks)
+    }
+
+    /// Surround a transaction's instructions with the injected ones.
+    pub fn wrap(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+        let mut wrapped = self.instructions_before.clone();
+        wrapped.extend(instructions.iter().cloned());
+        wrapped.extend(self.instructions_after.iter().cloned());
+        wrapped
+    }
+
+    /// Compute units reserved in every transaction for injected instructions.
+    pub fn instruction_units(&self) -> u32 {
+        self.instruction_units
+    }
+
+    /// Run the `before` actions; any failure aborts the operation.
+    pub fn run_before(&self) -> Result<()> {
+        for action in &self.before {
+            self.run(action, "before", None)?;
+        }
+        Ok(())
+    }
+
+    /// Run the `after_transaction` actions. The transaction has already
+    /// landed, so failures are only logged.
+    pub fn run_after_transaction(&self, signature: &Signature) {
+        if let Ok(mut signatures) = self.signatures.lock() {
+            signatures.push(signature.to_string());
+        }
+        for action in &self.after_transaction {
+            if let Err(e) = self.run(action, "after_transaction", Some(signature)) {
+                warn!("after_transaction hook failed: {}", e);
+            }
+        }
+    }
+
+    /// Run the `after` actions once the operation has succeeded.
+    pub fn run_after(&self) -> Result<()> {
+        for action in &self.after {
+            self.run(action, "after", None)?;
+        }
+        Ok(())
+    }
+
+    fn run(&self, action: &Action, event: &str, signature: Option<&Signature>) -> Result<()> {
+        let payload = HookEvent {
+            event,
+            operation: &self.operation,
+            mint: self.mint.as_deref(),
+            signature: signature.map(|signature| signature.to_string()),
+            signatures: self.signatures.lock().map(|signatures| signatures.clone()).unwrap_or_default(),
+        };
+
+        match action {
+            Action::Command { command } => {
+                let status = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .env("TOKEN_OPS_EVENT", payload.event)
+                    .env("TOKEN_OPS_OPERATION", payload.operation)
+                    .env("TOKEN_OPS_MINT", payload.mint.unwrap_or_default())
+                    .env("TOKEN_OPS_SIGNATURE", payload.signature.as_deref().unwrap_or_default())
+                    .env("TOKEN_OPS_SIGNATURES", payload.signatures.join(","))
+                    .status()
+                    .map_err(|e| anyhow!("Failed to run {} hook '{}': {}", event, command, e))?;
+                if !status.success() {
+                    return Err(anyhow!("{} hook '{}' exited with {}", event, command, status));
+                }
+            }
+            Action::Webhook { webhook } => {
+                reqwest::blocking::Client::builder()
+                    .timeout(WEBHOOK_TIMEOUT)
+                    .build()
+                    .and_then(|client| client.post(webhook).json(&payload).send())
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|e| anyhow!("{} webhook {} failed: {}", event, webhook, e))?;
+            }
+        }
+        Ok(())
+    }
+}
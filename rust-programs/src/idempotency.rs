@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use solana_sdk::{instruction::Instruction, signature::{Signature, Signer}};
+use std::time::{SystemTime, UNIX_EPOCH};
+use token_ops_core::instructions::memo;
+
+use crate::TokenOperations;
+
+/// Default location of the idempotency key database.
+pub const DEFAULT_IDEMPOTENCY_DB: &str = "token-ops-idempotency.db";
+
+/// Estimated compute units for the signed memo carrying the key.
+pub const IDEMPOTENCY_MEMO_UNITS: u32 = 15_000;
+
+/// Memo text before the key, so keys can be found in a wallet's history.
+const MEMO_PREFIX: &str = "token-ops idempotency-key ";
+
+/// Longest key accepted; the memo has to fit in the transaction.
+const MAX_KEY_LEN: usize = 64;
+
+/// Seconds after which an attempt that has not landed never will: its
+/// blockhash (valid for 150 blocks) has expired.
+pub(crate) const SETTLE_SECONDS: i64 = 120;
+
+/// Recent wallet signatures searched for the key's memo.
+const RECENT_SIGNATURES: usize = 1000;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS idempotency_keys (
+        key TEXT PRIMARY KEY,
+        request TEXT NOT NULL,
+        signature TEXT,
+        attempted_at INTEGER NOT NULL
+    );
+";
+
+pub(crate) fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Whether an RPC memo field, `[len] text` entries joined by `; `, holds `text`.
+fn memo_field_contains(field: &str, text: &str) -> bool {
+    field.split("; ").any(|entry| {
+        let entry = match entry.split_once("] ") {
+            Some((length, rest)) if length.starts_with('[') => rest,
+            _ => entry,
+        };
+        entry == text
+    })
+}
+
+/// A key given with `--idempotency-key` and the local record of its use.
+/// A key runs at most once: the result's signature is recorded locally and
+/// the key travels on chain as a memo, so other machines see it too.
+pub struct IdempotencyKey {
+    conn: Connection,
+    key: String,
+}
+
+/// What the database knows about a key.
+struct KeyRecord {
+    request: String,
+    signature: Option<String>,
+    attempted_at: i64,
+}
+
+impl IdempotencyKey {
+    pub fn open(path: &str, key: &str) -> Result<Self> {
+        if key.is_empty() || key.len() > MAX_KEY_LEN || !key.chars().all(|c| c.is_ascii_graphic()) {
+            return Err(anyhow!("--idempotency-key must be 1 to {} printable characters without spaces", MAX_KEY_LEN));
+        }
+        let conn = Connection::open(path)
+            .map_err(|e| anyhow!("Failed to open idempotency database {}: {}", path, e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| anyhow!("Failed to initialize idempotency database schema: {}", e))?;
+
+        Ok(Self { conn, key: key.to_string() })
+    }
+
+    fn memo_text(&self) -> String {
+        format!("{}{}", MEMO_PREFIX, self.key)
+    }
+
+    fn lookup(&self) -> Result<Option<KeyRecord>> {
+        self.conn
+            .query_row(
+                "SELECT request, signature, attempted_at FROM idempotency_keys WHERE key = ?1",
+                params![self.key],
+                |row| Ok(KeyRecord { request: row.get(0)?, signature: row.get(1)?, attempted_at: row.get(2)? }),
+            )
+            .optional()
+            .map_err(|e| anyhow!("Failed to read idempotency database: {}", e))
+    }
+
+    fn save(&self, request: &str, signature: Option<&str>) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO idempotency_keys (key, request, signature, attempted_at) VALUES (?1, ?2, ?3, ?4)",
+                params![self.key, request, signature, unix_now()],
+            )
+            .map_err(|e| anyhow!("Failed to record idempotency key {}: {}", self.key, e))?;
+        Ok(())
+    }
+
+    /// Record the signature that executed the key.
+    pub fn complete(&self, request: &str, signature: &Signature) -> Result<()> {
+        self.save(request, Some(&signature.to_string()))
+    }
+}
+
+impl TokenOperations {
+    /// Check that `key` has not executed `request` (e.g. "mint-to 5 of
+    /// <mint> to <wallet>") and record this attempt, returning the memo to
+    /// send with it. Refuses when the key already has a signature, was used
+    /// for a different request, shows up in the wallet's recent history, or
+    /// belongs to an attempt that may still land.
+    pub fn begin_idempotent(&self, key: &IdempotencyKey, request: &str) -> Result<Instruction> {
+        let record = key.lookup()?;
+        if let Some(record) = &record {
+            if record.request != request {
+                return Err(anyhow!("Idempotency key {} was already used for: {}", key.key, record.request));
+            }
+            if let Some(signature) = &record.signature {
+                return Err(anyhow!("Idempotency key {} already executed in {}; not repeating it", key.key, signature));
+            }
+        }
+
+        if let Some(signature) = self.find_idempotency_memo(key)? {
+            key.save(request, Some(&signature))?;
+            return Err(anyhow!("Idempotency key {} already executed on chain in {}; not repeating it", key.key, signature));
+        }
+
+        if let Some(record) = record {
+            let elapsed = unix_now() - record.attempted_at;
+            if elapsed < SETTLE_SECONDS {
+                return Err(anyhow!(
+                    "An earlier attempt with idempotency key {} may still land; retry in {}s",
+                    key.key,
+                    SETTLE_SECONDS - elapsed
+                ));
+            }
+            warn!("The earlier attempt with idempotency key {} never landed; trying again", key.key);
+        }
+
+        key.save(request, None)?;
+        info!("Idempotency key {} recorded", key.key);
+        Ok(memo(&key.memo_text(), &self.wallet.pubkey()))
+    }
+
+    /// A successful recent wallet transaction carrying the key's memo.
+    fn find_idempotency_memo(&self, key: &IdempotencyKey) -> Result<Option<String>> {
+        let text = key.memo_text();
+        let signatures = self.signatures_since(&self.wallet.pubkey(), None, Some(RECENT_SIGNATURES))?;
+        Ok(signatures
+            .into_iter()
+            .find(|status| status.err.is_none() && status.memo.as_deref().is_some_and(|field| memo_field_contains(field, &text)))
+            .map(|status| status.signature))
+    }
+}
@@ -0,0 +1,467 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::BTreeSet,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    holders::{format_amount, parse_amount, HolderBalance, HolderSnapshot},
+    output::{write_output, OutputFormat},
+    token_program::{is_token_program, unpack_token_account},
+    TokenOperations,
+};
+
+/// Progress of index-sync, saved when it stops early.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncProgress {
+    /// The mint's transactions since the last sync, newest first.
+    signatures: Vec<ObservedTransaction>,
+    /// Mint transactions already fetched, counted from the oldest.
+    processed: usize,
+    /// Tracked accounts already checked for activity, in address order.
+    checked: usize,
+    /// Newest signature of each tracked account that had activity, stored
+    /// once the accounts are refetched.
+    cursors: Vec<(String, String)>,
+    /// Token accounts to refetch.
+    touched: BTreeSet<String>,
+}
+
+/// Default location of the local index database.
+pub const DEFAULT_INDEX_PATH: &str = "token-index.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS snapshots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        mint TEXT NOT NULL,
+        slot INTEGER NOT NULL,
+        supply INTEGER NOT NULL,
+        decimals INTEGER NOT NULL,
+        taken_at INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS snapshot_balances (
+        snapshot_id INTEGER NOT NULL REFERENCES snapshots(id),
+        account TEXT NOT NULL,
+        owner TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        PRIMARY KEY (snapshot_id, account)
+    );
+    CREATE TABLE IF NOT EXISTS token_accounts (
+        address TEXT PRIMARY KEY,
+        mint TEXT NOT NULL,
+        owner TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        slot INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS token_accounts_mint ON token_accounts (mint, amount);
+    CREATE TABLE IF NOT EXISTS transactions (
+        signature TEXT PRIMARY KEY,
+        mint TEXT NOT NULL,
+        slot INTEGER NOT NULL,
+        block_time INTEGER,
+        failed INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS transactions_mint_slot ON transactions (mint, slot);
+    CREATE TABLE IF NOT EXISTS account_cursors (
+        address TEXT PRIMARY KEY,
+        last_signature TEXT NOT NULL
+    );
+";
+
+/// Token accounts refetched per getMultipleAccounts call during sync.
+const SYNC_FETCH_CHUNK: usize = 100;
+
+/// A mint-related transaction signature seen on chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObservedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+}
+
+/// Local SQLite store of holder snapshots, current token account state and
+/// observed transactions, so reports can be answered without the RPC.
+pub struct Index {
+    pub(crate) conn: Connection,
+}
+
+impl Index {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path)
+            .map_err(|e| anyhow!("Failed to open index database {}: {}", path, e))?;
+        conn.execute_batch(SCHEMA)
+            .and_then(|_| conn.execute_batch(crate::events::SCHEMA))
+            .map_err(|e| anyhow!("Failed to initialize index schema: {}", e))?;
+
+        Ok(Self { conn })
+    }
+
+    /// Store a snapshot and replace the current account state for its mint.
+    pub fn record_snapshot(&mut self, snapshot: &HolderSnapshot) -> Result<i64> {
+        let taken_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO snapshots (mint, slot, supply, decimals, taken_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![snapshot.mint, snapshot.slot, snapshot.supply, snapshot.decimals, taken_at],
+        )?;
+        let snapshot_id = tx.last_insert_rowid();
+
+        tx.execute("DELETE FROM token_accounts WHERE mint = ?1", params![snapshot.mint])?;
+        {
+            let mut insert_balance = tx.prepare(
+                "INSERT INTO snapshot_balances (snapshot_id, account, owner, amount) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            let mut upsert_account = tx.prepare(
+                "INSERT OR REPLACE INTO token_accounts (address, mint, owner, amount, slot) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+
+            for holder in &snapshot.holders {
+                insert_balance.execute(params![snapshot_id, holder.account, holder.owner, holder.amount])?;
+                upsert_account.execute(params![holder.account, snapshot.mint, holder.owner, holder.amount, snapshot.slot])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(snapshot_id)
+    }
+
+    pub fn record_transactions(&mut self, mint: &str, transactions: &[ObservedTransaction]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO transactions (signature, mint, slot, block_time, failed) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for transaction in transactions {
+                inserted += insert.execute(params![
+                    transaction.signature,
+                    mint,
+                    transaction.slot,
+                    transaction.block_time,
+                    transaction.failed,
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(inserted)
+    }
+
+    /// Decimals recorded with the most recent snapshot of a mint.
+    pub fn decimals(&self, mint: &str) -> Result<Option<u8>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT decimals FROM snapshots WHERE mint = ?1 ORDER BY id DESC LIMIT 1",
+                params![mint],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Newest indexed transaction signature for a mint, the starting point of the next sync.
+    pub fn latest_signature(&self, mint: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT signature FROM transactions WHERE mint = ?1 ORDER BY slot DESC LIMIT 1",
+                params![mint],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Every tracked token account for a mint, including emptied ones.
+    pub fn tracked_accounts(&self, mint: &str) -> Result<Vec<String>> {
+        let mut statement = self.conn.prepare("SELECT address FROM token_accounts WHERE mint = ?1 ORDER BY address")?;
+        let rows = statement.query_map(params![mint], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    pub fn account_cursor(&self, address: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT last_signature FROM account_cursors WHERE address = ?1",
+                params![address],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_account_cursor(&self, address: &str, signature: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO account_cursors (address, last_signature) VALUES (?1, ?2)",
+            params![address, signature],
+        )?;
+        Ok(())
+    }
+
+    pub fn upsert_account(&self, address: &str, mint: &str, owner: &str, amount: u64, slot: u64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO token_accounts (address, mint, owner, amount, slot) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![address, mint, owner, amount, slot],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_account(&self, address: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM token_accounts WHERE address = ?1", params![address])?;
+        self.conn.execute("DELETE FROM account_cursors WHERE address = ?1", params![address])?;
+        Ok(())
+    }
+
+    /// The most recent stored snapshot of `mint` taken at or before `slot`.
+    pub fn snapshot_at(&self, mint: &str, slot: u64) -> Result<Option<HolderSnapshot>> {
+        let header = self.conn
+            .query_row(
+                "SELECT id, slot, supply, decimals FROM snapshots
+                 WHERE mint = ?1 AND slot <= ?2 ORDER BY slot DESC, id DESC LIMIT 1",
+                params![mint, slot],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, u64>(1)?, row.get::<_, u64>(2)?, row.get::<_, u8>(3)?)),
+            )
+            .optional()?;
+        let Some((snapshot_id, slot, supply, decimals)) = header else {
+            return Ok(None);
+        };
+
+        let mut statement = self.conn.prepare(
+            "SELECT account, owner, amount FROM snapshot_balances WHERE snapshot_id = ?1 ORDER BY amount DESC",
+        )?;
+        let holders = statement
+            .query_map(params![snapshot_id], |row| {
+                Ok(HolderBalance {
+                    account: row.get(0)?,
+                    owner: row.get(1)?,
+                    amount: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(Some(HolderSnapshot {
+            mint: mint.to_string(),
+            slot,
+            supply,
+            decimals,
+            holders,
+        }))
+    }
+
+    /// Current funded accounts for a mint holding at least `min_amount`, largest first.
+    pub fn holders(&self, mint: &str, min_amount: u64) -> Result<Vec<HolderBalance>> {
+        let mut statement = self.conn.prepare(
+            "SELECT address, owner, amount FROM token_accounts
+             WHERE mint = ?1 AND amount >= ?2 AND amount > 0
+             ORDER BY amount DESC",
+        )?;
+
+        let rows = statement.query_map(params![mint, min_amount], |row| {
+            Ok(HolderBalance {
+                account: row.get(0)?,
+                owner: row.get(1)?,
+                amount: row.get(2)?,
+            })
+        })?;
+
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+}
+
+impl TokenOperations {
+    /// Take a holder snapshot into the index, also writing it to `output` when given.
+    pub fn index_snapshot(&self, mint_address: &str, index_path: &str, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+
+        let mut index = Index::open(index_path)?;
+        let snapshot = self.fetch_holder_snapshot(&mint_pubkey)?;
+        let snapshot_id = index.record_snapshot(&snapshot)?;
+
+        info!("Stored snapshot #{} with {} holder account(s) in {}", snapshot_id, snapshot.holders.len(), index_path);
+        write_output(output, &snapshot, &snapshot.holders)?;
+
+        // Remember the most recent activity on the mint alongside the snapshot.
+        let signatures = self.signatures_since(&mint_pubkey, None, Some(1000))?;
+
+        let observed: Vec<ObservedTransaction> = signatures
+            .into_iter()
+            .map(|status| ObservedTransaction {
+                signature: status.signature,
+                slot: status.slot,
+                block_time: status.block_time,
+                failed: status.err.is_some(),
+            })
+            .collect();
+        let inserted = index.record_transactions(mint_address, &observed)?;
+        info!("Recorded {} new transaction(s) for mint {}", inserted, mint_address);
+
+        // Seed each holder's cursor, so index-sync only reads activity after the snapshot.
+        for holder in &snapshot.holders {
+            let account = Pubkey::from_str(&holder.account)
+                .map_err(|e| anyhow!("Invalid account in snapshot: {}", e))?;
+            if let Some(newest) = self.signatures_since(&account, None, Some(1))?.first() {
+                index.set_account_cursor(&holder.account, &newest.signature)?;
+            }
+        }
+        info!("Seeded sync cursors for {} holder account(s)", snapshot.holders.len());
+        Ok(())
+    }
+}
+
+impl TokenOperations {
+    /// Bring the index for a mint up to date without a full program scan.
+    ///
+    /// New accounts and checked transfers are discovered from the mint's own
+    /// signatures; plain transfers, which never reference the mint, are
+    /// found by walking each tracked account's signatures since its cursor.
+    /// Only accounts that changed are refetched. Both walks save a
+    /// checkpoint when interrupted and continue with `--resume`.
+    pub fn index_sync(&self, mint_address: &str, index_path: &str) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+
+        let mut index = Index::open(index_path)?;
+        if index.decimals(mint_address)?.is_none() {
+            info!("Mint {} is not indexed yet; taking an initial full snapshot", mint_address);
+            return self.index_snapshot(mint_address, index_path, None);
+        }
+
+        let scan = self.checkpoints.scan(&format!("index-sync-{}", mint_address));
+        let mut progress: SyncProgress = match scan.resume()? {
+            Some(progress) => progress,
+            None => {
+                let cursor = index.latest_signature(mint_address)?;
+                let signatures = self
+                    .signatures_since(&mint_pubkey, cursor.as_deref(), None)?
+                    .into_iter()
+                    .map(|status| ObservedTransaction {
+                        signature: status.signature,
+                        slot: status.slot,
+                        block_time: status.block_time,
+                        failed: status.err.is_some(),
+                    })
+                    .collect();
+                SyncProgress { signatures, ..SyncProgress::default() }
+            }
+        };
+        info!("Found {} new mint transaction(s) since last sync", progress.signatures.len() - progress.processed);
+
+        // Oldest first, so `processed` counts from the end of the newest-first list.
+        while progress.processed < progress.signatures.len() {
+            let observed = &progress.signatures[progress.signatures.len() - 1 - progress.processed];
+            if !observed.failed {
+                let transaction = self.fetch_transaction(&observed.signature).map_err(|e| scan.fail(&progress, e))?;
+                if !transaction.failed() {
+                    for balance in transaction.pre_token_balances().iter().chain(transaction.post_token_balances().iter()) {
+                        if balance.mint != mint_address {
+                            continue;
+                        }
+                        if let Some(account) = transaction.account_keys.get(balance.account_index as usize) {
+                            progress.touched.insert(account.to_string());
+                        }
+                    }
+                }
+            }
+            progress.processed += 1;
+            scan.checkpoint(&progress)?;
+        }
+
+        // Plain Transfer instructions never reference the mint, so walk the
+        // tracked accounts themselves. An account without a cursor only has
+        // its newest signature read, and is refetched.
+        let tracked = index.tracked_accounts(mint_address)?;
+        for address in tracked.iter().skip(progress.checked) {
+            let account = Pubkey::from_str(address)
+                .map_err(|e| anyhow!("Invalid account in index: {}", e))?;
+            let cursor = index.account_cursor(address)?;
+            let limit = if cursor.is_none() { Some(1) } else { None };
+            let signatures = self
+                .signatures_since(&account, cursor.as_deref(), limit)
+                .map_err(|e| scan.fail(&progress, e))?;
+
+            if let Some(newest) = signatures.first() {
+                progress.touched.insert(address.clone());
+                progress.cursors.push((address.clone(), newest.signature.clone()));
+            }
+            progress.checked += 1;
+            scan.checkpoint(&progress)?;
+        }
+
+        let touched = progress
+            .touched
+            .iter()
+            .map(|address| Pubkey::from_str(address).map_err(|e| anyhow!("Invalid account in checkpoint: {}", e)))
+            .collect::<Result<Vec<Pubkey>>>()?;
+        info!("Refreshing {} touched account(s)", touched.len());
+
+        let slot = self.client.get_slot()
+            .map_err(|e| anyhow!("Failed to get current slot: {}", e))?;
+
+        for chunk in touched.chunks(SYNC_FETCH_CHUNK) {
+            let accounts = self.client.get_multiple_accounts(chunk)
+                .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
+
+            for (address, account) in chunk.iter().zip(accounts) {
+                let address = address.to_string();
+                let token_account = account
+                    .filter(|account| is_token_program(&account.owner))
+                    .and_then(|account| unpack_token_account(&account.data).ok())
+                    .filter(|token_account| token_account.mint == mint_pubkey);
+
+                match token_account {
+                    Some(token_account) => index.upsert_account(
+                        &address,
+                        mint_address,
+                        &token_account.owner.to_string(),
+                        token_account.amount,
+                        slot,
+                    )?,
+                    None => index.remove_account(&address)?,
+                }
+            }
+        }
+
+        // Cursors only advance once every touched account is stored.
+        index.record_transactions(mint_address, &progress.signatures)?;
+        let still_tracked: BTreeSet<String> = index.tracked_accounts(mint_address)?.into_iter().collect();
+        for (address, signature) in progress.cursors.iter().filter(|(address, _)| still_tracked.contains(address)) {
+            index.set_account_cursor(address, signature)?;
+        }
+        scan.finish()?;
+
+        info!("Index for {} synced to slot {}", mint_address, slot);
+        Ok(())
+    }
+}
+
+/// Answer `query-holders` from the local index only, without touching the RPC.
+pub fn query_holders(index_path: &str, mint_address: &str, min_balance: Option<&str>) -> Result<()> {
+    let index = Index::open(index_path)?;
+    let decimals = index
+        .decimals(mint_address)?
+        .ok_or_else(|| anyhow!("Mint {} has not been indexed yet; run index-snapshot first", mint_address))?;
+
+    let min_amount = match min_balance {
+        Some(value) => parse_amount(value, decimals)?,
+        None => 0,
+    };
+
+    let holders = index.holders(mint_address, min_amount)?;
+    info!("Found {} holder account(s) with at least {} tokens:", holders.len(), format_amount(min_amount, decimals));
+
+    for (i, holder) in holders.iter().enumerate() {
+        info!("  {}. {} (owner {}): {}", i + 1, holder.account, holder.owner, format_amount(holder.amount, decimals));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    fs,
+    io::{self, Read},
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Input path that means standard input.
+pub const STDIN_PATH: &str = "-";
+
+static STDIN_READ: AtomicBool = AtomicBool::new(false);
+
+/// Read an input file, or standard input when `path` is `-`.
+pub fn read_input(path: &str) -> Result<String> {
+    if path != STDIN_PATH {
+        return fs::read_to_string(path).map_err(|e| anyhow!("Failed to read {}: {}", path, e));
+    }
+
+    STDIN_READ.store(true, Ordering::Relaxed);
+    let mut contents = String::new();
+    io::stdin()
+        .read_to_string(&mut contents)
+        .map_err(|e| anyhow!("Failed to read standard input: {}", e))?;
+    Ok(contents)
+}
+
+/// Whether input was taken from stdin, which then cannot answer prompts.
+pub fn stdin_consumed() -> bool {
+    STDIN_READ.load(Ordering::Relaxed)
+}
+
+/// Read a list of addresses from a file, or stdin for `-`.
+///
+/// One address per line; blank lines and lines starting with `#` are skipped,
+/// and only the first comma-separated column is used so CSV files work too.
+pub fn read_addresses(path: &str) -> Result<Vec<Pubkey>> {
+    let contents = read_input(path)?;
+
+    let mut addresses = Vec::new();
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let field = line.split(',').next().unwrap_or("").trim();
+        let address = Pubkey::from_str(field)
+            .map_err(|e| anyhow!("{}:{}: invalid address '{}': {}", path, line_number + 1, field, e))?;
+        addresses.push(address);
+    }
+
+    Ok(addresses)
+}
@@ -0,0 +1,173 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use mpl_token_metadata::accounts::Metadata;
+use solana_sdk::{
+    account::Account,
+    native_token::lamports_to_sol,
+    nonce::{state::Versions, State as NonceState},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as TokenAccount, Multisig},
+};
+use std::str::FromStr;
+use token_ops_core::tlv::{extension_name, token_account_extensions};
+
+use crate::{
+    metadata::trim_padding,
+    token_program::{is_token_program, MintInfo},
+    TokenOperations,
+};
+
+/// Bytes of raw data shown for accounts that cannot be decoded.
+const MAX_HEX_DUMP: usize = 512;
+
+/// Token-2022 account type byte of a token account, after the padded base state.
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+fn show(key: Option<Pubkey>) -> String {
+    key.map(|key| key.to_string()).unwrap_or_else(|| "None".to_string())
+}
+
+/// Classic 16-bytes-per-line hex dump with an ASCII column.
+fn hex_dump(data: &[u8]) -> Vec<String> {
+    data.chunks(16)
+        .enumerate()
+        .map(|(line, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|byte| if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' })
+                .collect();
+            format!("{:08x}  {:<47}  {}", line * 16, hex.join(" "), ascii)
+        })
+        .collect()
+}
+
+fn is_mint(data: &[u8]) -> bool {
+    data.len() == spl_token_2022::state::Mint::LEN || (data.len() > TokenAccount::LEN && data[TokenAccount::LEN] == 1)
+}
+
+fn describe_token_program_account(address: &Pubkey, account: &Account) -> Result<Vec<String>> {
+    let data = &account.data;
+    if data.len() == Multisig::LEN {
+        let multisig = Multisig::unpack(data).map_err(|e| anyhow!("Failed to parse multisig: {}", e))?;
+        let mut lines = vec![
+            "Kind: Multisig".to_string(),
+            format!("Required signers: {} of {}", multisig.m, multisig.n),
+        ];
+        for (i, signer) in multisig.signers.iter().take(multisig.n as usize).enumerate() {
+            lines.push(format!("  Signer {}: {}", i + 1, signer));
+        }
+        return Ok(lines);
+    }
+
+    if is_mint(data) {
+        let summary = MintInfo { address: *address, program_id: account.owner, data: data.clone() }.summary()?;
+        let mut lines = vec![
+            format!("Kind: Mint ({})", summary.program),
+            format!("Supply: {}", summary.supply),
+            format!("Decimals: {}", summary.decimals),
+            format!("Mint Authority: {}", summary.mint_authority.unwrap_or_else(|| "None".to_string())),
+            format!("Freeze Authority: {}", summary.freeze_authority.unwrap_or_else(|| "None".to_string())),
+        ];
+        lines.extend(summary.extensions.into_iter().map(|line| format!("  {}", line)));
+        return Ok(lines);
+    }
+
+    let state = StateWithExtensions::<TokenAccount>::unpack(data)
+        .map_err(|e| anyhow!("Failed to parse token account: {}", e))?
+        .base;
+    let mut lines = vec![
+        "Kind: Token account".to_string(),
+        format!("Mint: {}", state.mint),
+        format!("Owner: {}", state.owner),
+        format!("Amount: {}", state.amount),
+        format!("State: {:?}", state.state),
+        format!("Delegate: {} ({} delegated)", show(state.delegate.into()), state.delegated_amount),
+        format!("Close Authority: {}", show(state.close_authority.into())),
+    ];
+    if let Some(rent_reserve) = Option::<u64>::from(state.is_native) {
+        lines.push(format!("Native (wrapped SOL), rent reserve {}", rent_reserve));
+    }
+    if data.len() > TokenAccount::LEN && data[TokenAccount::LEN] == ACCOUNT_TYPE_ACCOUNT {
+        for (extension, value) in token_account_extensions(data)? {
+            lines.push(format!("  Extension: {} ({} bytes)", extension_name(extension), value.len()));
+        }
+    }
+    Ok(lines)
+}
+
+impl TokenOperations {
+    /// Fetch any account and print it decoded by owner: mints, token accounts
+    /// (with extensions) and multisigs of either token program, Metaplex
+    /// metadata and nonce accounts, or a hex dump of anything else.
+    pub fn account_info(&self, address: &str) -> Result<()> {
+        let address = Pubkey::from_str(address).map_err(|e| anyhow!("Invalid address: {}", e))?;
+        let account = self.client
+            .get_account_with_commitment(&address, self.client.commitment())
+            .map_err(|e| anyhow!("Failed to get account: {}", e))?
+            .value
+            .ok_or_else(|| anyhow!("Account {} does not exist", address))?;
+        let rent_minimum = self.client.get_minimum_balance_for_rent_exemption(account.data.len())
+            .map_err(|e| anyhow!("Failed to get rent exemption: {}", e))?;
+
+        info!("Account {}", address);
+        info!("  Owner: {}", account.owner);
+        info!("  Lamports: {} ({} SOL)", account.lamports, lamports_to_sol(account.lamports));
+        info!(
+            "  Rent-exempt minimum: {} ({})",
+            rent_minimum,
+            if account.lamports >= rent_minimum { "exempt" } else { "NOT exempt" }
+        );
+        info!("  Data: {} bytes{}", account.data.len(), if account.executable { ", executable" } else { "" });
+
+        let decoded = if is_token_program(&account.owner) {
+            Some(describe_token_program_account(&address, &account)?)
+        } else if account.owner == mpl_token_metadata::ID {
+            Metadata::from_bytes(&account.data).ok().map(|metadata| {
+                vec![
+                    "Kind: Metaplex metadata".to_string(),
+                    format!("Mint: {}", metadata.mint),
+                    format!("Name: {}", trim_padding(&metadata.name)),
+                    format!("Symbol: {}", trim_padding(&metadata.symbol)),
+                    format!("URI: {}", trim_padding(&metadata.uri)),
+                    format!("Update Authority: {}", metadata.update_authority),
+                    format!("Seller Fee: {} bps", metadata.seller_fee_basis_points),
+                    format!("Mutable: {}", metadata.is_mutable),
+                ]
+            })
+        } else if account.owner == system_program::id() {
+            match bincode::deserialize::<Versions>(&account.data).map(|versions| versions.state().clone()) {
+                Ok(NonceState::Initialized(data)) => Some(vec![
+                    "Kind: Nonce account".to_string(),
+                    format!("Authority: {}", data.authority),
+                    format!("Nonce: {}", data.blockhash()),
+                    format!("Fee per signature: {}", data.fee_calculator.lamports_per_signature),
+                ]),
+                _ if account.data.is_empty() => Some(vec!["Kind: System account (wallet)".to_string()]),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        match decoded {
+            Some(lines) => {
+                for line in lines {
+                    info!("  {}", line);
+                }
+            }
+            None => {
+                info!("  Kind: unknown; raw data{}:", if account.data.len() > MAX_HEX_DUMP { " (truncated)" } else { "" });
+                for line in hex_dump(&account.data[..account.data.len().min(MAX_HEX_DUMP)]) {
+                    info!("    {}", line);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Result};
+use base58::{FromBase58, ToBase58};
+use log::info;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{keypair_from_seed, Keypair, Signer},
+};
+use std::{fs, io::Write, str::FromStr};
+
+use crate::{input::read_input, prompt};
+
+/// Encodings `key-convert` reads and writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// The Solana CLI keypair file: a JSON array of the 64 secret key bytes.
+    Json,
+    /// The 64 secret key bytes in base58, as wallets like Phantom export them.
+    Base58,
+    /// The 32-byte ed25519 seed in hex.
+    Hex,
+    /// Only the public key, in base58.
+    Pubkey,
+}
+
+impl FromStr for KeyFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "json" => Ok(KeyFormat::Json),
+            "base58" => Ok(KeyFormat::Base58),
+            "hex" => Ok(KeyFormat::Hex),
+            "pubkey" => Ok(KeyFormat::Pubkey),
+            other => Err(anyhow!("Unknown key format '{}'; use json, base58, hex or pubkey", other)),
+        }
+    }
+}
+
+/// A key read in any supported format.
+enum ParsedKey {
+    Keypair(Keypair),
+    Pubkey(Pubkey),
+}
+
+/// A keypair from 64 secret key bytes, rejecting bytes whose public half
+/// does not belong to the seed (a corrupted or hand-edited key).
+fn keypair_from_bytes(bytes: &[u8]) -> Result<Keypair> {
+    let keypair = Keypair::from_bytes(bytes).map_err(|e| anyhow!("Invalid secret key: {}", e))?;
+    let derived = seed_keypair(&bytes[..32])?;
+    if derived.pubkey() != keypair.pubkey() {
+        return Err(anyhow!("The public half of the secret key does not match its seed; the key is corrupted"));
+    }
+    Ok(keypair)
+}
+
+fn seed_keypair(seed: &[u8]) -> Result<Keypair> {
+    keypair_from_seed(seed).map_err(|e| anyhow!("Invalid seed: {}", e))
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok()).collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Detect the format of `text`: a JSON byte array (64-byte keypair or
+/// 32-byte seed), hex (seed or keypair), or base58 (keypair or public key).
+fn parse_key(text: &str) -> Result<ParsedKey> {
+    let text = text.trim();
+    if text.starts_with('[') {
+        let bytes: Vec<u8> = serde_json::from_str(text).map_err(|e| anyhow!("Invalid JSON key: {}", e))?;
+        return match bytes.len() {
+            64 => Ok(ParsedKey::Keypair(keypair_from_bytes(&bytes)?)),
+            32 => Ok(ParsedKey::Keypair(seed_keypair(&bytes)?)),
+            n => Err(anyhow!("JSON key has {} bytes; expected 64 (keypair) or 32 (seed)", n)),
+        };
+    }
+
+    if let Some(bytes) = decode_hex(text.trim_start_matches("0x")) {
+        match bytes.len() {
+            32 => return Ok(ParsedKey::Keypair(seed_keypair(&bytes)?)),
+            64 => return Ok(ParsedKey::Keypair(keypair_from_bytes(&bytes)?)),
+            _ => {}
+        }
+    }
+
+    let bytes = text
+        .from_base58()
+        .map_err(|_| anyhow!("Key is not a JSON byte array, hex or base58"))?;
+    match bytes.len() {
+        64 => Ok(ParsedKey::Keypair(keypair_from_bytes(&bytes)?)),
+        32 => Ok(ParsedKey::Pubkey(Pubkey::new_from_array(bytes.try_into().unwrap()))),
+        n => Err(anyhow!("Base58 key has {} bytes; expected 64 (secret key) or 32 (public key)", n)),
+    }
+}
+
+/// Write `contents` to a new file readable only by the owner.
+fn write_secret(path: &str, contents: &str) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path).map_err(|e| anyhow!("Failed to create {}: {}", path, e))?;
+    file.write_all(contents.as_bytes()).map_err(|e| anyhow!("Failed to write {}: {}", path, e))
+}
+
+/// Read a key from `input` (a file, or stdin for `-`) in any supported
+/// format and write it as `format`, to a new `output` file or stdout.
+///
+/// Secrets are never printed without confirmation; written files are
+/// created with owner-only permissions and never overwrite an existing file.
+pub fn convert_key(input: &str, format: KeyFormat, output: Option<&str>, assume_yes: bool) -> Result<()> {
+    let key = parse_key(&read_input(input)?)?;
+    let pubkey = match &key {
+        ParsedKey::Keypair(keypair) => keypair.pubkey(),
+        ParsedKey::Pubkey(pubkey) => *pubkey,
+    };
+    info!("Public key: {}", pubkey);
+
+    let encoded = match (format, &key) {
+        (KeyFormat::Pubkey, _) => {
+            match output {
+                Some(path) => fs::write(path, format!("{}\n", pubkey))
+                    .map_err(|e| anyhow!("Failed to write {}: {}", path, e))?,
+                None => println!("{}", pubkey),
+            }
+            return Ok(());
+        }
+        (_, ParsedKey::Pubkey(_)) => {
+            return Err(anyhow!("The input is only a public key; it cannot be converted to a secret format"))
+        }
+        (KeyFormat::Json, ParsedKey::Keypair(keypair)) => serde_json::to_string(&keypair.to_bytes().to_vec())?,
+        (KeyFormat::Base58, ParsedKey::Keypair(keypair)) => keypair.to_bytes().to_base58(),
+        (KeyFormat::Hex, ParsedKey::Keypair(keypair)) => encode_hex(&keypair.to_bytes()[..32]),
+    };
+
+    match output {
+        Some(path) => {
+            write_secret(path, &encoded)?;
+            info!("Secret key written to {}", path);
+        }
+        None => {
+            prompt::confirm(
+                &[
+                    "PRINT SECRET KEY".to_string(),
+                    format!("Public key: {}", pubkey),
+                    "The secret key will be printed to the terminal. Anyone who sees it, or".to_string(),
+                    "finds it in scrollback or logs, controls this wallet.".to_string(),
+                    "Pass --output FILE to write it to an owner-only file instead.".to_string(),
+                ],
+                assume_yes,
+            )?;
+            println!("{}", encoded);
+        }
+    }
+    Ok(())
+}
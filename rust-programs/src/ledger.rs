@@ -0,0 +1,146 @@
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::idempotency::unix_now;
+
+/// Default location of the airdrop ledger database.
+pub const DEFAULT_LEDGER_PATH: &str = "airdrop-ledger.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS airdrop_sends (
+        campaign TEXT NOT NULL,
+        recipient TEXT NOT NULL,
+        mint TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        signature TEXT NOT NULL,
+        sent_at INTEGER NOT NULL,
+        PRIMARY KEY (campaign, recipient)
+    );
+    CREATE TABLE IF NOT EXISTS airdrop_attempts (
+        campaign TEXT NOT NULL,
+        recipient TEXT NOT NULL,
+        mint TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        signature TEXT NOT NULL,
+        attempted_at INTEGER NOT NULL,
+        PRIMARY KEY (campaign, recipient)
+    );
+";
+
+/// A signed send that was submitted but not seen to land.
+pub struct PendingSend {
+    pub amount: u64,
+    pub signature: String,
+    pub attempted_at: i64,
+}
+
+/// Persistent record of completed airdrop sends, keyed by campaign and
+/// recipient, so a recipient is never paid twice within a campaign.
+///
+/// Each send is first recorded as an attempt carrying the signed
+/// transaction's signature, so a run interrupted before confirmation can
+/// tell from the chain whether the recipient was paid.
+pub struct AirdropLedger {
+    conn: Connection,
+}
+
+impl AirdropLedger {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path)
+            .map_err(|e| anyhow!("Failed to open airdrop ledger {}: {}", path, e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| anyhow!("Failed to initialize airdrop ledger schema: {}", e))?;
+
+        Ok(Self { conn })
+    }
+
+    /// The mint a campaign has paid out so far, if it has any sends.
+    pub fn campaign_mint(&self, campaign: &str) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT mint FROM airdrop_sends WHERE campaign = ?1
+                 UNION ALL SELECT mint FROM airdrop_attempts WHERE campaign = ?1 LIMIT 1",
+                params![campaign],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| anyhow!("Failed to read airdrop ledger: {}", e))
+    }
+
+    /// Signature of the send that already paid `recipient` in `campaign`.
+    pub fn sent(&self, campaign: &str, recipient: &Pubkey) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT signature FROM airdrop_sends WHERE campaign = ?1 AND recipient = ?2",
+                params![campaign, recipient.to_string()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| anyhow!("Failed to read airdrop ledger: {}", e))
+    }
+
+    /// The unresolved attempt to pay `recipient` in `campaign`, if any.
+    pub fn attempt(&self, campaign: &str, recipient: &Pubkey) -> Result<Option<PendingSend>> {
+        self.conn
+            .query_row(
+                "SELECT amount, signature, attempted_at FROM airdrop_attempts WHERE campaign = ?1 AND recipient = ?2",
+                params![campaign, recipient.to_string()],
+                |row| Ok(PendingSend { amount: row.get(0)?, signature: row.get(1)?, attempted_at: row.get(2)? }),
+            )
+            .optional()
+            .map_err(|e| anyhow!("Failed to read airdrop ledger: {}", e))
+    }
+
+    /// Record a signed send before it is submitted.
+    pub fn record_attempt(
+        &self,
+        campaign: &str,
+        recipient: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        signature: &Signature,
+    ) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO airdrop_attempts (campaign, recipient, mint, amount, signature, attempted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![campaign, recipient.to_string(), mint.to_string(), amount, signature.to_string(), unix_now()],
+            )
+            .map_err(|e| anyhow!("Failed to record attempt to pay {} in airdrop ledger: {}", recipient, e))?;
+
+        Ok(())
+    }
+
+    /// Forget an attempt that is known not to have landed.
+    pub fn clear_attempt(&self, campaign: &str, recipient: &Pubkey) -> Result<()> {
+        self.conn
+            .execute(
+                "DELETE FROM airdrop_attempts WHERE campaign = ?1 AND recipient = ?2",
+                params![campaign, recipient.to_string()],
+            )
+            .map_err(|e| anyhow!("Failed to update airdrop ledger: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Record a send that landed, replacing its attempt.
+    pub fn record(
+        &self,
+        campaign: &str,
+        recipient: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        signature: &Signature,
+    ) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO airdrop_sends (campaign, recipient, mint, amount, signature, sent_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![campaign, recipient.to_string(), mint.to_string(), amount, signature.to_string(), unix_now()],
+            )
+            .map_err(|e| anyhow!("Failed to record send to {} in airdrop ledger: {}", recipient, e))?;
+
+        self.clear_attempt(campaign, recipient)
+    }
+}
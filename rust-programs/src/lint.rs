@@ -0,0 +1,291 @@
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use mpl_token_metadata::types::TokenStandard;
+use reqwest::blocking::Client;
+use serde_json::Value;
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey, signature::Signer};
+use spl_token::state::Mint;
+use std::{str::FromStr, time::Duration};
+
+use crate::{metadata::trim_padding, TokenOperations};
+
+/// Images larger than this are slow to load and often rejected by listing sites.
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+const MAX_NAME_LENGTH: usize = 32;
+const MAX_SYMBOL_LENGTH: usize = 10;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct Findings(Vec<Finding>);
+
+impl Findings {
+    fn error(&mut self, message: impl Into<String>) {
+        self.0.push(Finding { severity: Severity::Error, message: message.into() });
+    }
+
+    fn warning(&mut self, message: impl Into<String>) {
+        self.0.push(Finding { severity: Severity::Warning, message: message.into() });
+    }
+
+    fn info(&mut self, message: impl Into<String>) {
+        self.0.push(Finding { severity: Severity::Info, message: message.into() });
+    }
+}
+
+fn is_nft_standard(standard: &TokenStandard) -> bool {
+    matches!(
+        standard,
+        TokenStandard::NonFungible
+            | TokenStandard::NonFungibleEdition
+            | TokenStandard::ProgrammableNonFungible
+            | TokenStandard::ProgrammableNonFungibleEdition
+    )
+}
+
+impl TokenOperations {
+    /// Check a mint's on-chain and off-chain metadata before listing sites index it.
+    /// Fails when any error-level finding is reported.
+    pub fn lint_metadata(&self, mint_address: &str) -> Result<()> {
+        info!("Linting metadata for mint: {}", mint_address);
+
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+
+        let mint_account = self.client.get_account(&mint_pubkey)
+            .map_err(|e| anyhow!("Failed to get mint account: {}", e))?;
+        let mint = Mint::unpack(&mint_account.data)
+            .map_err(|e| anyhow!("Failed to parse mint data: {}", e))?;
+
+        let mut findings = Findings::default();
+
+        let Some((metadata_address, metadata)) = self.fetch_metadata(&mint_pubkey)? else {
+            findings.error("No Metaplex metadata account exists for this mint; wallets and explorers will show it as unknown");
+            return report(findings.0);
+        };
+        info!("Metadata account: {}", metadata_address);
+
+        let name = trim_padding(&metadata.name);
+        let symbol = trim_padding(&metadata.symbol);
+        let uri = trim_padding(&metadata.uri);
+
+        // On-chain fields
+        if name.trim().is_empty() {
+            findings.error("On-chain name is empty");
+        } else if name != name.trim() {
+            findings.warning(format!("On-chain name '{}' has leading or trailing whitespace", name));
+        }
+        if name.len() > MAX_NAME_LENGTH {
+            findings.error(format!("On-chain name is longer than {} bytes", MAX_NAME_LENGTH));
+        }
+        if symbol.trim().is_empty() {
+            findings.error("On-chain symbol is empty");
+        } else if symbol != symbol.trim() {
+            findings.warning(format!("On-chain symbol '{}' has leading or trailing whitespace", symbol));
+        }
+        if symbol.len() > MAX_SYMBOL_LENGTH {
+            findings.error(format!("On-chain symbol is longer than {} bytes", MAX_SYMBOL_LENGTH));
+        }
+        if symbol.chars().any(|c| c.is_lowercase()) {
+            findings.info(format!("Symbol '{}' contains lowercase letters; most listings expect upper case", symbol));
+        }
+
+        // Decimals and token standard consistency
+        let looks_like_nft = mint.decimals == 0 && mint.supply == 1;
+        match metadata.token_standard.as_ref() {
+            Some(standard) if is_nft_standard(standard) => {
+                if mint.decimals != 0 {
+                    findings.error(format!("Token standard is {:?} but the mint has {} decimals", standard, mint.decimals));
+                }
+                if mint.supply > 1 {
+                    findings.error(format!("Token standard is {:?} but supply is {}", standard, mint.supply));
+                }
+            }
+            Some(standard) => {
+                if looks_like_nft {
+                    findings.warning(format!("Mint looks like an NFT (0 decimals, supply 1) but token standard is {:?}", standard));
+                }
+                if metadata.seller_fee_basis_points > 0 {
+                    findings.warning("Fungible token declares seller_fee_basis_points; royalties are ignored for fungibles");
+                }
+            }
+            None => findings.warning("Token standard is not set on the metadata account"),
+        }
+        if mint.decimals == 0 && !looks_like_nft {
+            findings.info("Mint has 0 decimals; amounts cannot be fractional");
+        }
+
+        // Authorities and mutability
+        if metadata.is_mutable {
+            if metadata.update_authority == self.wallet.pubkey() {
+                findings.info("Metadata is mutable and the loaded wallet is its update authority");
+            } else {
+                findings.warning(format!("Metadata is mutable; update authority is {}", metadata.update_authority));
+            }
+        }
+
+        // Off-chain JSON
+        if uri.is_empty() {
+            findings.error("Metadata URI is empty");
+            return report(findings.0);
+        }
+        if uri != uri.trim() {
+            findings.error("Metadata URI has leading or trailing whitespace");
+        }
+
+        let client = Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .map_err(|e| anyhow!("Failed to build HTTP client: {}", e))?;
+
+        if !uri.starts_with("https://") {
+            if uri.starts_with("ipfs://") || uri.starts_with("ar://") {
+                findings.warning(format!("URI '{}' uses a protocol scheme many indexers cannot resolve; use an HTTPS gateway URL", uri));
+            } else {
+                findings.error(format!("URI '{}' is not served over HTTPS", uri));
+            }
+        }
+
+        let json = match client.get(uri.trim()).send() {
+            Ok(response) if response.status().is_success() => {
+                let content_type = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or("")
+                    .to_string();
+                if !content_type.contains("json") {
+                    findings.warning(format!("URI is served with content type '{}' instead of application/json", content_type));
+                }
+
+                match response.json::<Value>() {
+                    Ok(json) => Some(json),
+                    Err(e) => {
+                        findings.error(format!("URI does not return valid JSON: {}", e));
+                        None
+                    }
+                }
+            }
+            Ok(response) => {
+                findings.error(format!("URI returned HTTP {}", response.status()));
+                None
+            }
+            Err(e) => {
+                findings.error(format!("URI is not reachable: {}", e));
+                None
+            }
+        };
+
+        if let Some(json) = json {
+            lint_json(&client, &json, name, symbol, looks_like_nft, &mut findings);
+        }
+
+        report(findings.0)
+    }
+}
+
+fn lint_json(client: &Client, json: &Value, name: &str, symbol: &str, is_nft: bool, findings: &mut Findings) {
+    let field = |key: &str| json.get(key).and_then(Value::as_str);
+
+    match field("name") {
+        None => findings.error("JSON is missing the 'name' field"),
+        Some(json_name) if json_name != name => {
+            findings.warning(format!("JSON name '{}' does not match on-chain name '{}'", json_name, name))
+        }
+        _ => {}
+    }
+    match field("symbol") {
+        None => findings.warning("JSON is missing the 'symbol' field"),
+        Some(json_symbol) if json_symbol != symbol => {
+            findings.warning(format!("JSON symbol '{}' does not match on-chain symbol '{}'", json_symbol, symbol))
+        }
+        _ => {}
+    }
+    if field("description").unwrap_or("").is_empty() {
+        findings.warning("JSON has no description");
+    }
+
+    if is_nft {
+        if !json.get("attributes").is_some_and(Value::is_array) {
+            findings.warning("NFT JSON has no 'attributes' array");
+        }
+        let files = json.pointer("/properties/files").and_then(Value::as_array);
+        if files.map(|files| files.is_empty()).unwrap_or(true) {
+            findings.warning("NFT JSON has no 'properties.files' entries");
+        }
+        if json.pointer("/properties/category").is_none() {
+            findings.info("NFT JSON has no 'properties.category'");
+        }
+    }
+
+    let Some(image) = field("image") else {
+        findings.error("JSON is missing the 'image' field");
+        return;
+    };
+    if !image.starts_with("https://") {
+        findings.warning(format!("Image '{}' is not an HTTPS URL", image));
+        return;
+    }
+
+    match client.get(image).send() {
+        Ok(response) if response.status().is_success() => {
+            let content_type = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("")
+                .to_string();
+            if !content_type.starts_with("image/") {
+                findings.error(format!("Image is served with content type '{}'", content_type));
+            }
+
+            let size = match response.content_length() {
+                Some(length) => Some(length),
+                None => response.bytes().ok().map(|bytes| bytes.len() as u64),
+            };
+            match size {
+                Some(size) if size > MAX_IMAGE_BYTES => findings.warning(format!(
+                    "Image is {} bytes; keep it under {} bytes",
+                    size, MAX_IMAGE_BYTES
+                )),
+                Some(_) => {}
+                None => findings.info("Could not determine image size"),
+            }
+        }
+        Ok(response) => findings.error(format!("Image returned HTTP {}", response.status())),
+        Err(e) => findings.error(format!("Image is not reachable: {}", e)),
+    }
+}
+
+fn report(findings: Vec<Finding>) -> Result<()> {
+    let errors = findings.iter().filter(|f| f.severity == Severity::Error).count();
+    let warnings = findings.iter().filter(|f| f.severity == Severity::Warning).count();
+
+    for finding in &findings {
+        match finding.severity {
+            Severity::Error => error!("  [error] {}", finding.message),
+            Severity::Warning => warn!("  [warning] {}", finding.message),
+            Severity::Info => info!("  [info] {}", finding.message),
+        }
+    }
+    info!("Metadata lint finished: {} error(s), {} warning(s)", errors, warnings);
+
+    if errors > 0 {
+        return Err(anyhow!("Metadata lint found {} error(s)", errors));
+    }
+    Ok(())
+}
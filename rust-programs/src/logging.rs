@@ -0,0 +1,210 @@
+use anyhow::{anyhow, Result};
+use chrono::{Local, Utc};
+use log::{LevelFilter, Log, Metadata, Record};
+use serde_json::json;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    str::FromStr,
+    sync::Mutex,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(anyhow!("Unknown log format: {}", other)),
+        }
+    }
+}
+
+/// When the log file is rotated regardless of its size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl FromStr for Rotation {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "never" => Ok(Rotation::Never),
+            "hourly" => Ok(Rotation::Hourly),
+            "daily" => Ok(Rotation::Daily),
+            other => Err(anyhow!("Unknown log rotation: {}", other)),
+        }
+    }
+}
+
+impl Rotation {
+    /// Identifier of the current rotation period; a change triggers rotation.
+    fn period(&self) -> String {
+        match self {
+            Rotation::Never => String::new(),
+            Rotation::Hourly => Utc::now().format("%Y%m%d%H").to_string(),
+            Rotation::Daily => Utc::now().format("%Y%m%d").to_string(),
+        }
+    }
+}
+
+/// Settings for `--log-file`.
+#[derive(Debug, Clone)]
+pub struct FileLogConfig {
+    pub path: PathBuf,
+    pub level: LevelFilter,
+    pub format: LogFormat,
+    pub max_bytes: u64,
+    pub rotation: Rotation,
+    /// Rotated files kept as `<path>.1` (newest) to `<path>.<keep>`.
+    pub keep: usize,
+}
+
+struct RotatingFile {
+    config: FileLogConfig,
+    file: File,
+    written: u64,
+    period: String,
+}
+
+impl RotatingFile {
+    fn open(config: FileLogConfig) -> Result<Self> {
+        let file = open_append(&config.path)?;
+        let written = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        let period = config.rotation.period();
+        Ok(Self { config, file, written, period })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let period = self.config.rotation.period();
+        let len = line.len() as u64 + 1;
+        if period != self.period || (self.written > 0 && self.written + len > self.config.max_bytes) {
+            self.rotate()?;
+            self.period = period;
+        }
+
+        writeln!(self.file, "{}", line)?;
+        self.written += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        let rotated = |n: usize| {
+            let mut name = self.config.path.clone().into_os_string();
+            name.push(format!(".{}", n));
+            PathBuf::from(name)
+        };
+
+        if self.config.keep == 0 {
+            fs::remove_file(&self.config.path)?;
+        } else {
+            let _ = fs::remove_file(rotated(self.config.keep));
+            for n in (1..self.config.keep).rev() {
+                let _ = fs::rename(rotated(n), rotated(n + 1));
+            }
+            fs::rename(&self.config.path, rotated(1))?;
+        }
+
+        self.file = open_append(&self.config.path).map_err(std::io::Error::other)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &PathBuf) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| anyhow!("Failed to open log file {}: {}", path.display(), e))
+}
+
+/// Console logging controlled by `RUST_LOG` as before, plus an optional
+/// rotating log file with its own level and format.
+struct TeeLogger {
+    console: env_logger::Logger,
+    file: Option<(LevelFilter, LogFormat, Mutex<RotatingFile>)>,
+}
+
+impl Log for TeeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.console.enabled(metadata)
+            || self.file.as_ref().is_some_and(|(level, _, _)| metadata.level() <= *level)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.console.matches(record) {
+            self.console.log(record);
+        }
+
+        let Some((level, format, file)) = &self.file else {
+            return;
+        };
+        if record.level() > *level {
+            return;
+        }
+
+        let line = match format {
+            LogFormat::Text => format!(
+                "{} {:<5} {}: {}",
+                Local::now().to_rfc3339(),
+                record.level(),
+                record.target(),
+                record.args()
+            ),
+            LogFormat::Json => json!({
+                "timestamp": Utc::now().to_rfc3339(),
+                "level": record.level().to_string(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            })
+            .to_string(),
+        };
+        if let Ok(mut file) = file.lock() {
+            if let Err(e) = file.write_line(&line) {
+                eprintln!("Failed to write log file: {}", e);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Some((_, _, file)) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.file.flush();
+            }
+        }
+    }
+}
+
+/// Install the global logger.
+pub fn init(file: Option<FileLogConfig>) -> Result<()> {
+    let console = env_logger::Builder::from_default_env().build();
+
+    let mut max_level = console.filter();
+    let file = match file {
+        Some(config) => {
+            max_level = max_level.max(config.level);
+            Some((config.level, config.format, Mutex::new(RotatingFile::open(config)?)))
+        }
+        None => None,
+    };
+
+    log::set_boxed_logger(Box::new(TeeLogger { console, file }))
+        .map_err(|e| anyhow!("Failed to install logger: {}", e))?;
+    log::set_max_level(max_level);
+    Ok(())
+}
@@ -1,51 +1,233 @@
 use anyhow::{anyhow, Result};
-use clap::{Arg, Command};
+use clap::{parser::ValueSource, Arg, ArgAction, ArgMatches, Command};
+use clap_complete::Shell;
 use log::{info, warn, error};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount,
     commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    program_pack::Pack,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
-    transaction::Transaction,
-};
-use spl_token::{
-    instruction::{initialize_mint, mint_to, set_authority},
-    state::{Account, Mint},
+    signature::{Keypair, Signature, Signer},
 };
+use spl_token::state::Account;
+use spl_token_2022::instruction::{set_authority, AuthorityType};
 use std::{
+    collections::BTreeMap,
     fs,
+    io,
+    process::ExitCode,
     str::FromStr,
 };
+use token_ops_core::distribution::Rounding;
+
+mod accounts;
+mod airdrop;
+mod allowlist;
+mod alt;
+mod apply;
+mod audit;
+mod balances;
+mod batcher;
+mod bench;
+mod checkpoint;
+mod circulating;
+mod clawback;
+mod compare;
+mod confidential;
+mod consolidate;
+mod creators;
+mod crosscheck;
+mod das;
+mod deltas;
+mod dev;
+mod diff;
+mod editions;
+mod events;
+mod faucet;
+mod fees;
+mod freeze;
+mod gate;
+mod geyser;
+mod grpc;
+mod health;
+mod history;
+mod hooks;
+mod idempotency;
+mod holders;
+mod index;
+mod input;
+mod inspect;
+mod keys;
+mod ledger;
+mod lint;
+mod logging;
+mod memo;
+mod message;
+mod metadata;
+mod migrate;
+mod mint;
+mod misdirected;
+mod monitor;
+mod multi;
+mod multisig;
+mod nft;
+mod output;
+mod outputs;
+mod pause;
+mod pipeline;
+mod portfolio;
+mod priority;
+mod profile;
+mod prompt;
+mod proposals;
+mod prorata;
+mod providers;
+mod reallocate;
+mod rebroadcast;
+mod receipts;
+mod relayer;
+mod rent;
+mod report;
+mod reserves;
+mod rpc_headers;
+mod scheduler;
+mod simulation;
+mod sweep;
+mod telemetry;
+mod token_program;
+mod transfer;
+mod vanity;
+mod webhooks;
+mod whitelist;
+mod wizard;
+
+use batcher::{TxBatcher, DEFAULT_COMPUTE_UNIT_LIMIT};
+use checkpoint::Checkpoints;
+use freeze::FreezeAction;
+use hooks::Hooks;
+use output::OutputFormat;
+use receipts::Receipts;
+
+/// Estimated compute units consumed by a single SetAuthority instruction.
+const SET_AUTHORITY_UNITS: u32 = 5_000;
+
+/// Executable name used in completion scripts and the man page.
+const BIN_NAME: &str = "token-ops";
 
 /// Solana Token Operations CLI
 /// 
 /// This Rust program provides advanced token operations for Solana SPL tokens.
 /// It can verify token creation, perform additional minting, and manage token accounts.
-
-#[derive(Debug)]
 struct TokenOperations {
     client: RpcClient,
     wallet: Keypair,
+    compute_unit_limit: u32,
+    compute_unit_price: Option<u64>,
+    auto_priority_fee: Option<priority::AutoPriorityFee>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    cross_check: Vec<RpcClient>,
+    wait_finalized: bool,
+    receipts: Option<Receipts>,
+    hooks: Option<Hooks>,
+    das: Option<das::DasClient>,
+    relayer: Option<relayer::FeeRelayer>,
+    checkpoints: Checkpoints,
 }
 
 impl TokenOperations {
     fn new(rpc_url: &str, wallet_path: &str) -> Result<Self> {
-        info!("Initializing Solana client with RPC URL: {}", rpc_url);
-        let client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+        info!("Initializing Solana client with RPC URL: {}", providers::redact_url(rpc_url));
+        let client = telemetry::rpc_client(rpc_url, CommitmentConfig::confirmed());
         
         info!("Loading wallet from: {}", wallet_path);
-        let wallet_data = fs::read(wallet_path)
-            .map_err(|e| anyhow!("Failed to read wallet file: {}", e))?;
-        
-        let wallet: Vec<u8> = serde_json::from_slice(&wallet_data)
-            .map_err(|e| anyhow!("Failed to parse wallet JSON: {}", e))?;
-        
-        let wallet = Keypair::from_bytes(&wallet)
-            .map_err(|e| anyhow!("Failed to create keypair from wallet data: {}", e))?;
+        let wallet = read_keypair(wallet_path)?;
         
         info!("Wallet loaded successfully: {}", wallet.pubkey());
         
-        Ok(Self { client, wallet })
+        Ok(Self {
+            client,
+            wallet,
+            compute_unit_limit: DEFAULT_COMPUTE_UNIT_LIMIT,
+            compute_unit_price: None,
+            auto_priority_fee: None,
+            lookup_tables: Vec::new(),
+            cross_check: Vec::new(),
+            wait_finalized: false,
+            receipts: None,
+            hooks: None,
+            das: None,
+            relayer: None,
+            checkpoints: Checkpoints::new(checkpoint::DEFAULT_CHECKPOINT_DIR, false),
+        })
+    }
+    
+    fn with_compute_budget(mut self, limit: u32, price: Option<u64>) -> Self {
+        self.compute_unit_limit = limit;
+        self.compute_unit_price = price;
+        self
+    }
+    
+    fn with_auto_priority_fee(mut self, auto: Option<priority::AutoPriorityFee>) -> Self {
+        self.auto_priority_fee = auto;
+        self
+    }
+    
+    fn with_wait_finalized(mut self, wait_finalized: bool) -> Self {
+        self.wait_finalized = wait_finalized;
+        self
+    }
+    
+    fn with_receipts(mut self, receipts: Option<Receipts>) -> Self {
+        self.receipts = receipts;
+        self
+    }
+    
+    fn with_checkpoints(mut self, checkpoints: Checkpoints) -> Self {
+        self.checkpoints = checkpoints;
+        self
+    }
+    
+    fn load_hooks(&mut self, path: Option<&String>, operation: &str, mint: Option<&String>) -> Result<()> {
+        if let Some(path) = path {
+            self.hooks = Some(Hooks::load(path, operation, mint.map(String::as_str), &self.wallet.pubkey())?);
+        }
+        Ok(())
+    }
+    
+    fn batcher(&self) -> TxBatcher<'_> {
+        TxBatcher::new(&self.client, &self.wallet)
+            .with_compute_unit_limit(self.compute_unit_limit)
+            .with_compute_unit_price(self.compute_unit_price)
+            .with_auto_priority_fee(self.auto_priority_fee)
+            .with_lookup_tables(self.lookup_tables.clone())
+            .with_wait_finalized(self.wait_finalized)
+            .with_receipts(self.receipts.as_ref())
+            .with_hooks(self.hooks.as_ref())
+            .with_relayer(self.relayer.as_ref())
+    }
+    
+    /// Send one atomic group of instructions as a single transaction.
+    fn send_instructions(&self, instructions: Vec<Instruction>, units: u32) -> Result<Signature> {
+        self.send_instructions_with_signers(instructions, units, &[])
+    }
+    
+    /// Like `send_instructions`, with extra keypairs that must also sign.
+    fn send_instructions_with_signers(
+        &self,
+        instructions: Vec<Instruction>,
+        units: u32,
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        let mut batcher = self.batcher().with_signers(signers);
+        batcher.push(instructions, units)?;
+        
+        batcher.finish()
+            .pop()
+            .ok_or_else(|| anyhow!("No transaction was submitted"))?
+            .signature
+            .map_err(|e| anyhow!(e))
     }
     
     fn verify_token(&self, mint_address: &str) -> Result<()> {
@@ -54,25 +236,37 @@ impl TokenOperations {
         let mint_pubkey = Pubkey::from_str(mint_address)
             .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
         
-        // Get mint account info
-        let mint_account = self.client.get_account(&mint_pubkey)
-            .map_err(|e| anyhow!("Failed to get mint account: {}", e))?;
+        // Get mint account info; Token-2022 mints are parsed with their extensions
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+        let summary = mint_info.summary()?;
+        self.cross_check_mint(&mint_pubkey)?;
         
-        // Verify it's a valid mint account
-        if mint_account.owner != spl_token::id() {
-            return Err(anyhow!("Account is not owned by SPL Token program"));
+        let show = |key: &Option<String>| key.clone().unwrap_or_else(|| "None".to_string());
+        info!("Token verification successful!");
+        info!("  Program: {}", summary.program);
+        info!("  Mint Authority: {}", show(&summary.mint_authority));
+        info!("  Supply: {}", summary.supply);
+        if let Some(config) = mint_info.scaled_ui_amount()? {
+            let now = chrono::Utc::now().timestamp();
+            info!("  UI Supply: {} (multiplier {})", mint_info.ui_amount(summary.supply, now)?, config.multiplier_at(now));
         }
+        if mint_info.pausable()?.is_some_and(|config| config.paused) {
+            warn!("  PAUSED: transfers, mints and burns of this token are halted");
+        }
+        info!("  Decimals: {}", summary.decimals);
+        info!("  Is Initialized: {}", summary.is_initialized);
+        info!("  Freeze Authority: {}", show(&summary.freeze_authority));
         
-        // Parse mint data
-        let mint_data = Mint::unpack(&mint_account.data)
-            .map_err(|e| anyhow!("Failed to parse mint data: {}", e))?;
-        
-        info!("Token verification successful!");
-        info!("  Mint Authority: {:?}", mint_data.mint_authority);
-        info!("  Supply: {}", mint_data.supply);
-        info!("  Decimals: {}", mint_data.decimals);
-        info!("  Is Initialized: {}", mint_data.is_initialized);
-        info!("  Freeze Authority: {:?}", mint_data.freeze_authority);
+        if summary.program == "Token-2022" {
+            if summary.extensions.is_empty() {
+                info!("  Extensions: none");
+            } else {
+                info!("  Extensions:");
+                for line in &summary.extensions {
+                    info!("    {}", line);
+                }
+            }
+        }
         
         Ok(())
     }
@@ -158,31 +352,40 @@ impl TokenOperations {
         Ok(())
     }
     
-    fn revoke_mint_authority(&self, mint_address: &str) -> Result<()> {
+    fn revoke_mint_authority(&self, mint_address: &str, assume_yes: bool) -> Result<()> {
         info!("Revoking mint authority for token: {}", mint_address);
         
         let mint_pubkey = Pubkey::from_str(mint_address)
             .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+        let summary = mint_info.summary()?;
+        if summary.mint_authority != Some(self.wallet.pubkey().to_string()) {
+            return Err(anyhow!("Loaded wallet is not the mint authority of this mint"));
+        }
+        
+        prompt::confirm_typed(
+            &[
+                "REVOKE MINT AUTHORITY (irreversible)".to_string(),
+                format!("Mint:              {}", mint_pubkey),
+                format!("Current authority: {}", self.wallet.pubkey()),
+                format!("Supply:            {}", holders::format_amount(summary.supply, summary.decimals)),
+                "No more tokens can ever be minted; the supply above is final.".to_string(),
+            ],
+            mint_address,
+            assume_yes,
+        )?;
         
         // Create instruction to disable mint authority
         let instruction = set_authority(
-            &spl_token::id(),
+            &mint_info.program_id,
             &mint_pubkey,
             None, // Set authority to None (disable)
-            spl_token::instruction::AuthorityType::MintTokens,
+            AuthorityType::MintTokens,
             &self.wallet.pubkey(),
             &[&self.wallet.pubkey()],
         )?;
         
-        let recent_blockhash = self.client.get_latest_blockhash()?;
-        let transaction = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&self.wallet.pubkey()),
-            &[&self.wallet],
-            recent_blockhash,
-        );
-        
-        match self.client.send_and_confirm_transaction(&transaction) {
+        match self.send_instructions(vec![instruction], SET_AUTHORITY_UNITS) {
             Ok(signature) => {
                 info!("Mint authority revoked successfully! Signature: {}", signature);
                 Ok(())
@@ -194,31 +397,38 @@ impl TokenOperations {
         }
     }
     
-    fn revoke_freeze_authority(&self, mint_address: &str) -> Result<()> {
+    fn revoke_freeze_authority(&self, mint_address: &str, assume_yes: bool) -> Result<()> {
         info!("Revoking freeze authority for token: {}", mint_address);
         
         let mint_pubkey = Pubkey::from_str(mint_address)
             .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+        if mint_info.summary()?.freeze_authority != Some(self.wallet.pubkey().to_string()) {
+            return Err(anyhow!("Loaded wallet is not the freeze authority of this mint"));
+        }
+        
+        prompt::confirm_typed(
+            &[
+                "REVOKE FREEZE AUTHORITY (irreversible)".to_string(),
+                format!("Mint:              {}", mint_pubkey),
+                format!("Current authority: {}", self.wallet.pubkey()),
+                "No account can be frozen again, and accounts frozen now can never be thawed.".to_string(),
+            ],
+            mint_address,
+            assume_yes,
+        )?;
         
         // Create instruction to disable freeze authority
         let instruction = set_authority(
-            &spl_token::id(),
+            &mint_info.program_id,
             &mint_pubkey,
             None, // Set authority to None (disable)
-            spl_token::instruction::AuthorityType::FreezeAccount,
+            AuthorityType::FreezeAccount,
             &self.wallet.pubkey(),
             &[&self.wallet.pubkey()],
         )?;
         
-        let recent_blockhash = self.client.get_latest_blockhash()?;
-        let transaction = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&self.wallet.pubkey()),
-            &[&self.wallet],
-            recent_blockhash,
-        );
-        
-        match self.client.send_and_confirm_transaction(&transaction) {
+        match self.send_instructions(vec![instruction], SET_AUTHORITY_UNITS) {
             Ok(signature) => {
                 info!("Freeze authority revoked successfully! Signature: {}", signature);
                 Ok(())
@@ -242,15 +452,85 @@ impl TokenOperations {
         // Get wallet SOL balance
         self.get_wallet_balance()?;
         
+        // Holder concentration metrics
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        self.report_distribution(&mint_pubkey)?;
+        
         info!("Token analysis completed successfully!");
         Ok(())
     }
 }
 
-fn main() -> Result<()> {
-    env_logger::init();
+/// Load a keypair saved in the Solana CLI JSON format.
+fn read_keypair(path: &str) -> Result<Keypair> {
+    let data = fs::read(path)
+        .map_err(|e| anyhow!("Failed to read wallet file: {}", e))?;
+    
+    let bytes: Vec<u8> = serde_json::from_slice(&data)
+        .map_err(|e| anyhow!("Failed to parse wallet JSON: {}", e))?;
+    
+    Keypair::from_bytes(&bytes)
+        .map_err(|e| anyhow!("Failed to create keypair from wallet data: {}", e))
+}
+
+/// Save a keypair in the Solana CLI JSON format.
+fn write_keypair(path: &str, keypair: &Keypair) -> Result<()> {
+    let json = serde_json::to_string(&keypair.to_bytes().to_vec())?;
     
-    let matches = Command::new("Solana Token Operations")
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    
+    let mut file = options.open(path)
+        .map_err(|e| anyhow!("Failed to create {}: {}", path, e))?;
+    std::io::Write::write_all(&mut file, json.as_bytes())
+        .map_err(|e| anyhow!("Failed to write {}: {}", path, e))
+}
+
+/// Unwrap an argument that is only mandatory for some operations.
+fn required<'a>(value: Option<&'a String>, name: &str) -> Result<&'a str> {
+    value
+        .map(|s| s.as_str())
+        .ok_or_else(|| anyhow!("--{} is required for this operation", name))
+}
+
+/// Arguments given explicitly on the command line, for receipts and pipelines.
+fn command_line_parameters(matches: &ArgMatches) -> BTreeMap<String, Vec<String>> {
+    matches
+        .ids()
+        .filter(|id| matches.value_source(id.as_str()) == Some(ValueSource::CommandLine))
+        .map(|id| {
+            let values = matches
+                .get_raw(id.as_str())
+                .map(|values| values.map(|value| value.to_string_lossy().into_owned()).collect())
+                .unwrap_or_default();
+            (id.to_string(), values)
+        })
+        .collect()
+}
+
+/// Resolve `--output`/`--format` into a path and format, inferring the
+/// format from the file extension when not given explicitly.
+fn output_target<'a>(output: Option<&'a String>, format: Option<&String>) -> Result<Option<(&'a str, OutputFormat)>> {
+    let Some(path) = output else {
+        return Ok(None);
+    };
+
+    let format = match format {
+        Some(format) => format.parse()?,
+        None if path.ends_with(".csv") => OutputFormat::Csv,
+        None => OutputFormat::Json,
+    };
+
+    Ok(Some((path.as_str(), format)))
+}
+
+/// The command-line interface, shared by argument parsing and the
+/// completion and man page generators.
+fn cli() -> Command {
+    Command::new("Solana Token Operations")
         .version("1.0")
         .author("Solana Token Creator")
         .about("Advanced operations for Solana SPL tokens")
@@ -259,21 +539,116 @@ fn main() -> Result<()> {
                 .long("wallet-path")
                 .value_name("FILE")
                 .help("Path to wallet keypair file")
-                .required(true),
+                .required_unless_present("generate-man"),
         )
         .arg(
             Arg::new("mint-address")
                 .long("mint-address")
+                .visible_alias("mint")
                 .value_name("ADDRESS")
-                .help("Token mint address")
-                .required(true),
+                .action(ArgAction::Append)
+                .help("Token mint address; verify, balance, supply, analyze and proof-of-reserves accept it more than once"),
         )
         .arg(
             Arg::new("operation")
                 .long("operation")
                 .value_name("OP")
                 .help("Operation to perform")
-                .value_parser(["verify", "balance", "analyze", "list-accounts", "revoke-mint-authority", "revoke-freeze-authority"])
+                .value_parser([
+                    "verify",
+                    "balance",
+                    "analyze",
+                    "list-accounts",
+                    "revoke-mint-authority",
+                    "revoke-freeze-authority",
+                    "alt-create",
+                    "alt-extend",
+                    "alt-deactivate",
+                    "alt-close",
+                    "index-snapshot",
+                    "index-sync",
+                    "index-events",
+                    "query-holders",
+                    "report-activity",
+                    "lint-metadata",
+                    "freeze-list",
+                    "thaw-list",
+                    "approve-holder",
+                    "pending-holders",
+                    "set-default-account-state",
+                    "clawback",
+                    "serve-faucet",
+                    "create-mint",
+                    "consolidate",
+                    "find-misdirected",
+                    "rent-report",
+                    "fee-report",
+                    "migrate",
+                    "dev-bootstrap",
+                    "snapshot-diff",
+                    "monitor-authorities",
+                    "sign-message",
+                    "verify-message",
+                    "gate-check",
+                    "bulk-balance",
+                    "proof-of-reserves",
+                    "validate-airdrop",
+                    "airdrop",
+                    "rpc-health",
+                    "rpc-bench",
+                    "run",
+                    "apply",
+                    "plan",
+                    "diff",
+                    "serve-grpc",
+                    "asset-info",
+                    "search-assets",
+                    "update-ui-multiplier",
+                    "pause",
+                    "resume",
+                    "transfer",
+                    "enable-required-memo",
+                    "disable-required-memo",
+                    "create-token-account",
+                    "audit-accounts",
+                    "reallocate",
+                    "account-info",
+                    "top-holders",
+                    "supply",
+                    "circulating-supply",
+                    "report-burns",
+                    "confidential-configure",
+                    "confidential-approve",
+                    "confidential-deposit",
+                    "confidential-apply",
+                    "confidential-withdraw",
+                    "confidential-balance",
+                    "create-multisig",
+                    "mint-to",
+                    "scheduler",
+                    "portfolio",
+                    "transfer-from",
+                    "burn-from",
+                    "transfer-update-authority",
+                    "set-creators",
+                    "set-royalty",
+                    "sign-metadata",
+                    "print-edition",
+                    "burn-nft",
+                    "collection-holders",
+                    "export-allowlist",
+                    "compare-holders",
+                    "watch-events",
+                    "key-convert",
+                    "rebroadcast",
+                    "sweep",
+                    "audit-verify",
+                    "propose-authority",
+                    "accept-authority",
+                    "cancel-authority",
+                    "pro-rata-airdrop",
+                    "watch-balances",
+                ])
                 .default_value("verify"),
         )
         .arg(
@@ -283,52 +658,1651 @@ fn main() -> Result<()> {
                 .help("Solana RPC URL")
                 .default_value("https://api.devnet.solana.com"),
         )
+        .arg(
+            Arg::new("provider")
+                .long("provider")
+                .value_name("NAME")
+                .value_parser(["helius", "quicknode", "triton", "ankr"])
+                .conflicts_with("rpc-url")
+                .requires("cluster")
+                .help("Build the RPC URL for a hosted provider from its API key environment variables instead of --rpc-url"),
+        )
+        .arg(
+            Arg::new("cluster")
+                .long("cluster")
+                .value_name("CLUSTER")
+                .value_parser(["mainnet", "devnet"])
+                .help("Cluster of the --provider URL"),
+        )
+        .arg(
+            Arg::new("rpc-header")
+                .long("rpc-header")
+                .value_name("NAME: VALUE")
+                .env("TOKEN_OPS_RPC_HEADER")
+                .hide_env_values(true)
+                .help("Extra header sent with every RPC and DAS request, e.g. 'x-api-key: KEY'; repeatable")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("rpc-basic-auth")
+                .long("rpc-basic-auth")
+                .value_name("USER:PASSWORD")
+                .env("TOKEN_OPS_RPC_BASIC_AUTH")
+                .hide_env_values(true)
+                .help("HTTP basic auth credentials for the RPC node"),
+        )
         .arg(
             Arg::new("owner")
                 .long("owner")
                 .value_name("ADDRESS")
-                .help("Token account owner (for balance operation)"),
+                .help("Token account owner (for balance, approve-holder, gate-check, search-assets, create-token-account, watch-balances and portfolio operations)"),
+        )
+        .arg(
+            Arg::new("state")
+                .long("state")
+                .value_name("STATE")
+                .help("Default state for new token accounts (for set-default-account-state)")
+                .value_parser(["frozen", "initialized"]),
+        )
+        .arg(
+            Arg::new("compute-unit-limit")
+                .long("compute-unit-limit")
+                .value_name("UNITS")
+                .help("Compute units requested per transaction; batches are packed to fit this budget")
+                .value_parser(clap::value_parser!(u32))
+                .default_value("200000"),
+        )
+        .arg(
+            Arg::new("compute-unit-price")
+                .long("compute-unit-price")
+                .value_name("MICRO_LAMPORTS")
+                .help("Priority fee per compute unit, in micro-lamports")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("priority-fee")
+                .long("priority-fee")
+                .value_name("auto[:PERCENTILE]")
+                .conflicts_with("compute-unit-price")
+                .help("Price each transaction at a percentile (default 75) of the recent prioritization fees for the accounts it writes"),
+        )
+        .arg(
+            Arg::new("max-priority-fee")
+                .long("max-priority-fee")
+                .value_name("MICRO_LAMPORTS")
+                .help("Cap on the --priority-fee auto price per compute unit")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("1000000"),
+        )
+        .arg(
+            Arg::new("lookup-table")
+                .long("lookup-table")
+                .value_name("ADDRESS")
+                .help("Address lookup table for v0 batch transactions, or the table managed by alt-* operations")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("addresses-file")
+                .long("addresses-file")
+                .value_name("FILE")
+                .help("File with one address per line, - for stdin (for alt-extend, freeze-list, thaw-list, monitor-authorities, bulk-balance, proof-of-reserves, watch-balances)"),
+        )
+        .arg(
+            Arg::new("index-db")
+                .long("index-db")
+                .value_name("FILE")
+                .help("SQLite database used by the local holder index")
+                .default_value(index::DEFAULT_INDEX_PATH),
+        )
+        .arg(
+            Arg::new("min-balance")
+                .long("min-balance")
+                .value_name("AMOUNT")
+                .help("Minimum token balance, in whole tokens (for query-holders)"),
+        )
+        .arg(
+            Arg::new("since")
+                .long("since")
+                .value_name("WINDOW")
+                .help("Report window, e.g. 7d, 12h or 2024-01-31")
+                .default_value("7d"),
+        )
+        .arg(
+            Arg::new("until")
+                .long("until")
+                .value_name("WHEN")
+                .help("End of the report window (exclusive), same format as --since; defaults to now"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .value_name("FILE")
+                .help("Write the report to a file"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Output file format; defaults to the file extension, then json")
+                .value_parser(["json", "csv"]),
+        )
+        .arg(
+            Arg::new("from")
+                .long("from")
+                .value_name("ADDRESS")
+                .help("Wallet or token account to claw back from (for clawback), or the delegated token account (for transfer-from and burn-from)"),
+        )
+        .arg(
+            Arg::new("to")
+                .long("to")
+                .value_name("ADDRESS")
+                .help("Wallet receiving tokens (for transfer, transfer-from, mint-to and sweep), or clawed-back tokens (defaults to the loaded wallet)"),
+        )
+        .arg(
+            Arg::new("amount")
+                .long("amount")
+                .value_name("AMOUNT")
+                .help("Amount in whole tokens (for transfer, transfer-from, burn-from, mint-to, clawback, confidential-deposit and confidential-withdraw, in total for pro-rata-airdrop, or per request for serve-faucet); \"all\" for clawback"),
+        )
+        .arg(
+            Arg::new("burn")
+                .long("burn")
+                .help("Burn clawed-back tokens instead of transferring them")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .help("Skip confirmation prompts")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("listen")
+                .long("listen")
+                .value_name("ADDR")
+                .help("Address the faucet HTTP server or gRPC server binds to")
+                .default_value("127.0.0.1:8080"),
+        )
+        .arg(
+            Arg::new("cooldown")
+                .long("cooldown")
+                .value_name("DURATION")
+                .help("Minimum time between faucet drips per wallet and per IP, e.g. 24h or 30m")
+                .default_value("24h"),
+        )
+        .arg(
+            Arg::new("decimals")
+                .long("decimals")
+                .value_name("N")
+                .help("Decimals for a new mint (for create-mint)")
+                .value_parser(clap::value_parser!(u8))
+                .default_value("9"),
+        )
+        .arg(
+            Arg::new("mint-keypair")
+                .long("mint-keypair")
+                .value_name("FILE")
+                .help("Keypair file for the new mint; reused if it exists, otherwise generated and saved")
+                .default_value("mint-keypair.json"),
+        )
+        .arg(
+            Arg::new("starts-with")
+                .long("starts-with")
+                .value_name("PREFIX")
+                .help("Grind a vanity mint address with this prefix across all cores (for create-mint)"),
+        )
+        .arg(
+            Arg::new("ignore-case")
+                .long("ignore-case")
+                .help("Match the --starts-with prefix case-insensitively")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("recover")
+                .long("recover")
+                .help("Send recovery transactions for recoverable findings (for find-misdirected)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("execute")
+                .long("execute")
+                .help("Close the closable accounts found by rent-report, or airdrop the distribution generated by pro-rata-airdrop")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("token-2022")
+                .long("token-2022")
+                .help("Use the Token-2022 program (for create-mint, and create-multisig without --mint-address)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("extension")
+                .long("extension")
+                .value_name("EXT")
+                .help("Token-2022 extension for a new mint, e.g. transfer-fee=50:1000, permanent-delegate, default-frozen, confidential-transfers[=auto]; for reallocate, memo-transfer or cpi-guard")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("mode")
+                .long("mode")
+                .value_name("MODE")
+                .help("How holders receive the new token (for migrate)")
+                .value_parser(["airdrop", "escrow"])
+                .default_value("airdrop"),
+        )
+        .arg(
+            Arg::new("fixtures")
+                .long("fixtures")
+                .value_name("FILE")
+                .help("Where dev-bootstrap writes the localnet wallet and mint addresses")
+                .default_value("dev-fixtures.json"),
+        )
+        .arg(
+            Arg::new("before")
+                .long("before")
+                .value_name("FILE|SLOT")
+                .help("Earlier holder snapshot: a file written by index-snapshot --output, or an indexed slot"),
+        )
+        .arg(
+            Arg::new("after")
+                .long("after")
+                .value_name("FILE|SLOT")
+                .help("Later holder snapshot: a file written by index-snapshot --output, or an indexed slot"),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .value_name("SECONDS")
+                .help("Polling interval for monitor-authorities (ignored with --geyser-endpoint), watch-events and watch-balances")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .default_value("5"),
+        )
+        .arg(
+            Arg::new("webhook")
+                .long("webhook")
+                .value_name("URL")
+                .help("URL that receives a JSON POST for every alert or event; repeatable")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("webhook-secret")
+                .long("webhook-secret")
+                .value_name("SECRET")
+                .env("TOKEN_OPS_WEBHOOK_SECRET")
+                .hide_env_values(true)
+                .help("Sign webhook deliveries with HMAC-SHA256 in the X-Token-Ops-Signature header"),
+        )
+        .arg(
+            Arg::new("message")
+                .long("message")
+                .value_name("TEXT")
+                .help("Message to sign or verify (for sign-message, verify-message)"),
+        )
+        .arg(
+            Arg::new("signature")
+                .long("signature")
+                .value_name("SIGNATURE")
+                .help("Base58 signature to check (for verify-message) or resend (for rebroadcast)"),
+        )
+        .arg(
+            Arg::new("signer")
+                .long("signer")
+                .value_name("ADDRESS")
+                .help("Public key that signed the message (for verify-message)"),
+        )
+        .arg(
+            Arg::new("raw")
+                .long("raw")
+                .help("Sign or verify the message bytes directly instead of the offchain message format")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("min-amount")
+                .long("min-amount")
+                .value_name("AMOUNT")
+                .help("Minimum balance, in tokens, required to pass (for gate-check and export-allowlist)"),
+        )
+        .arg(
+            Arg::new("mints-file")
+                .long("mints-file")
+                .value_name("FILE")
+                .help("File with one mint address per line (for verify, balance, supply, analyze, proof-of-reserves and watch-balances)"),
+        )
+        .arg(
+            Arg::new("signer-keypair")
+                .long("signer-keypair")
+                .value_name("FILE")
+                .help("Additional keypair that signs the report; repeatable (for proof-of-reserves)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("recipients")
+                .long("recipients")
+                .value_name("FILE")
+                .help("CSV of wallet,amount lines, amounts in whole tokens, - for stdin (for validate-airdrop, airdrop; written by pro-rata-airdrop)"),
+        )
+        .arg(
+            Arg::new("campaign")
+                .long("campaign")
+                .value_name("ID")
+                .help("Campaign id; each recipient is paid at most once per campaign (for airdrop and pro-rata-airdrop --execute)"),
+        )
+        .arg(
+            Arg::new("ledger")
+                .long("ledger")
+                .value_name("FILE")
+                .help("SQLite ledger of completed airdrop sends")
+                .default_value(ledger::DEFAULT_LEDGER_PATH),
+        )
+        .arg(
+            Arg::new("estimate")
+                .long("estimate")
+                .help("Report the transactions, ATA creations and SOL cost without sending (for airdrop)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("cross-check-rpc")
+                .long("cross-check-rpc")
+                .value_name("URL")
+                .help("Extra RPC endpoint that must agree on mint supply and authorities; repeatable (for verify, analyze, rpc-health, rpc-bench)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("wait-finalized")
+                .long("wait-finalized")
+                .help("Only report transactions as successful once finalized, not just confirmed")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("receipts-dir")
+                .long("receipts-dir")
+                .value_name("DIR")
+                .help("Write a receipt JSON for every transaction sent into this directory"),
+        )
+        .arg(
+            Arg::new("checkpoint-dir")
+                .long("checkpoint-dir")
+                .value_name("DIR")
+                .default_value(checkpoint::DEFAULT_CHECKPOINT_DIR)
+                .help("Where interrupted signature walks, DAS snapshots, index-events and index-sync save their progress"),
+        )
+        .arg(
+            Arg::new("resume")
+                .long("resume")
+                .help("Continue an interrupted scan from its checkpoint instead of starting over")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .help("Simulate each transaction before sending, record its compute units, size and fee, and print a cost summary at the end")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("profile-output")
+                .long("profile-output")
+                .value_name("FILE")
+                .requires("profile")
+                .help("Also write the --profile summary and per-transaction costs as JSON"),
+        )
+        .arg(
+            Arg::new("audit-log")
+                .long("audit-log")
+                .value_name("FILE")
+                .help("Append a hash-chained JSONL entry for every transaction sent, or the file audit-verify checks (default on mainnet: token-ops-audit.jsonl)"),
+        )
+        .arg(
+            Arg::new("no-audit-log")
+                .long("no-audit-log")
+                .help("Do not write the audit log, even on mainnet")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("audit-log"),
+        )
+        .arg(
+            Arg::new("log-file")
+                .long("log-file")
+                .value_name("FILE")
+                .help("Also write logs to this file, independent of the RUST_LOG console level"),
+        )
+        .arg(
+            Arg::new("log-level")
+                .long("log-level")
+                .value_name("LEVEL")
+                .help("Level written to --log-file")
+                .value_parser(["error", "warn", "info", "debug", "trace"])
+                .default_value("info"),
+        )
+        .arg(
+            Arg::new("log-format")
+                .long("log-format")
+                .value_name("FORMAT")
+                .help("Format of --log-file lines")
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
+        .arg(
+            Arg::new("log-max-size")
+                .long("log-max-size")
+                .value_name("MB")
+                .help("Rotate --log-file when it would grow past this size")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .default_value("100"),
+        )
+        .arg(
+            Arg::new("log-rotate")
+                .long("log-rotate")
+                .value_name("PERIOD")
+                .help("Also rotate --log-file on this schedule")
+                .value_parser(["never", "hourly", "daily"])
+                .default_value("daily"),
+        )
+        .arg(
+            Arg::new("log-keep")
+                .long("log-keep")
+                .value_name("COUNT")
+                .help("Rotated log files to keep")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("7"),
+        )
+        .arg(
+            Arg::new("otlp-endpoint")
+                .long("otlp-endpoint")
+                .value_name("URL")
+                .help("Export RPC and transaction spans over OTLP/HTTP, e.g. http://localhost:4318"),
+        )
+        .arg(
+            Arg::new("reference-rpc")
+                .long("reference-rpc")
+                .value_name("URL")
+                .help("Endpoint whose slot is the reference for lag (for rpc-health); defaults to the highest slot seen"),
+        )
+        .arg(
+            Arg::new("max-slot-lag")
+                .long("max-slot-lag")
+                .value_name("SLOTS")
+                .help("Fail rpc-health when an endpoint is further behind than this")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("50"),
+        )
+        .arg(
+            Arg::new("samples")
+                .long("samples")
+                .value_name("COUNT")
+                .help("Calls per method and endpoint (for rpc-bench)")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("20"),
+        )
+        .arg(
+            Arg::new("hooks")
+                .long("hooks")
+                .value_name("FILE")
+                .help("JSON file of instructions to inject into every transaction and actions to run before/after the operation"),
+        )
+        .arg(
+            Arg::new("pipeline")
+                .long("pipeline")
+                .value_name("FILE")
+                .help("YAML file declaring a sequence of operations (for run)"),
+        )
+        .arg(
+            Arg::new("schedule")
+                .long("schedule")
+                .value_name("FILE")
+                .help("YAML file of cron-style entries to run (for scheduler)"),
         )
-        .get_matches();
+        .arg(
+            Arg::new("price-api")
+                .long("price-api")
+                .value_name("URL")
+                .default_value(portfolio::DEFAULT_PRICE_API)
+                .help("Jupiter-compatible price API queried with ?ids=MINT,... (for portfolio)"),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("ORDER")
+                .value_parser(["value", "amount", "symbol"])
+                .default_value("value")
+                .help("Order of portfolio rows"),
+        )
+        .arg(
+            Arg::new("spec")
+                .long("spec")
+                .value_name("FILE")
+                .help("YAML file declaring the desired state of a mint (for apply and plan)"),
+        )
+        .arg(
+            Arg::new("interactive")
+                .long("interactive")
+                .help("Ask for the token's details step by step (for create-mint)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("ipfs-api")
+                .long("ipfs-api")
+                .value_name("URL")
+                .help("IPFS HTTP API used to upload the image and metadata JSON (for create-mint --interactive)"),
+        )
+        .arg(
+            Arg::new("input")
+                .value_name("INPUT")
+                .help("Input file of a batch operation, - for stdin; shorthand for --addresses-file or --recipients"),
+        )
+        .arg(
+            Arg::new("backend")
+                .long("backend")
+                .value_name("BACKEND")
+                .help("Holder snapshot source: rpc (getProgramAccounts), das (DAS getTokenAccounts) or auto")
+                .value_parser(["rpc", "das", "auto"])
+                .default_value("rpc"),
+        )
+        .arg(
+            Arg::new("collection")
+                .long("collection")
+                .value_name("ADDRESS")
+                .help("Collection address (for search-assets and collection-holders)"),
+        )
+        .arg(
+            Arg::new("geyser-endpoint")
+                .long("geyser-endpoint")
+                .value_name("URL")
+                .help("Yellowstone gRPC endpoint; monitor-authorities streams account updates instead of polling"),
+        )
+        .arg(
+            Arg::new("geyser-token")
+                .long("geyser-token")
+                .value_name("TOKEN")
+                .help("x-token sent to the Yellowstone endpoint"),
+        )
+        .arg(
+            Arg::new("group-max-size")
+                .long("group-max-size")
+                .value_name("N")
+                .help("Create the new Token-2022 mint as a token group of at most N members")
+                .value_parser(clap::value_parser!(u32))
+                .conflicts_with("group"),
+        )
+        .arg(
+            Arg::new("group")
+                .long("group")
+                .value_name("ADDRESS")
+                .help("Create the new Token-2022 mint as a member of this group (the wallet must be its update authority)"),
+        )
+        .arg(
+            Arg::new("multiplier")
+                .long("multiplier")
+                .value_name("FACTOR")
+                .help("New ScaledUiAmount multiplier (for update-ui-multiplier)"),
+        )
+        .arg(
+            Arg::new("effective-at")
+                .long("effective-at")
+                .value_name("TIME")
+                .help("When the new multiplier takes effect: unix timestamp or RFC 3339; defaults to now"),
+        )
+        .arg(
+            Arg::new("memo")
+                .long("memo")
+                .value_name("TEXT")
+                .help("Memo sent with the transfer; one is added anyway if the destination requires it"),
+        )
+        .arg(
+            Arg::new("immutable-owner")
+                .long("immutable-owner")
+                .help("Add the ImmutableOwner extension to the new Token-2022 account (for create-token-account)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("token-account")
+                .long("token-account")
+                .value_name("ADDRESS")
+                .help("Token account to act on (for reallocate and confidential-approve); defaults to the wallet's ATA of --mint-address"),
+        )
+        .arg(
+            Arg::new("address")
+                .long("address")
+                .value_name("ADDRESS")
+                .help("Any account to fetch and decode (for account-info)"),
+        )
+        .arg(
+            Arg::new("limit")
+                .long("limit")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("20")
+                .help("Number of holders to list (for top-holders)"),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .value_name("FILE")
+                .help("Treasury, vesting, burn and locker addresses excluded from circulating supply, or holders excluded from pro-rata-airdrop, one per line"),
+        )
+        .arg(
+            Arg::new("keys-dir")
+                .long("keys-dir")
+                .value_name("DIR")
+                .default_value(confidential::DEFAULT_KEYS_DIR)
+                .help("Directory holding confidential transfer ElGamal and AES keys, one pair per token account"),
+        )
+        .arg(
+            Arg::new("multisig-signer")
+                .long("multisig-signer")
+                .value_name("PUBKEY")
+                .help("Signer of the new multisig; repeatable (for create-multisig)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("threshold")
+                .long("threshold")
+                .value_name("M")
+                .value_parser(clap::value_parser!(u8))
+                .help("Signatures the multisig requires (for create-multisig)"),
+        )
+        .arg(
+            Arg::new("new-authority")
+                .long("new-authority")
+                .value_name("PUBKEY")
+                .help("Key that receives the authority (for transfer-update-authority and propose-authority)"),
+        )
+        .arg(
+            Arg::new("authority-type")
+                .long("authority-type")
+                .value_name("TYPE")
+                .value_parser(["mint", "freeze"])
+                .default_value("mint")
+                .help("Authority moved by propose-authority, accept-authority and cancel-authority"),
+        )
+        .arg(
+            Arg::new("delay")
+                .long("delay")
+                .value_name("DURATION")
+                .default_value("24h")
+                .help("Wait before a proposed authority transfer can be accepted, e.g. 24h or 30m"),
+        )
+        .arg(
+            Arg::new("proposals-dir")
+                .long("proposals-dir")
+                .value_name("DIR")
+                .default_value("authority-proposals")
+                .help("Directory holding pending authority proposals"),
+        )
+        .arg(
+            Arg::new("new-authority-keypair")
+                .long("new-authority-keypair")
+                .value_name("FILE")
+                .help("Keypair of the new authority, which co-signs accept-authority to prove control of the key"),
+        )
+        .arg(
+            Arg::new("creator")
+                .long("creator")
+                .value_name("PUBKEY:SHARE")
+                .help("Creator and percentage share of royalties; repeatable, shares must total 100 (for set-creators)")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("royalty-bps")
+                .long("royalty-bps")
+                .value_name("BPS")
+                .value_parser(clap::value_parser!(u16))
+                .help("Royalty in basis points of the sale price, e.g. 500 for 5% (for set-royalty)"),
+        )
+        .arg(
+            Arg::new("master")
+                .long("master")
+                .value_name("MINT")
+                .help("Master edition NFT to print from (for print-edition, and burn-nft of a print edition)"),
+        )
+        .arg(
+            Arg::new("count")
+                .long("count")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("1")
+                .help("Number of editions to print (for print-edition)"),
+        )
+        .arg(
+            Arg::new("at-slot")
+                .long("at-slot")
+                .value_name("SLOT")
+                .value_parser(clap::value_parser!(u64))
+                .help("Use the latest indexed snapshot at or before this slot (for export-allowlist and pro-rata-airdrop)"),
+        )
+        .arg(
+            Arg::new("merkle")
+                .long("merkle")
+                .help("Also compute a Merkle root and per-address proofs (for export-allowlist)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("mint-a")
+                .long("mint-a")
+                .value_name("ADDRESS")
+                .help("First mint to compare (for compare-holders), or the snapshotted mint (for pro-rata-airdrop)"),
+        )
+        .arg(
+            Arg::new("mint-b")
+                .long("mint-b")
+                .value_name("ADDRESS")
+                .help("Second mint to compare (for compare-holders), or the distributed mint (for pro-rata-airdrop)"),
+        )
+        .arg(
+            Arg::new("key-input")
+                .long("key-input")
+                .value_name("FILE")
+                .help("Key to convert (for key-convert): a JSON, base58 or hex key file, or - for stdin; defaults to --wallet-path"),
+        )
+        .arg(
+            Arg::new("key-format")
+                .long("key-format")
+                .value_name("FORMAT")
+                .value_parser(["json", "base58", "hex", "pubkey"])
+                .default_value("pubkey")
+                .help("Output format of key-convert"),
+        )
+        .arg(
+            Arg::new("rounding")
+                .long("rounding")
+                .value_name("POLICY")
+                .value_parser(["floor", "largest-remainder"])
+                .default_value("largest-remainder")
+                .help("How pro-rata-airdrop rounds shares: floor leaves the remainder undistributed, largest-remainder hands it out so the total is exact"),
+        )
+        .arg(
+            Arg::new("idempotency-key")
+                .long("idempotency-key")
+                .value_name("KEY")
+                .help("Run this mint-to or transfer at most once: the key is recorded with the signature and sent as an on-chain memo, and a repeat is refused"),
+        )
+        .arg(
+            Arg::new("idempotency-db")
+                .long("idempotency-db")
+                .value_name("FILE")
+                .help("SQLite database of used idempotency keys")
+                .default_value(idempotency::DEFAULT_IDEMPOTENCY_DB),
+        )
+        .arg(
+            Arg::new("fee-relayer")
+                .long("fee-relayer")
+                .value_name("URL")
+                .help("Octane-compatible relayer that pays transaction fees in exchange for an SPL token fee"),
+        )
+        .arg(
+            Arg::new("fee-token")
+                .long("fee-token")
+                .value_name("MINT")
+                .help("Token to pay the fee relayer in (default: the first one it accepts)"),
+        )
+        .arg(
+            Arg::new("generate-man")
+                .long("generate-man")
+                .help("Print the man page in roff format and exit")
+                .action(ArgAction::SetTrue),
+        )
+        .subcommand(
+            Command::new("completions")
+                .about("Print a shell completion script")
+                .arg(
+                    Arg::new("shell")
+                        .value_name("SHELL")
+                        .required(true)
+                        .value_parser(clap::value_parser!(Shell)),
+                ),
+        )
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+}
+
+fn main() -> Result<ExitCode> {
+    let result = run();
+    if let Err(e) = profile::finish() {
+        warn!("Failed to write the profile: {}", e);
+    }
+    let audited = audit::finish();
+    match result {
+        // A failed gate is an answer, not an error: its JSON line is already on stdout.
+        Err(e) if e.is::<gate::GateCheckFailed>() => audited.map(|()| ExitCode::FAILURE),
+        result => result.and(audited).map(|()| ExitCode::SUCCESS),
+    }
+}
+
+fn run() -> Result<()> {
+    let matches = cli().get_matches();
+    
+    if let Some(completions) = matches.subcommand_matches("completions") {
+        let shell = *completions.get_one::<Shell>("shell").unwrap();
+        clap_complete::generate(shell, &mut cli(), BIN_NAME, &mut io::stdout());
+        return Ok(());
+    }
+    if matches.get_flag("generate-man") {
+        clap_mangen::Man::new(cli().name(BIN_NAME)).render(&mut io::stdout())?;
+        return Ok(());
+    }
+    
+    let file_log = match matches.get_one::<String>("log-file") {
+        Some(path) => Some(logging::FileLogConfig {
+            path: path.into(),
+            level: matches.get_one::<String>("log-level").unwrap().parse()?,
+            format: matches.get_one::<String>("log-format").unwrap().parse()?,
+            max_bytes: *matches.get_one::<u64>("log-max-size").unwrap() * 1024 * 1024,
+            rotation: matches.get_one::<String>("log-rotate").unwrap().parse()?,
+            keep: *matches.get_one::<usize>("log-keep").unwrap(),
+        }),
+        None => None,
+    };
+    logging::init(file_log)?;
+    let _telemetry = telemetry::init(matches.get_one::<String>("otlp-endpoint").map(String::as_str))?;
+    let rpc_header_values: Vec<String> = matches
+        .get_many::<String>("rpc-header")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    rpc_headers::configure(&rpc_header_values, matches.get_one::<String>("rpc-basic-auth").map(String::as_str))?;
     
     let wallet_path = matches.get_one::<String>("wallet-path").unwrap();
-    let mint_address = matches.get_one::<String>("mint-address").unwrap();
+    let mint_address = matches.get_one::<String>("mint-address");
     let operation = matches.get_one::<String>("operation").unwrap();
-    let rpc_url = matches.get_one::<String>("rpc-url").unwrap();
+    let rpc_url = &match matches.get_one::<String>("provider") {
+        Some(provider) => {
+            let provider: providers::Provider = provider.parse()?;
+            provider.rpc_url(matches.get_one::<String>("cluster").unwrap().parse()?)?
+        }
+        None => matches.get_one::<String>("rpc-url").unwrap().clone(),
+    };
     let owner = matches.get_one::<String>("owner");
+    let state = matches.get_one::<String>("state");
+    let compute_unit_limit = *matches.get_one::<u32>("compute-unit-limit").unwrap();
+    let compute_unit_price = matches.get_one::<u64>("compute-unit-price").copied();
+    let auto_priority_fee = matches
+        .get_one::<String>("priority-fee")
+        .map(|value| priority::AutoPriorityFee::parse(value, *matches.get_one::<u64>("max-priority-fee").unwrap()))
+        .transpose()?;
+    let lookup_tables: Vec<String> = matches
+        .get_many::<String>("lookup-table")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    let input = matches.get_one::<String>("input");
+    let addresses_file = matches.get_one::<String>("addresses-file").or(input);
+    let index_db = matches.get_one::<String>("index-db").unwrap();
+    let min_balance = matches.get_one::<String>("min-balance");
+    let since = matches.get_one::<String>("since").unwrap();
+    let until = matches.get_one::<String>("until");
+    let from = matches.get_one::<String>("from");
+    let to = matches.get_one::<String>("to");
+    let amount = matches.get_one::<String>("amount");
+    let burn = matches.get_flag("burn");
+    let assume_yes = matches.get_flag("yes");
+    let listen = matches.get_one::<String>("listen").unwrap();
+    let cooldown = matches.get_one::<String>("cooldown").unwrap();
+    let decimals = *matches.get_one::<u8>("decimals").unwrap();
+    let mint_keypair = matches.get_one::<String>("mint-keypair").unwrap();
+    let starts_with = matches.get_one::<String>("starts-with");
+    let ignore_case = matches.get_flag("ignore-case");
+    let recover = matches.get_flag("recover");
+    let execute = matches.get_flag("execute");
+    let token_2022 = matches.get_flag("token-2022");
+    let extensions: Vec<String> = matches
+        .get_many::<String>("extension")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    let mode = matches.get_one::<String>("mode").unwrap();
+    let fixtures = matches.get_one::<String>("fixtures").unwrap();
+    let before = matches.get_one::<String>("before");
+    let after = matches.get_one::<String>("after");
+    let interval = *matches.get_one::<u64>("interval").unwrap();
+    let webhooks = webhooks::Webhooks::new(
+        matches.get_many::<String>("webhook").map(|values| values.cloned().collect()).unwrap_or_default(),
+        matches.get_one::<String>("webhook-secret").cloned(),
+    )?;
+    let message = matches.get_one::<String>("message");
+    let signature = matches.get_one::<String>("signature");
+    let signer = matches.get_one::<String>("signer");
+    let raw = matches.get_flag("raw");
+    let min_amount = matches.get_one::<String>("min-amount");
+    let mints_file = matches.get_one::<String>("mints-file");
+    let multiple_mints = mints_file.is_some()
+        || matches.get_many::<String>("mint-address").is_some_and(|values| values.len() > 1);
+    let recipients = matches.get_one::<String>("recipients").or(input);
+    let campaign = matches.get_one::<String>("campaign");
+    let ledger_path = matches.get_one::<String>("ledger").unwrap();
+    let estimate = matches.get_flag("estimate");
+    let wait_finalized = matches.get_flag("wait-finalized");
+    let reference_rpc = matches.get_one::<String>("reference-rpc");
+    let max_slot_lag = *matches.get_one::<u64>("max-slot-lag").unwrap();
+    let samples = *matches.get_one::<usize>("samples").unwrap();
+    let hooks_file = matches.get_one::<String>("hooks");
+    let pipeline_file = matches.get_one::<String>("pipeline");
+    let schedule_file = matches.get_one::<String>("schedule");
+    let price_api = matches.get_one::<String>("price-api").unwrap();
+    let sort: portfolio::PortfolioSort = matches.get_one::<String>("sort").unwrap().parse()?;
+    let spec_file = matches.get_one::<String>("spec");
+    let interactive = matches.get_flag("interactive");
+    let ipfs_api = matches.get_one::<String>("ipfs-api");
+    let backend: das::Backend = matches.get_one::<String>("backend").unwrap().parse()?;
+    let collection = matches.get_one::<String>("collection");
+    let geyser = matches.get_one::<String>("geyser-endpoint").map(|endpoint| geyser::GeyserConfig {
+        endpoint: endpoint.clone(),
+        x_token: matches.get_one::<String>("geyser-token").cloned(),
+    });
+    let group_max_size = matches.get_one::<u32>("group-max-size").copied();
+    let group = matches.get_one::<String>("group");
+    let multiplier = matches.get_one::<String>("multiplier");
+    let effective_at = matches.get_one::<String>("effective-at");
+    let memo = matches.get_one::<String>("memo");
+    let immutable_owner = matches.get_flag("immutable-owner");
+    let token_account = matches.get_one::<String>("token-account");
+    let address = matches.get_one::<String>("address");
+    let limit = *matches.get_one::<usize>("limit").unwrap();
+    let exclude = matches.get_one::<String>("exclude");
+    let keys_dir = matches.get_one::<String>("keys-dir").unwrap();
+    let multisig_signers: Vec<String> = matches
+        .get_many::<String>("multisig-signer")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    let threshold = matches.get_one::<u8>("threshold").copied();
+    let new_authority = matches.get_one::<String>("new-authority");
+    let authority_type = matches.get_one::<String>("authority-type").unwrap();
+    let delay = matches.get_one::<String>("delay").unwrap();
+    let proposals_dir = matches.get_one::<String>("proposals-dir").unwrap();
+    let new_authority_keypair = matches.get_one::<String>("new-authority-keypair");
+    let creators: Vec<String> = matches
+        .get_many::<String>("creator")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    let royalty_bps = matches.get_one::<u16>("royalty-bps").copied();
+    let master = matches.get_one::<String>("master");
+    let count = *matches.get_one::<u64>("count").unwrap();
+    let at_slot = matches.get_one::<u64>("at-slot").copied();
+    let merkle = matches.get_flag("merkle");
+    let mint_a = matches.get_one::<String>("mint-a");
+    let mint_b = matches.get_one::<String>("mint-b");
+    let rounding: Rounding = matches.get_one::<String>("rounding").unwrap().parse()?;
+    let fee_relayer = matches.get_one::<String>("fee-relayer");
+    let fee_token = matches.get_one::<String>("fee-token");
+    let checkpoints = Checkpoints::new(matches.get_one::<String>("checkpoint-dir").unwrap(), matches.get_flag("resume"));
+    let idempotency_key = match matches.get_one::<String>("idempotency-key") {
+        Some(_) if !matches!(operation.as_str(), "mint-to" | "transfer") => {
+            return Err(anyhow!("--idempotency-key only applies to mint-to and transfer"));
+        }
+        Some(key) => Some(idempotency::IdempotencyKey::open(matches.get_one::<String>("idempotency-db").unwrap(), key)?),
+        None => None,
+    };
+    let receipts = match matches.get_one::<String>("receipts-dir") {
+        Some(dir) => Some(Receipts::new(dir, operation, command_line_parameters(&matches))?),
+        None => None,
+    };
+    let cross_check_rpcs: Vec<String> = matches
+        .get_many::<String>("cross-check-rpc")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    let signer_keypairs: Vec<String> = matches
+        .get_many::<String>("signer-keypair")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    let output = output_target(
+        matches.get_one::<String>("output"),
+        matches.get_one::<String>("format"),
+    )?;
     
     info!("Starting Solana Token Operations");
     info!("Wallet: {}", wallet_path);
-    info!("Mint: {}", mint_address);
+    if let Some(mint_address) = mint_address {
+        info!("Mint: {}", mint_address);
+    }
     info!("Operation: {}", operation);
-    info!("RPC URL: {}", rpc_url);
+    info!("RPC URL: {}", providers::redact_url(rpc_url));
+    
+    // run orchestrates other operations, each in its own process
+    if operation == "run" {
+        info!("Running pipeline...");
+        pipeline::run_pipeline(required(pipeline_file, "pipeline")?, &command_line_parameters(&matches))?;
+        info!("Operation completed successfully!");
+        return Ok(());
+    }
     
-    let token_ops = TokenOperations::new(rpc_url, wallet_path)?;
+    // scheduler runs operations on a timer, each in its own process
+    if operation == "scheduler" {
+        info!("Starting scheduler...");
+        return scheduler::run_scheduler(required(schedule_file, "schedule")?, &command_line_parameters(&matches));
+    }
+    
+    // rpc-health and rpc-bench only probe endpoints and need no wallet
+    if operation == "rpc-health" {
+        info!("Checking RPC endpoint health...");
+        let mut urls = vec![rpc_url.to_string()];
+        urls.extend(cross_check_rpcs.iter().cloned());
+        health::rpc_health(
+            &urls,
+            reference_rpc.map(String::as_str),
+            max_slot_lag,
+            output,
+        )?;
+        info!("Operation completed successfully!");
+        return Ok(());
+    }
     
+    if operation == "rpc-bench" {
+        info!("Benchmarking RPC endpoints...");
+        let mut urls = vec![rpc_url.to_string()];
+        urls.extend(cross_check_rpcs.iter().cloned());
+        let account = match mint_address {
+            Some(mint) => Pubkey::from_str(mint).map_err(|e| anyhow!("Invalid mint address: {}", e))?,
+            None => spl_token::id(),
+        };
+        bench::rpc_bench(&urls, &account, samples, output)?;
+        info!("Operation completed successfully!");
+        return Ok(());
+    }
+    
+    // key-convert only rewrites a key file and touches no RPC
+    if operation == "key-convert" {
+        info!("Converting key...");
+        keys::convert_key(
+            matches.get_one::<String>("key-input").unwrap_or(wallet_path),
+            matches.get_one::<String>("key-format").unwrap().parse()?,
+            matches.get_one::<String>("output").map(String::as_str),
+            assume_yes,
+        )?;
+        info!("Operation completed successfully!");
+        return Ok(());
+    }
+    
+    // audit-verify only reads the audit file
+    if operation == "audit-verify" {
+        info!("Verifying audit log...");
+        audit::verify_audit_log(
+            matches.get_one::<String>("audit-log").map(String::as_str).unwrap_or(audit::DEFAULT_AUDIT_LOG),
+        )?;
+        info!("Operation completed successfully!");
+        return Ok(());
+    }
+    
+    // dev-bootstrap starts its own localnet and creates the wallet if needed
+    if operation == "dev-bootstrap" {
+        info!("Bootstrapping localnet environment...");
+        dev::bootstrap(wallet_path, fixtures)?;
+        info!("Operation completed successfully!");
+        return Ok(());
+    }
+    
+    // Long scans stop at a page boundary on the first interrupt and save a checkpoint
+    checkpoint::install_interrupt_handler()?;
+    
+    let mut token_ops = TokenOperations::new(rpc_url, wallet_path)?
+        .with_compute_budget(compute_unit_limit, compute_unit_price)
+        .with_auto_priority_fee(auto_priority_fee)
+        .with_cross_check(&cross_check_rpcs)
+        .with_wait_finalized(wait_finalized)
+        .with_receipts(receipts)
+        .with_checkpoints(checkpoints)
+        .with_backend(backend)?;
+    
+    // alt-* operations manage the table itself rather than transacting through it
+    if !operation.starts_with("alt-") {
+        token_ops.load_lookup_tables(&lookup_tables)?;
+    }
+    token_ops.load_fee_relayer(fee_relayer, fee_token)?;
+    let audit_log = match matches.get_one::<String>("audit-log") {
+        _ if matches.get_flag("no-audit-log") => None,
+        Some(path) => Some(path.as_str()),
+        None => match token_ops.is_mainnet() {
+            Ok(true) => Some(audit::DEFAULT_AUDIT_LOG),
+            Ok(false) => None,
+            // Unknown network: audit as on mainnet rather than fail, since read-only runs write nothing.
+            Err(e) => {
+                warn!("Cannot tell whether this is mainnet ({}); auditing to {}", e, audit::DEFAULT_AUDIT_LOG);
+                Some(audit::DEFAULT_AUDIT_LOG)
+            }
+        },
+    };
+    if let Some(path) = audit_log {
+        audit::configure(path, &token_ops.wallet.pubkey().to_string(), operation, &command_line_parameters(&matches))?;
+    }
+    if matches.get_flag("profile") {
+        profile::enable(operation, matches.get_one::<String>("profile-output").map(String::as_str))?;
+    }
+    token_ops.load_hooks(hooks_file, operation, mint_address)?;
+    if let Some(hooks) = &token_ops.hooks {
+        hooks.run_before()?;
+    }
+    
+    // Set by gate-check, which still tears down normally when the gate fails.
+    let mut gate_failed = false;
     match operation.as_str() {
+        "verify" | "balance" | "supply" | "analyze" if multiple_mints => {
+            let mints = multi::mint_list(&matches, mints_file)?;
+            info!("Running {} for {} mint(s)...", operation, mints.len());
+            token_ops.run_for_mints(operation.as_str(), &mints, owner.map(|s| s.as_str()), output)?
+        }
         "verify" => {
             info!("Performing token verification...");
-            token_ops.verify_token(mint_address)?
+            token_ops.verify_token(required(mint_address, "mint-address")?)?
         }
         "balance" => {
             info!("Getting token balance...");
-            token_ops.get_token_balance(mint_address, owner.map(|s| s.as_str()))?
+            token_ops.get_token_balance(required(mint_address, "mint-address")?, owner.map(|s| s.as_str()))?
         }
         "analyze" => {
             info!("Performing comprehensive token analysis...");
-            token_ops.analyze_token(mint_address)?
+            token_ops.analyze_token(required(mint_address, "mint-address")?)?
         }
         "list-accounts" => {
             info!("Listing all token accounts...");
             token_ops.list_token_accounts()?
         }
+        "portfolio" => {
+            info!("Valuing portfolio...");
+            token_ops.portfolio(owner.map(String::as_str), price_api, sort, output)?
+        }
         "revoke-mint-authority" => {
             info!("Revoking mint authority...");
-            token_ops.revoke_mint_authority(mint_address)?
+            token_ops.revoke_mint_authority(required(mint_address, "mint-address")?, assume_yes)?
         }
         "revoke-freeze-authority" => {
             info!("Revoking freeze authority...");
-            token_ops.revoke_freeze_authority(mint_address)?
+            token_ops.revoke_freeze_authority(required(mint_address, "mint-address")?, assume_yes)?
+        }
+        "update-ui-multiplier" => {
+            info!("Updating UI multiplier...");
+            token_ops.update_ui_multiplier(
+                required(mint_address, "mint-address")?,
+                required(multiplier, "multiplier")?,
+                effective_at.map(String::as_str),
+            )?
+        }
+        "pause" => {
+            info!("Pausing mint...");
+            token_ops.set_paused(required(mint_address, "mint-address")?, true)?
+        }
+        "resume" => {
+            info!("Resuming mint...");
+            token_ops.set_paused(required(mint_address, "mint-address")?, false)?
+        }
+        "alt-create" => {
+            info!("Creating address lookup table...");
+            token_ops.alt_create()?
+        }
+        "alt-extend" => {
+            info!("Extending address lookup table...");
+            token_ops.alt_extend(
+                required(lookup_tables.first(), "lookup-table")?,
+                required(addresses_file, "addresses-file")?,
+                mint_address.map(|s| s.as_str()),
+            )?
+        }
+        "alt-deactivate" => {
+            info!("Deactivating address lookup table...");
+            token_ops.alt_deactivate(required(lookup_tables.first(), "lookup-table")?)?
+        }
+        "alt-close" => {
+            info!("Closing address lookup table...");
+            token_ops.alt_close(required(lookup_tables.first(), "lookup-table")?, assume_yes)?
+        }
+        "index-snapshot" => {
+            info!("Indexing holder snapshot...");
+            token_ops.index_snapshot(required(mint_address, "mint-address")?, index_db, output)?
+        }
+        "index-sync" => {
+            info!("Syncing local holder index...");
+            token_ops.index_sync(required(mint_address, "mint-address")?, index_db)?
+        }
+        "index-events" => {
+            info!("Indexing transfer events...");
+            token_ops.index_events(required(mint_address, "mint-address")?, index_db)?
+        }
+        "supply" => token_ops.supply(required(mint_address, "mint-address")?, output)?,
+        "circulating-supply" => {
+            info!("Calculating circulating supply...");
+            token_ops.circulating_supply(required(mint_address, "mint-address")?, required(exclude, "exclude")?, output)?
+        }
+        "confidential-configure" => {
+            info!("Configuring account for confidential transfers...");
+            token_ops.confidential_configure(required(mint_address, "mint-address")?, keys_dir)?
+        }
+        "confidential-approve" => {
+            info!("Approving account for confidential transfers...");
+            token_ops.confidential_approve(required(mint_address, "mint-address")?, required(token_account, "token-account")?)?
+        }
+        "confidential-deposit" => {
+            info!("Depositing into confidential balance...");
+            token_ops.confidential_deposit(required(mint_address, "mint-address")?, required(amount, "amount")?)?
+        }
+        "confidential-apply" => {
+            info!("Applying pending confidential balance...");
+            token_ops.confidential_apply(required(mint_address, "mint-address")?, keys_dir)?
+        }
+        "confidential-withdraw" => {
+            info!("Withdrawing from confidential balance...");
+            token_ops.confidential_withdraw(required(mint_address, "mint-address")?, required(amount, "amount")?, keys_dir)?
+        }
+        "confidential-balance" => {
+            info!("Reading confidential balances...");
+            token_ops.confidential_balance(required(mint_address, "mint-address")?, keys_dir)?
+        }
+        "create-multisig" => {
+            info!("Creating multisig...");
+            token_ops.create_multisig(
+                &multisig_signers,
+                threshold.ok_or_else(|| anyhow!("--threshold is required for this operation"))?,
+                mint_address.map(String::as_str),
+                token_2022,
+                assume_yes,
+            )?
+        }
+        "top-holders" => {
+            info!("Resolving top holders...");
+            token_ops.top_holders(required(mint_address, "mint-address")?, limit, output)?
+        }
+        "query-holders" => {
+            info!("Querying local holder index...");
+            index::query_holders(
+                index_db,
+                required(mint_address, "mint-address")?,
+                min_balance.map(|s| s.as_str()),
+            )?
+        }
+        "report-activity" => {
+            info!("Building activity report...");
+            token_ops.report_activity(required(mint_address, "mint-address")?, index_db, since, output)?
+        }
+        "report-burns" => {
+            info!("Building burn report...");
+            token_ops.report_burns(required(mint_address, "mint-address")?, index_db, since, output)?
+        }
+        "lint-metadata" => {
+            info!("Linting token metadata...");
+            token_ops.lint_metadata(required(mint_address, "mint-address")?)?
+        }
+        "freeze-list" | "thaw-list" => {
+            let action = if operation == "freeze-list" { FreezeAction::Freeze } else { FreezeAction::Thaw };
+            info!("Updating freeze state for listed wallets...");
+            token_ops.freeze_list(
+                required(mint_address, "mint-address")?,
+                required(addresses_file, "addresses-file")?,
+                action,
+                output,
+            )?
+        }
+        "bulk-balance" => {
+            info!("Getting token balances for listed wallets...");
+            token_ops.bulk_balance(
+                required(mint_address, "mint-address")?,
+                required(addresses_file, "addresses-file")?,
+                output,
+            )?
+        }
+        "proof-of-reserves" => {
+            info!("Generating proof-of-reserves report...");
+            let mints = multi::mint_list(&matches, mints_file)?;
+            let wallets = match addresses_file {
+                Some(path) => input::read_addresses(path)?,
+                None => Vec::new(),
+            };
+            let signers = signer_keypairs
+                .iter()
+                .map(|path| read_keypair(path))
+                .collect::<Result<Vec<_>>>()?;
+            token_ops.proof_of_reserves(&mints, &wallets, &signers, output)?
+        }
+        "validate-airdrop" => {
+            info!("Validating airdrop recipients...");
+            token_ops.validate_airdrop(
+                required(mint_address, "mint-address")?,
+                required(recipients, "recipients")?,
+                output,
+            )?
+        }
+        "airdrop" => {
+            let mint_address = required(mint_address, "mint-address")?;
+            let recipients = required(recipients, "recipients")?;
+            let campaign = required(campaign, "campaign")?;
+            if estimate {
+                info!("Estimating airdrop cost...");
+                token_ops.estimate_airdrop(mint_address, recipients, campaign, ledger_path, output)?
+            } else {
+                info!("Running airdrop...");
+                token_ops.airdrop(mint_address, recipients, campaign, ledger_path, assume_yes, output)?
+            }
+        }
+        "approve-holder" => {
+            info!("Approving holder...");
+            token_ops.approve_holder(required(mint_address, "mint-address")?, required(owner, "owner")?)?
+        }
+        "pending-holders" => {
+            info!("Listing holders awaiting approval...");
+            token_ops.pending_holders(required(mint_address, "mint-address")?, output)?
+        }
+        "set-default-account-state" => {
+            info!("Updating default account state...");
+            token_ops.set_default_account_state(
+                required(mint_address, "mint-address")?,
+                required(state, "state")? == "frozen",
+            )?
+        }
+        "transfer-from" => {
+            info!("Transferring tokens as delegate...");
+            token_ops.transfer_from(
+                required(mint_address, "mint-address")?,
+                required(from, "from")?,
+                required(to, "to")?,
+                required(amount, "amount")?,
+                memo.map(String::as_str),
+                assume_yes,
+            )?
+        }
+        "burn-from" => {
+            info!("Burning tokens as delegate...");
+            token_ops.burn_from(
+                required(mint_address, "mint-address")?,
+                required(from, "from")?,
+                required(amount, "amount")?,
+                assume_yes,
+            )?
+        }
+        "transfer-update-authority" => {
+            info!("Transferring metadata update authority...");
+            token_ops.transfer_update_authority(
+                required(mint_address, "mint-address")?,
+                required(new_authority, "new-authority")?,
+                assume_yes,
+            )?
+        }
+        "propose-authority" => {
+            info!("Proposing authority transfer...");
+            token_ops.propose_authority(
+                required(mint_address, "mint-address")?,
+                authority_type,
+                required(new_authority, "new-authority")?,
+                delay,
+                proposals_dir,
+                assume_yes,
+            )?
+        }
+        "accept-authority" => {
+            info!("Accepting authority transfer...");
+            let keypair = new_authority_keypair.map(|path| read_keypair(path)).transpose()?;
+            token_ops.accept_authority(
+                required(mint_address, "mint-address")?,
+                authority_type,
+                proposals_dir,
+                keypair.as_ref(),
+                assume_yes,
+            )?
+        }
+        "cancel-authority" => {
+            info!("Cancelling authority transfer...");
+            token_ops.cancel_authority(required(mint_address, "mint-address")?, authority_type, proposals_dir)?
+        }
+        "set-creators" => {
+            info!("Setting metadata creators...");
+            token_ops.set_creators(required(mint_address, "mint-address")?, &creators, assume_yes)?
+        }
+        "set-royalty" => {
+            info!("Setting metadata royalty...");
+            token_ops.set_royalty(
+                required(mint_address, "mint-address")?,
+                royalty_bps.ok_or_else(|| anyhow!("--royalty-bps is required for this operation"))?,
+                assume_yes,
+            )?
+        }
+        "sign-metadata" => {
+            info!("Signing metadata as creator...");
+            token_ops.sign_metadata(required(mint_address, "mint-address")?)?
+        }
+        "print-edition" => {
+            info!("Printing editions...");
+            token_ops.print_editions(required(master, "master")?, required(to, "to")?, count, assume_yes, output)?
+        }
+        "burn-nft" => {
+            info!("Burning NFT...");
+            token_ops.burn_nft(required(mint_address, "mint-address")?, master.map(String::as_str), assume_yes)?
+        }
+        "collection-holders" => {
+            info!("Snapshotting collection holders...");
+            token_ops.collection_holders(required(collection, "collection")?, output)?
+        }
+        "export-allowlist" => {
+            info!("Exporting allowlist...");
+            token_ops.export_allowlist(
+                required(mint_address, "mint-address")?,
+                required(min_amount, "min-amount")?,
+                at_slot,
+                index_db,
+                merkle,
+                output,
+            )?
+        }
+        "compare-holders" => {
+            info!("Comparing holders...");
+            token_ops.compare_holders(required(mint_a, "mint-a")?, required(mint_b, "mint-b")?, output)?
+        }
+        "pro-rata-airdrop" => {
+            let mint_b = required(mint_b, "mint-b")?;
+            let recipients = required(recipients, "recipients")?;
+            let campaign = if execute { Some(required(campaign, "campaign")?) } else { None };
+            info!("Generating pro-rata distribution...");
+            let plan = prorata::ProRataPlan {
+                snapshot_mint: required(mint_a, "mint-a")?,
+                distribution_mint: mint_b,
+                total: required(amount, "amount")?,
+                rounding,
+                at_slot,
+                index_path: index_db,
+                exclude: exclude.map(String::as_str),
+                recipients_path: recipients,
+            };
+            match campaign {
+                // --output then carries the airdrop report.
+                Some(campaign) => {
+                    token_ops.pro_rata_airdrop(&plan, None)?;
+                    info!("Running airdrop...");
+                    token_ops.airdrop(mint_b, recipients, campaign, ledger_path, assume_yes, output)?
+                }
+                None => token_ops.pro_rata_airdrop(&plan, output)?,
+            }
+        }
+        "mint-to" => {
+            info!("Minting tokens...");
+            token_ops.mint_tokens(
+                required(mint_address, "mint-address")?,
+                required(to, "to")?,
+                required(amount, "amount")?,
+                idempotency_key.as_ref(),
+                assume_yes,
+            )?
+        }
+        "transfer" => {
+            info!("Transferring tokens...");
+            token_ops.transfer(
+                required(mint_address, "mint-address")?,
+                required(to, "to")?,
+                required(amount, "amount")?,
+                memo.map(String::as_str),
+                idempotency_key.as_ref(),
+                assume_yes,
+            )?
+        }
+        "enable-required-memo" | "disable-required-memo" => {
+            let enable = operation == "enable-required-memo";
+            info!("{} required memos...", if enable { "Enabling" } else { "Disabling" });
+            token_ops.set_required_memo(mint_address.map(String::as_str), enable)?
+        }
+        "create-token-account" => {
+            info!("Creating token account...");
+            token_ops.create_token_account(
+                required(mint_address, "mint-address")?,
+                owner.map(String::as_str),
+                immutable_owner,
+            )?
+        }
+        "audit-accounts" => {
+            info!("Auditing wallet token accounts...");
+            token_ops.audit_accounts(output)?
+        }
+        "reallocate" => {
+            info!("Reallocating token account...");
+            token_ops.reallocate_account(
+                token_account.map(String::as_str),
+                mint_address.map(String::as_str),
+                &extensions,
+            )?
+        }
+        "account-info" => {
+            info!("Decoding account...");
+            token_ops.account_info(required(address, "address")?)?
+        }
+        "clawback" => {
+            info!("Clawing back tokens...");
+            token_ops.clawback(
+                required(mint_address, "mint-address")?,
+                required(from, "from")?,
+                required(amount, "amount")?,
+                to.map(String::as_str),
+                burn,
+                assume_yes,
+            )?
+        }
+        "serve-faucet" => {
+            info!("Starting token faucet...");
+            token_ops.serve_faucet(
+                required(mint_address, "mint-address")?,
+                listen,
+                required(amount, "amount")?,
+                cooldown,
+            )?
+        }
+        "create-mint" => {
+            if interactive {
+                info!("Starting token creation wizard...");
+                token_ops.create_interactive(mint_keypair, ipfs_api.map(String::as_str))?
+            } else {
+                info!("Creating mint...");
+                let mut config = mint::MintConfig::new(decimals, token_2022, &extensions)?;
+                if let Some(max_size) = group_max_size {
+                    config = config.with_group(max_size)?;
+                }
+                if let Some(group) = group {
+                    let group = Pubkey::from_str(group).map_err(|e| anyhow!("Invalid group address: {}", e))?;
+                    config = config.with_group_member(group)?;
+                }
+                token_ops.create_mint(&config, mint_keypair, starts_with.map(String::as_str), ignore_case)?
+            }
+        }
+        "consolidate" => {
+            info!("Consolidating token accounts...");
+            token_ops.consolidate(mint_address.map(String::as_str), output)?
+        }
+        "sweep" => {
+            info!("Sweeping token accounts...");
+            token_ops.sweep(required(to, "to")?, mint_address.map(String::as_str), assume_yes, output)?
+        }
+        "find-misdirected" => {
+            info!("Looking for misdirected tokens...");
+            token_ops.find_misdirected(mint_address.map(String::as_str), recover, assume_yes, output)?
+        }
+        "rent-report" => {
+            info!("Scanning wallet accounts for reclaimable rent...");
+            token_ops.rent_report(execute, assume_yes, output)?
+        }
+        "fee-report" => {
+            info!("Building fee-spend report...");
+            token_ops.fee_report(since, until.map(String::as_str), output)?
+        }
+        "snapshot-diff" => {
+            info!("Diffing holder snapshots...");
+            diff::snapshot_diff(
+                required(before, "before")?,
+                required(after, "after")?,
+                mint_address.map(String::as_str),
+                index_db,
+                output,
+            )?
+        }
+        "monitor-authorities" => {
+            info!("Starting authority monitor...");
+            token_ops.monitor_authorities(
+                mint_address.map(String::as_str),
+                addresses_file.map(String::as_str),
+                interval,
+                &webhooks,
+                geyser.as_ref(),
+            )?
+        }
+        "watch-events" => {
+            let mints = multi::mint_list(&matches, mints_file)?;
+            info!("Watching mint, burn and transfer events...");
+            token_ops.watch_events(&mints, interval, &webhooks, index_db)?
+        }
+        "watch-balances" => {
+            let mut wallets = match addresses_file {
+                Some(path) => input::read_addresses(path)?,
+                None => Vec::new(),
+            };
+            if let Some(owner) = owner {
+                wallets.push(Pubkey::from_str(owner).map_err(|e| anyhow!("Invalid owner address: {}", e))?);
+            }
+            wallets.sort();
+            wallets.dedup();
+            let mints = multi::mint_list(&matches, mints_file)?;
+            info!("Streaming balance changes...");
+            token_ops.watch_balances(&wallets, &mints, interval)?
+        }
+        "sign-message" => {
+            info!("Signing message...");
+            token_ops.sign_message(required(message, "message")?, raw)?
+        }
+        "rebroadcast" => {
+            info!("Rebroadcasting transaction...");
+            token_ops.rebroadcast(signature.map(String::as_str), input.map(String::as_str), output)?
+        }
+        "verify-message" => {
+            info!("Verifying message signature...");
+            message::verify_message(
+                required(signer, "signer")?,
+                required(signature, "signature")?,
+                required(message, "message")?,
+                raw,
+            )?
+        }
+        "gate-check" => {
+            info!("Checking token gate...");
+            let passed = token_ops.gate_check(
+                required(mint_address, "mint-address")?,
+                required(owner, "owner")?,
+                required(min_amount, "min-amount")?,
+            )?;
+            gate_failed = !passed;
+        }
+        "migrate" => {
+            info!("Migrating to Token-2022...");
+            token_ops.migrate(
+                required(mint_address, "mint-address")?,
+                &extensions,
+                mint_keypair,
+                mode.parse()?,
+                assume_yes,
+                output,
+            )?
+        }
+        "apply" => {
+            info!("Applying token spec...");
+            token_ops.apply_spec(required(spec_file, "spec")?, assume_yes)?
+        }
+        "asset-info" => {
+            info!("Fetching asset via DAS...");
+            token_ops.asset_info(required(mint_address, "mint-address")?, output)?
+        }
+        "search-assets" => {
+            info!("Searching assets via DAS...");
+            token_ops.search_assets(owner.map(String::as_str), collection.map(String::as_str), output)?
+        }
+        "serve-grpc" => {
+            info!("Starting gRPC server...");
+            // Serves until interrupted; the service owns the operations.
+            return grpc::serve_grpc(token_ops, listen);
+        }
+        "plan" | "diff" => {
+            info!("Comparing token spec with chain state...");
+            token_ops.plan_token_spec(required(spec_file, "spec")?, output)?
         }
         _ => {
             error!("Unknown operation: {}", operation);
@@ -336,6 +2310,13 @@ fn main() -> Result<()> {
         }
     }
     
+    if let Some(hooks) = &token_ops.hooks {
+        hooks.run_after()?;
+    }
+    if gate_failed {
+        return Err(gate::GateCheckFailed.into());
+    }
+    
     info!("Operation completed successfully!");
     Ok(())
 }
\ No newline at end of file
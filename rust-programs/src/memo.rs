@@ -0,0 +1,95 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use spl_token_2022::{
+    extension::{memo_transfer, ExtensionType},
+    instruction::reallocate,
+};
+use std::{collections::HashMap, str::FromStr};
+use token_ops_core::{mint::requires_incoming_memo, tlv::token_account_extensions};
+
+use crate::TokenOperations;
+
+/// Estimated compute units for a Reallocate plus Enable/DisableRequiredTransferMemos.
+const MEMO_TOGGLE_UNITS: u32 = 15_000;
+
+impl TokenOperations {
+    /// Turn the Token-2022 required-memo setting on or off for every
+    /// Token-2022 account the wallet owns, or only those of `mint_address`.
+    /// Accounts created without room for MemoTransfer are reallocated first,
+    /// paid by the wallet.
+    pub fn set_required_memo(&self, mint_address: Option<&str>, enable: bool) -> Result<()> {
+        let mint = mint_address
+            .map(|mint| Pubkey::from_str(mint).map_err(|e| anyhow!("Invalid mint address: {}", e)))
+            .transpose()?;
+        let owner = self.wallet.pubkey();
+        let program_id = spl_token_2022::id();
+        let memo_transfer_type = ExtensionType::MemoTransfer as u16;
+
+        let mut batcher = self.batcher();
+        // batcher item index -> token account
+        let mut queued: HashMap<usize, Pubkey> = HashMap::new();
+        let mut unchanged = 0;
+
+        for owned in self.owned_token_accounts(&owner, mint.as_ref())? {
+            if owned.program_id != program_id {
+                continue;
+            }
+            if requires_incoming_memo(&owned.data)? == enable {
+                unchanged += 1;
+                continue;
+            }
+
+            let mut instructions = Vec::new();
+            let has_extension = token_account_extensions(&owned.data)?
+                .iter()
+                .any(|(extension, _)| *extension == memo_transfer_type);
+            if enable && !has_extension {
+                instructions.push(reallocate(
+                    &program_id,
+                    &owned.address,
+                    &owner,
+                    &owner,
+                    &[],
+                    &[ExtensionType::MemoTransfer],
+                )?);
+            }
+            instructions.push(if enable {
+                memo_transfer::instruction::enable_required_transfer_memos(&program_id, &owned.address, &owner, &[])?
+            } else {
+                memo_transfer::instruction::disable_required_transfer_memos(&program_id, &owned.address, &owner, &[])?
+            });
+
+            let item = batcher.push(instructions, MEMO_TOGGLE_UNITS)?;
+            queued.insert(item, owned.address);
+        }
+
+        let verb = if enable { "enabled" } else { "disabled" };
+        if queued.is_empty() {
+            info!("Required memos already {} on all {} Token-2022 account(s)", verb, unchanged);
+            return Ok(());
+        }
+
+        let mut failed = 0;
+        for batch in batcher.finish() {
+            for item in &batch.items {
+                let Some(account) = queued.get(item) else {
+                    continue;
+                };
+                match &batch.signature {
+                    Ok(signature) => info!("  {}: required memos {} ({})", account, verb, signature),
+                    Err(e) => {
+                        warn!("  {}: failed: {}", account, e);
+                        failed += 1;
+                    }
+                }
+            }
+        }
+        info!("Required memos {} on {} account(s), {} already set", verb, queued.len() - failed, unchanged);
+
+        if failed > 0 {
+            return Err(anyhow!("{} account(s) were not updated", failed));
+        }
+        Ok(())
+    }
+}
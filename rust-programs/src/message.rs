@@ -0,0 +1,65 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use solana_sdk::{
+    offchain_message::OffchainMessage,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+};
+use std::str::FromStr;
+
+use crate::TokenOperations;
+
+/// Offchain message header version used when signing.
+const OFFCHAIN_MESSAGE_VERSION: u8 = 0;
+
+impl TokenOperations {
+    /// Sign `message` with the loaded wallet.
+    ///
+    /// By default the message is wrapped in the Solana offchain message
+    /// format, so the signature can never be replayed as a transaction;
+    /// `raw` signs the bytes as given, as most browser wallets' signMessage does.
+    pub fn sign_message(&self, message: &str, raw: bool) -> Result<()> {
+        let signature = if raw {
+            self.wallet.sign_message(message.as_bytes())
+        } else {
+            sign_offchain(&self.wallet, message)?
+        };
+
+        info!("Signer: {}", self.wallet.pubkey());
+        info!("Format: {}", if raw { "raw" } else { "offchain" });
+        info!("Signature: {}", signature);
+        println!("{}", signature);
+
+        Ok(())
+    }
+}
+
+/// Sign `message` wrapped in the offchain message format.
+pub fn sign_offchain(keypair: &Keypair, message: &str) -> Result<Signature> {
+    OffchainMessage::new(OFFCHAIN_MESSAGE_VERSION, message.as_bytes())
+        .map_err(|e| anyhow!("Message cannot be used as an offchain message: {}", e))?
+        .sign(keypair)
+        .map_err(|e| anyhow!("Failed to sign message: {}", e))
+}
+
+/// Check that `signature` over `message` was made by `signer`.
+pub fn verify_message(signer: &str, signature: &str, message: &str, raw: bool) -> Result<()> {
+    let signer = Pubkey::from_str(signer).map_err(|e| anyhow!("Invalid signer address: {}", e))?;
+    let signature = Signature::from_str(signature).map_err(|e| anyhow!("Invalid signature: {}", e))?;
+
+    let valid = if raw {
+        signature.verify(signer.as_ref(), message.as_bytes())
+    } else {
+        OffchainMessage::new(OFFCHAIN_MESSAGE_VERSION, message.as_bytes())
+            .map_err(|e| anyhow!("Message cannot be used as an offchain message: {}", e))?
+            .verify(&signer, &signature)
+            .map_err(|e| anyhow!("Failed to verify message: {}", e))?
+    };
+
+    if !valid {
+        return Err(anyhow!("Signature is NOT valid for {} and this message", signer));
+    }
+
+    info!("Signature is valid for {}", signer);
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use mpl_token_metadata::{accounts::Metadata, instructions::UpdateMetadataAccountV2Builder};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use std::str::FromStr;
+
+use crate::{prompt::confirm_typed, TokenOperations};
+
+/// Estimated compute units for an UpdateMetadataAccountV2 instruction.
+pub const UPDATE_METADATA_UNITS: u32 = 40_000;
+
+/// Metaplex pads name, symbol and uri with NUL bytes up to their maximum length.
+pub fn trim_padding(value: &str) -> &str {
+    value.trim_end_matches('\0')
+}
+
+impl TokenOperations {
+    /// Fetch the Metaplex metadata account for a mint, if one exists.
+    pub fn fetch_metadata(&self, mint_pubkey: &Pubkey) -> Result<Option<(Pubkey, Metadata)>> {
+        let (metadata_address, _) = Metadata::find_pda(mint_pubkey);
+
+        let account = self.client
+            .get_account_with_commitment(&metadata_address, self.client.commitment())
+            .map_err(|e| anyhow!("Failed to get metadata account: {}", e))?
+            .value;
+        let Some(account) = account else {
+            return Ok(None);
+        };
+        if account.owner != mpl_token_metadata::ID {
+            return Err(anyhow!("Metadata account {} is not owned by the Token Metadata program", metadata_address));
+        }
+
+        let metadata = Metadata::from_bytes(&account.data)
+            .map_err(|e| anyhow!("Failed to parse metadata account {}: {}", metadata_address, e))?;
+
+        Ok(Some((metadata_address, metadata)))
+    }
+
+    /// Hand the Metaplex update authority of a mint's metadata to another
+    /// key, such as a multisig or DAO, then check the change on-chain.
+    /// Unlike making the metadata immutable this keeps it editable, by the
+    /// new authority only.
+    pub fn transfer_update_authority(&self, mint_address: &str, new_authority: &str, assume_yes: bool) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let new_authority = Pubkey::from_str(new_authority)
+            .map_err(|e| anyhow!("Invalid new authority: {}", e))?;
+        let (metadata_address, metadata) = self
+            .fetch_metadata(&mint_pubkey)?
+            .ok_or_else(|| anyhow!("Mint {} has no Metaplex metadata account", mint_pubkey))?;
+
+        let wallet = self.wallet.pubkey();
+        if metadata.update_authority != wallet {
+            return Err(anyhow!(
+                "Loaded wallet is not the update authority of this metadata; {} is",
+                metadata.update_authority
+            ));
+        }
+        if new_authority == wallet {
+            return Err(anyhow!("{} is already the update authority", new_authority));
+        }
+
+        confirm_typed(
+            &[
+                "TRANSFER METADATA UPDATE AUTHORITY".to_string(),
+                format!("Mint:              {}", mint_pubkey),
+                format!("Metadata:          {} ({})", metadata_address, trim_padding(&metadata.name)),
+                format!("Current authority: {}", wallet),
+                format!("New authority:     {}", new_authority),
+                if metadata.is_mutable {
+                    "Only the new authority can update the metadata afterwards; this wallet cannot take it back.".to_string()
+                } else {
+                    "The metadata is immutable, so the new authority only inherits the authority role.".to_string()
+                },
+            ],
+            mint_address,
+            assume_yes,
+        )?;
+
+        let instruction = UpdateMetadataAccountV2Builder::new()
+            .metadata(metadata_address)
+            .update_authority(wallet)
+            .new_update_authority(new_authority)
+            .instruction();
+        let signature = self
+            .send_instructions(vec![instruction], UPDATE_METADATA_UNITS)
+            .map_err(|e| anyhow!("Failed to transfer update authority: {}", e))?;
+        info!("Update authority transferred! Signature: {}", signature);
+
+        let (_, after) = self
+            .fetch_metadata(&mint_pubkey)?
+            .ok_or_else(|| anyhow!("Metadata account {} disappeared after the transfer", metadata_address))?;
+        if after.update_authority != new_authority {
+            return Err(anyhow!(
+                "Update authority is {} after the transfer, not {}",
+                after.update_authority,
+                new_authority
+            ));
+        }
+        info!("Verified on-chain: update authority is now {}", new_authority);
+        Ok(())
+    }
+}
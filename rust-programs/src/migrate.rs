@@ -0,0 +1,245 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    instruction::{mint_to_checked, thaw_account},
+    state::Mint,
+};
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    holders::format_amount,
+    mint::{load_or_generate_mint_keypair, MintConfig, MintExtension},
+    output::{write_output, OutputFormat},
+    outputs,
+    prompt::confirm,
+    TokenOperations,
+};
+
+/// Estimated compute units for an idempotent ATA creation.
+const CREATE_ATA_UNITS: u32 = 40_000;
+
+/// Estimated compute units for a MintToChecked instruction.
+const MINT_TO_UNITS: u32 = 20_000;
+
+/// Estimated compute units for a ThawAccount instruction.
+const THAW_UNITS: u32 = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationMode {
+    /// Mint the new token 1:1 into every holder's ATA.
+    Airdrop,
+    /// Mint the snapshot supply into the wallet's ATA to back later swaps.
+    Escrow,
+}
+
+impl FromStr for MigrationMode {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "airdrop" => Ok(MigrationMode::Airdrop),
+            "escrow" => Ok(MigrationMode::Escrow),
+            other => Err(anyhow!("Unknown migration mode: {}", other)),
+        }
+    }
+}
+
+/// One legacy token account and what it was migrated to.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationEntry {
+    pub legacy_account: String,
+    pub owner: String,
+    pub amount: u64,
+    pub new_account: String,
+    pub status: String,
+    pub signature: Option<String>,
+}
+
+/// Auditable record of a migration, written as the mapping file.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationManifest {
+    pub legacy_mint: String,
+    pub new_mint: String,
+    pub snapshot_slot: u64,
+    pub decimals: u8,
+    pub mode: MigrationMode,
+    pub total_amount: String,
+    pub escrow_account: Option<String>,
+    pub escrow_signature: Option<String>,
+    pub entries: Vec<MigrationEntry>,
+}
+
+impl TokenOperations {
+    /// Snapshot a legacy SPL Token mint, create an equivalent Token-2022 mint
+    /// and either airdrop it 1:1 or fund a swap escrow, writing a mapping file.
+    ///
+    /// Rerunning with the same `--mint-keypair` reuses the new mint if it
+    /// exists but has no supply yet.
+    pub fn migrate(
+        &self,
+        legacy_mint_address: &str,
+        extensions: &[String],
+        mint_keypair_path: &str,
+        mode: MigrationMode,
+        assume_yes: bool,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let legacy_mint = Pubkey::from_str(legacy_mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        if self.fetch_mint_info(&legacy_mint)?.is_token_2022() {
+            return Err(anyhow!("Mint is already a Token-2022 mint"));
+        }
+
+        let snapshot = self.fetch_holder_snapshot(&legacy_mint)?;
+        let config = MintConfig::new(snapshot.decimals, true, extensions)?;
+        let default_frozen = config.extensions.contains(&MintExtension::DefaultFrozen);
+        let total: u64 = snapshot.holders.iter().map(|holder| holder.amount).sum();
+
+        confirm(
+            &[
+                format!("Migrate {} to Token-2022", legacy_mint),
+                format!("Snapshot slot: {}", snapshot.slot),
+                format!("Holders:       {}", snapshot.holders.len()),
+                format!("Amount:        {}", format_amount(total, snapshot.decimals)),
+                format!("Mode:          {:?}", mode),
+                format!("Extensions:    {:?}", config.extensions),
+            ],
+            assume_yes,
+        )?;
+
+        let mint_keypair = load_or_generate_mint_keypair(mint_keypair_path, None, false)?;
+        let new_mint = mint_keypair.pubkey();
+        let existing = self.client
+            .get_account_with_commitment(&new_mint, self.client.commitment())
+            .map_err(|e| anyhow!("Failed to get mint account: {}", e))?
+            .value;
+        match existing {
+            Some(account) if account.owner == spl_token_2022::id() => {
+                let supply = StateWithExtensions::<Mint>::unpack(&account.data)
+                    .map_err(|e| anyhow!("Failed to parse mint data: {}", e))?
+                    .base
+                    .supply;
+                if supply > 0 {
+                    return Err(anyhow!("{} already has supply; refusing to migrate into it again", new_mint));
+                }
+                info!("Reusing existing Token-2022 mint {}", new_mint);
+            }
+            Some(_) => return Err(anyhow!("{} exists and is not a Token-2022 mint", new_mint)),
+            None => {
+                let signature = self.create_mint_account(&mint_keypair, &config)?;
+                info!("Created Token-2022 mint {}: {}", new_mint, signature);
+            }
+        }
+        outputs::record("new_mint", &new_mint.to_string());
+
+        let program_id = spl_token_2022::id();
+        let wallet = self.wallet.pubkey();
+        let ata = |owner: &Pubkey| spl_associated_token_account::get_associated_token_address_with_program_id(owner, &new_mint, &program_id);
+
+        // ATA creation, a thaw when new accounts start frozen, then the mint.
+        let credit = |owner: &Pubkey, amount: u64| -> Result<(Vec<Instruction>, u32)> {
+            let destination = ata(owner);
+            let mut instructions = vec![
+                spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    &wallet, owner, &new_mint, &program_id,
+                ),
+            ];
+            let mut units = CREATE_ATA_UNITS + MINT_TO_UNITS;
+            if default_frozen {
+                instructions.push(thaw_account(&program_id, &destination, &new_mint, &wallet, &[])?);
+                units += THAW_UNITS;
+            }
+            instructions.push(mint_to_checked(&program_id, &new_mint, &destination, &wallet, &[], amount, config.decimals)?);
+            Ok((instructions, units))
+        };
+
+        let mut entries: Vec<MigrationEntry> = snapshot
+            .holders
+            .iter()
+            .map(|holder| {
+                let owner = Pubkey::from_str(&holder.owner).map_err(|e| anyhow!("Invalid holder owner: {}", e))?;
+                Ok(MigrationEntry {
+                    legacy_account: holder.account.clone(),
+                    owner: holder.owner.clone(),
+                    amount: holder.amount,
+                    new_account: ata(&owner).to_string(),
+                    status: "pending".to_string(),
+                    signature: None,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let mut manifest = MigrationManifest {
+            legacy_mint: legacy_mint.to_string(),
+            new_mint: new_mint.to_string(),
+            snapshot_slot: snapshot.slot,
+            decimals: snapshot.decimals,
+            mode,
+            total_amount: format_amount(total, snapshot.decimals),
+            escrow_account: None,
+            escrow_signature: None,
+            entries: Vec::new(),
+        };
+
+        match mode {
+            MigrationMode::Airdrop => {
+                // batcher item index -> entry index
+                let mut queued: HashMap<usize, usize> = HashMap::new();
+                let mut batcher = self.batcher();
+                for (index, entry) in entries.iter().enumerate() {
+                    let owner = Pubkey::from_str(&entry.owner)?;
+                    let (instructions, units) = credit(&owner, entry.amount)?;
+                    queued.insert(batcher.push(instructions, units)?, index);
+                }
+
+                for batch in batcher.finish() {
+                    for item in &batch.items {
+                        let Some(index) = queued.get(item) else {
+                            continue;
+                        };
+                        let entry = &mut entries[*index];
+                        match &batch.signature {
+                            Ok(signature) => {
+                                entry.status = "migrated".to_string();
+                                entry.signature = Some(signature.to_string());
+                            }
+                            Err(e) => entry.status = format!("failed: {}", e),
+                        }
+                    }
+                }
+            }
+            MigrationMode::Escrow => {
+                let (instructions, units) = credit(&wallet, total)?;
+                let signature = self.send_instructions(instructions, units)
+                    .map_err(|e| anyhow!("Failed to fund escrow: {}", e))?;
+                info!("Escrow {} funded with {}: {}", ata(&wallet), manifest.total_amount, signature);
+
+                manifest.escrow_account = Some(ata(&wallet).to_string());
+                manifest.escrow_signature = Some(signature.to_string());
+                for entry in &mut entries {
+                    entry.status = "claimable".to_string();
+                }
+            }
+        }
+
+        let failed = entries.iter().filter(|entry| entry.status.starts_with("failed")).count();
+        for entry in entries.iter().filter(|entry| entry.status.starts_with("failed")) {
+            warn!("  {} ({}): {}", entry.owner, entry.legacy_account, entry.status);
+        }
+        info!("Migrated {} holder(s) to {}, {} failed", entries.len() - failed, new_mint, failed);
+        manifest.entries = entries;
+
+        let default_path = format!("migration-{}.json", legacy_mint);
+        let output = output.unwrap_or((default_path.as_str(), OutputFormat::Json));
+        write_output(Some(output), &manifest, &manifest.entries)?;
+
+        if failed > 0 {
+            return Err(anyhow!("{} holder(s) could not be migrated; see the mapping file", failed));
+        }
+        Ok(())
+    }
+}
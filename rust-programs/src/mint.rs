@@ -0,0 +1,569 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use log::info;
+use solana_sdk::{
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    system_instruction,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::{
+    extension::{
+        confidential_transfer, default_account_state, group_member_pointer, group_pointer, interest_bearing_mint, metadata_pointer,
+        transfer_fee, transfer_hook, ExtensionType,
+    },
+    instruction::{
+        initialize_mint2, initialize_mint_close_authority, initialize_non_transferable_mint,
+        initialize_permanent_delegate, mint_to_checked,
+    },
+    state::{Account, AccountState, Mint, Multisig},
+};
+use spl_token_group_interface::state::{TokenGroup, TokenGroupMember};
+use std::{mem::size_of, path::Path, str::FromStr};
+use token_ops_core::{pausable, scaled_ui_amount, tlv};
+
+use crate::{
+    holders::{format_amount, parse_amount},
+    idempotency::{IdempotencyKey, IDEMPOTENCY_MEMO_UNITS},
+    outputs,
+    prompt::confirm,
+    read_keypair,
+    vanity::{grind, VanityPattern},
+    write_keypair, TokenOperations,
+};
+
+/// Estimated compute units for CreateAccount, extension setup and InitializeMint2.
+const CREATE_MINT_UNITS: u32 = 30_000;
+
+/// Estimated compute units for an idempotent ATA creation plus MintToChecked.
+const MINT_TO_UNITS: u32 = 50_000;
+
+/// Estimated compute units for one ScaledUiAmount UpdateMultiplier.
+const UPDATE_MULTIPLIER_UNITS: u32 = 10_000;
+
+/// A Token-2022 mint extension requested with `--extension`.
+///
+/// Accepted forms: `transfer-fee=BPS:MAX_TOKENS`, `interest-rate=BPS`,
+/// `permanent-delegate`, `default-frozen`, `non-transferable`,
+/// `close-authority`, `metadata-pointer[=ADDRESS]`, `group-pointer[=ADDRESS]`,
+/// `group-member-pointer[=ADDRESS]`, `transfer-hook=PROGRAM`,
+/// `scaled-ui-amount=MULTIPLIER`, `pausable` and
+/// `confidential-transfers[=auto]`.
+/// Authorities are always the loaded wallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MintExtension {
+    TransferFee { basis_points: u16, maximum_fee: String },
+    InterestRate { basis_points: i16 },
+    PermanentDelegate,
+    DefaultFrozen,
+    NonTransferable,
+    CloseAuthority,
+    MetadataPointer { address: Option<Pubkey> },
+    GroupPointer { address: Option<Pubkey> },
+    GroupMemberPointer { address: Option<Pubkey> },
+    TransferHook { program_id: Pubkey },
+    /// Kept as text so the enum stays `Eq`; validated when parsed.
+    ScaledUiAmount { multiplier: String },
+    Pausable,
+    /// With `auto_approve`, accounts can use confidential transfers as soon
+    /// as they are configured; otherwise the authority approves each one.
+    ConfidentialTransfers { auto_approve: bool },
+}
+
+impl FromStr for MintExtension {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let (name, argument) = match value.split_once('=') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (value, None),
+        };
+
+        let pubkey = |value: &str| Pubkey::from_str(value).map_err(|e| anyhow!("Invalid address in --extension {}: {}", name, e));
+
+        match (name, argument) {
+            ("transfer-fee", Some(argument)) => {
+                let (bps, max) = argument
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("Use transfer-fee=BPS:MAX_TOKENS"))?;
+                Ok(MintExtension::TransferFee {
+                    basis_points: bps.parse().map_err(|_| anyhow!("Invalid transfer fee basis points: {}", bps))?,
+                    maximum_fee: max.to_string(),
+                })
+            }
+            ("interest-rate", Some(bps)) => Ok(MintExtension::InterestRate {
+                basis_points: bps.parse().map_err(|_| anyhow!("Invalid interest rate basis points: {}", bps))?,
+            }),
+            ("permanent-delegate", None) => Ok(MintExtension::PermanentDelegate),
+            ("default-frozen", None) => Ok(MintExtension::DefaultFrozen),
+            ("non-transferable", None) => Ok(MintExtension::NonTransferable),
+            ("pausable", None) => Ok(MintExtension::Pausable),
+            ("confidential-transfers", None) => Ok(MintExtension::ConfidentialTransfers { auto_approve: false }),
+            ("confidential-transfers", Some("auto")) => Ok(MintExtension::ConfidentialTransfers { auto_approve: true }),
+            ("close-authority", None) => Ok(MintExtension::CloseAuthority),
+            ("metadata-pointer", address) => Ok(MintExtension::MetadataPointer {
+                address: address.map(pubkey).transpose()?,
+            }),
+            ("group-pointer", address) => Ok(MintExtension::GroupPointer {
+                address: address.map(pubkey).transpose()?,
+            }),
+            ("group-member-pointer", address) => Ok(MintExtension::GroupMemberPointer {
+                address: address.map(pubkey).transpose()?,
+            }),
+            ("transfer-hook", Some(program)) => Ok(MintExtension::TransferHook { program_id: pubkey(program)? }),
+            ("scaled-ui-amount", Some(multiplier)) => {
+                match multiplier.parse::<f64>() {
+                    Ok(value) if value.is_finite() && value > 0.0 => {}
+                    _ => return Err(anyhow!("Invalid UI multiplier '{}'; use a positive number such as 1.5", multiplier)),
+                }
+                Ok(MintExtension::ScaledUiAmount { multiplier: multiplier.to_string() })
+            }
+            _ => Err(anyhow!("Unknown or malformed --extension '{}'", value)),
+        }
+    }
+}
+
+impl MintExtension {
+    /// The extension's `ExtensionType`, or `None` for extensions newer than
+    /// the pinned spl-token-2022 release.
+    pub fn extension_type(&self) -> Option<ExtensionType> {
+        let extension_type = match self {
+            MintExtension::TransferFee { .. } => ExtensionType::TransferFeeConfig,
+            MintExtension::InterestRate { .. } => ExtensionType::InterestBearingConfig,
+            MintExtension::PermanentDelegate => ExtensionType::PermanentDelegate,
+            MintExtension::DefaultFrozen => ExtensionType::DefaultAccountState,
+            MintExtension::NonTransferable => ExtensionType::NonTransferable,
+            MintExtension::CloseAuthority => ExtensionType::MintCloseAuthority,
+            MintExtension::MetadataPointer { .. } => ExtensionType::MetadataPointer,
+            MintExtension::GroupPointer { .. } => ExtensionType::GroupPointer,
+            MintExtension::GroupMemberPointer { .. } => ExtensionType::GroupMemberPointer,
+            MintExtension::TransferHook { .. } => ExtensionType::TransferHook,
+            MintExtension::ConfidentialTransfers { .. } => ExtensionType::ConfidentialTransferMint,
+            MintExtension::ScaledUiAmount { .. } | MintExtension::Pausable => return None,
+        };
+        Some(extension_type)
+    }
+
+    /// The extension's TLV type number as stored in the mint.
+    pub fn type_id(&self) -> u16 {
+        match self {
+            MintExtension::ScaledUiAmount { .. } => tlv::SCALED_UI_AMOUNT,
+            MintExtension::Pausable => tlv::PAUSABLE,
+            _ => self.extension_type().map(|extension_type| extension_type as u16).unwrap_or_default(),
+        }
+    }
+
+    /// TLV bytes of an extension `ExtensionType` cannot size.
+    fn newer_tlv_len(&self) -> usize {
+        match self {
+            MintExtension::ScaledUiAmount { .. } => scaled_ui_amount::tlv_len(),
+            MintExtension::Pausable => pausable::tlv_len(),
+            _ => 0,
+        }
+    }
+
+    /// The extension's initialize instruction, which must run before InitializeMint2.
+    fn instruction(&self, mint: &Pubkey, authority: &Pubkey, decimals: u8) -> Result<Instruction> {
+        let program_id = &spl_token_2022::id();
+        let instruction = match self {
+            MintExtension::TransferFee { basis_points, maximum_fee } => transfer_fee::instruction::initialize_transfer_fee_config(
+                program_id,
+                mint,
+                Some(authority),
+                Some(authority),
+                *basis_points,
+                parse_amount(maximum_fee, decimals)?,
+            )?,
+            MintExtension::InterestRate { basis_points } => {
+                interest_bearing_mint::instruction::initialize(program_id, mint, Some(*authority), *basis_points)?
+            }
+            MintExtension::PermanentDelegate => initialize_permanent_delegate(program_id, mint, authority)?,
+            MintExtension::DefaultFrozen => {
+                default_account_state::instruction::initialize_default_account_state(program_id, mint, &AccountState::Frozen)?
+            }
+            MintExtension::NonTransferable => initialize_non_transferable_mint(program_id, mint)?,
+            MintExtension::CloseAuthority => initialize_mint_close_authority(program_id, mint, Some(authority))?,
+            MintExtension::MetadataPointer { address } => metadata_pointer::instruction::initialize(
+                program_id,
+                mint,
+                Some(*authority),
+                Some(address.unwrap_or(*mint)),
+            )?,
+            MintExtension::GroupPointer { address } => {
+                group_pointer::instruction::initialize(program_id, mint, Some(*authority), Some(address.unwrap_or(*mint)))?
+            }
+            MintExtension::GroupMemberPointer { address } => group_member_pointer::instruction::initialize(
+                program_id,
+                mint,
+                Some(*authority),
+                Some(address.unwrap_or(*mint)),
+            )?,
+            MintExtension::TransferHook { program_id: hook } => {
+                transfer_hook::instruction::initialize(program_id, mint, Some(*authority), Some(*hook))?
+            }
+            MintExtension::ScaledUiAmount { multiplier } => {
+                let multiplier = multiplier.parse().map_err(|_| anyhow!("Invalid UI multiplier: {}", multiplier))?;
+                scaled_ui_amount::initialize(mint, Some(authority), multiplier)?
+            }
+            MintExtension::Pausable => pausable::initialize(mint, authority),
+            MintExtension::ConfidentialTransfers { auto_approve } => {
+                confidential_transfer::instruction::initialize_mint(program_id, mint, Some(*authority), *auto_approve, None)?
+            }
+        };
+        Ok(instruction)
+    }
+}
+
+/// Name, symbol and URI stored in the mint itself (Token-2022 TokenMetadata).
+#[derive(Debug, Clone)]
+pub struct EmbeddedMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+impl EmbeddedMetadata {
+    /// Bytes the TokenMetadata TLV entry adds to the mint: the 4-byte TLV
+    /// header, update authority, mint, three length-prefixed strings and an
+    /// empty additional-metadata list.
+    fn tlv_len(&self) -> usize {
+        4 + 32 + 32 + (4 + self.name.len()) + (4 + self.symbol.len()) + (4 + self.uri.len()) + 4
+    }
+}
+
+/// Token-2022 group data stored in the mint itself: either a group other
+/// mints can join, or membership of an existing group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupRole {
+    Group { max_size: u32 },
+    Member { group: Pubkey },
+}
+
+impl GroupRole {
+    /// Bytes the TokenGroup or TokenGroupMember TLV entry adds to the mint,
+    /// including the 4-byte TLV header.
+    fn tlv_len(&self) -> usize {
+        4 + match self {
+            GroupRole::Group { .. } => size_of::<TokenGroup>(),
+            GroupRole::Member { .. } => size_of::<TokenGroupMember>(),
+        }
+    }
+}
+
+/// Parameters of a mint to create.
+#[derive(Debug, Clone)]
+pub struct MintConfig {
+    pub decimals: u8,
+    pub token_2022: bool,
+    pub extensions: Vec<MintExtension>,
+    pub metadata: Option<EmbeddedMetadata>,
+    pub group: Option<GroupRole>,
+}
+
+impl MintConfig {
+    pub fn new(decimals: u8, token_2022: bool, extensions: &[String]) -> Result<Self> {
+        let extensions = extensions
+            .iter()
+            .map(|extension| extension.parse())
+            .collect::<Result<Vec<MintExtension>>>()?;
+
+        if !extensions.is_empty() && !token_2022 {
+            return Err(anyhow!("--extension requires --token-2022"));
+        }
+
+        Ok(Self { decimals, token_2022, extensions, metadata: None, group: None })
+    }
+
+    /// Store metadata in the mint, pointing the MetadataPointer at the mint itself.
+    pub fn with_metadata(mut self, name: &str, symbol: &str, uri: &str) -> Result<Self> {
+        if !self.token_2022 {
+            return Err(anyhow!("Embedded metadata requires a Token-2022 mint"));
+        }
+        self.extensions.retain(|extension| !matches!(extension, MintExtension::MetadataPointer { .. }));
+        self.extensions.push(MintExtension::MetadataPointer { address: None });
+        self.metadata = Some(EmbeddedMetadata {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri: uri.to_string(),
+        });
+        Ok(self)
+    }
+
+    /// Make the mint a group of at most `max_size` members, pointing the
+    /// GroupPointer at the mint itself.
+    pub fn with_group(mut self, max_size: u32) -> Result<Self> {
+        if self.group.is_some() {
+            return Err(anyhow!("A mint cannot be both a group and a group member"));
+        }
+        if !self.token_2022 {
+            return Err(anyhow!("Token groups require a Token-2022 mint"));
+        }
+        self.extensions.retain(|extension| !matches!(extension, MintExtension::GroupPointer { .. }));
+        self.extensions.push(MintExtension::GroupPointer { address: None });
+        self.group = Some(GroupRole::Group { max_size });
+        Ok(self)
+    }
+
+    /// Add the mint to `group`, pointing the GroupMemberPointer at the mint
+    /// itself. The loaded wallet must be the group's update authority.
+    pub fn with_group_member(mut self, group: Pubkey) -> Result<Self> {
+        if self.group.is_some() {
+            return Err(anyhow!("A mint cannot be both a group and a group member"));
+        }
+        if !self.token_2022 {
+            return Err(anyhow!("Token groups require a Token-2022 mint"));
+        }
+        self.extensions.retain(|extension| !matches!(extension, MintExtension::GroupMemberPointer { .. }));
+        self.extensions.push(MintExtension::GroupMemberPointer { address: None });
+        self.group = Some(GroupRole::Member { group });
+        Ok(self)
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        if self.token_2022 { spl_token_2022::id() } else { spl_token::id() }
+    }
+}
+
+/// Parse `--effective-at` as a unix timestamp or an RFC 3339 time.
+fn parse_timestamp(value: &str) -> Result<i64> {
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return Ok(timestamp);
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.timestamp())
+        .map_err(|_| anyhow!("Invalid time '{}'; use a unix timestamp or e.g. 2025-01-31T00:00:00Z", value))
+}
+
+/// Read the mint keypair from `path`, or generate one (ground for
+/// `starts_with` when given) and save it there before anything is sent.
+pub fn load_or_generate_mint_keypair(path: &str, starts_with: Option<&str>, ignore_case: bool) -> Result<Keypair> {
+    if Path::new(path).exists() {
+        if starts_with.is_some() {
+            return Err(anyhow!(
+                "{} already exists; remove it or choose another --mint-keypair to grind a new address",
+                path
+            ));
+        }
+        info!("Using existing mint keypair from {}", path);
+        return read_keypair(path);
+    }
+
+    let keypair = match starts_with {
+        Some(prefix) => grind(&VanityPattern::new(prefix, ignore_case)?),
+        None => Keypair::new(),
+    };
+    write_keypair(path, &keypair)?;
+    info!("Mint keypair saved to {}", path);
+    Ok(keypair)
+}
+
+impl TokenOperations {
+    /// Create a new mint with the loaded wallet as mint and freeze authority.
+    pub fn create_mint(
+        &self,
+        config: &MintConfig,
+        mint_keypair_path: &str,
+        starts_with: Option<&str>,
+        ignore_case: bool,
+    ) -> Result<()> {
+        let mint_keypair = load_or_generate_mint_keypair(mint_keypair_path, starts_with, ignore_case)?;
+
+        match self.create_mint_account(&mint_keypair, config) {
+            Ok(signature) => {
+                info!("Mint created! Address: {}", mint_keypair.pubkey());
+                outputs::record("mint", &mint_keypair.pubkey().to_string());
+                info!("Signature: {}", signature);
+                Ok(())
+            }
+            Err(e) => Err(anyhow!("Failed to create mint: {}", e)),
+        }
+    }
+
+    /// Allocate and initialize the mint account, including any extensions.
+    pub fn create_mint_account(&self, mint_keypair: &Keypair, config: &MintConfig) -> Result<Signature> {
+        let mint_pubkey = mint_keypair.pubkey();
+        let program_id = config.program_id();
+        let authority = self.wallet.pubkey();
+
+        let extension_types: Vec<ExtensionType> = config.extensions.iter().filter_map(MintExtension::extension_type).collect();
+        let newer_len: usize = config.extensions.iter().map(MintExtension::newer_tlv_len).sum();
+        let space = if config.extensions.is_empty() {
+            Mint::LEN
+        } else {
+            let known_len = if extension_types.is_empty() {
+                Account::LEN + 1
+            } else {
+                ExtensionType::try_calculate_account_len::<Mint>(&extension_types)
+                    .map_err(|e| anyhow!("Failed to size mint account: {}", e))?
+            };
+            // Token-2022 pads accounts that would be mistaken for a multisig.
+            match known_len + newer_len {
+                len if len == Multisig::LEN => len + size_of::<ExtensionType>(),
+                len => len,
+            }
+        };
+
+        info!(
+            "Creating {} mint {} with {} decimals{}",
+            if config.token_2022 { "Token-2022" } else { "SPL Token" },
+            mint_pubkey,
+            config.decimals,
+            if config.extensions.is_empty() {
+                String::new()
+            } else {
+                let names: Vec<String> = config.extensions.iter().map(|extension| tlv::extension_name(extension.type_id())).collect();
+                format!(" and extensions [{}]", names.join(", "))
+            }
+        );
+
+        // TokenMetadata and the group extensions reallocate the mint when
+        // initialized, so fund that space up front but allocate only the rest.
+        let funded_space = space
+            + config.metadata.as_ref().map(EmbeddedMetadata::tlv_len).unwrap_or(0)
+            + config.group.as_ref().map(GroupRole::tlv_len).unwrap_or(0);
+        let rent = self.client.get_minimum_balance_for_rent_exemption(funded_space)
+            .map_err(|e| anyhow!("Failed to get rent exemption: {}", e))?;
+
+        let mut instructions = vec![system_instruction::create_account(
+            &authority,
+            &mint_pubkey,
+            rent,
+            space as u64,
+            &program_id,
+        )];
+        for extension in &config.extensions {
+            instructions.push(extension.instruction(&mint_pubkey, &authority, config.decimals)?);
+        }
+        instructions.push(initialize_mint2(
+            &program_id,
+            &mint_pubkey,
+            &authority,
+            Some(&authority),
+            config.decimals,
+        )?);
+        if let Some(metadata) = &config.metadata {
+            instructions.push(spl_token_metadata_interface::instruction::initialize(
+                &program_id,
+                &mint_pubkey,
+                &authority,
+                &mint_pubkey,
+                &authority,
+                metadata.name.clone(),
+                metadata.symbol.clone(),
+                metadata.uri.clone(),
+            ));
+        }
+        match &config.group {
+            Some(GroupRole::Group { max_size }) => {
+                instructions.push(spl_token_group_interface::instruction::initialize_group(
+                    &program_id,
+                    &mint_pubkey,
+                    &mint_pubkey,
+                    &authority,
+                    Some(authority),
+                    *max_size,
+                ));
+            }
+            Some(GroupRole::Member { group }) => {
+                instructions.push(spl_token_group_interface::instruction::initialize_member(
+                    &program_id,
+                    &mint_pubkey,
+                    &mint_pubkey,
+                    &authority,
+                    group,
+                    &authority,
+                ));
+            }
+            None => {}
+        }
+
+        self.send_instructions_with_signers(instructions, CREATE_MINT_UNITS, &[mint_keypair])
+    }
+
+    /// Change the ScaledUiAmount multiplier of a mint, from `effective_at`
+    /// (a unix timestamp or RFC 3339 time) or immediately.
+    pub fn update_ui_multiplier(&self, mint_address: &str, multiplier: &str, effective_at: Option<&str>) -> Result<()> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let multiplier: f64 = multiplier.parse().map_err(|_| anyhow!("Invalid UI multiplier: {}", multiplier))?;
+        let now = Utc::now().timestamp();
+        let effective_at = effective_at.map(parse_timestamp).transpose()?.unwrap_or(now);
+
+        let mint_info = self.fetch_mint_info(&mint)?;
+        let config = mint_info
+            .scaled_ui_amount()?
+            .ok_or_else(|| anyhow!("Mint {} does not have the ScaledUiAmount extension", mint))?;
+        if config.authority != Some(self.wallet.pubkey()) {
+            return Err(anyhow!("Loaded wallet is not the UI multiplier authority of this mint"));
+        }
+
+        info!(
+            "Changing UI multiplier of {} from {} to {}{}",
+            mint,
+            config.multiplier_at(now),
+            multiplier,
+            if effective_at > now { format!(" at {}", effective_at) } else { String::new() }
+        );
+        let instruction = scaled_ui_amount::update_multiplier(&mint, &self.wallet.pubkey(), multiplier, effective_at)?;
+        let signature = self
+            .send_instructions(vec![instruction], UPDATE_MULTIPLIER_UNITS)
+            .map_err(|e| anyhow!("Failed to update UI multiplier: {}", e))?;
+        info!("UI multiplier updated! Signature: {}", signature);
+        Ok(())
+    }
+}
+
+impl TokenOperations {
+    /// Mint `amount` new tokens into `to`'s ATA, creating it if needed. The
+    /// loaded wallet must be the mint authority.
+    pub fn mint_tokens(
+        &self,
+        mint_address: &str,
+        to: &str,
+        amount: &str,
+        idempotency: Option<&IdempotencyKey>,
+        assume_yes: bool,
+    ) -> Result<()> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let recipient = Pubkey::from_str(to).map_err(|e| anyhow!("Invalid destination address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint)?;
+        let base = mint_info.base()?;
+        if Option::<Pubkey>::from(base.mint_authority) != Some(self.wallet.pubkey()) {
+            return Err(anyhow!("Loaded wallet is not the mint authority of {}", mint));
+        }
+        let amount = parse_amount(amount, base.decimals)?;
+        if amount == 0 {
+            return Err(anyhow!("Amount must be greater than zero"));
+        }
+
+        let destination = mint_info.associated_token_address(&recipient);
+        let request = format!("mint-to {} of {} to {}", format_amount(amount, base.decimals), mint, recipient);
+        confirm(
+            &[
+                format!("Mint {} new tokens of {}", format_amount(amount, base.decimals), mint),
+                format!("To:     {} ({})", recipient, destination),
+                format!("Supply: {} -> {}", format_amount(base.supply, base.decimals), format_amount(base.supply.saturating_add(amount), base.decimals)),
+            ],
+            assume_yes,
+        )?;
+
+        let mut instructions = Vec::new();
+        let mut units = MINT_TO_UNITS;
+        if let Some(key) = idempotency {
+            instructions.push(self.begin_idempotent(key, &request)?);
+            units += IDEMPOTENCY_MEMO_UNITS;
+        }
+        instructions.push(create_associated_token_account_idempotent(&self.wallet.pubkey(), &recipient, &mint, &mint_info.program_id));
+        instructions.push(mint_to_checked(&mint_info.program_id, &mint, &destination, &self.wallet.pubkey(), &[], amount, base.decimals)?);
+        let signature = self
+            .send_instructions(instructions, units)
+            .map_err(|e| anyhow!("Failed to mint tokens: {}", e))?;
+        info!("Minted {} to {}! Signature: {}", format_amount(amount, base.decimals), destination, signature);
+        if let Some(key) = idempotency {
+            key.complete(&request, &signature)?;
+        }
+        outputs::record("signature", &signature.to_string());
+        Ok(())
+    }
+}
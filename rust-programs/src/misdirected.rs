@@ -0,0 +1,224 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use spl_associated_token_account::{get_associated_token_address_with_program_id, instruction::recover_nested};
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    consolidate::OwnedTokenAccount,
+    holders::HolderBalance,
+    output::{write_output, OutputFormat},
+    prompt::confirm,
+    token_program::is_token_program,
+    TokenOperations,
+};
+
+/// Accounts fetched per getMultipleAccounts call.
+const FETCH_CHUNK: usize = 100;
+
+/// Estimated compute units for one RecoverNested instruction.
+const RECOVER_NESTED_UNITS: u32 = 50_000;
+
+/// A token account whose owner cannot (easily) move the tokens.
+#[derive(Debug, Clone, Serialize)]
+pub struct MisdirectedAccount {
+    pub token_account: String,
+    pub mint: String,
+    pub owner: String,
+    pub amount: u64,
+    pub reason: String,
+    pub recoverable: bool,
+    pub status: String,
+    pub signature: Option<String>,
+}
+
+/// A nested ATA the loaded wallet can recover with RecoverNested.
+struct NestedRecovery {
+    result_index: usize,
+    owner_mint: Pubkey,
+    nested_mint: Pubkey,
+    program_id: Pubkey,
+}
+
+impl TokenOperations {
+    /// Find tokens sent to the mint address, to a program, or to one of the
+    /// wallet's own token accounts (a nested ATA). Nested ATAs of the loaded
+    /// wallet are recovered with RecoverNested when `recover` is set.
+    pub fn find_misdirected(
+        &self,
+        mint_address: Option<&str>,
+        recover: bool,
+        assume_yes: bool,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let mut findings: Vec<MisdirectedAccount> = Vec::new();
+        let mut recoveries = Vec::new();
+
+        let mint_pubkey = mint_address
+            .map(|mint| Pubkey::from_str(mint).map_err(|e| anyhow!("Invalid mint address: {}", e)))
+            .transpose()?;
+
+        let wallet = self.wallet.pubkey();
+        for owner_account in self.owned_token_accounts(&wallet, None)? {
+            for nested in self.owned_token_accounts(&owner_account.address, mint_pubkey.as_ref())? {
+                let recovery = nested_recovery(&wallet, &owner_account, &nested);
+                let recoverable = recovery.is_some();
+                if let Some(mut recovery) = recovery {
+                    recovery.result_index = findings.len();
+                    recoveries.push(recovery);
+                }
+
+                findings.push(MisdirectedAccount {
+                    token_account: nested.address.to_string(),
+                    mint: nested.account.mint.to_string(),
+                    owner: owner_account.address.to_string(),
+                    amount: nested.account.amount,
+                    reason: format!("nested in your token account {}", owner_account.address),
+                    recoverable,
+                    status: "found".to_string(),
+                    signature: None,
+                });
+            }
+        }
+
+        if let Some(mint_pubkey) = mint_pubkey {
+            for finding in self.misdirected_holders(&mint_pubkey)? {
+                if !findings.iter().any(|known| known.token_account == finding.token_account) {
+                    findings.push(finding);
+                }
+            }
+        }
+
+        if findings.is_empty() {
+            info!("No misdirected tokens found");
+            return Ok(());
+        }
+
+        warn!("Found {} misdirected token account(s):", findings.len());
+        for finding in &findings {
+            warn!(
+                "  {} holds {} of {}: {}{}",
+                finding.token_account,
+                finding.amount,
+                finding.mint,
+                finding.reason,
+                if finding.recoverable { " (recoverable)" } else { "" }
+            );
+        }
+
+        if recover && !recoveries.is_empty() {
+            let summary: Vec<String> = std::iter::once(format!("Recover {} nested token account(s) to your ATAs:", recoveries.len()))
+                .chain(recoveries.iter().map(|r| {
+                    let finding = &findings[r.result_index];
+                    format!("  {} ({} of {})", finding.token_account, finding.amount, finding.mint)
+                }))
+                .collect();
+            confirm(&summary, assume_yes)?;
+
+            // batcher item index -> finding index
+            let mut queued: HashMap<usize, usize> = HashMap::new();
+            let mut batcher = self.batcher();
+            for recovery in &recoveries {
+                let instruction = recover_nested(&wallet, &recovery.owner_mint, &recovery.nested_mint, &recovery.program_id);
+                let item = batcher.push(vec![instruction], RECOVER_NESTED_UNITS)?;
+                queued.insert(item, recovery.result_index);
+            }
+
+            for batch in batcher.finish() {
+                for item in &batch.items {
+                    let Some(index) = queued.get(item) else {
+                        continue;
+                    };
+                    let finding = &mut findings[*index];
+                    match &batch.signature {
+                        Ok(signature) => {
+                            finding.status = "recovered".to_string();
+                            finding.signature = Some(signature.to_string());
+                        }
+                        Err(e) => finding.status = format!("failed: {}", e),
+                    }
+                }
+            }
+
+            let recovered = findings.iter().filter(|f| f.status == "recovered").count();
+            info!("Recovered {} of {} nested account(s)", recovered, recoveries.len());
+        } else if !recoveries.is_empty() {
+            info!("Re-run with --recover to move {} nested account(s) back to your ATAs", recoveries.len());
+        }
+
+        write_output(output, &findings, &findings)?;
+
+        if findings.iter().any(|f| f.status.starts_with("failed")) {
+            return Err(anyhow!("Some nested accounts could not be recovered"));
+        }
+        Ok(())
+    }
+
+    /// Holders of `mint` whose owner is the mint itself, a program, or another token account.
+    fn misdirected_holders(&self, mint: &Pubkey) -> Result<Vec<MisdirectedAccount>> {
+        let snapshot = self.fetch_holder_snapshot(mint)?;
+        let mut findings = Vec::new();
+
+        // Wallets are on-curve keys; only check the off-curve owners (PDAs,
+        // programs and ATAs) plus the mint address itself.
+        let suspects: Vec<(&HolderBalance, Pubkey)> = snapshot
+            .holders
+            .iter()
+            .filter_map(|holder| {
+                let owner = Pubkey::from_str(&holder.owner).ok()?;
+                (owner == *mint || !owner.is_on_curve()).then_some((holder, owner))
+            })
+            .collect();
+
+        for chunk in suspects.chunks(FETCH_CHUNK) {
+            let owners: Vec<Pubkey> = chunk.iter().map(|(_, owner)| *owner).collect();
+            let accounts = self.client.get_multiple_accounts(&owners)
+                .map_err(|e| anyhow!("Failed to get owner accounts: {}", e))?;
+
+            for ((holder, owner), account) in chunk.iter().zip(accounts) {
+                let reason = if owner == mint {
+                    "sent to the mint address".to_string()
+                } else {
+                    match account {
+                        Some(account) if account.executable => format!("sent to program {}", owner),
+                        Some(account) if is_token_program(&account.owner) => format!("nested in token account {}", owner),
+                        _ => continue,
+                    }
+                };
+
+                findings.push(MisdirectedAccount {
+                    token_account: holder.account.clone(),
+                    mint: mint.to_string(),
+                    owner: holder.owner.clone(),
+                    amount: holder.amount,
+                    reason,
+                    recoverable: false,
+                    status: "found".to_string(),
+                    signature: None,
+                });
+            }
+        }
+
+        Ok(findings)
+    }
+}
+
+/// RecoverNested only handles canonical ATAs nested under a canonical ATA of
+/// the same token program.
+fn nested_recovery(wallet: &Pubkey, owner_account: &OwnedTokenAccount, nested: &OwnedTokenAccount) -> Option<NestedRecovery> {
+    let program_id = owner_account.program_id;
+    let owner_mint = owner_account.account.mint;
+    let nested_mint = nested.account.mint;
+
+    let canonical = nested.program_id == program_id
+        && owner_account.address == get_associated_token_address_with_program_id(wallet, &owner_mint, &program_id)
+        && nested.address == get_associated_token_address_with_program_id(&owner_account.address, &nested_mint, &program_id);
+
+    canonical.then_some(NestedRecovery {
+        result_index: 0,
+        owner_mint,
+        nested_mint,
+        program_id,
+    })
+}
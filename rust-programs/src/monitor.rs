@@ -0,0 +1,213 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::{error, info, warn};
+use mpl_token_metadata::accounts::Metadata;
+use serde::Serialize;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+use std::{collections::HashMap, str::FromStr, thread, time::Duration};
+
+use crate::{geyser::GeyserConfig, input::read_addresses, webhooks::Webhooks, TokenOperations};
+
+/// Mints (plus their metadata accounts) fetched per getMultipleAccounts call.
+const MINTS_PER_FETCH: usize = 50;
+
+/// The authorities watched for each mint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Authorities {
+    exists: bool,
+    mint_authority: Option<Pubkey>,
+    freeze_authority: Option<Pubkey>,
+    update_authority: Option<Pubkey>,
+}
+
+/// A detected authority change, logged and posted to the webhooks.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorityAlert {
+    pub mint: String,
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    pub slot: u64,
+    pub detected_at: String,
+}
+
+impl TokenOperations {
+    /// Poll a set of mints every `interval` seconds and alert when the mint,
+    /// freeze or metadata update authority changes. With `geyser`, account
+    /// updates are streamed instead of polled. Runs until interrupted.
+    pub fn monitor_authorities(
+        &self,
+        mint_address: Option<&str>,
+        addresses_file: Option<&str>,
+        interval: u64,
+        webhooks: &Webhooks,
+        geyser: Option<&GeyserConfig>,
+    ) -> Result<()> {
+        let mut mints = match addresses_file {
+            Some(path) => read_addresses(path)?,
+            None => Vec::new(),
+        };
+        if let Some(mint) = mint_address {
+            mints.push(Pubkey::from_str(mint).map_err(|e| anyhow!("Invalid mint address: {}", e))?);
+        }
+        mints.sort();
+        mints.dedup();
+        if mints.is_empty() {
+            return Err(anyhow!("Nothing to monitor; pass --mint-address or --addresses-file"));
+        }
+
+        let alerts = Alerts::new(webhooks);
+
+        if let Some(geyser) = geyser {
+            return self.monitor_authorities_geyser(&mints, geyser, &alerts);
+        }
+
+        let mut known = self.fetch_authorities(&mints)?;
+        info!("Monitoring authorities of {} mint(s) every {}s", mints.len(), interval);
+        log_authorities(&known);
+
+        loop {
+            thread::sleep(Duration::from_secs(interval));
+
+            let current = match self.fetch_authorities(&mints) {
+                Ok(current) => current,
+                Err(e) => {
+                    warn!("Poll failed, retrying: {}", e);
+                    continue;
+                }
+            };
+            let slot = self.client.get_slot().unwrap_or_default();
+
+            for mint in &mints {
+                let (Some(old), Some(new)) = (known.get(mint), current.get(mint)) else {
+                    continue;
+                };
+                alerts.send_all(compare(mint, old, new, slot));
+            }
+
+            known = current;
+        }
+    }
+
+    fn fetch_authorities(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, Authorities>> {
+        let mut authorities = HashMap::new();
+
+        for chunk in mints.chunks(MINTS_PER_FETCH) {
+            let mut addresses = chunk.to_vec();
+            addresses.extend(chunk.iter().map(|mint| Metadata::find_pda(mint).0));
+
+            let accounts = self.client.get_multiple_accounts(&addresses)
+                .map_err(|e| anyhow!("Failed to get mint accounts: {}", e))?;
+            let (mint_accounts, metadata_accounts) = accounts.split_at(chunk.len());
+
+            for ((mint, mint_account), metadata_account) in chunk.iter().zip(mint_accounts).zip(metadata_accounts) {
+                authorities.insert(*mint, parse_authorities(mint_account.as_ref(), metadata_account.as_ref()));
+            }
+        }
+
+        Ok(authorities)
+    }
+}
+
+/// Logs every alert and posts it to the webhooks, if any.
+pub(crate) struct Alerts<'a> {
+    webhooks: &'a Webhooks,
+}
+
+impl<'a> Alerts<'a> {
+    fn new(webhooks: &'a Webhooks) -> Self {
+        Self { webhooks }
+    }
+
+    pub(crate) fn send_all(&self, alerts: Vec<AuthorityAlert>) {
+        for alert in alerts {
+            error!(
+                "AUTHORITY CHANGE on {}: {} {} -> {} (slot {})",
+                alert.mint,
+                alert.field,
+                alert.old.as_deref().unwrap_or("None"),
+                alert.new.as_deref().unwrap_or("None"),
+                alert.slot
+            );
+            self.webhooks.deliver(&alert);
+        }
+    }
+}
+
+pub(crate) fn log_authorities(known: &HashMap<Pubkey, Authorities>) {
+    for (mint, authorities) in known {
+        info!(
+            "  {}: mint {}, freeze {}, update {}",
+            mint,
+            show(authorities.mint_authority),
+            show(authorities.freeze_authority),
+            show(authorities.update_authority)
+        );
+    }
+}
+
+pub(crate) fn parse_authorities(mint_account: Option<&Account>, metadata_account: Option<&Account>) -> Authorities {
+    let mut authorities = Authorities {
+        exists: mint_account.is_some(),
+        mint_authority: None,
+        freeze_authority: None,
+        update_authority: None,
+    };
+
+    let Some(state) = mint_account.and_then(|account| StateWithExtensions::<Mint>::unpack(&account.data).ok()) else {
+        return authorities;
+    };
+    authorities.mint_authority = state.base.mint_authority.into();
+    authorities.freeze_authority = state.base.freeze_authority.into();
+
+    // Token-2022 metadata lives in the mint; otherwise use the Metaplex account.
+    authorities.update_authority = match state.get_variable_len_extension::<TokenMetadata>() {
+        Ok(metadata) => Option::<Pubkey>::from(metadata.update_authority),
+        Err(_) => metadata_account
+            .filter(|account| account.owner == mpl_token_metadata::ID)
+            .and_then(|account| Metadata::from_bytes(&account.data).ok())
+            .map(|metadata| metadata.update_authority),
+    };
+
+    authorities
+}
+
+pub(crate) fn compare(mint: &Pubkey, old: &Authorities, new: &Authorities, slot: u64) -> Vec<AuthorityAlert> {
+    let detected_at = Utc::now().to_rfc3339();
+    let alert = |field: &str, old: Option<String>, new: Option<String>| AuthorityAlert {
+        mint: mint.to_string(),
+        field: field.to_string(),
+        old,
+        new,
+        slot,
+        detected_at: detected_at.clone(),
+    };
+
+    let mut alerts = Vec::new();
+    if old.exists != new.exists {
+        alerts.push(alert(
+            "mint_account",
+            Some(if old.exists { "exists" } else { "missing" }.to_string()),
+            Some(if new.exists { "exists" } else { "missing" }.to_string()),
+        ));
+    }
+    for (field, old, new) in [
+        ("mint_authority", old.mint_authority, new.mint_authority),
+        ("freeze_authority", old.freeze_authority, new.freeze_authority),
+        ("update_authority", old.update_authority, new.update_authority),
+    ] {
+        if old != new {
+            alerts.push(alert(field, old.map(|key| key.to_string()), new.map(|key| key.to_string())));
+        }
+    }
+    alerts
+}
+
+fn show(key: Option<Pubkey>) -> String {
+    key.map(|key| key.to_string()).unwrap_or_else(|| "None".to_string())
+}
@@ -0,0 +1,119 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use clap::ArgMatches;
+use log::{error, info};
+use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use std::{collections::HashSet, str::FromStr};
+use token_ops_core::rpc;
+
+use crate::{
+    input::read_addresses,
+    output::{write_output, OutputFormat},
+    TokenOperations,
+};
+
+/// One mint's row in a multi-mint verify, balance, supply or analyze run.
+/// Fields the operation does not look at are left empty.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MintRow {
+    pub mint: String,
+    pub program: Option<String>,
+    pub supply: Option<String>,
+    pub decimals: Option<u8>,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    pub owner: Option<String>,
+    pub balance: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Every `--mint-address` given plus the mints in `--mints-file`, in order
+/// and without duplicates.
+pub fn mint_list(matches: &ArgMatches, mints_file: Option<&String>) -> Result<Vec<Pubkey>> {
+    let mut mints = Vec::new();
+    if let Some(values) = matches.get_many::<String>("mint-address") {
+        for value in values {
+            mints.push(Pubkey::from_str(value).map_err(|e| anyhow!("Invalid mint address {}: {}", value, e))?);
+        }
+    }
+    if let Some(path) = mints_file {
+        mints.extend(read_addresses(path)?);
+    }
+
+    let mut seen = HashSet::new();
+    mints.retain(|mint| seen.insert(*mint));
+    Ok(mints)
+}
+
+impl TokenOperations {
+    fn mint_row(&self, operation: &str, mint: &Pubkey, owner: &Pubkey) -> Result<MintRow> {
+        let mint_address = mint.to_string();
+        let mut row = MintRow { mint: mint_address.clone(), ..MintRow::default() };
+
+        match operation {
+            "supply" => {
+                let supply = rpc::fetch_supply(&self.client, mint)?;
+                info!("Supply: {} (raw {}, {} decimals)", supply.ui_amount, supply.amount, supply.decimals);
+                row.supply = Some(supply.ui_amount);
+                row.decimals = Some(supply.decimals);
+                return Ok(row);
+            }
+            "balance" => {}
+            "verify" => self.verify_token(&mint_address)?,
+            "analyze" => self.analyze_token(&mint_address)?,
+            _ => return Err(anyhow!("{} does not support multiple mints", operation)),
+        }
+
+        let mint_info = self.fetch_mint_info(mint)?;
+        let summary = mint_info.summary()?;
+        row.program = Some(summary.program);
+        row.supply = Some(mint_info.ui_amount(summary.supply, Utc::now().timestamp())?);
+        row.decimals = Some(summary.decimals);
+        row.mint_authority = summary.mint_authority;
+        row.freeze_authority = summary.freeze_authority;
+
+        if operation != "verify" {
+            let balance = rpc::fetch_balance(&self.client, mint, owner)?;
+            info!("Balance of {}: {}", owner, balance.ui_amount);
+            row.owner = Some(balance.owner);
+            row.balance = Some(balance.ui_amount);
+        }
+        Ok(row)
+    }
+
+    /// Run a read operation for every mint and collect one row per mint. A
+    /// failing mint is recorded and the rest still run; the command fails at
+    /// the end if any did.
+    pub fn run_for_mints(
+        &self,
+        operation: &str,
+        mints: &[Pubkey],
+        owner: Option<&str>,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let owner = match owner {
+            Some(owner) => Pubkey::from_str(owner).map_err(|e| anyhow!("Invalid owner address: {}", e))?,
+            None => self.wallet.pubkey(),
+        };
+
+        let mut rows = Vec::with_capacity(mints.len());
+        for (i, mint) in mints.iter().enumerate() {
+            info!("[{}/{}] {} {}", i + 1, mints.len(), operation, mint);
+            let row = self.mint_row(operation, mint, &owner).unwrap_or_else(|e| {
+                error!("{} failed for {}: {}", operation, mint, e);
+                MintRow { mint: mint.to_string(), error: Some(e.to_string()), ..MintRow::default() }
+            });
+            rows.push(row);
+        }
+
+        let failed = rows.iter().filter(|row| row.error.is_some()).count();
+        info!("{} of {} mint(s) succeeded", rows.len() - failed, rows.len());
+        write_output(output, &rows, &rows)?;
+
+        if failed > 0 {
+            return Err(anyhow!("{} of {} mint(s) failed", failed, rows.len()));
+        }
+        Ok(())
+    }
+}
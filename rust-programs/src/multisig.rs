@@ -0,0 +1,179 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use solana_sdk::{
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use spl_token_2022::{
+    instruction::{initialize_multisig2, set_authority, AuthorityType},
+    state::Multisig,
+};
+use std::{collections::HashSet, str::FromStr};
+
+use crate::{outputs, prompt::confirm_typed, token_program::MintInfo, TokenOperations};
+
+/// Estimated compute units for CreateAccount plus InitializeMultisig2.
+const CREATE_MULTISIG_UNITS: u32 = 20_000;
+
+/// Estimated compute units for the SetAuthority instructions of a handover.
+const HANDOVER_UNITS: u32 = 10_000;
+
+/// Signer limit of an SPL Token multisig.
+const MAX_SIGNERS: usize = 11;
+
+/// Parse and check an M-of-N signer set.
+fn signer_set(signers: &[String], threshold: u8) -> Result<Vec<Pubkey>> {
+    let signers = signers
+        .iter()
+        .map(|signer| Pubkey::from_str(signer).map_err(|e| anyhow!("Invalid multisig signer {}: {}", signer, e)))
+        .collect::<Result<Vec<Pubkey>>>()?;
+
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = signers.iter().find(|signer| !seen.insert(**signer)) {
+        return Err(anyhow!("Multisig signer {} is listed twice", duplicate));
+    }
+    if signers.is_empty() || signers.len() > MAX_SIGNERS {
+        return Err(anyhow!("A multisig needs 1 to {} signers, got {}", MAX_SIGNERS, signers.len()));
+    }
+    if threshold == 0 || threshold as usize > signers.len() {
+        return Err(anyhow!("--threshold must be between 1 and {} for {} signer(s)", signers.len(), signers.len()));
+    }
+    Ok(signers)
+}
+
+impl TokenOperations {
+    /// Create an M-of-N token multisig and, when `mint_address` is given,
+    /// hand the wallet's mint and freeze authorities over to it, checking
+    /// the on-chain state after each step. Without a mint the multisig is
+    /// created for SPL Token, or Token-2022 with `token_2022`.
+    pub fn create_multisig(
+        &self,
+        signers: &[String],
+        threshold: u8,
+        mint_address: Option<&str>,
+        token_2022: bool,
+        assume_yes: bool,
+    ) -> Result<()> {
+        let signers = signer_set(signers, threshold)?;
+        let mint_info = mint_address
+            .map(|mint| {
+                let mint = Pubkey::from_str(mint).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+                self.fetch_mint_info(&mint)
+            })
+            .transpose()?;
+        let program_id = match &mint_info {
+            Some(mint_info) => mint_info.program_id,
+            None if token_2022 => spl_token_2022::id(),
+            None => spl_token::id(),
+        };
+
+        let multisig = Keypair::new();
+        let rent = self.client.get_minimum_balance_for_rent_exemption(Multisig::LEN)
+            .map_err(|e| anyhow!("Failed to get rent exemption: {}", e))?;
+        let signer_refs: Vec<&Pubkey> = signers.iter().collect();
+        let instructions = vec![
+            system_instruction::create_account(
+                &self.wallet.pubkey(),
+                &multisig.pubkey(),
+                rent,
+                Multisig::LEN as u64,
+                &program_id,
+            ),
+            initialize_multisig2(&program_id, &multisig.pubkey(), &signer_refs, threshold)?,
+        ];
+        let signature = self
+            .send_instructions_with_signers(instructions, CREATE_MULTISIG_UNITS, &[&multisig])
+            .map_err(|e| anyhow!("Failed to create multisig: {}", e))?;
+        info!("Multisig created! Address: {} ({}-of-{}) Signature: {}", multisig.pubkey(), threshold, signers.len(), signature);
+        outputs::record("multisig", &multisig.pubkey().to_string());
+
+        self.check_multisig(&multisig.pubkey(), &signers, threshold)?;
+        info!("Verified on-chain: {}-of-{} with the expected signers", threshold, signers.len());
+
+        match mint_info {
+            Some(mint_info) => self.hand_over_authorities(&mint_info, &multisig.pubkey(), threshold, signers.len(), assume_yes),
+            None => Ok(()),
+        }
+    }
+
+    fn check_multisig(&self, address: &Pubkey, signers: &[Pubkey], threshold: u8) -> Result<()> {
+        let account = self.client.get_account(address)
+            .map_err(|e| anyhow!("Failed to get multisig {}: {}", address, e))?;
+        let state = Multisig::unpack(&account.data).map_err(|e| anyhow!("Failed to parse multisig: {}", e))?;
+
+        let stored: HashSet<&Pubkey> = state.signers[..state.n as usize].iter().collect();
+        if state.m != threshold || state.n as usize != signers.len() || signers.iter().any(|signer| !stored.contains(signer)) {
+            return Err(anyhow!("Multisig {} does not match the requested signers and threshold", address));
+        }
+        Ok(())
+    }
+
+    /// Move every authority of the mint that the wallet holds to `multisig`.
+    fn hand_over_authorities(
+        &self,
+        mint_info: &MintInfo,
+        multisig: &Pubkey,
+        threshold: u8,
+        signers: usize,
+        assume_yes: bool,
+    ) -> Result<()> {
+        let wallet = self.wallet.pubkey().to_string();
+        let summary = mint_info.summary()?;
+        let mut authorities = Vec::new();
+        if summary.mint_authority.as_deref() == Some(wallet.as_str()) {
+            authorities.push((AuthorityType::MintTokens, "Mint authority"));
+        }
+        if summary.freeze_authority.as_deref() == Some(wallet.as_str()) {
+            authorities.push((AuthorityType::FreezeAccount, "Freeze authority"));
+        }
+        if authorities.is_empty() {
+            return Err(anyhow!("The loaded wallet holds neither the mint nor the freeze authority of {}", mint_info.address));
+        }
+
+        let mut lines = vec![
+            format!("HAND OVER AUTHORITIES of {} to multisig {}", mint_info.address, multisig),
+        ];
+        for (_, name) in &authorities {
+            lines.push(format!("{}: {} -> {}", name, wallet, multisig));
+        }
+        lines.push(format!(
+            "Afterwards {} of the {} signers must sign every use of these authorities; this wallet alone no longer can.",
+            threshold, signers
+        ));
+        confirm_typed(&lines, &mint_info.address.to_string(), assume_yes)?;
+
+        let instructions = authorities
+            .iter()
+            .map(|(authority_type, _)| {
+                set_authority(
+                    &mint_info.program_id,
+                    &mint_info.address,
+                    Some(multisig),
+                    authority_type.clone(),
+                    &self.wallet.pubkey(),
+                    &[],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let signature = self
+            .send_instructions(instructions, HANDOVER_UNITS)
+            .map_err(|e| anyhow!("Failed to hand over authorities: {}", e))?;
+        info!("Authorities handed over! Signature: {}", signature);
+
+        let after = self.fetch_mint_info(&mint_info.address)?.summary()?;
+        let multisig = multisig.to_string();
+        for (authority_type, name) in &authorities {
+            let current = match authority_type {
+                AuthorityType::MintTokens => after.mint_authority.as_deref(),
+                _ => after.freeze_authority.as_deref(),
+            };
+            if current != Some(multisig.as_str()) {
+                return Err(anyhow!("{} is {} after the handover, not the multisig", name, current.unwrap_or("None")));
+            }
+            info!("Verified on-chain: {} is now {}", name, multisig);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use mpl_token_metadata::{
+    accounts::{Edition, EditionMarker, MasterEdition, Metadata, TokenRecord},
+    instructions::BurnV1Builder,
+    types::{Key, TokenStandard},
+};
+use serde::Serialize;
+use serde_json::json;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{native_token::lamports_to_sol, pubkey::Pubkey, signature::Signer};
+use spl_associated_token_account::get_associated_token_address;
+use std::{collections::BTreeMap, str::FromStr};
+
+use crate::{
+    editions::EDITION_MARKER_BIT_SIZE,
+    metadata::trim_padding,
+    output::{write_output, OutputFormat},
+    prompt::confirm_typed,
+    token_program::unpack_token_account,
+    TokenOperations,
+};
+
+/// Byte offset of the first creator's address in a metadata account whose
+/// name, symbol and uri are padded to their maximum lengths, as the Token
+/// Metadata program stores them.
+const FIRST_CREATOR_OFFSET: usize = 326;
+
+/// Token accounts fetched per getMultipleAccounts call.
+const FETCH_CHUNK: usize = 100;
+
+/// Estimated compute units for a BurnV1 instruction.
+const BURN_NFT_UNITS: u32 = 100_000;
+
+/// One wallet holding members of a collection.
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionHolder {
+    pub owner: String,
+    pub count: usize,
+    pub mints: Vec<String>,
+}
+
+/// A holder as a flat CSV row.
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionHolderRow {
+    pub owner: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionSnapshot {
+    pub collection: String,
+    pub source: String,
+    pub members: usize,
+    pub holders: Vec<CollectionHolder>,
+}
+
+/// The print edition a burn must also update on its master.
+struct PrintAccounts {
+    master_edition: Pubkey,
+    master_mint: Pubkey,
+    master_token: Pubkey,
+    edition_marker: Pubkey,
+}
+
+impl TokenOperations {
+    /// Accounts of the master edition a print edition was printed from.
+    /// Editions do not record their master's mint, so it comes from `--master`.
+    fn print_accounts(&self, edition_data: &[u8], master: Option<&str>) -> Result<PrintAccounts> {
+        let edition = Edition::from_bytes(edition_data)
+            .map_err(|e| anyhow!("Failed to parse edition account: {}", e))?;
+        let master = master.ok_or_else(|| {
+            anyhow!("This NFT is print edition #{}; pass its master edition's mint with --master", edition.edition)
+        })?;
+        let master_mint = Pubkey::from_str(master).map_err(|e| anyhow!("Invalid master mint address: {}", e))?;
+        let (master_edition, _) = MasterEdition::find_pda(&master_mint);
+        if master_edition != edition.parent {
+            return Err(anyhow!("Edition #{} was printed from {}, not from {}", edition.edition, edition.parent, master_mint));
+        }
+
+        // Any account of the master mint proves which mint the master is.
+        let master_token = self.client
+            .get_token_largest_accounts(&master_mint)
+            .map_err(|e| anyhow!("Failed to get master edition token accounts: {}", e))?
+            .first()
+            .map(|account| Pubkey::from_str(&account.address))
+            .transpose()
+            .map_err(|e| anyhow!("Invalid master edition token account: {}", e))?
+            .ok_or_else(|| anyhow!("Master edition mint {} has no token accounts", master_mint))?;
+
+        let (edition_marker, _) = EditionMarker::find_pda(&master_mint, &(edition.edition / EDITION_MARKER_BIT_SIZE).to_string());
+        Ok(PrintAccounts { master_edition, master_mint, master_token, edition_marker })
+    }
+
+    /// Burn an NFT held by the loaded wallet with Token Metadata's BurnV1,
+    /// which burns the token and closes the token account, metadata, edition
+    /// and (for programmable NFTs) token record accounts, returning their rent
+    /// to the wallet. A plain SPL burn would leave the metadata accounts funded
+    /// forever. Print editions also need their master's mint in `master`.
+    pub fn burn_nft(&self, mint_address: &str, master: Option<&str>, assume_yes: bool) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let (metadata_address, metadata) = self
+            .fetch_metadata(&mint_pubkey)?
+            .ok_or_else(|| anyhow!("Mint {} has no Metaplex metadata account", mint_pubkey))?;
+
+        let wallet = self.wallet.pubkey();
+        let token = get_associated_token_address(&wallet, &mint_pubkey);
+        let (edition, _) = MasterEdition::find_pda(&mint_pubkey);
+        let token_record = matches!(metadata.token_standard, Some(TokenStandard::ProgrammableNonFungible))
+            .then(|| TokenRecord::find_pda(&mint_pubkey, &token).0);
+        let collection_metadata = metadata
+            .collection
+            .as_ref()
+            .filter(|collection| collection.verified)
+            .map(|collection| Metadata::find_pda(&collection.key).0);
+
+        let mut closed = vec![token, metadata_address, edition];
+        closed.extend(token_record);
+        let accounts = self.client.get_multiple_accounts(&closed)
+            .map_err(|e| anyhow!("Failed to get NFT accounts: {}", e))?;
+        let [Some(_), _, edition_account, ..] = accounts.as_slice() else {
+            return Err(anyhow!("Loaded wallet has no token account for {} at {}", mint_pubkey, token));
+        };
+        if self.client.get_token_account_balance(&token).map(|balance| balance.amount).unwrap_or_default() != "1" {
+            return Err(anyhow!("Loaded wallet does not hold {} in {}", mint_pubkey, token));
+        }
+
+        let print = match edition_account {
+            Some(account) if account.data.first() == Some(&(Key::EditionV1 as u8)) => {
+                Some(self.print_accounts(&account.data, master)?)
+            }
+            Some(_) => None,
+            None => return Err(anyhow!("{} has no edition account; it is not an NFT", mint_pubkey)),
+        };
+        let reclaimed: u64 = accounts.iter().flatten().map(|account| account.lamports).sum();
+
+        let mut lines = vec![
+            "BURN NFT (irreversible)".to_string(),
+            format!("Mint:     {} ({})", mint_pubkey, trim_padding(&metadata.name)),
+            format!("Closes:   token account {}, metadata {} and edition {}", token, metadata_address, edition),
+        ];
+        if let Some(print) = &print {
+            lines.push(format!("Printed from master edition {} ({})", print.master_edition, print.master_mint));
+        }
+        lines.push(format!("Reclaims: ~{} SOL to {}", lamports_to_sol(reclaimed), wallet));
+        confirm_typed(&lines, mint_address, assume_yes)?;
+
+        let instruction = BurnV1Builder::new()
+            .authority(wallet)
+            .collection_metadata(collection_metadata)
+            .metadata(metadata_address)
+            .edition(Some(edition))
+            .mint(mint_pubkey)
+            .token(token)
+            .master_edition(print.as_ref().map(|print| print.master_edition))
+            .master_edition_mint(print.as_ref().map(|print| print.master_mint))
+            .master_edition_token(print.as_ref().map(|print| print.master_token))
+            .edition_marker(print.as_ref().map(|print| print.edition_marker))
+            .token_record(token_record)
+            .amount(1)
+            .instruction();
+
+        let balance_before = self.client.get_balance(&wallet).unwrap_or_default();
+        let signature = self
+            .send_instructions(vec![instruction], BURN_NFT_UNITS)
+            .map_err(|e| anyhow!("Failed to burn NFT: {}", e))?;
+        info!("NFT burned! Signature: {}", signature);
+
+        let remaining = self.client.get_multiple_accounts(&closed)
+            .map_err(|e| anyhow!("Failed to get NFT accounts: {}", e))?;
+        if remaining.first().is_some_and(|account| account.is_some()) {
+            return Err(anyhow!("Token account {} is still open after the burn", token));
+        }
+        for (address, _) in closed.iter().zip(&remaining).filter(|(_, account)| account.is_some()) {
+            warn!("Account {} is still open after the burn; its rent was not reclaimed", address);
+        }
+        let balance_after = self.client.get_balance(&wallet).unwrap_or_default();
+        info!(
+            "Verified on-chain: NFT burned; wallet balance {} -> {} SOL",
+            lamports_to_sol(balance_before),
+            lamports_to_sol(balance_after)
+        );
+        Ok(())
+    }
+
+    /// Member mints of a verified collection and their current owners, by
+    /// scanning metadata accounts that share the collection NFT's first
+    /// creator and keeping those verified into the collection.
+    fn scan_collection_members(&self, collection: &Pubkey) -> Result<Vec<(Pubkey, Pubkey)>> {
+        let (_, collection_metadata) = self
+            .fetch_metadata(collection)?
+            .ok_or_else(|| anyhow!("Collection {} has no Metaplex metadata account", collection))?;
+        let creator = collection_metadata
+            .creators
+            .iter()
+            .flatten()
+            .next()
+            .map(|creator| creator.address)
+            .ok_or_else(|| anyhow!("Collection {} has no creators to scan members by; use --backend das", collection))?;
+        info!("Scanning metadata accounts whose first creator is {}...", creator);
+
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &[Key::MetadataV1 as u8])),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(FIRST_CREATOR_OFFSET, creator.as_ref())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = self.client.get_program_accounts_with_config(&mpl_token_metadata::ID, config)
+            .map_err(|e| anyhow!("Failed to scan metadata accounts: {}", e))?;
+
+        let mints: Vec<Pubkey> = accounts
+            .into_iter()
+            .filter_map(|(_, account)| Metadata::from_bytes(&account.data).ok())
+            .filter(|metadata| {
+                metadata
+                    .collection
+                    .as_ref()
+                    .is_some_and(|member_of| member_of.verified && member_of.key == *collection)
+            })
+            .map(|metadata| metadata.mint)
+            .collect();
+        info!("Found {} verified member(s); resolving holders...", mints.len());
+
+        let mut members = Vec::with_capacity(mints.len());
+        for (i, mint) in mints.iter().enumerate() {
+            // An NFT's single token sits in the largest account of its mint.
+            let holding = self.client
+                .get_token_largest_accounts(mint)
+                .map_err(|e| anyhow!("Failed to get token accounts of {}: {}", mint, e))?
+                .into_iter()
+                .find(|account| account.amount.amount == "1");
+            if let Some(holding) = holding {
+                let address = Pubkey::from_str(&holding.address)
+                    .map_err(|e| anyhow!("Invalid token account {}: {}", holding.address, e))?;
+                members.push((*mint, address));
+            }
+            if (i + 1) % 500 == 0 {
+                info!("  {} of {} member(s) resolved", i + 1, mints.len());
+            }
+        }
+
+        let mut owners = Vec::with_capacity(members.len());
+        for chunk in members.chunks(FETCH_CHUNK) {
+            let addresses: Vec<Pubkey> = chunk.iter().map(|(_, account)| *account).collect();
+            let accounts = self.client.get_multiple_accounts(&addresses)
+                .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
+            for ((mint, _), account) in chunk.iter().zip(accounts) {
+                if let Some(token) = account.and_then(|account| unpack_token_account(&account.data).ok()) {
+                    owners.push((*mint, token.owner));
+                }
+            }
+        }
+        Ok(owners)
+    }
+
+    /// List every wallet holding members of a verified collection, with how
+    /// many each holds, for collection-based airdrops. Members come from
+    /// DAS searchAssets when `--backend` enables DAS, otherwise from a
+    /// metadata account scan.
+    pub fn collection_holders(&self, collection_address: &str, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let collection = Pubkey::from_str(collection_address)
+            .map_err(|e| anyhow!("Invalid collection address: {}", e))?;
+
+        let (source, members): (&str, Vec<(String, String)>) = match &self.das {
+            Some(das) => {
+                let assets = das.search_assets(json!({ "grouping": ["collection", collection.to_string()] }))?;
+                let members = assets
+                    .into_iter()
+                    .filter(|asset| !asset.burnt && !asset.ownership.owner.is_empty())
+                    .map(|asset| (asset.id, asset.ownership.owner))
+                    .collect();
+                ("das", members)
+            }
+            None => {
+                let members = self
+                    .scan_collection_members(&collection)?
+                    .into_iter()
+                    .map(|(mint, owner)| (mint.to_string(), owner.to_string()))
+                    .collect();
+                ("metadata-scan", members)
+            }
+        };
+
+        let mut by_owner: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (mint, owner) in &members {
+            by_owner.entry(owner.clone()).or_default().push(mint.clone());
+        }
+        let mut holders: Vec<CollectionHolder> = by_owner
+            .into_iter()
+            .map(|(owner, mints)| CollectionHolder { owner, count: mints.len(), mints })
+            .collect();
+        holders.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.owner.cmp(&b.owner)));
+
+        info!("Collection {}: {} member(s) held by {} wallet(s)", collection, members.len(), holders.len());
+        for holder in holders.iter().take(10) {
+            info!("  {} holds {}", holder.owner, holder.count);
+        }
+
+        let rows: Vec<CollectionHolderRow> = holders
+            .iter()
+            .map(|holder| CollectionHolderRow { owner: holder.owner.clone(), count: holder.count })
+            .collect();
+        let snapshot = CollectionSnapshot {
+            collection: collection.to_string(),
+            source: source.to_string(),
+            members: members.len(),
+            holders,
+        };
+        write_output(output, &snapshot, &rows)
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Serialize;
+use std::{fs::File, io::BufWriter, str::FromStr};
+
+/// File formats accepted by `--format` for exported reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            other => Err(anyhow!("Unsupported output format: {}", other)),
+        }
+    }
+}
+
+pub fn write_json<T: Serialize + ?Sized>(path: &str, value: &T) -> Result<()> {
+    let file = File::create(path).map_err(|e| anyhow!("Failed to create {}: {}", path, e))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), value)
+        .map_err(|e| anyhow!("Failed to write {}: {}", path, e))
+}
+
+pub fn write_csv<T: Serialize>(path: &str, rows: &[T]) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path).map_err(|e| anyhow!("Failed to create {}: {}", path, e))?;
+    for row in rows {
+        writer.serialize(row).map_err(|e| anyhow!("Failed to write {}: {}", path, e))?;
+    }
+    writer.flush().map_err(|e| anyhow!("Failed to write {}: {}", path, e))
+}
+
+/// Write a report to `--output`: the full `report` as JSON, or `rows` as CSV.
+pub fn write_output<J: Serialize + ?Sized, R: Serialize>(
+    output: Option<(&str, OutputFormat)>,
+    report: &J,
+    rows: &[R],
+) -> Result<()> {
+    let Some((path, format)) = output else {
+        return Ok(());
+    };
+
+    match format {
+        OutputFormat::Json => write_json(path, report)?,
+        OutputFormat::Csv => write_csv(path, rows)?,
+    }
+    info!("Report written to {}", path);
+
+    Ok(())
+}
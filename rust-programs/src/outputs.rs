@@ -0,0 +1,23 @@
+use log::warn;
+use std::{env, fs::OpenOptions, io::Write};
+
+/// Environment variable naming the file a pipeline step reports its
+/// results to, one `key=value` per line.
+pub const OUTPUTS_ENV: &str = "TOKEN_OPS_OUTPUTS";
+
+/// Report a result (e.g. a created address) to the pipeline running this
+/// step. Does nothing outside a pipeline.
+pub fn record(key: &str, value: &str) {
+    let Ok(path) = env::var(OUTPUTS_ENV) else {
+        return;
+    };
+
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}={}", key, value));
+    if let Err(e) = written {
+        warn!("Failed to record step output {} to {}: {}", key, path, e);
+    }
+}
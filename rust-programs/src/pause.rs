@@ -0,0 +1,44 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use std::str::FromStr;
+use token_ops_core::pausable;
+
+use crate::TokenOperations;
+
+/// Estimated compute units for one Pause or Resume instruction.
+const PAUSE_UNITS: u32 = 5_000;
+
+impl TokenOperations {
+    /// Pause (or resume) every transfer, mint and burn of a Token-2022 mint
+    /// with the Pausable extension. The loaded wallet must be the pause
+    /// authority.
+    pub fn set_paused(&self, mint_address: &str, paused: bool) -> Result<()> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let config = self
+            .fetch_mint_info(&mint)?
+            .pausable()?
+            .ok_or_else(|| anyhow!("Mint {} does not have the Pausable extension", mint))?;
+
+        if config.authority != Some(self.wallet.pubkey()) {
+            return Err(anyhow!("Loaded wallet is not the pause authority of this mint"));
+        }
+        if config.paused == paused {
+            warn!("Mint {} is already {}", mint, if paused { "paused" } else { "running" });
+            return Ok(());
+        }
+
+        let instruction = pausable::set_paused(&mint, &self.wallet.pubkey(), paused);
+        let signature = self
+            .send_instructions(vec![instruction], PAUSE_UNITS)
+            .map_err(|e| anyhow!("Failed to {} mint: {}", if paused { "pause" } else { "resume" }, e))?;
+
+        if paused {
+            warn!("Mint {} is PAUSED; transfers, mints and burns now fail until resumed", mint);
+        } else {
+            info!("Mint {} resumed", mint);
+        }
+        info!("Signature: {}", signature);
+        Ok(())
+    }
+}
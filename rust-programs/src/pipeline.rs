@@ -0,0 +1,174 @@
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::outputs::OUTPUTS_ENV;
+
+/// Arguments of the `run` invocation itself that steps do not inherit.
+const NOT_INHERITED: &[&str] = &["operation", "pipeline", "input"];
+
+#[derive(Debug, Deserialize)]
+struct Pipeline {
+    #[serde(default)]
+    vars: BTreeMap<String, serde_yaml::Value>,
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Step {
+    name: String,
+    operation: String,
+    /// Long option names without the leading `--`. `true` passes a flag,
+    /// `false` omits it and a list repeats the option.
+    #[serde(default)]
+    args: BTreeMap<String, serde_yaml::Value>,
+    #[serde(default)]
+    continue_on_error: bool,
+}
+
+/// Run the operations declared in a YAML pipeline file in order, stopping at
+/// the first failed step unless it sets `continue_on_error`.
+///
+/// Every step runs as a separate `token-ops` process that inherits the
+/// arguments given to `run` (RPC URL, wallet, compute budget, ...). Values
+/// can reference `${var}` from `vars` and `${step.key}` results reported by
+/// earlier steps, e.g. `${create.mint}` or `${create.signature}`.
+///
+/// ```yaml
+/// vars:
+///   decimals: 6
+/// steps:
+///   - name: create
+///     operation: create-mint
+///     args: { decimals: "${decimals}", token-2022: true }
+///   - name: verify
+///     operation: verify
+///     args: { mint-address: "${create.mint}" }
+/// ```
+pub fn run_pipeline(path: &str, inherited: &BTreeMap<String, Vec<String>>) -> Result<()> {
+    let data = fs::read_to_string(path).map_err(|e| anyhow!("Failed to read pipeline {}: {}", path, e))?;
+    let pipeline: Pipeline = serde_yaml::from_str(&data).map_err(|e| anyhow!("Failed to parse pipeline {}: {}", path, e))?;
+
+    let mut variables: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in &pipeline.vars {
+        variables.insert(name.clone(), scalar(value).ok_or_else(|| anyhow!("Variable '{}' must be a scalar", name))?);
+    }
+
+    let executable = env::current_exe().map_err(|e| anyhow!("Failed to locate token-ops executable: {}", e))?;
+    let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+    let mut failed = Vec::new();
+
+    for (number, step) in pipeline.steps.iter().enumerate() {
+        info!("Step {}/{}: {} ({})", number + 1, pipeline.steps.len(), step.name, step.operation);
+
+        let args = child_args(inherited, NOT_INHERITED, &step.operation, &step.args, &variables)
+            .map_err(|e| anyhow!("Step '{}': {}", step.name, e))?;
+
+        let outputs_path = env::temp_dir().join(format!("token-ops-{}-{}-{}.out", std::process::id(), started, number));
+        let status = Command::new(&executable)
+            .args(&args)
+            .env(OUTPUTS_ENV, &outputs_path)
+            .status()
+            .map_err(|e| anyhow!("Failed to start step '{}': {}", step.name, e))?;
+
+        if let Ok(outputs) = fs::read_to_string(&outputs_path) {
+            for line in outputs.lines() {
+                if let Some((key, value)) = line.split_once('=') {
+                    variables.insert(format!("{}.{}", step.name, key), value.to_string());
+                }
+            }
+            let _ = fs::remove_file(&outputs_path);
+        }
+
+        if status.success() {
+            info!("Step '{}' succeeded", step.name);
+            continue;
+        }
+        if step.continue_on_error {
+            warn!("Step '{}' failed ({}); continuing", step.name, status);
+            failed.push(step.name.clone());
+            continue;
+        }
+        error!("Step '{}' failed ({}); stopping", step.name, status);
+        return Err(anyhow!("Pipeline stopped at step '{}'", step.name));
+    }
+
+    if !failed.is_empty() {
+        warn!("Pipeline finished with failed step(s): {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Arguments for a child `token-ops` process running `operation` with
+/// `args`, plus every `inherited` argument not in `skip` or overridden.
+pub(crate) fn child_args(
+    inherited: &BTreeMap<String, Vec<String>>,
+    skip: &[&str],
+    operation: &str,
+    args: &BTreeMap<String, serde_yaml::Value>,
+    variables: &BTreeMap<String, String>,
+) -> Result<Vec<String>> {
+    let mut command: Vec<String> = Vec::new();
+    for (name, values) in inherited {
+        if skip.contains(&name.as_str()) || args.contains_key(name) {
+            continue;
+        }
+        for value in values {
+            command.push(format!("--{}", name));
+            if value != "true" {
+                command.push(value.clone());
+            }
+        }
+    }
+    command.push("--operation".to_string());
+    command.push(operation.to_string());
+    for (name, value) in args {
+        let values = match value {
+            serde_yaml::Value::Sequence(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            match value {
+                serde_yaml::Value::Bool(true) => command.push(format!("--{}", name)),
+                serde_yaml::Value::Bool(false) => {}
+                value => {
+                    let value = scalar(value).ok_or_else(|| anyhow!("argument '{}' must be a scalar", name))?;
+                    command.push(format!("--{}", name));
+                    command.push(substitute(&value, variables)?);
+                }
+            }
+        }
+    }
+    Ok(command)
+}
+
+fn scalar(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(value) => Some(value.clone()),
+        serde_yaml::Value::Number(value) => Some(value.to_string()),
+        serde_yaml::Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Replace every `${name}` in `value`.
+fn substitute(value: &str, variables: &BTreeMap<String, String>) -> Result<String> {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| anyhow!("Unterminated ${{ in '{}'", value))? + start;
+        let name = &rest[start + 2..end];
+        let replacement = variables.get(name).ok_or_else(|| anyhow!("Unknown variable '{}'", name))?;
+        result.push_str(replacement);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
@@ -0,0 +1,240 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::{info, warn};
+use mpl_token_metadata::accounts::Metadata;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{native_token::lamports_to_sol, pubkey::Pubkey, signature::Signer};
+use spl_token_2022::extension::BaseStateWithExtensions;
+use spl_token_metadata_interface::state::TokenMetadata;
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    metadata::trim_padding,
+    output::{write_output, OutputFormat},
+    token_program::MintInfo,
+    TokenOperations,
+};
+
+/// Default price source: Jupiter's price API, keyed by mint address.
+pub const DEFAULT_PRICE_API: &str = "https://api.jup.ag/price/v2";
+
+/// Mints priced per price API request, and accounts per getMultipleAccounts call.
+const CHUNK: usize = 100;
+
+const PRICE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Wrapped SOL, used to price the wallet's native SOL balance.
+const WRAPPED_SOL: &str = "So11111111111111111111111111111111111111112";
+
+/// How portfolio rows are ordered, chosen with `--sort`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortfolioSort {
+    /// Largest USD value first; unpriced holdings last.
+    Value,
+    /// Largest token amount first.
+    Amount,
+    /// By symbol, then mint address.
+    Symbol,
+}
+
+impl FromStr for PortfolioSort {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "value" => Ok(PortfolioSort::Value),
+            "amount" => Ok(PortfolioSort::Amount),
+            "symbol" => Ok(PortfolioSort::Symbol),
+            other => Err(anyhow!("Unknown sort '{}'; use value, amount or symbol", other)),
+        }
+    }
+}
+
+/// One token the wallet holds, summed over all its accounts of that mint.
+#[derive(Debug, Clone, Serialize)]
+pub struct Holding {
+    pub mint: String,
+    pub symbol: Option<String>,
+    pub accounts: usize,
+    pub amount: String,
+    pub raw_amount: u64,
+    pub price_usd: Option<f64>,
+    pub value_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Portfolio {
+    pub owner: String,
+    pub generated_at: String,
+    pub total_usd: f64,
+    /// Holdings without a price, left out of `total_usd`.
+    pub unpriced: usize,
+    pub holdings: Vec<Holding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceResponse {
+    data: HashMap<String, Option<PriceEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceEntry {
+    price: Option<String>,
+}
+
+/// USD prices of `mints` from a Jupiter-compatible price API; mints it does
+/// not know are missing from the result.
+fn fetch_prices(price_api: &str, mints: &[String]) -> Result<HashMap<String, f64>> {
+    let http = reqwest::blocking::Client::builder()
+        .timeout(PRICE_TIMEOUT)
+        .build()
+        .map_err(|e| anyhow!("Failed to build HTTP client: {}", e))?;
+
+    let mut prices = HashMap::new();
+    for chunk in mints.chunks(CHUNK) {
+        let response: PriceResponse = http
+            .get(price_api)
+            .query(&[("ids", chunk.join(","))])
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| anyhow!("Price request to {} failed: {}", price_api, e))?
+            .json()
+            .map_err(|e| anyhow!("Invalid price response from {}: {}", price_api, e))?;
+
+        for (mint, entry) in response.data {
+            if let Some(price) = entry.and_then(|entry| entry.price).and_then(|price| price.parse::<f64>().ok()) {
+                prices.insert(mint, price);
+            }
+        }
+    }
+    Ok(prices)
+}
+
+impl TokenOperations {
+    /// Value every token `owner` (default: the wallet) holds, plus native
+    /// SOL, at prices from `price_api`, and write the holdings and USD total.
+    pub fn portfolio(
+        &self,
+        owner: Option<&str>,
+        price_api: &str,
+        sort: PortfolioSort,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let owner = match owner {
+            Some(owner) => Pubkey::from_str(owner).map_err(|e| anyhow!("Invalid owner address: {}", e))?,
+            None => self.wallet.pubkey(),
+        };
+        let now = Utc::now();
+
+        // mint -> (raw amount, accounts)
+        let mut totals: BTreeMap<Pubkey, (u64, usize)> = BTreeMap::new();
+        for account in self.owned_token_accounts(&owner, None)? {
+            let total = totals.entry(account.account.mint).or_default();
+            total.0 = total.0.saturating_add(account.account.amount);
+            total.1 += 1;
+        }
+        totals.retain(|_, (amount, _)| *amount > 0);
+        info!("{} holds {} token(s) with a balance", owner, totals.len());
+
+        let mints: Vec<Pubkey> = totals.keys().copied().collect();
+        let mut holdings = Vec::with_capacity(mints.len() + 1);
+        for chunk in mints.chunks(CHUNK / 2) {
+            let mut addresses = chunk.to_vec();
+            addresses.extend(chunk.iter().map(|mint| Metadata::find_pda(mint).0));
+            let accounts = self.client.get_multiple_accounts(&addresses)
+                .map_err(|e| anyhow!("Failed to get mint accounts: {}", e))?;
+            let (mint_accounts, metadata_accounts) = accounts.split_at(chunk.len());
+
+            for ((mint, mint_account), metadata_account) in chunk.iter().zip(mint_accounts).zip(metadata_accounts) {
+                let Some(mint_account) = mint_account else {
+                    warn!("Mint {} no longer exists; skipping", mint);
+                    continue;
+                };
+                let mint_info = MintInfo { address: *mint, program_id: mint_account.owner, data: mint_account.data.clone() };
+                let symbol = metadata_account
+                    .as_ref()
+                    .and_then(|account| Metadata::from_bytes(&account.data).ok())
+                    .map(|metadata| trim_padding(&metadata.symbol).to_string())
+                    .or_else(|| {
+                        let state = mint_info.state().ok()?;
+                        Some(state.get_variable_len_extension::<TokenMetadata>().ok()?.symbol)
+                    })
+                    .filter(|symbol| !symbol.is_empty());
+                let (raw_amount, accounts) = totals[mint];
+
+                holdings.push(Holding {
+                    mint: mint.to_string(),
+                    symbol,
+                    accounts,
+                    amount: mint_info.ui_amount(raw_amount, now.timestamp())?,
+                    raw_amount,
+                    price_usd: None,
+                    value_usd: None,
+                });
+            }
+        }
+
+        let lamports = self.client.get_balance(&owner)
+            .map_err(|e| anyhow!("Failed to get SOL balance: {}", e))?;
+        if lamports > 0 {
+            holdings.push(Holding {
+                mint: WRAPPED_SOL.to_string(),
+                symbol: Some("SOL (native)".to_string()),
+                accounts: 1,
+                amount: lamports_to_sol(lamports).to_string(),
+                raw_amount: lamports,
+                price_usd: None,
+                value_usd: None,
+            });
+        }
+
+        let mut priced_mints: Vec<String> = holdings.iter().map(|holding| holding.mint.clone()).collect();
+        priced_mints.sort();
+        priced_mints.dedup();
+        let prices = fetch_prices(price_api, &priced_mints)?;
+        for holding in &mut holdings {
+            holding.price_usd = prices.get(&holding.mint).copied();
+            holding.value_usd = holding
+                .price_usd
+                .zip(holding.amount.parse::<f64>().ok())
+                .map(|(price, amount)| price * amount);
+        }
+
+        match sort {
+            PortfolioSort::Value => holdings.sort_by(|a, b| b.value_usd.unwrap_or(-1.0).total_cmp(&a.value_usd.unwrap_or(-1.0))),
+            PortfolioSort::Amount => holdings.sort_by(|a, b| {
+                let amount = |holding: &Holding| holding.amount.parse::<f64>().unwrap_or_default();
+                amount(b).total_cmp(&amount(a))
+            }),
+            PortfolioSort::Symbol => holdings.sort_by(|a, b| a.symbol.cmp(&b.symbol).then_with(|| a.mint.cmp(&b.mint))),
+        }
+
+        let report = Portfolio {
+            owner: owner.to_string(),
+            generated_at: now.to_rfc3339(),
+            total_usd: holdings.iter().filter_map(|holding| holding.value_usd).sum(),
+            unpriced: holdings.iter().filter(|holding| holding.price_usd.is_none()).count(),
+            holdings,
+        };
+
+        info!("Portfolio of {}:", report.owner);
+        for holding in &report.holdings {
+            info!(
+                "  {:<12} {} {}",
+                holding.symbol.as_deref().unwrap_or("?"),
+                holding.amount,
+                match holding.value_usd {
+                    Some(value) => format!("${:.2}", value),
+                    None => "(no price)".to_string(),
+                }
+            );
+        }
+        info!("Total: ${:.2} ({} holding(s) without a price)", report.total_usd, report.unpriced);
+
+        write_output(output, &report, &report.holdings)
+    }
+}
@@ -0,0 +1,68 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+/// Percentile used by a bare `--priority-fee auto`.
+pub const DEFAULT_FEE_PERCENTILE: u8 = 75;
+
+/// Addresses accepted per getRecentPrioritizationFees request.
+const MAX_FEE_ACCOUNTS: usize = 128;
+
+/// `--priority-fee auto[:PERCENTILE]`: price each transaction from the
+/// prioritization fees recently paid to write the accounts it touches,
+/// never above `max` micro-lamports per compute unit.
+#[derive(Debug, Clone, Copy)]
+pub struct AutoPriorityFee {
+    pub percentile: u8,
+    pub max: u64,
+}
+
+impl AutoPriorityFee {
+    pub fn parse(value: &str, max: u64) -> Result<Self> {
+        let percentile = match value.split_once(':') {
+            None if value == "auto" => DEFAULT_FEE_PERCENTILE,
+            Some(("auto", percentile)) => percentile
+                .parse::<u8>()
+                .ok()
+                .filter(|percentile| *percentile <= 100)
+                .ok_or_else(|| anyhow!("Invalid --priority-fee percentile '{}'; expected 0-100", percentile))?,
+            _ => return Err(anyhow!("Invalid --priority-fee '{}'; expected auto or auto:PERCENTILE", value)),
+        };
+        Ok(Self { percentile, max })
+    }
+
+    /// The fee at the configured percentile of the recent slots' minimum
+    /// fees for the instructions' writable accounts, capped at `max`.
+    pub fn price(&self, client: &RpcClient, instructions: &[Instruction]) -> Result<u64> {
+        let mut accounts: Vec<Pubkey> = Vec::new();
+        for meta in instructions.iter().flat_map(|instruction| &instruction.accounts) {
+            if meta.is_writable && !accounts.contains(&meta.pubkey) {
+                accounts.push(meta.pubkey);
+            }
+        }
+        accounts.truncate(MAX_FEE_ACCOUNTS);
+
+        let mut fees: Vec<u64> = client
+            .get_recent_prioritization_fees(&accounts)
+            .map_err(|e| anyhow!("Failed to get recent prioritization fees: {}", e))?
+            .into_iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+        if fees.is_empty() {
+            return Ok(0);
+        }
+        fees.sort_unstable();
+
+        let index = ((fees.len() - 1) * self.percentile as usize).div_ceil(100);
+        let price = fees[index].min(self.max);
+        info!(
+            "Priority fee: {} micro-lamports per CU (p{} of {} recent slots{})",
+            price,
+            self.percentile,
+            fees.len(),
+            if fees[index] > self.max { ", capped" } else { "" }
+        );
+        Ok(price)
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{native_token::lamports_to_sol, packet::PACKET_DATA_SIZE, signature::Signature};
+use std::sync::{Mutex, OnceLock};
+
+use crate::{history::fetch_transaction, output::write_json};
+
+/// Base fee per signature; anything a transaction pays above it is priority fee.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Headroom over the largest observed consumption in the suggested limit.
+const LIMIT_HEADROOM_PERCENT: u64 = 10;
+
+/// Cost of one transaction sent under `--profile`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionProfile {
+    pub signature: String,
+    pub landed: bool,
+    /// Serialized size in bytes, out of the 1232-byte packet limit.
+    pub size: usize,
+    pub signatures: usize,
+    pub compute_unit_limit: u32,
+    pub compute_unit_price: Option<u64>,
+    pub units_simulated: Option<u64>,
+    /// Units the confirmed transaction consumed, per its metadata.
+    pub units_consumed: Option<u64>,
+    /// Lamports paid, read back after confirmation.
+    pub fee: Option<u64>,
+}
+
+impl TransactionProfile {
+    fn units(&self) -> Option<u64> {
+        self.units_consumed.or(self.units_simulated)
+    }
+}
+
+/// Minimum, mean, 90th percentile and maximum of a set of values.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Spread {
+    pub min: u64,
+    pub mean: u64,
+    pub p90: u64,
+    pub max: u64,
+}
+
+impl Spread {
+    fn of(mut values: Vec<u64>) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_unstable();
+        let p90 = values[(values.len() * 9).div_ceil(10) - 1];
+        Some(Self {
+            min: values[0],
+            mean: values.iter().sum::<u64>() / values.len() as u64,
+            p90,
+            max: values[values.len() - 1],
+        })
+    }
+}
+
+/// Cost summary of one run, written to `--profile-output`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileReport {
+    pub operation: String,
+    pub transactions: usize,
+    pub landed: usize,
+    pub units: Option<Spread>,
+    pub size: Option<Spread>,
+    pub fees: Option<Spread>,
+    pub total_fee: u64,
+    pub total_priority_fee: u64,
+    /// Largest consumption plus headroom, rounded up to a thousand units.
+    pub suggested_compute_unit_limit: Option<u64>,
+    pub details: Vec<TransactionProfile>,
+}
+
+/// The profiled run.
+struct Profile {
+    operation: String,
+    output: Option<String>,
+}
+
+static PROFILE: OnceLock<Profile> = OnceLock::new();
+static TRANSACTIONS: Mutex<Vec<TransactionProfile>> = Mutex::new(Vec::new());
+
+/// Profile every transaction this run sends and summarize them at the end.
+pub fn enable(operation: &str, output: Option<&str>) -> Result<()> {
+    PROFILE
+        .set(Profile { operation: operation.to_string(), output: output.map(str::to_string) })
+        .map_err(|_| anyhow!("Profiling is already enabled"))
+}
+
+pub fn enabled() -> bool {
+    PROFILE.get().is_some()
+}
+
+/// Fee and compute units of a confirmed transaction, from its metadata.
+pub fn landed_cost(client: &RpcClient, signature: &Signature) -> (Option<u64>, Option<u64>) {
+    match fetch_transaction(client, &signature.to_string()) {
+        Ok(transaction) => match transaction.transaction.transaction.meta {
+            Some(meta) => (Some(meta.fee), Option::<u64>::from(meta.compute_units_consumed)),
+            None => (None, None),
+        },
+        Err(e) => {
+            warn!("Failed to read the fee of {} for profiling: {}", signature, e);
+            (None, None)
+        }
+    }
+}
+
+pub fn record(transaction: TransactionProfile) {
+    if let Ok(mut transactions) = TRANSACTIONS.lock() {
+        transactions.push(transaction);
+    }
+}
+
+/// Log the cost summary of the run's transactions, if profiling, and
+/// write it to `--profile-output` when given.
+pub fn finish() -> Result<()> {
+    let Some(profile) = PROFILE.get() else {
+        return Ok(());
+    };
+    let details = match TRANSACTIONS.lock() {
+        Ok(transactions) => transactions.clone(),
+        Err(_) => return Err(anyhow!("Profiled transactions are unavailable")),
+    };
+
+    let landed: Vec<&TransactionProfile> = details.iter().filter(|transaction| transaction.landed).collect();
+    let units = Spread::of(landed.iter().filter_map(|transaction| transaction.units()).collect());
+    let fees: Vec<u64> = landed.iter().filter_map(|transaction| transaction.fee).collect();
+    let total_priority_fee: u64 = landed
+        .iter()
+        .filter_map(|transaction| {
+            let fee = transaction.fee?;
+            Some(fee.saturating_sub(transaction.signatures as u64 * LAMPORTS_PER_SIGNATURE))
+        })
+        .sum();
+    let report = ProfileReport {
+        operation: profile.operation.clone(),
+        transactions: details.len(),
+        landed: landed.len(),
+        units,
+        size: Spread::of(details.iter().map(|transaction| transaction.size as u64).collect()),
+        total_fee: fees.iter().sum(),
+        fees: Spread::of(fees),
+        total_priority_fee,
+        suggested_compute_unit_limit: units
+            .map(|units| (units.max * (100 + LIMIT_HEADROOM_PERCENT) / 100).div_ceil(1_000) * 1_000),
+        details,
+    };
+
+    info!(
+        "Profile of {}: {} transaction(s), {} landed",
+        report.operation, report.transactions, report.landed
+    );
+    if let Some(units) = report.units {
+        let limit = report.details.iter().map(|transaction| transaction.compute_unit_limit).max().unwrap_or_default();
+        info!(
+            "  Compute units: min {}, mean {}, p90 {}, max {} (limit {})",
+            units.min, units.mean, units.p90, units.max, limit
+        );
+    }
+    if let Some(size) = report.size {
+        info!("  Size: min {}, mean {}, max {} of {} bytes", size.min, size.mean, size.max, PACKET_DATA_SIZE);
+    }
+    if let Some(fees) = report.fees {
+        info!(
+            "  Fees: {} SOL total ({} SOL priority), mean {} lamports, max {} lamports",
+            lamports_to_sol(report.total_fee),
+            lamports_to_sol(report.total_priority_fee),
+            fees.mean,
+            fees.max
+        );
+    }
+    if let Some(limit) = report.suggested_compute_unit_limit {
+        info!("  Suggested --compute-unit-limit: {}", limit);
+    }
+
+    if let Some(path) = &profile.output {
+        write_json(path, &report)?;
+        info!("Profile written to {}", path);
+    }
+    Ok(())
+}
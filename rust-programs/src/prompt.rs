@@ -0,0 +1,101 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fmt::Display,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+use crate::input;
+
+/// Show what is about to happen and require the operator to type `yes`.
+/// `assume_yes` (the `--yes` flag) skips the prompt for automation.
+pub fn confirm(summary: &[String], assume_yes: bool) -> Result<()> {
+    confirm_answer(summary, "Type 'yes' to continue: ", "yes", assume_yes)
+}
+
+/// Like `confirm`, but for irreversible changes: the operator must retype
+/// `expected` (usually the mint address) so a stray `yes` cannot slip through.
+pub fn confirm_typed(summary: &[String], expected: &str, assume_yes: bool) -> Result<()> {
+    confirm_answer(summary, &format!("This cannot be undone. Retype {} to continue: ", expected), expected, assume_yes)
+}
+
+fn confirm_answer(summary: &[String], question: &str, expected: &str, assume_yes: bool) -> Result<()> {
+    eprintln!();
+    for line in summary {
+        eprintln!("  {}", line);
+    }
+    eprintln!();
+
+    if assume_yes {
+        eprintln!("Proceeding without confirmation (--yes)");
+        return Ok(());
+    }
+    if input::stdin_consumed() {
+        return Err(anyhow!("Input was read from stdin, which cannot also answer the prompt; pass --yes"));
+    }
+
+    eprint!("{}", question);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    if answer.trim() == expected {
+        Ok(())
+    } else {
+        Err(anyhow!("Aborted by user"))
+    }
+}
+
+/// Ask a question on stderr and read one line, returning `default` for an
+/// empty answer.
+pub fn ask(question: &str, default: Option<&str>) -> Result<String> {
+    loop {
+        match default {
+            Some(default) => eprint!("{} [{}]: ", question, default),
+            None => eprint!("{}: ", question),
+        }
+        io::stderr().flush()?;
+
+        let mut answer = String::new();
+        if io::stdin().lock().read_line(&mut answer)? == 0 {
+            return Err(anyhow!("Input closed"));
+        }
+        let answer = answer.trim();
+
+        match (answer.is_empty(), default) {
+            (false, _) => return Ok(answer.to_string()),
+            (true, Some(default)) => return Ok(default.to_string()),
+            (true, None) => eprintln!("  An answer is required"),
+        }
+    }
+}
+
+/// Like `ask`, re-asking until the answer parses and passes `validate`.
+pub fn ask_parsed<T>(question: &str, default: Option<&str>, validate: impl Fn(&T) -> Result<()>) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    loop {
+        let answer = ask(question, default)?;
+        match answer.parse::<T>() {
+            Ok(value) => match validate(&value) {
+                Ok(()) => return Ok(value),
+                Err(e) => eprintln!("  {}", e),
+            },
+            Err(e) => eprintln!("  Invalid answer '{}': {}", answer, e),
+        }
+    }
+}
+
+/// Ask a yes/no question.
+pub fn ask_yes_no(question: &str, default: bool) -> Result<bool> {
+    loop {
+        match ask(question, Some(if default { "y" } else { "n" }))?.to_lowercase().as_str() {
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => eprintln!("  Answer y or n"),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_token_2022::instruction::{set_authority, AuthorityType};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use token_ops_core::instructions::memo;
+
+use crate::{
+    output::write_json,
+    prompt::{confirm, confirm_typed},
+    report::parse_duration,
+    token_program::MintInfo,
+    TokenOperations,
+};
+
+/// Estimated compute units for SetAuthority plus the acceptance memo.
+const ACCEPT_UNITS: u32 = 30_000;
+
+/// Memo the new authority signs to prove it controls the key.
+const ACCEPT_MEMO: &str = "token-ops accept authority";
+
+/// A pending two-step authority transfer, saved by propose-authority.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorityProposal {
+    pub mint: String,
+    /// "mint" or "freeze".
+    pub authority_type: String,
+    pub current_authority: String,
+    pub new_authority: String,
+    pub proposed_at: String,
+    /// Unix time from which accept-authority may execute the transfer.
+    pub executable_after: i64,
+}
+
+fn parse_authority_type(value: &str) -> Result<AuthorityType> {
+    match value {
+        "mint" => Ok(AuthorityType::MintTokens),
+        "freeze" => Ok(AuthorityType::FreezeAccount),
+        other => Err(anyhow!("Unknown authority type '{}'; use mint or freeze", other)),
+    }
+}
+
+/// The mint's current holder of `authority_type`.
+fn current_authority(mint_info: &MintInfo, authority_type: &AuthorityType) -> Result<Option<String>> {
+    let summary = mint_info.summary()?;
+    Ok(match authority_type {
+        AuthorityType::MintTokens => summary.mint_authority,
+        _ => summary.freeze_authority,
+    })
+}
+
+fn proposal_path(dir: &str, mint: &Pubkey, authority_type: &str) -> PathBuf {
+    Path::new(dir).join(format!("{}-{}.json", mint, authority_type))
+}
+
+fn format_time(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn load_proposal(path: &Path) -> Result<AuthorityProposal> {
+    let contents = fs::read_to_string(path)
+        .map_err(|_| anyhow!("No pending proposal at {}; create one with propose-authority", path.display()))?;
+    serde_json::from_str(&contents).map_err(|e| anyhow!("Failed to parse proposal {}: {}", path.display(), e))
+}
+
+impl TokenOperations {
+    /// Record a transfer of the mint's mint or freeze authority to
+    /// `new_authority` that accept-authority can execute once `delay` has
+    /// passed. Nothing changes on chain yet; the pause gives time to spot a
+    /// wrong or substituted key before it holds the authority.
+    pub fn propose_authority(
+        &self,
+        mint_address: &str,
+        authority_type: &str,
+        new_authority: &str,
+        delay: &str,
+        proposals_dir: &str,
+        assume_yes: bool,
+    ) -> Result<()> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let new_authority = Pubkey::from_str(new_authority).map_err(|e| anyhow!("Invalid --new-authority: {}", e))?;
+        let delay_seconds = parse_duration(delay)?;
+        let kind = parse_authority_type(authority_type)?;
+
+        let mint_info = self.fetch_mint_info(&mint)?;
+        let wallet = self.wallet.pubkey();
+        if current_authority(&mint_info, &kind)? != Some(wallet.to_string()) {
+            return Err(anyhow!("Loaded wallet is not the {} authority of this mint", authority_type));
+        }
+        if new_authority == wallet {
+            return Err(anyhow!("--new-authority is already the {} authority", authority_type));
+        }
+        let path = proposal_path(proposals_dir, &mint, authority_type);
+        if path.exists() {
+            return Err(anyhow!("A {} authority proposal for {} is already pending; cancel-authority it first", authority_type, mint));
+        }
+        if self.client.get_account(&new_authority).is_err() {
+            warn!("{} has no account on chain; make sure it is the intended key", new_authority);
+        }
+
+        let now = Utc::now().timestamp();
+        let proposal = AuthorityProposal {
+            mint: mint.to_string(),
+            authority_type: authority_type.to_string(),
+            current_authority: wallet.to_string(),
+            new_authority: new_authority.to_string(),
+            proposed_at: format_time(now),
+            executable_after: now + delay_seconds,
+        };
+        confirm(
+            &[
+                format!("PROPOSE {} AUTHORITY TRANSFER", authority_type.to_uppercase()),
+                format!("Mint:          {}", mint),
+                format!("Current:       {}", wallet),
+                format!("New authority: {}", new_authority),
+                format!("Executable:    after {}", format_time(proposal.executable_after)),
+            ],
+            assume_yes,
+        )?;
+
+        fs::create_dir_all(proposals_dir)
+            .map_err(|e| anyhow!("Failed to create proposals directory {}: {}", proposals_dir, e))?;
+        write_json(&path.to_string_lossy(), &proposal)?;
+        info!("Proposal saved to {}", path.display());
+        info!("Run accept-authority after {} to execute it", format_time(proposal.executable_after));
+        Ok(())
+    }
+
+    /// Execute a proposal whose delay has passed, after the operator
+    /// retypes the new authority. With `new_authority_keypair` the new key
+    /// also signs, proving it is held by the intended party.
+    pub fn accept_authority(
+        &self,
+        mint_address: &str,
+        authority_type: &str,
+        proposals_dir: &str,
+        new_authority_keypair: Option<&Keypair>,
+        assume_yes: bool,
+    ) -> Result<()> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let kind = parse_authority_type(authority_type)?;
+        let path = proposal_path(proposals_dir, &mint, authority_type);
+        let proposal = load_proposal(&path)?;
+        let new_authority = Pubkey::from_str(&proposal.new_authority)
+            .map_err(|e| anyhow!("Invalid new authority in {}: {}", path.display(), e))?;
+
+        let now = Utc::now().timestamp();
+        if now < proposal.executable_after {
+            return Err(anyhow!(
+                "The proposal becomes executable at {} ({} minute(s) from now)",
+                format_time(proposal.executable_after),
+                (proposal.executable_after - now + 59) / 60
+            ));
+        }
+
+        let mint_info = self.fetch_mint_info(&mint)?;
+        let current = current_authority(&mint_info, &kind)?;
+        if current.as_deref() != Some(proposal.current_authority.as_str()) {
+            return Err(anyhow!(
+                "The {} authority is now {}, not {} as proposed; cancel-authority and propose again",
+                authority_type,
+                current.unwrap_or_else(|| "None".to_string()),
+                proposal.current_authority
+            ));
+        }
+        if proposal.current_authority != self.wallet.pubkey().to_string() {
+            return Err(anyhow!("Load the current authority {} to accept this proposal", proposal.current_authority));
+        }
+        if let Some(keypair) = new_authority_keypair {
+            if keypair.pubkey() != new_authority {
+                return Err(anyhow!("--new-authority-keypair is {}, not the proposed {}", keypair.pubkey(), new_authority));
+            }
+        }
+
+        confirm_typed(
+            &[
+                format!("TRANSFER {} AUTHORITY (proposed {})", authority_type.to_uppercase(), proposal.proposed_at),
+                format!("Mint:          {}", mint),
+                format!("Current:       {}", proposal.current_authority),
+                format!("New authority: {}", new_authority),
+                "Check the new authority against an independent source before retyping it.".to_string(),
+            ],
+            &new_authority.to_string(),
+            assume_yes,
+        )?;
+
+        let mut instructions = Vec::new();
+        if new_authority_keypair.is_some() {
+            instructions.push(memo(ACCEPT_MEMO, &new_authority));
+        }
+        instructions.push(set_authority(
+            &mint_info.program_id,
+            &mint,
+            Some(&new_authority),
+            kind.clone(),
+            &self.wallet.pubkey(),
+            &[],
+        )?);
+        let signers: Vec<&Keypair> = new_authority_keypair.into_iter().collect();
+        let signature = self
+            .send_instructions_with_signers(instructions, ACCEPT_UNITS, &signers)
+            .map_err(|e| anyhow!("Failed to transfer the {} authority: {}", authority_type, e))?;
+        info!("{} authority transferred! Signature: {}", authority_type, signature);
+
+        let after = current_authority(&self.fetch_mint_info(&mint)?, &kind)?;
+        if after.as_deref() != Some(proposal.new_authority.as_str()) {
+            return Err(anyhow!("The {} authority is {} after the transfer", authority_type, after.unwrap_or_else(|| "None".to_string())));
+        }
+        info!("Verified on-chain: {} authority is now {}", authority_type, new_authority);
+
+        fs::remove_file(&path).map_err(|e| anyhow!("Failed to remove executed proposal {}: {}", path.display(), e))?;
+        Ok(())
+    }
+
+    /// Discard a pending proposal.
+    pub fn cancel_authority(&self, mint_address: &str, authority_type: &str, proposals_dir: &str) -> Result<()> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        parse_authority_type(authority_type)?;
+        let path = proposal_path(proposals_dir, &mint, authority_type);
+        let proposal = load_proposal(&path)?;
+        fs::remove_file(&path).map_err(|e| anyhow!("Failed to remove {}: {}", path.display(), e))?;
+        info!("Cancelled the {} authority transfer of {} to {}", authority_type, mint, proposal.new_authority);
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use std::{collections::HashSet, str::FromStr};
+use token_ops_core::distribution::{pro_rata, Rounding};
+
+use crate::{
+    holders::{format_amount, merge_by_owner, parse_amount},
+    index::Index,
+    input::read_addresses,
+    output::{write_csv, write_output, OutputFormat},
+    TokenOperations,
+};
+
+/// What to snapshot and how to split the distribution.
+#[derive(Debug, Clone)]
+pub struct ProRataPlan<'a> {
+    /// Mint whose holders receive the distribution.
+    pub snapshot_mint: &'a str,
+    /// Mint being distributed.
+    pub distribution_mint: &'a str,
+    /// Total to distribute, in whole tokens of the distribution mint.
+    pub total: &'a str,
+    pub rounding: Rounding,
+    /// Use the latest indexed snapshot at or before this slot.
+    pub at_slot: Option<u64>,
+    pub index_path: &'a str,
+    /// File of owners left out of the distribution, one per line.
+    pub exclude: Option<&'a str>,
+    /// Where the generated `wallet,amount` CSV goes, in the format airdrop reads.
+    pub recipients_path: &'a str,
+}
+
+/// One holder's share.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProRataShare {
+    pub wallet: String,
+    /// Holding of the snapshot mint.
+    pub balance: String,
+    /// Share of the distribution mint.
+    pub amount: String,
+    pub raw_amount: u64,
+}
+
+/// A `wallet,amount` line of the generated recipients file.
+#[derive(Debug, Clone, Serialize)]
+struct RecipientRow {
+    wallet: String,
+    amount: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProRataDistribution {
+    pub snapshot_mint: String,
+    pub slot: u64,
+    pub distribution_mint: String,
+    pub rounding: String,
+    pub total: String,
+    /// Less than `total` with floor rounding.
+    pub distributed: String,
+    pub excluded_holders: usize,
+    pub shares: Vec<ProRataShare>,
+}
+
+impl TokenOperations {
+    /// Split `plan.total` of the distribution mint over the snapshot mint's
+    /// holders in proportion to their balances, and write the result as a
+    /// recipients file for airdrop. Excluded addresses, the loaded wallet
+    /// and program-owned holders get nothing and do not dilute the others.
+    /// Holders whose share rounds to zero are left out of the file.
+    pub fn pro_rata_airdrop(&self, plan: &ProRataPlan, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let snapshot_mint =
+            Pubkey::from_str(plan.snapshot_mint).map_err(|e| anyhow!("Invalid --mint-a: {}", e))?;
+        let distribution_mint =
+            Pubkey::from_str(plan.distribution_mint).map_err(|e| anyhow!("Invalid --mint-b: {}", e))?;
+        let decimals = self.fetch_mint_info(&distribution_mint)?.summary()?.decimals;
+        let total = parse_amount(plan.total, decimals)?;
+        if total == 0 {
+            return Err(anyhow!("--amount must be greater than zero"));
+        }
+
+        let snapshot = match plan.at_slot {
+            Some(slot) => Index::open(plan.index_path)?.snapshot_at(plan.snapshot_mint, slot)?.ok_or_else(|| {
+                anyhow!(
+                    "No snapshot of {} at or before slot {} in {}; take one with index-snapshot",
+                    snapshot_mint,
+                    slot,
+                    plan.index_path
+                )
+            })?,
+            None => self.fetch_holder_snapshot(&snapshot_mint)?,
+        };
+
+        let mut excluded: HashSet<Pubkey> = match plan.exclude {
+            Some(path) => read_addresses(path)?.into_iter().collect(),
+            None => HashSet::new(),
+        };
+        excluded.insert(self.wallet.pubkey());
+
+        let owners = merge_by_owner(&snapshot);
+        let holders = owners.iter().filter(|owner| owner.amount > 0).count();
+        let mut wallets: Vec<(Pubkey, u64)> = owners
+            .iter()
+            .filter(|owner| owner.amount > 0)
+            .filter_map(|owner| Some((Pubkey::from_str(&owner.owner).ok()?, owner.amount)))
+            .filter(|(owner, _)| owner.is_on_curve() && !excluded.contains(owner))
+            .collect();
+        wallets.sort_by_key(|(owner, _)| owner.to_string());
+
+        let weights: Vec<u64> = wallets.iter().map(|(_, amount)| *amount).collect();
+        let amounts = pro_rata(&weights, total, plan.rounding)
+            .map_err(|_| anyhow!("No eligible holders of {} at slot {}", snapshot_mint, snapshot.slot))?;
+
+        let shares: Vec<ProRataShare> = wallets
+            .iter()
+            .zip(&amounts)
+            .filter(|(_, amount)| **amount > 0)
+            .map(|((owner, balance), amount)| ProRataShare {
+                wallet: owner.to_string(),
+                balance: format_amount(*balance, snapshot.decimals),
+                amount: format_amount(*amount, decimals),
+                raw_amount: *amount,
+            })
+            .collect();
+        let distributed: u64 = shares.iter().map(|share| share.raw_amount).sum();
+
+        info!(
+            "Pro-rata split of {} over {} holder(s) of {} at slot {}",
+            format_amount(total, decimals),
+            wallets.len(),
+            snapshot_mint,
+            snapshot.slot
+        );
+        info!("  Excluded {} holder(s)", holders - wallets.len());
+        if shares.len() < wallets.len() {
+            info!("  {} holder(s) round to zero and are left out", wallets.len() - shares.len());
+        }
+        if distributed < total {
+            info!("  Undistributed by rounding: {}", format_amount(total - distributed, decimals));
+        }
+
+        let rows: Vec<RecipientRow> = shares
+            .iter()
+            .map(|share| RecipientRow { wallet: share.wallet.clone(), amount: share.amount.clone() })
+            .collect();
+        write_csv(plan.recipients_path, &rows)?;
+        info!("{} recipient(s) written to {}", rows.len(), plan.recipients_path);
+
+        let distribution = ProRataDistribution {
+            snapshot_mint: snapshot_mint.to_string(),
+            slot: snapshot.slot,
+            distribution_mint: distribution_mint.to_string(),
+            rounding: match plan.rounding {
+                Rounding::Floor => "floor".to_string(),
+                Rounding::LargestRemainder => "largest-remainder".to_string(),
+            },
+            total: format_amount(total, decimals),
+            distributed: format_amount(distributed, decimals),
+            excluded_holders: holders - wallets.len(),
+            shares,
+        };
+        write_output(output, &distribution, &distribution.shares)
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use spl_token_2022::{extension::ExtensionType, instruction::reallocate};
+use std::str::FromStr;
+use token_ops_core::{
+    mint::unpack_token_account,
+    tlv::{extension_name, token_account_extensions},
+};
+
+use crate::TokenOperations;
+
+/// Estimated compute units for one Reallocate instruction.
+const REALLOCATE_UNITS: u32 = 10_000;
+
+/// Parse an account extension name given to `--extension` for reallocate.
+fn account_extension(name: &str) -> Result<ExtensionType> {
+    match name {
+        "memo-transfer" => Ok(ExtensionType::MemoTransfer),
+        "cpi-guard" => Ok(ExtensionType::CpiGuard),
+        other => Err(anyhow!("Unknown account extension '{}'; use memo-transfer or cpi-guard", other)),
+    }
+}
+
+impl TokenOperations {
+    /// Grow a Token-2022 account owned by the wallet so it has room for
+    /// `extensions`, paying the extra rent from the wallet. The target is
+    /// `token_account`, or the wallet's ATA of `mint_address`. Extensions
+    /// already present are skipped; enabling them is a separate step.
+    pub fn reallocate_account(
+        &self,
+        token_account: Option<&str>,
+        mint_address: Option<&str>,
+        extensions: &[String],
+    ) -> Result<()> {
+        let owner = self.wallet.pubkey();
+        let address = match (token_account, mint_address) {
+            (Some(account), _) => Pubkey::from_str(account).map_err(|e| anyhow!("Invalid token account: {}", e))?,
+            (None, Some(mint)) => {
+                let mint = Pubkey::from_str(mint).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+                self.fetch_mint_info(&mint)?.associated_token_address(&owner)
+            }
+            (None, None) => return Err(anyhow!("Pass --token-account or --mint-address")),
+        };
+        if extensions.is_empty() {
+            return Err(anyhow!("Pass at least one --extension (memo-transfer or cpi-guard)"));
+        }
+
+        let account = self.client.get_account(&address)
+            .map_err(|e| anyhow!("Failed to get token account {}: {}", address, e))?;
+        if account.owner != spl_token_2022::id() {
+            return Err(anyhow!("{} is not a Token-2022 account; only those can be reallocated", address));
+        }
+        if unpack_token_account(&account.data)?.owner != owner {
+            return Err(anyhow!("Token account {} is not owned by the loaded wallet", address));
+        }
+
+        let present: Vec<u16> = token_account_extensions(&account.data)?
+            .into_iter()
+            .map(|(extension, _)| extension)
+            .collect();
+        let mut missing = Vec::new();
+        for name in extensions {
+            let extension = account_extension(name)?;
+            if present.contains(&(extension as u16)) {
+                info!("{} already has room for {}", address, extension_name(extension as u16));
+            } else if !missing.contains(&extension) {
+                missing.push(extension);
+            }
+        }
+        if missing.is_empty() {
+            info!("Nothing to reallocate");
+            return Ok(());
+        }
+
+        let instruction = reallocate(&spl_token_2022::id(), &address, &owner, &owner, &[], &missing)?;
+        let signature = self
+            .send_instructions(vec![instruction], REALLOCATE_UNITS)
+            .map_err(|e| anyhow!("Failed to reallocate {}: {}", address, e))?;
+        info!("Reallocated {} for {:?}! Signature: {}", address, missing, signature);
+        Ok(())
+    }
+}
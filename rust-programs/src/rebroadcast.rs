@@ -0,0 +1,167 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature, transaction::VersionedTransaction};
+use std::{str::FromStr, thread, time::Duration};
+
+use crate::{
+    audit,
+    input::read_input,
+    output::{write_output, OutputFormat},
+    receipts::{decode_transaction, PendingTransaction},
+    TokenOperations,
+};
+
+/// Delay between resends while the transaction is unprocessed.
+const REBROADCAST_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What became of a rebroadcast transaction.
+#[derive(Debug, Clone, Serialize)]
+pub struct RebroadcastOutcome {
+    pub signature: String,
+    /// "confirmed", "failed" or "expired".
+    pub outcome: &'static str,
+    pub resends: u32,
+    pub slot: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl TokenOperations {
+    /// Resend a signed transaction that has not landed until it confirms or
+    /// its blockhash expires. The transaction comes from `input` (a pending
+    /// file saved under `--receipts-dir`, or a base64 transaction) or, with
+    /// only a signature, from the pending file in the receipts directory.
+    ///
+    /// An expired transaction can never land, so the operation that built
+    /// it is safe to run again.
+    pub fn rebroadcast(
+        &self,
+        signature: Option<&str>,
+        input: Option<&str>,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let signature = signature
+            .map(|signature| Signature::from_str(signature).map_err(|e| anyhow!("Invalid signature: {}", e)))
+            .transpose()?;
+        let (transaction, last_valid_block_height) = self.load_pending(signature.as_ref(), input)?;
+        let signature = transaction.signatures[0];
+
+        let mut outcome = RebroadcastOutcome {
+            signature: signature.to_string(),
+            outcome: "expired",
+            resends: 0,
+            slot: None,
+            error: None,
+        };
+        let config = RpcSendTransactionConfig { skip_preflight: true, max_retries: Some(0), ..Default::default() };
+
+        loop {
+            let status = self
+                .client
+                .get_signature_statuses(&[signature])
+                .map_err(|e| anyhow!("Failed to get status of {}: {}", signature, e))?
+                .value
+                .remove(0);
+            if let Some(status) = status {
+                if let Some(e) = &status.err {
+                    outcome.outcome = "failed";
+                    outcome.error = Some(e.to_string());
+                    outcome.slot = Some(status.slot);
+                    break;
+                }
+                if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                    outcome.outcome = "confirmed";
+                    outcome.slot = Some(status.slot);
+                    break;
+                }
+            } else {
+                let valid = match last_valid_block_height {
+                    Some(height) => {
+                        self.client
+                            .get_block_height_with_commitment(CommitmentConfig::confirmed())
+                            .map_err(|e| anyhow!("Failed to get block height: {}", e))?
+                            <= height
+                    }
+                    None => self
+                        .client
+                        .is_blockhash_valid(transaction.message.recent_blockhash(), CommitmentConfig::processed())
+                        .map_err(|e| anyhow!("Failed to check blockhash: {}", e))?,
+                };
+                if !valid {
+                    break;
+                }
+                match self.client.send_transaction_with_config(&transaction, config) {
+                    Ok(_) => outcome.resends += 1,
+                    Err(e) => warn!("Resend of {} failed: {}", signature, e),
+                }
+            }
+            thread::sleep(REBROADCAST_INTERVAL);
+        }
+
+        match outcome.outcome {
+            "confirmed" => info!("{} confirmed at slot {} after {} resend(s)", signature, outcome.slot.unwrap_or(0), outcome.resends),
+            "failed" => warn!("{} landed but failed: {}", signature, outcome.error.as_deref().unwrap_or("")),
+            _ => warn!("{} expired unprocessed after {} resend(s); it can never land and may be rebuilt", signature, outcome.resends),
+        }
+        // Only runs that resent the transaction submitted anything.
+        if outcome.resends > 0 {
+            match outcome.outcome {
+                "confirmed" => audit::record_transaction(Ok(&signature)),
+                "failed" => audit::record_transaction(Err(outcome.error.as_deref().unwrap_or("failed"))),
+                _ => audit::record_transaction(Err("expired without landing")),
+            }
+        }
+        if let Some(receipts) = &self.receipts {
+            if outcome.outcome == "confirmed" {
+                match receipts.write(&self.client, &signature) {
+                    Ok(path) => info!("Receipt written to {}", path.display()),
+                    Err(e) => warn!("Failed to write receipt for {}: {}", signature, e),
+                }
+            }
+            receipts.clear_pending(&signature);
+        }
+
+        write_output(output, &outcome, std::slice::from_ref(&outcome))?;
+        match outcome.outcome {
+            "confirmed" => Ok(()),
+            "failed" => Err(anyhow!("Transaction {} failed", signature)),
+            _ => Err(anyhow!("Transaction {} expired without landing", signature)),
+        }
+    }
+
+    /// The transaction to rebroadcast and, for saved pending files, the
+    /// block height its blockhash expires at.
+    fn load_pending(&self, signature: Option<&Signature>, input: Option<&str>) -> Result<(VersionedTransaction, Option<u64>)> {
+        let contents = match (input, signature, &self.receipts) {
+            (Some(path), _, _) => read_input(path)?,
+            (None, Some(signature), Some(receipts)) => {
+                let path = receipts.pending_path(signature);
+                read_input(&path.to_string_lossy())
+                    .map_err(|_| anyhow!("No saved transaction for {} in --receipts-dir", signature))?
+            }
+            (None, Some(_), None) => {
+                return Err(anyhow!("Pass --receipts-dir holding the saved transaction, or the transaction file"))
+            }
+            (None, None, _) => return Err(anyhow!("--signature or a saved transaction file is required")),
+        };
+
+        let (transaction, last_valid_block_height) = match serde_json::from_str::<PendingTransaction>(&contents) {
+            Ok(pending) => (pending.decode()?, Some(pending.last_valid_block_height)),
+            Err(_) => (decode_transaction(&contents)?, None),
+        };
+
+        if transaction.signatures.is_empty() {
+            return Err(anyhow!("The transaction is not signed"));
+        }
+        if let Some(signature) = signature {
+            if transaction.signatures[0] != *signature {
+                return Err(anyhow!("The saved transaction has signature {}, not {}", transaction.signatures[0], signature));
+            }
+        }
+        if !transaction.verify_with_results().iter().all(|valid| *valid) {
+            return Err(anyhow!("The transaction's signatures are incomplete or invalid"));
+        }
+        Ok((transaction, last_valid_block_height))
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{signature::Signature, transaction::VersionedTransaction};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{history::fetch_transaction, output::write_json};
+
+/// A token balance held by an account after the transaction.
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenState {
+    pub mint: String,
+    pub owner: Option<String>,
+    pub amount: String,
+}
+
+/// A writable account's state after the transaction.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountState {
+    pub address: String,
+    pub lamports: Option<u64>,
+    pub token: Option<TokenState>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Receipt {
+    pub operation: String,
+    pub parameters: BTreeMap<String, Vec<String>>,
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub fee: Option<u64>,
+    pub status: String,
+    pub fee_payer: Option<String>,
+    pub accounts: Vec<AccountState>,
+}
+
+/// A signed transaction saved before it is sent, so rebroadcast can
+/// resubmit it if the tool stops before it confirms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTransaction {
+    pub operation: String,
+    pub signature: String,
+    /// Base64 of the bincode-serialized transaction.
+    pub transaction: String,
+    /// Block height after which the transaction's blockhash has expired.
+    pub last_valid_block_height: u64,
+}
+
+impl PendingTransaction {
+    pub fn decode(&self) -> Result<VersionedTransaction> {
+        decode_transaction(&self.transaction)
+    }
+}
+
+/// Decode a base64 bincode-serialized transaction.
+pub fn decode_transaction(encoded: &str) -> Result<VersionedTransaction> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| anyhow!("Invalid base64 transaction: {}", e))?;
+    bincode::deserialize(&bytes).map_err(|e| anyhow!("Failed to decode transaction: {}", e))
+}
+
+/// Writes one receipt JSON per confirmed transaction into a directory.
+///
+/// Each signed transaction is also saved as `pending-<signature>.json`
+/// before it is sent and removed once it lands, so whatever is left there
+/// after an interrupted or timed-out run can be rebroadcast.
+#[derive(Debug, Clone)]
+pub struct Receipts {
+    dir: PathBuf,
+    operation: String,
+    parameters: BTreeMap<String, Vec<String>>,
+}
+
+impl Receipts {
+    pub fn new(dir: &str, operation: &str, parameters: BTreeMap<String, Vec<String>>) -> Result<Self> {
+        fs::create_dir_all(dir).map_err(|e| anyhow!("Failed to create receipts directory {}: {}", dir, e))?;
+        Ok(Self { dir: Path::new(dir).to_path_buf(), operation: operation.to_string(), parameters })
+    }
+
+    pub fn pending_path(&self, signature: &Signature) -> PathBuf {
+        self.dir.join(format!("pending-{}.json", signature))
+    }
+
+    /// Save a signed transaction before sending it, returning the path.
+    pub fn save_pending(&self, transaction: &VersionedTransaction, last_valid_block_height: u64) -> Result<PathBuf> {
+        let bytes = bincode::serialize(transaction).map_err(|e| anyhow!("Failed to serialize transaction: {}", e))?;
+        let pending = PendingTransaction {
+            operation: self.operation.clone(),
+            signature: transaction.signatures[0].to_string(),
+            transaction: STANDARD.encode(bytes),
+            last_valid_block_height,
+        };
+        let path = self.pending_path(&transaction.signatures[0]);
+        write_json(&path.to_string_lossy(), &pending)?;
+        Ok(path)
+    }
+
+    /// Forget a saved transaction once it has landed or can no longer land.
+    pub fn clear_pending(&self, signature: &Signature) {
+        let _ = fs::remove_file(self.pending_path(signature));
+    }
+
+    /// Fetch the landed transaction and write its receipt, returning the path.
+    pub fn write(&self, client: &RpcClient, signature: &Signature) -> Result<PathBuf> {
+        let transaction = fetch_transaction(client, &signature.to_string())?;
+        let meta = transaction.transaction.transaction.meta.as_ref();
+        let post_balances = meta.map(|meta| meta.post_balances.clone()).unwrap_or_default();
+        let token_balances = transaction.post_token_balances();
+        let message = &transaction.decoded.message;
+
+        let accounts = transaction
+            .account_keys
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| message.is_maybe_writable(*index))
+            .map(|(index, address)| AccountState {
+                address: address.to_string(),
+                lamports: post_balances.get(index).copied(),
+                token: token_balances
+                    .iter()
+                    .find(|balance| balance.account_index as usize == index)
+                    .map(|balance| TokenState {
+                        mint: balance.mint.clone(),
+                        owner: Option::<String>::from(balance.owner.clone()),
+                        amount: balance.ui_token_amount.ui_amount_string.clone(),
+                    }),
+            })
+            .collect();
+
+        let receipt = Receipt {
+            operation: self.operation.clone(),
+            parameters: self.parameters.clone(),
+            signature: signature.to_string(),
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+            fee: meta.map(|meta| meta.fee),
+            status: match meta.and_then(|meta| meta.err.as_ref()) {
+                Some(e) => format!("failed: {}", e),
+                None => "ok".to_string(),
+            },
+            fee_payer: transaction.account_keys.first().map(|key| key.to_string()),
+            accounts,
+        };
+
+        let timestamp = transaction.block_time.unwrap_or_else(|| Utc::now().timestamp());
+        let path = self.dir.join(format!("{}-{}-{}.json", timestamp, self.operation, signature));
+        write_json(&path.to_string_lossy(), &receipt)?;
+        Ok(path)
+    }
+}
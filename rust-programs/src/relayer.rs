@@ -0,0 +1,156 @@
+use anyhow::{anyhow, Result};
+use base58::ToBase58;
+use log::info;
+use serde::Deserialize;
+use serde_json::json;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::{Signature, Signer}, transaction::Transaction};
+use spl_token_2022::instruction::transfer_checked;
+use std::{str::FromStr, time::Duration};
+
+use crate::{holders::format_amount, TokenOperations};
+
+/// Relayer request timeout; a transfer is only answered once it is sent.
+const RELAYER_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RelayerConfig {
+    fee_payer: String,
+    endpoints: RelayerEndpoints,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelayerEndpoints {
+    transfer: TransferEndpoint,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransferEndpoint {
+    #[serde(default)]
+    tokens: Vec<FeeToken>,
+}
+
+/// A token the relayer accepts as payment, with its fee in raw units.
+#[derive(Debug, Clone, Deserialize)]
+struct FeeToken {
+    mint: String,
+    account: String,
+    decimals: u8,
+    fee: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransferResponse {
+    signature: Option<String>,
+    message: Option<String>,
+}
+
+/// An Octane-compatible fee relayer, from `--fee-relayer` and `--fee-token`.
+///
+/// The relayer is the fee payer of every transaction. In exchange, each
+/// transaction starts with a TransferChecked of the relayer's fee from the
+/// wallet's ATA of the fee token to the relayer's fee account, so the wallet
+/// needs no SOL for fees. Rent for accounts the wallet creates is still paid
+/// by the wallet, as relayers refuse to fund anything but signatures.
+#[derive(Debug)]
+pub struct FeeRelayer {
+    http: reqwest::blocking::Client,
+    url: String,
+    pub fee_payer: Pubkey,
+    fee_instruction: Instruction,
+}
+
+impl FeeRelayer {
+    /// The instruction paying the relayer, placed first in every transaction.
+    pub fn fee_instruction(&self) -> &Instruction {
+        &self.fee_instruction
+    }
+
+    /// Hand a transaction signed by everyone except the fee payer to the
+    /// relayer, which co-signs and sends it.
+    pub fn submit(&self, transaction: &Transaction) -> std::result::Result<Signature, String> {
+        let encoded = bincode::serialize(transaction).map_err(|e| format!("Failed to serialize transaction: {}", e))?;
+        let response = self.http
+            .post(format!("{}/api/transfer", self.url))
+            .json(&json!({ "transaction": encoded.to_base58() }))
+            .send()
+            .map_err(|e| format!("Fee relayer request failed: {}", e))?;
+
+        let status = response.status();
+        let body: TransferResponse = response.json().map_err(|e| format!("Invalid fee relayer response: {}", e))?;
+        if !status.is_success() {
+            return Err(format!(
+                "Fee relayer rejected the transaction ({}): {}",
+                status,
+                body.message.unwrap_or_default()
+            ));
+        }
+        let signature = body.signature.ok_or_else(|| "Fee relayer returned no signature".to_string())?;
+        Signature::from_str(&signature).map_err(|e| format!("Fee relayer returned an invalid signature: {}", e))
+    }
+}
+
+impl TokenOperations {
+    /// Route every transaction through the fee relayer at `url`, paying it in
+    /// `fee_token` or, when none is given, the first token it accepts.
+    pub fn load_fee_relayer(&mut self, url: Option<&String>, fee_token: Option<&String>) -> Result<()> {
+        let Some(url) = url else {
+            return Ok(());
+        };
+        if !self.lookup_tables.is_empty() {
+            return Err(anyhow!("--fee-relayer sends legacy transactions and cannot be combined with --lookup-table"));
+        }
+        let url = url.trim_end_matches('/').to_string();
+        let http = reqwest::blocking::Client::builder()
+            .timeout(RELAYER_TIMEOUT)
+            .build()
+            .map_err(|e| anyhow!("Failed to build HTTP client: {}", e))?;
+
+        let config: RelayerConfig = http
+            .get(format!("{}/api", url))
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| anyhow!("Failed to get fee relayer config from {}: {}", url, e))?
+            .json()
+            .map_err(|e| anyhow!("Invalid fee relayer config: {}", e))?;
+
+        let token = match fee_token {
+            Some(mint) => config.endpoints.transfer.tokens.iter().find(|token| &token.mint == mint),
+            None => config.endpoints.transfer.tokens.first(),
+        }
+        .ok_or_else(|| match fee_token {
+            Some(mint) => anyhow!("Fee relayer does not accept {} as payment", mint),
+            None => anyhow!("Fee relayer accepts no tokens as payment"),
+        })?
+        .clone();
+
+        let fee_payer = Pubkey::from_str(&config.fee_payer)
+            .map_err(|e| anyhow!("Invalid fee payer from relayer: {}", e))?;
+        let mint = Pubkey::from_str(&token.mint).map_err(|e| anyhow!("Invalid fee token from relayer: {}", e))?;
+        let fee_account = Pubkey::from_str(&token.account)
+            .map_err(|e| anyhow!("Invalid fee account from relayer: {}", e))?;
+
+        let mint_info = self.fetch_mint_info(&mint)?;
+        let source = mint_info.associated_token_address(&self.wallet.pubkey());
+        let fee_instruction = transfer_checked(
+            &mint_info.program_id,
+            &source,
+            &mint,
+            &fee_account,
+            &self.wallet.pubkey(),
+            &[],
+            token.fee,
+            token.decimals,
+        )?;
+
+        info!(
+            "Fees paid by relayer {} ({}) for {} of {} per transaction",
+            url,
+            fee_payer,
+            format_amount(token.fee, token.decimals),
+            mint
+        );
+        self.relayer = Some(FeeRelayer { http, url, fee_payer, fee_instruction });
+        Ok(())
+    }
+}
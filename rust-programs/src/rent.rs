@@ -0,0 +1,224 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    native_token::lamports_to_sol,
+    nonce::{state::Versions, State as NonceState},
+    program_option::COption,
+    pubkey::Pubkey,
+    signature::Signer,
+    system_instruction, system_program,
+};
+use spl_token_2022::{instruction::close_account, state::AccountState};
+use std::collections::HashMap;
+
+use crate::{
+    output::{write_output, OutputFormat},
+    prompt::confirm,
+    TokenOperations,
+};
+
+/// Size of a system-program nonce account.
+const NONCE_ACCOUNT_LEN: u64 = 80;
+
+/// Byte offset of the authority in a nonce account (after the version and state tags).
+const NONCE_AUTHORITY_OFFSET: usize = 8;
+
+/// Estimated compute units for a CloseAccount instruction.
+const CLOSE_UNITS: u32 = 5_000;
+
+/// Estimated compute units for a WithdrawNonceAccount instruction.
+const NONCE_WITHDRAW_UNITS: u32 = 5_000;
+
+/// One account held by the wallet and whether its lamports can be reclaimed.
+#[derive(Debug, Clone, Serialize)]
+pub struct RentItem {
+    pub account: String,
+    pub kind: String,
+    pub mint: Option<String>,
+    pub lamports: u64,
+    pub closable: bool,
+    pub note: String,
+    pub status: String,
+    pub signature: Option<String>,
+}
+
+/// How a closable account is closed.
+enum Reclaim {
+    TokenAccount { program_id: Pubkey },
+    Nonce,
+}
+
+impl TokenOperations {
+    /// Report how much SOL is locked in the wallet's token and nonce accounts
+    /// and which of them can be closed, closing them when `execute` is set.
+    pub fn rent_report(&self, execute: bool, assume_yes: bool, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let wallet = self.wallet.pubkey();
+        let mut items: Vec<RentItem> = Vec::new();
+        let mut reclaims: Vec<(usize, Pubkey, Reclaim)> = Vec::new();
+
+        for owned in self.owned_token_accounts(&wallet, None)? {
+            let account = &owned.account;
+            let native = account.is_native();
+            let close_authority_ok = match account.close_authority {
+                COption::Some(authority) => authority == wallet,
+                COption::None => true,
+            };
+
+            let (closable, note) = if account.state == AccountState::Frozen {
+                (false, "frozen".to_string())
+            } else if !close_authority_ok {
+                (false, "close authority is another key".to_string())
+            } else if native {
+                (true, "wrapped SOL; closing unwraps the balance".to_string())
+            } else if account.amount > 0 {
+                (false, format!("holds {} tokens", account.amount))
+            } else {
+                (true, "empty".to_string())
+            };
+
+            if closable {
+                reclaims.push((items.len(), owned.address, Reclaim::TokenAccount { program_id: owned.program_id }));
+            }
+            items.push(RentItem {
+                account: owned.address.to_string(),
+                kind: if native { "wrapped-sol" } else { "token-account" }.to_string(),
+                mint: Some(account.mint.to_string()),
+                lamports: owned.lamports,
+                closable,
+                note,
+                status: "found".to_string(),
+                signature: None,
+            });
+        }
+
+        match self.nonce_accounts(&wallet) {
+            Ok(nonces) => {
+                for (address, lamports) in nonces {
+                    reclaims.push((items.len(), address, Reclaim::Nonce));
+                    items.push(RentItem {
+                        account: address.to_string(),
+                        kind: "nonce".to_string(),
+                        mint: None,
+                        lamports,
+                        closable: true,
+                        note: "durable nonce with this wallet as authority".to_string(),
+                        status: "found".to_string(),
+                        signature: None,
+                    });
+                }
+            }
+            // Some RPC providers refuse getProgramAccounts on the system program.
+            Err(e) => warn!("Skipping nonce accounts: {}", e),
+        }
+
+        let recoverable: u64 = items.iter().filter(|item| item.closable).map(|item| item.lamports).sum();
+        let locked: u64 = items.iter().filter(|item| !item.closable).map(|item| item.lamports).sum();
+
+        info!("Found {} account(s) owned by {}:", items.len(), wallet);
+        for item in &items {
+            info!(
+                "  {} [{}] {} SOL - {}{}",
+                item.account,
+                item.kind,
+                lamports_to_sol(item.lamports),
+                item.note,
+                if item.closable { " (closable)" } else { "" }
+            );
+        }
+        info!("Recoverable now: {} SOL across {} account(s)", lamports_to_sol(recoverable), reclaims.len());
+        info!("Locked in accounts that must be emptied first: {} SOL", lamports_to_sol(locked));
+
+        if execute && !reclaims.is_empty() {
+            confirm(
+                &[format!(
+                    "Close {} account(s) and return {} SOL to {}",
+                    reclaims.len(),
+                    lamports_to_sol(recoverable),
+                    wallet
+                )],
+                assume_yes,
+            )?;
+
+            // batcher item index -> item index
+            let mut queued: HashMap<usize, usize> = HashMap::new();
+            let mut batcher = self.batcher();
+            for (index, address, reclaim) in &reclaims {
+                let (instruction, units) = match reclaim {
+                    Reclaim::TokenAccount { program_id } => {
+                        (close_account(program_id, address, &wallet, &wallet, &[])?, CLOSE_UNITS)
+                    }
+                    Reclaim::Nonce => (
+                        system_instruction::withdraw_nonce_account(address, &wallet, &wallet, items[*index].lamports),
+                        NONCE_WITHDRAW_UNITS,
+                    ),
+                };
+                let item = batcher.push(vec![instruction], units)?;
+                queued.insert(item, *index);
+            }
+
+            let mut reclaimed = 0;
+            for batch in batcher.finish() {
+                for item in &batch.items {
+                    let Some(index) = queued.get(item) else {
+                        continue;
+                    };
+                    let rent_item = &mut items[*index];
+                    match &batch.signature {
+                        Ok(signature) => {
+                            rent_item.status = "closed".to_string();
+                            rent_item.signature = Some(signature.to_string());
+                            reclaimed += rent_item.lamports;
+                        }
+                        Err(e) => rent_item.status = format!("failed: {}", e),
+                    }
+                }
+            }
+            info!("Reclaimed {} SOL", lamports_to_sol(reclaimed));
+        } else if !reclaims.is_empty() {
+            info!("Re-run with --execute to close the closable accounts");
+        }
+
+        write_output(output, &items, &items)?;
+
+        let failed = items.iter().filter(|item| item.status.starts_with("failed")).count();
+        if failed > 0 {
+            return Err(anyhow!("{} account(s) could not be closed", failed));
+        }
+        Ok(())
+    }
+
+    /// Initialized nonce accounts whose authority is `authority`, with their balances.
+    fn nonce_accounts(&self, authority: &Pubkey) -> Result<Vec<(Pubkey, u64)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(NONCE_ACCOUNT_LEN),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(NONCE_AUTHORITY_OFFSET, authority.as_ref())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let accounts = self.client.get_program_accounts_with_config(&system_program::id(), config)
+            .map_err(|e| anyhow!("Failed to get nonce accounts: {}", e))?;
+
+        Ok(accounts
+            .into_iter()
+            .filter(|(_, account)| {
+                matches!(
+                    bincode::deserialize::<Versions>(&account.data).map(|versions| versions.state().clone()),
+                    Ok(NonceState::Initialized(data)) if data.authority == *authority
+                )
+            })
+            .map(|(address, account)| (address, account.lamports))
+            .collect())
+    }
+}
@@ -0,0 +1,379 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use log::info;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+    str::FromStr,
+};
+
+use crate::{
+    events::{extract_events, EventKind, TokenEvent},
+    holders::format_amount,
+    index::Index,
+    output::{write_output, OutputFormat},
+    TokenOperations,
+};
+
+/// Number of largest transfers listed in an activity report.
+const LARGEST_TRANSFERS: usize = 10;
+
+/// Number of largest burns listed in a burn report.
+const LARGEST_BURNS: usize = 10;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyActivity {
+    pub date: String,
+    pub transfers: usize,
+    pub volume: String,
+    pub unique_senders: usize,
+    pub unique_receivers: usize,
+    pub mints: usize,
+    pub burns: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeTransfer {
+    pub signature: String,
+    pub date: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub amount: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityReport {
+    pub mint: String,
+    pub since: String,
+    pub total_transfers: usize,
+    pub total_volume: String,
+    pub days: Vec<DailyActivity>,
+    pub largest_transfers: Vec<LargeTransfer>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyBurns {
+    pub date: String,
+    pub burns: usize,
+    pub burned: String,
+    /// Burned from the start of the window to the end of this day.
+    pub cumulative: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeBurn {
+    pub signature: String,
+    pub date: String,
+    pub from: Option<String>,
+    pub amount: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BurnReport {
+    pub mint: String,
+    pub since: String,
+    pub total_burns: usize,
+    pub total_burned: String,
+    pub current_supply: String,
+    /// Share of current supply plus burned tokens that has been burned in the window.
+    pub burned_pct: f64,
+    pub daily_average: String,
+    pub days: Vec<DailyBurns>,
+    pub largest_burns: Vec<LargeBurn>,
+}
+
+/// Parse `--since` as a relative window (`7d`, `12h`) or a `YYYY-MM-DD` date,
+/// returning a unix timestamp.
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<i64> {
+    let value = value.trim();
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let start = date
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(|| anyhow!("Invalid date: {}", value))?;
+        return Ok(start.and_utc().timestamp());
+    }
+
+    let seconds = parse_duration(value)
+        .map_err(|_| anyhow!("Invalid --since value '{}'; use e.g. 7d, 12h or 2024-01-31", value))?;
+
+    Ok(now.timestamp() - seconds)
+}
+
+/// Parse a relative duration such as `7d`, `12h` or `30m` into seconds.
+pub fn parse_duration(value: &str) -> Result<i64> {
+    let value = value.trim();
+    if !value.is_ascii() || value.is_empty() {
+        return Err(anyhow!("Invalid duration '{}'", value));
+    }
+
+    let (number, unit) = value.split_at(value.len() - 1);
+    let number: i64 = number
+        .parse()
+        .map_err(|_| anyhow!("Invalid duration '{}'; use e.g. 7d, 12h or 30m", value))?;
+    match unit {
+        "d" => Ok(number * 86_400),
+        "h" => Ok(number * 3_600),
+        "m" => Ok(number * 60),
+        _ => Err(anyhow!("Invalid duration unit in '{}'; use d, h or m", value)),
+    }
+}
+
+pub fn format_date(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .map(|time| time.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Aggregate indexed events into per-day activity.
+pub fn build_activity_report(mint: &str, since: i64, decimals: u8, events: &[TokenEvent]) -> ActivityReport {
+    #[derive(Default)]
+    struct Day {
+        transfers: usize,
+        volume: u128,
+        senders: HashSet<String>,
+        receivers: HashSet<String>,
+        mints: usize,
+        burns: usize,
+    }
+
+    let mut days: BTreeMap<String, Day> = BTreeMap::new();
+    let mut transfers: Vec<&TokenEvent> = Vec::new();
+
+    for event in events {
+        let date = event.block_time.map(format_date).unwrap_or_else(|| "unknown".to_string());
+        let day = days.entry(date).or_default();
+
+        match event.kind {
+            EventKind::Transfer => {
+                day.transfers += 1;
+                day.volume += event.amount as u128;
+                if let Some(sender) = event.source_owner.as_ref().or(event.source.as_ref()) {
+                    day.senders.insert(sender.clone());
+                }
+                if let Some(receiver) = event.destination_owner.as_ref().or(event.destination.as_ref()) {
+                    day.receivers.insert(receiver.clone());
+                }
+                transfers.push(event);
+            }
+            EventKind::MintTo => day.mints += 1,
+            EventKind::Burn => day.burns += 1,
+        }
+    }
+
+    transfers.sort_by_key(|event| Reverse(event.amount));
+    let total_volume: u128 = transfers.iter().map(|event| event.amount as u128).sum();
+
+    ActivityReport {
+        mint: mint.to_string(),
+        since: format_date(since),
+        total_transfers: transfers.len(),
+        total_volume: format_wide_amount(total_volume, decimals),
+        days: days
+            .into_iter()
+            .map(|(date, day)| DailyActivity {
+                date,
+                transfers: day.transfers,
+                volume: format_wide_amount(day.volume, decimals),
+                unique_senders: day.senders.len(),
+                unique_receivers: day.receivers.len(),
+                mints: day.mints,
+                burns: day.burns,
+            })
+            .collect(),
+        largest_transfers: transfers
+            .iter()
+            .take(LARGEST_TRANSFERS)
+            .map(|event| LargeTransfer {
+                signature: event.signature.clone(),
+                date: event.block_time.map(format_date).unwrap_or_else(|| "unknown".to_string()),
+                from: event.source_owner.clone().or_else(|| event.source.clone()),
+                to: event.destination_owner.clone().or_else(|| event.destination.clone()),
+                amount: format_amount(event.amount, decimals),
+            })
+            .collect(),
+    }
+}
+
+/// Aggregate Burn events into totals, a per-day burn rate and the largest burns.
+pub fn build_burn_report(mint: &str, since: i64, now: i64, decimals: u8, supply: u64, events: &[TokenEvent]) -> BurnReport {
+    let mut burns: Vec<&TokenEvent> = events.iter().filter(|event| event.kind == EventKind::Burn).collect();
+
+    let mut days: BTreeMap<String, (usize, u128)> = BTreeMap::new();
+    for event in &burns {
+        let date = event.block_time.map(format_date).unwrap_or_else(|| "unknown".to_string());
+        let day = days.entry(date).or_default();
+        day.0 += 1;
+        day.1 += event.amount as u128;
+    }
+
+    let total_burned: u128 = burns.iter().map(|event| event.amount as u128).sum();
+    let window_days = ((now - since) as f64 / SECONDS_PER_DAY as f64).max(1.0);
+    let burned_pct = match supply as u128 + total_burned {
+        0 => 0.0,
+        minted => total_burned as f64 / minted as f64 * 100.0,
+    };
+
+    let mut cumulative = 0u128;
+    let days = days
+        .into_iter()
+        .map(|(date, (count, burned))| {
+            cumulative += burned;
+            DailyBurns {
+                date,
+                burns: count,
+                burned: format_wide_amount(burned, decimals),
+                cumulative: format_wide_amount(cumulative, decimals),
+            }
+        })
+        .collect();
+
+    burns.sort_by_key(|event| Reverse(event.amount));
+
+    BurnReport {
+        mint: mint.to_string(),
+        since: format_date(since),
+        total_burns: burns.len(),
+        total_burned: format_wide_amount(total_burned, decimals),
+        current_supply: format_amount(supply, decimals),
+        burned_pct,
+        daily_average: format_wide_amount((total_burned as f64 / window_days) as u128, decimals),
+        days,
+        largest_burns: burns
+            .iter()
+            .take(LARGEST_BURNS)
+            .map(|event| LargeBurn {
+                signature: event.signature.clone(),
+                date: event.block_time.map(format_date).unwrap_or_else(|| "unknown".to_string()),
+                from: event.source_owner.clone().or_else(|| event.authority.clone()),
+                amount: format_amount(event.amount, decimals),
+            })
+            .collect(),
+    }
+}
+
+/// Volumes can exceed u64 when summed; fall back to raw units if they do.
+fn format_wide_amount(amount: u128, decimals: u8) -> String {
+    match u64::try_from(amount) {
+        Ok(amount) => format_amount(amount, decimals),
+        Err(_) => format!("{} (raw units)", amount),
+    }
+}
+
+impl TokenOperations {
+    /// Mint decimals from the local index when available, otherwise from the chain.
+    pub fn mint_decimals(&self, index: &Index, mint_address: &str) -> Result<u8> {
+        if let Some(decimals) = index.decimals(mint_address)? {
+            return Ok(decimals);
+        }
+
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        Ok(self.fetch_mint_info(&mint_pubkey)?.base()?.decimals)
+    }
+
+    pub fn report_activity(
+        &self,
+        mint_address: &str,
+        index_path: &str,
+        since: &str,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let index = Index::open(index_path)?;
+        let since = parse_since(since, Utc::now())?;
+        let decimals = self.mint_decimals(&index, mint_address)?;
+
+        let events = index.events(mint_address, Some(since))?;
+        if events.is_empty() {
+            info!("No indexed events since {}; run index-events first", format_date(since));
+        }
+
+        let report = build_activity_report(mint_address, since, decimals, &events);
+
+        info!("Activity for {} since {}:", report.mint, report.since);
+        info!("  Transfers: {} (volume {})", report.total_transfers, report.total_volume);
+        for day in &report.days {
+            info!(
+                "  {}: {} transfer(s), volume {}, {} sender(s), {} receiver(s), {} mint(s), {} burn(s)",
+                day.date, day.transfers, day.volume, day.unique_senders, day.unique_receivers, day.mints, day.burns
+            );
+        }
+        info!("Largest transfers:");
+        for (i, transfer) in report.largest_transfers.iter().enumerate() {
+            info!(
+                "  {}. {} from {} to {} on {} ({})",
+                i + 1,
+                transfer.amount,
+                transfer.from.as_deref().unwrap_or("?"),
+                transfer.to.as_deref().unwrap_or("?"),
+                transfer.date,
+                transfer.signature
+            );
+        }
+
+        write_output(output, &report, &report.days)?;
+
+        Ok(())
+    }
+
+    /// Report burns since `since` from the local event index, or straight
+    /// from the mint's transaction history when the mint is not indexed.
+    /// Every Burn lists the mint account, so the mint's own signatures are
+    /// enough to find them all.
+    pub fn report_burns(
+        &self,
+        mint_address: &str,
+        index_path: &str,
+        since: &str,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let now = Utc::now().timestamp();
+        let since = parse_since(since, Utc::now())?;
+        let supply = token_ops_core::rpc::fetch_supply(&self.client, &mint_pubkey)?;
+
+        let index = Index::open(index_path)?;
+        let events = if !index.events_indexed(mint_address)? {
+            info!("Mint is not in the event index; scanning its history since {}", format_date(since));
+            let signatures = self.signatures_in_window(&mint_pubkey, since)?;
+            let mut events = Vec::new();
+            for status in signatures.iter().filter(|status| status.err.is_none()) {
+                let transaction = self.fetch_transaction(&status.signature)?;
+                if !transaction.failed() {
+                    events.extend(extract_events(&transaction, &mint_pubkey));
+                }
+            }
+            events
+        } else {
+            index.events(mint_address, Some(since))?
+        };
+
+        let report = build_burn_report(mint_address, since, now, supply.decimals, supply.amount, &events);
+
+        info!("Burns for {} since {}:", report.mint, report.since);
+        info!("  Burned: {} in {} burn(s) ({:.4}% of supply)", report.total_burned, report.total_burns, report.burned_pct);
+        info!("  Average per day: {}", report.daily_average);
+        info!("  Current supply: {}", report.current_supply);
+        for day in &report.days {
+            info!("  {}: {} burn(s), {} burned, {} cumulative", day.date, day.burns, day.burned, day.cumulative);
+        }
+        info!("Largest burns:");
+        for (i, burn) in report.largest_burns.iter().enumerate() {
+            info!(
+                "  {}. {} by {} on {} ({})",
+                i + 1,
+                burn.amount,
+                burn.from.as_deref().unwrap_or("?"),
+                burn.date,
+                burn.signature
+            );
+        }
+
+        write_output(output, &report, &report.days)
+    }
+}
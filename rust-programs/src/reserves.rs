@@ -0,0 +1,174 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::{info, warn};
+use serde::Serialize;
+use solana_sdk::{hash::hash, pubkey::Pubkey, signature::{Keypair, Signer}};
+use std::collections::BTreeMap;
+
+use crate::{
+    message::sign_offchain,
+    output::{write_output, OutputFormat},
+    token_program::{unpack_token_account, MintInfo},
+    TokenOperations,
+};
+
+/// ATAs fetched per getMultipleAccounts call.
+const FETCH_CHUNK: usize = 100;
+
+/// One treasury wallet's balance of one mint.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReserveBalance {
+    pub mint: String,
+    pub wallet: String,
+    pub token_account: String,
+    pub balance: String,
+    pub raw_amount: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MintReserves {
+    pub mint: String,
+    pub decimals: u8,
+    pub total: String,
+    pub raw_total: u64,
+}
+
+/// The attested part of the report; `report_hash` is the SHA-256 of its
+/// compact JSON encoding.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReservesBody {
+    pub generated_at: String,
+    pub slot: u64,
+    pub wallets: Vec<String>,
+    pub totals: Vec<MintReserves>,
+    pub balances: Vec<ReserveBalance>,
+}
+
+/// A wallet's offchain-message signature over the report hash.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReserveAttestation {
+    pub signer: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofOfReserves {
+    pub report: ReservesBody,
+    pub report_hash: String,
+    pub attestations: Vec<ReserveAttestation>,
+}
+
+impl TokenOperations {
+    /// Read the ATA balance of every treasury wallet for every mint and write
+    /// a report signed by the loaded wallet and any `signers`.
+    ///
+    /// Each attestation signs the base58 report hash as an offchain message,
+    /// so it can be checked with `verify-message --message <report_hash>`.
+    pub fn proof_of_reserves(
+        &self,
+        mints: &[Pubkey],
+        wallets: &[Pubkey],
+        signers: &[Keypair],
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        if mints.is_empty() {
+            return Err(anyhow!("No mints given; pass --mint-address or --mints-file"));
+        }
+        let wallets = if wallets.is_empty() { vec![self.wallet.pubkey()] } else { wallets.to_vec() };
+        let generated_at = Utc::now();
+        let now = generated_at.timestamp();
+
+        let mint_infos = mints
+            .iter()
+            .map(|mint| self.fetch_mint_info(mint))
+            .collect::<Result<Vec<MintInfo>>>()?;
+        let mut decimals = BTreeMap::new();
+        for info in &mint_infos {
+            decimals.insert(info.address, info.base()?.decimals);
+        }
+
+        let pairs: Vec<(&MintInfo, Pubkey, Pubkey)> = mint_infos
+            .iter()
+            .flat_map(|info| wallets.iter().map(move |wallet| (info, *wallet, info.associated_token_address(wallet))))
+            .collect();
+
+        // Pin every chunk to the slot of the first read so balances come from
+        // one point in time as closely as the RPC allows.
+        let mut slot: Option<u64> = None;
+        let mut balances = Vec::with_capacity(pairs.len());
+        for chunk in pairs.chunks(FETCH_CHUNK) {
+            let addresses: Vec<Pubkey> = chunk.iter().map(|(_, _, ata)| *ata).collect();
+            let response = self.client
+                .get_multiple_accounts_with_commitment(&addresses, self.client.commitment())
+                .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
+
+            match slot {
+                None => slot = Some(response.context.slot),
+                Some(first) if first != response.context.slot => {
+                    warn!("Balances span slots {} to {}", first, response.context.slot);
+                }
+                Some(_) => {}
+            }
+
+            for ((info, wallet, ata), account) in chunk.iter().zip(response.value) {
+                let amount = account
+                    .filter(|account| account.owner == info.program_id)
+                    .and_then(|account| unpack_token_account(&account.data).ok())
+                    .map(|account| account.amount)
+                    .unwrap_or(0);
+                balances.push(ReserveBalance {
+                    mint: info.address.to_string(),
+                    wallet: wallet.to_string(),
+                    token_account: ata.to_string(),
+                    balance: info.ui_amount(amount, now)?,
+                    raw_amount: amount,
+                });
+            }
+        }
+
+        let totals = mint_infos
+            .iter()
+            .map(|info| {
+                let mint = info.address.to_string();
+                let raw_total: u64 = balances.iter().filter(|balance| balance.mint == mint).map(|balance| balance.raw_amount).sum();
+                Ok(MintReserves {
+                    mint,
+                    decimals: decimals[&info.address],
+                    total: info.ui_amount(raw_total, now)?,
+                    raw_total,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        let body = ReservesBody {
+            generated_at: generated_at.to_rfc3339(),
+            slot: slot.unwrap_or_default(),
+            wallets: wallets.iter().map(|wallet| wallet.to_string()).collect(),
+            totals,
+            balances,
+        };
+        let report_hash = hash(&serde_json::to_vec(&body)?).to_string();
+
+        let mut attestations = Vec::new();
+        for keypair in std::iter::once(&self.wallet).chain(signers) {
+            attestations.push(ReserveAttestation {
+                signer: keypair.pubkey().to_string(),
+                signature: sign_offchain(keypair, &report_hash)?.to_string(),
+            });
+        }
+
+        info!("Reserves at slot {}:", body.slot);
+        for total in &body.totals {
+            info!("  {}: {}", total.mint, total.total);
+        }
+        info!("Report hash: {}", report_hash);
+        for attestation in &attestations {
+            info!("  Signed by {}: {}", attestation.signer, attestation.signature);
+        }
+
+        let report = ProofOfReserves { report: body, report_hash, attestations };
+        let default_path = format!("proof-of-reserves-{}.json", report.report.slot);
+        let output = output.unwrap_or((default_path.as_str(), OutputFormat::Json));
+        write_output(Some(output), &report, &report.report.balances)
+    }
+}
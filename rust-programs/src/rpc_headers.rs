@@ -0,0 +1,49 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use std::sync::OnceLock;
+
+/// Headers sent with every RPC and DAS request, from `--rpc-header` and
+/// `--rpc-basic-auth`. Set once at startup, before any client is built.
+///
+/// Every RPC call this tool makes goes over HTTP; it opens no RPC
+/// WebSocket connections (streaming uses Yellowstone gRPC with
+/// `--geyser-token`), so these headers cover all traffic to the node.
+static RPC_HEADERS: OnceLock<HeaderMap> = OnceLock::new();
+
+/// Parse `Name: value` headers and a `user:password` pair into the
+/// process-wide header map. Values are marked sensitive so they never
+/// appear in debug output.
+pub fn configure(headers: &[String], basic_auth: Option<&str>) -> Result<()> {
+    let mut map = HeaderMap::new();
+    for header in headers {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid --rpc-header '{}'; expected 'Name: value'", header))?;
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|e| anyhow!("Invalid header name '{}': {}", name.trim(), e))?;
+        let mut value = HeaderValue::from_str(value.trim())
+            .map_err(|e| anyhow!("Invalid value for header {}: {}", name, e))?;
+        value.set_sensitive(true);
+        map.append(name, value);
+    }
+
+    if let Some(credentials) = basic_auth {
+        if !credentials.contains(':') {
+            return Err(anyhow!("Invalid --rpc-basic-auth; expected 'user:password'"));
+        }
+        let mut value = HeaderValue::from_str(&format!("Basic {}", STANDARD.encode(credentials)))
+            .map_err(|e| anyhow!("Invalid basic auth credentials: {}", e))?;
+        value.set_sensitive(true);
+        map.insert(AUTHORIZATION, value);
+    }
+
+    RPC_HEADERS
+        .set(map)
+        .map_err(|_| anyhow!("RPC headers are already configured"))
+}
+
+/// The configured headers, empty when none were given.
+pub fn header_map() -> HeaderMap {
+    RPC_HEADERS.get().cloned().unwrap_or_default()
+}
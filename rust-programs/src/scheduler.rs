@@ -0,0 +1,363 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, env, fs, path::Path, process::Command, thread, time::Duration};
+
+use crate::pipeline::child_args;
+
+/// Arguments of the `scheduler` invocation itself that runs do not inherit.
+const NOT_INHERITED: &[&str] = &["operation", "schedule", "input", "yes"];
+
+/// Default file recording executed runs and each entry's progress.
+const DEFAULT_STATE_PATH: &str = "scheduler-state.json";
+
+/// How late an occurrence may start and still count as on time under `missed: skip`.
+const ON_TIME_GRACE: i64 = 120;
+
+/// Furthest back missed occurrences are looked for, and how many are replayed at most.
+const MAX_BACKFILL_WINDOW: i64 = 366 * 86_400;
+const MAX_BACKFILL_RUNS: usize = 1_000;
+
+#[derive(Debug, Deserialize)]
+struct Schedule {
+    #[serde(default = "default_state_path")]
+    state: String,
+    #[serde(default)]
+    vars: BTreeMap<String, serde_yaml::Value>,
+    entries: Vec<Entry>,
+}
+
+fn default_state_path() -> String {
+    DEFAULT_STATE_PATH.to_string()
+}
+
+#[derive(Debug, Deserialize)]
+struct Entry {
+    name: String,
+    /// Five-field cron expression in UTC, or `@hourly`, `@daily`, `@weekly`, `@monthly`.
+    #[serde(default)]
+    cron: Option<String>,
+    /// A single run at an RFC 3339 time, instead of `cron`.
+    #[serde(default)]
+    at: Option<String>,
+    operation: String,
+    #[serde(default)]
+    args: BTreeMap<String, serde_yaml::Value>,
+    #[serde(default)]
+    missed: Missed,
+}
+
+/// What to do with occurrences that passed while the scheduler was not running.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Missed {
+    /// Drop them; only occurrences that are due now run.
+    #[default]
+    Skip,
+    /// Run once to catch up, however many were missed.
+    Once,
+    /// Replay every missed occurrence in order.
+    All,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RunRecord {
+    entry: String,
+    scheduled_for: String,
+    started_at: String,
+    success: bool,
+    status: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SchedulerState {
+    /// Entry name to the last occurrence handled (run or skipped), as a unix timestamp.
+    cursors: BTreeMap<String, i64>,
+    runs: Vec<RunRecord>,
+}
+
+impl SchedulerState {
+    fn load(path: &str) -> Result<Self> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(path).map_err(|e| anyhow!("Failed to read {}: {}", path, e))?;
+        serde_json::from_str(&data).map_err(|e| anyhow!("Failed to parse {}: {}", path, e))
+    }
+
+    /// Write through a temporary file so a crash never leaves a torn state file.
+    fn save(&self, path: &str) -> Result<()> {
+        let temporary = format!("{}.tmp", path);
+        fs::write(&temporary, serde_json::to_string_pretty(self)?).map_err(|e| anyhow!("Failed to write {}: {}", temporary, e))?;
+        fs::rename(&temporary, path).map_err(|e| anyhow!("Failed to replace {}: {}", path, e))
+    }
+}
+
+/// A parsed five-field cron expression: minute, hour, day of month, month
+/// and day of week (0 or 7 is Sunday). Each field takes `*`, numbers,
+/// ranges, lists and `/step`.
+#[derive(Debug, Clone)]
+struct Cron {
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+fn cron_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut bits = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| anyhow!("Invalid step in '{}'", item))?),
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (
+                    start.parse().map_err(|_| anyhow!("Invalid range '{}'", range))?,
+                    end.parse().map_err(|_| anyhow!("Invalid range '{}'", range))?,
+                ),
+                None => {
+                    let value = range.parse().map_err(|_| anyhow!("Invalid value '{}'", range))?;
+                    (value, if step > 1 { max } else { value })
+                }
+            },
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(anyhow!("'{}' is outside {}-{}", item, min, max));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl Cron {
+    fn parse(expression: &str) -> Result<Self> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            expression => expression,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(anyhow!("Cron expression '{}' must have five fields", expression));
+        };
+
+        let mut weekdays = cron_field(weekday, 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: cron_field(minute, 0, 59)?,
+            hours: cron_field(hour, 0, 23)? as u32,
+            days: cron_field(day, 1, 31)? as u32,
+            months: cron_field(month, 1, 12)? as u16,
+            weekdays: (weekdays & 0x7f) as u8,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    fn matches(&self, time: &DateTime<Utc>) -> bool {
+        let day = self.days & (1 << time.day()) != 0;
+        let weekday = self.weekdays & (1 << time.weekday().num_days_from_sunday()) != 0;
+        // As in cron, a restricted day of month and day of week match either.
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (false, true) => day,
+            (true, false) => weekday,
+            (false, false) => day || weekday,
+        };
+        self.minutes & (1 << time.minute()) != 0
+            && self.hours & (1 << time.hour()) != 0
+            && self.months & (1 << time.month()) != 0
+            && day_matches
+    }
+
+    /// Occurrences after `after` up to and including `until`, oldest first.
+    fn occurrences(&self, after: i64, until: i64) -> Vec<i64> {
+        let start = after.max(until - MAX_BACKFILL_WINDOW);
+        let mut minute = (start / 60 + 1) * 60;
+        let mut found = Vec::new();
+        while minute <= until {
+            if DateTime::<Utc>::from_timestamp(minute, 0).is_some_and(|time| self.matches(&time)) {
+                found.push(minute);
+            }
+            minute += 60;
+        }
+        found
+    }
+}
+
+enum When {
+    Cron(Cron),
+    At(i64),
+}
+
+impl Entry {
+    fn when(&self) -> Result<When> {
+        match (&self.cron, &self.at) {
+            (Some(cron), None) => Ok(When::Cron(Cron::parse(cron).map_err(|e| anyhow!("Entry '{}': {}", self.name, e))?)),
+            (None, Some(at)) => DateTime::parse_from_rfc3339(at)
+                .map(|time| When::At(time.timestamp()))
+                .map_err(|e| anyhow!("Entry '{}': invalid `at` time '{}': {}", self.name, at, e)),
+            _ => Err(anyhow!("Entry '{}' needs exactly one of `cron` or `at`", self.name)),
+        }
+    }
+}
+
+fn format_time(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0).map(|time| time.to_rfc3339()).unwrap_or_default()
+}
+
+/// Run operations on a schedule until interrupted.
+///
+/// Each entry has a five-field UTC `cron` expression or a one-off `at`
+/// time, and runs as a separate `token-ops` process with `--yes` that
+/// inherits the arguments given to `scheduler`, like pipeline steps. Args
+/// can use `${var}` from `vars` plus `${scheduled_for}` and `${date}` of the
+/// occurrence. Every run is recorded in the `state` file, which also tracks
+/// progress so a restart neither repeats nor silently loses runs: `missed`
+/// picks whether occurrences that passed while stopped are skipped, run
+/// once, or all replayed.
+///
+/// ```yaml
+/// state: scheduler-state.json
+/// entries:
+///   - name: weekly-rewards
+///     cron: "0 9 * * 1"
+///     operation: mint-to
+///     args: { mint-address: "...", to: "...", amount: "1000" }
+///     missed: once
+///   - name: launch-airdrop
+///     at: "2025-01-01T00:00:00Z"
+///     operation: airdrop
+///     args: { mint-address: "...", recipients: airdrop.csv, campaign: "launch" }
+///     missed: all
+/// ```
+pub fn run_scheduler(path: &str, inherited: &BTreeMap<String, Vec<String>>) -> Result<()> {
+    let data = fs::read_to_string(path).map_err(|e| anyhow!("Failed to read schedule {}: {}", path, e))?;
+    let schedule: Schedule = serde_yaml::from_str(&data).map_err(|e| anyhow!("Failed to parse schedule {}: {}", path, e))?;
+    let entries = schedule
+        .entries
+        .iter()
+        .map(|entry| Ok((entry, entry.when()?)))
+        .collect::<Result<Vec<(&Entry, When)>>>()?;
+
+    let mut variables: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in &schedule.vars {
+        let value = match value {
+            serde_yaml::Value::String(value) => value.clone(),
+            serde_yaml::Value::Number(value) => value.to_string(),
+            serde_yaml::Value::Bool(value) => value.to_string(),
+            _ => return Err(anyhow!("Variable '{}' must be a scalar", name)),
+        };
+        variables.insert(name.clone(), value);
+    }
+
+    let executable = env::current_exe().map_err(|e| anyhow!("Failed to locate token-ops executable: {}", e))?;
+    let mut state = SchedulerState::load(&schedule.state)?;
+    let started = Utc::now().timestamp();
+    for (entry, when) in &entries {
+        // Cron entries seen for the first time start from now; a one-off
+        // entry that has never run is still owed its run.
+        if !state.cursors.contains_key(&entry.name) {
+            let cursor = match when {
+                When::Cron(_) => started,
+                When::At(_) => i64::MIN,
+            };
+            state.cursors.insert(entry.name.clone(), cursor);
+        }
+    }
+    state.save(&schedule.state)?;
+    info!("Scheduler running {} entr(ies) from {}; state in {}", entries.len(), path, schedule.state);
+
+    loop {
+        let now = Utc::now().timestamp();
+        for (entry, when) in &entries {
+            let cursor = state.cursors[&entry.name];
+            let due: Vec<i64> = match when {
+                When::Cron(cron) => cron.occurrences(cursor, now),
+                When::At(at) => if cursor < *at && *at <= now { vec![*at] } else { Vec::new() },
+            };
+            let Some(&latest) = due.last() else {
+                continue;
+            };
+
+            let runs: Vec<i64> = match entry.missed {
+                Missed::All => due.iter().copied().take(MAX_BACKFILL_RUNS).collect(),
+                Missed::Once => vec![latest],
+                Missed::Skip => due.iter().copied().filter(|time| now - time <= ON_TIME_GRACE).collect(),
+            };
+            if runs.len() < due.len() {
+                warn!("Entry '{}': skipping {} missed occurrence(s)", entry.name, due.len() - runs.len());
+            }
+
+            for scheduled_for in runs {
+                let record = run_entry(&executable, entry, scheduled_for, inherited, &variables);
+                state.runs.push(record);
+                state.cursors.insert(entry.name.clone(), scheduled_for);
+                state.save(&schedule.state)?;
+            }
+            state.cursors.insert(entry.name.clone(), latest);
+            state.save(&schedule.state)?;
+        }
+
+        // Wake just after the next minute boundary.
+        let now = Utc::now().timestamp();
+        thread::sleep(Duration::from_secs((60 - now.rem_euclid(60) + 1) as u64));
+    }
+}
+
+fn run_entry(
+    executable: &Path,
+    entry: &Entry,
+    scheduled_for: i64,
+    inherited: &BTreeMap<String, Vec<String>>,
+    variables: &BTreeMap<String, String>,
+) -> RunRecord {
+    let mut variables = variables.clone();
+    variables.insert("scheduled_for".to_string(), format_time(scheduled_for));
+    variables.insert("date".to_string(), format_time(scheduled_for).chars().take(10).collect());
+
+    info!("Running '{}' ({}) scheduled for {}", entry.name, entry.operation, format_time(scheduled_for));
+    let started_at = Utc::now().to_rfc3339();
+    let status = child_args(inherited, NOT_INHERITED, &entry.operation, &entry.args, &variables).and_then(|mut args| {
+        args.push("--yes".to_string());
+        Command::new(executable)
+            .args(&args)
+            .status()
+            .map_err(|e| anyhow!("Failed to start: {}", e))
+    });
+
+    let (success, status) = match status {
+        Ok(status) if status.success() => {
+            info!("'{}' succeeded", entry.name);
+            (true, status.to_string())
+        }
+        Ok(status) => {
+            error!("'{}' failed ({})", entry.name, status);
+            (false, status.to_string())
+        }
+        Err(e) => {
+            error!("'{}' failed: {}", entry.name, e);
+            (false, e.to_string())
+        }
+    };
+    RunRecord {
+        entry: entry.name.clone(),
+        scheduled_for: format_time(scheduled_for),
+        started_at,
+        success,
+        status,
+    }
+}
@@ -0,0 +1,81 @@
+use log::warn;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+    rpc_config::RpcSimulateTransactionConfig,
+    rpc_request::{RpcError, RpcResponseErrorData},
+};
+use solana_sdk::transaction::VersionedTransaction;
+use token_ops_core::errors::{describe_failure, find_failure};
+
+/// Whether `error` is the RPC node rejecting a transaction in preflight.
+fn is_preflight_failure(error: &ClientError) -> bool {
+    matches!(
+        error.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(_),
+            ..
+        })
+    )
+}
+
+/// Turn a send error into a readable message. Preflight failures are
+/// simulated again to log the program output and name the failing program
+/// and error; anything else is returned as the RPC reported it.
+pub fn describe_send_error(client: &RpcClient, transaction: &VersionedTransaction, error: ClientError) -> String {
+    if !is_preflight_failure(&error) {
+        return error.to_string();
+    }
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(client.commitment()),
+        ..RpcSimulateTransactionConfig::default()
+    };
+    let simulation = match client.simulate_transaction_with_config(transaction, config) {
+        Ok(response) => response.value,
+        Err(e) => {
+            warn!("Failed to simulate the rejected transaction: {}", e);
+            return error.to_string();
+        }
+    };
+    let logs = simulation.logs.unwrap_or_default();
+
+    if !logs.is_empty() {
+        warn!("Simulation logs of {}:", transaction.signatures[0]);
+        for line in &logs {
+            warn!("  {}", line);
+        }
+    }
+
+    match (find_failure(&logs), simulation.err) {
+        (Some(failure), _) => format!("Simulation failed in the {}", describe_failure(&failure)),
+        (None, Some(e)) => format!("Simulation failed: {}", e),
+        // Passed on a fresh blockhash, so whatever failed preflight was transient.
+        (None, None) => format!("{} (the transaction simulates cleanly now; retry)", error),
+    }
+}
+
+/// Compute units `transaction` consumes in simulation, for `--profile`.
+/// Signatures are not checked, so relayed transactions simulate before the
+/// relayer signs them.
+pub fn simulated_units(client: &RpcClient, transaction: &VersionedTransaction) -> Option<u64> {
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        commitment: Some(client.commitment()),
+        ..RpcSimulateTransactionConfig::default()
+    };
+    match client.simulate_transaction_with_config(transaction, config) {
+        Ok(response) => {
+            if let Some(e) = &response.value.err {
+                warn!("Profiling simulation of {} failed: {}", transaction.signatures[0], e);
+            }
+            response.value.units_consumed
+        }
+        Err(e) => {
+            warn!("Failed to simulate {} for profiling: {}", transaction.signatures[0], e);
+            None
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_sdk::{native_token::lamports_to_sol, program_option::COption, pubkey::Pubkey, signature::Signer};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::{
+    extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions},
+    instruction::{close_account, transfer_checked},
+    state::{Account, AccountState, Mint},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+use token_ops_core::{
+    instructions::{memo, DEFAULT_TRANSFER_MEMO},
+    mint::requires_incoming_memo,
+};
+
+use crate::{
+    consolidate::OwnedTokenAccount,
+    holders::format_amount,
+    output::{write_output, OutputFormat},
+    prompt, TokenOperations,
+};
+
+/// Accounts fetched per getMultipleAccounts request.
+const FETCH_CHUNK: usize = 100;
+
+/// Estimated compute units for an idempotent ATA creation.
+const CREATE_ATA_UNITS: u32 = 40_000;
+
+/// Estimated compute units for a TransferChecked instruction.
+const TRANSFER_UNITS: u32 = 20_000;
+
+/// Estimated compute units for a CloseAccount instruction.
+const CLOSE_UNITS: u32 = 5_000;
+
+/// Per-account outcome of a sweep.
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepResult {
+    pub mint: String,
+    pub token_account: String,
+    pub destination: String,
+    pub amount: String,
+    pub raw_amount: u64,
+    pub status: String,
+    pub signature: Option<String>,
+}
+
+impl TokenOperations {
+    /// Move the full balance of every token account of the wallet (or only
+    /// those of one mint) to `to`'s associated token accounts, creating them
+    /// as needed, and close the emptied accounts.
+    ///
+    /// Closed accounts return their rent to the wallet, which also pays for
+    /// the new ATAs; sweep the remaining SOL separately. Frozen accounts are
+    /// skipped, and accounts holding withheld transfer fees or with another
+    /// close authority are emptied but left open.
+    pub fn sweep(
+        &self,
+        to: &str,
+        mint_address: Option<&str>,
+        assume_yes: bool,
+        output: Option<(&str, OutputFormat)>,
+    ) -> Result<()> {
+        let destination = Pubkey::from_str(to).map_err(|e| anyhow!("Invalid --to address: {}", e))?;
+        let owner = self.wallet.pubkey();
+        if destination == owner {
+            return Err(anyhow!("--to is the loaded wallet; sweep moves balances to another wallet"));
+        }
+        let mint_filter = mint_address
+            .map(|mint| Pubkey::from_str(mint).map_err(|e| anyhow!("Invalid mint address: {}", e)))
+            .transpose()?;
+
+        let mut by_mint: BTreeMap<Pubkey, Vec<OwnedTokenAccount>> = BTreeMap::new();
+        for owned in self.owned_token_accounts(&owner, mint_filter.as_ref())? {
+            by_mint.entry(owned.account.mint).or_default().push(owned);
+        }
+        if by_mint.is_empty() {
+            info!("No token accounts to sweep");
+            return Ok(());
+        }
+
+        let mints: Vec<Pubkey> = by_mint.keys().copied().collect();
+        let destination_atas: Vec<Pubkey> = by_mint
+            .iter()
+            .map(|(mint, accounts)| get_associated_token_address_with_program_id(&destination, mint, &accounts[0].program_id))
+            .collect();
+        let mut mint_accounts = Vec::with_capacity(mints.len());
+        let mut destination_accounts = Vec::with_capacity(mints.len());
+        for (mint_chunk, ata_chunk) in mints.chunks(FETCH_CHUNK).zip(destination_atas.chunks(FETCH_CHUNK)) {
+            mint_accounts.extend(self.client.get_multiple_accounts(mint_chunk)
+                .map_err(|e| anyhow!("Failed to get mint accounts: {}", e))?);
+            destination_accounts.extend(self.client.get_multiple_accounts(ata_chunk)
+                .map_err(|e| anyhow!("Failed to get destination token accounts: {}", e))?);
+        }
+
+        let mut summary = vec![
+            format!("SWEEP {} token account(s) of {} to {}", by_mint.values().map(Vec::len).sum::<usize>(), owner, destination),
+        ];
+        let mut decimals_by_mint: HashMap<Pubkey, u8> = HashMap::new();
+        for (i, (mint, accounts)) in by_mint.iter().enumerate() {
+            let mint_account = mint_accounts[i].as_ref().ok_or_else(|| anyhow!("Mint {} not found", mint))?;
+            let decimals = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+                .map_err(|e| anyhow!("Failed to parse mint {}: {}", mint, e))?
+                .base
+                .decimals;
+            decimals_by_mint.insert(*mint, decimals);
+            let total: u64 = accounts.iter().map(|owned| owned.account.amount).sum();
+            summary.push(format!("{}: {} in {} account(s)", mint, format_amount(total, decimals), accounts.len()));
+        }
+        prompt::confirm(&summary, assume_yes)?;
+
+        let mut results: Vec<SweepResult> = Vec::new();
+        // batcher item index -> (result index, lamports reclaimed if closed)
+        let mut queued: HashMap<usize, (usize, u64)> = HashMap::new();
+        let mut batcher = self.batcher();
+
+        for (i, (mint, accounts)) in by_mint.iter().enumerate() {
+            let program_id = accounts[0].program_id;
+            let decimals = decimals_by_mint[mint];
+            let destination_ata = destination_atas[i];
+            let mut ata_ready = destination_accounts[i].is_some();
+            let needs_memo = match &destination_accounts[i] {
+                Some(account) => requires_incoming_memo(&account.data)?,
+                None => false,
+            };
+
+            for owned in accounts {
+                let mut result = SweepResult {
+                    mint: mint.to_string(),
+                    token_account: owned.address.to_string(),
+                    destination: destination_ata.to_string(),
+                    amount: format_amount(owned.account.amount, decimals),
+                    raw_amount: owned.account.amount,
+                    status: String::new(),
+                    signature: None,
+                };
+
+                if owned.account.state == AccountState::Frozen {
+                    result.status = "skipped: frozen".to_string();
+                    results.push(result);
+                    continue;
+                }
+
+                let mut instructions = Vec::new();
+                let mut units = 0;
+
+                if owned.account.amount > 0 {
+                    if !ata_ready {
+                        instructions.push(create_associated_token_account_idempotent(
+                            &owner, &destination, mint, &program_id,
+                        ));
+                        units += CREATE_ATA_UNITS;
+                        ata_ready = true;
+                    }
+                    if needs_memo {
+                        instructions.push(memo(DEFAULT_TRANSFER_MEMO, &owner));
+                    }
+                    instructions.push(transfer_checked(
+                        &program_id,
+                        &owned.address,
+                        mint,
+                        &destination_ata,
+                        &owner,
+                        &[],
+                        owned.account.amount,
+                        decimals,
+                    )?);
+                    units += TRANSFER_UNITS;
+                }
+
+                let withheld = StateWithExtensions::<Account>::unpack(&owned.data)
+                    .ok()
+                    .and_then(|state| state.get_extension::<TransferFeeAmount>().ok().map(|fee| u64::from(fee.withheld_amount)))
+                    .unwrap_or(0);
+                let close_authority_ok = match owned.account.close_authority {
+                    COption::Some(authority) => authority == owner,
+                    COption::None => true,
+                };
+                let reclaimed = if close_authority_ok && withheld == 0 {
+                    instructions.push(close_account(&program_id, &owned.address, &owner, &owner, &[])?);
+                    units += CLOSE_UNITS;
+                    owned.lamports
+                } else {
+                    0
+                };
+
+                if instructions.is_empty() {
+                    result.status = "skipped: empty and not closable".to_string();
+                    results.push(result);
+                    continue;
+                }
+                let item = batcher.push(instructions, units)?;
+                queued.insert(item, (results.len(), reclaimed));
+                results.push(result);
+            }
+        }
+
+        let mut reclaimed_lamports = 0;
+        for batch in batcher.finish() {
+            for item in &batch.items {
+                let Some((result_index, reclaimed)) = queued.get(item) else {
+                    continue;
+                };
+                let result = &mut results[*result_index];
+                match &batch.signature {
+                    Ok(signature) => {
+                        result.status = if *reclaimed > 0 { "closed" } else { "emptied" }.to_string();
+                        result.signature = Some(signature.to_string());
+                        reclaimed_lamports += reclaimed;
+                    }
+                    Err(e) => result.status = format!("failed: {}", e),
+                }
+            }
+        }
+
+        let failed = results.iter().filter(|r| r.status.starts_with("failed")).count();
+        for result in results.iter().filter(|r| r.status != "closed") {
+            warn!("  {} ({}): {}", result.token_account, result.mint, result.status);
+        }
+        info!(
+            "Swept {} account(s) to {}, reclaimed {} SOL of rent, {} failed",
+            results.iter().filter(|r| r.signature.is_some()).count(),
+            destination,
+            lamports_to_sol(reclaimed_lamports),
+            failed
+        );
+
+        write_output(output, &results, &results)?;
+
+        if failed > 0 {
+            return Err(anyhow!("{} account(s) could not be swept", failed));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::warn;
+use opentelemetry::{
+    global,
+    trace::{Span, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{trace, Resource};
+use solana_client::{
+    client_error::Result as ClientResult,
+    rpc_client::{RpcClient, RpcClientConfig},
+    rpc_request::RpcRequest,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_rpc_client::http_sender::HttpSender;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::time::Duration;
+
+use crate::{providers::redact_url, rpc_headers};
+
+/// Instrumentation name of every span this tool emits.
+const TRACER: &str = "token-ops";
+
+/// Same request timeout as the default HTTP sender.
+const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Flushes and shuts down the exporter when dropped at the end of `main`.
+pub struct TelemetryGuard {
+    enabled: bool,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if self.enabled {
+            global::shutdown_tracer_provider();
+        }
+    }
+}
+
+/// Export spans over OTLP/HTTP to `endpoint` (e.g. http://localhost:4318).
+/// Without an endpoint the global tracer stays a no-op.
+pub fn init(endpoint: Option<&str>) -> Result<TelemetryGuard> {
+    let Some(endpoint) = endpoint else {
+        return Ok(TelemetryGuard { enabled: false });
+    };
+
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().http().with_endpoint(endpoint))
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new("service.name", TRACER)])),
+        )
+        .install_simple()
+        .map_err(|e| anyhow!("Failed to start OTLP exporter: {}", e))?;
+
+    Ok(TelemetryGuard { enabled: true })
+}
+
+/// Run `f` inside a span named `name`, marking the span as failed when `f`
+/// returns an error. Spans started inside `f`, including RPC calls, become
+/// its children.
+pub fn in_span<T, E: std::fmt::Display>(
+    name: &'static str,
+    attributes: Vec<KeyValue>,
+    f: impl FnOnce() -> std::result::Result<T, E>,
+) -> std::result::Result<T, E> {
+    global::tracer(TRACER).in_span(name, |cx| {
+        let span = cx.span();
+        for attribute in attributes {
+            span.set_attribute(attribute);
+        }
+        let result = f();
+        if let Err(e) = &result {
+            span.set_status(Status::error(e.to_string()));
+        }
+        result
+    })
+}
+
+/// An RPC client whose every request is recorded as an `rpc <method>` span
+/// and carries the configured `--rpc-header`s.
+pub fn rpc_client(url: &str, commitment: CommitmentConfig) -> RpcClient {
+    let inner = match reqwest::Client::builder()
+        .default_headers(rpc_headers::header_map())
+        .timeout(RPC_TIMEOUT)
+        .build()
+    {
+        Ok(client) => HttpSender::new_with_client(url.to_string(), client),
+        Err(e) => {
+            warn!("Failed to build RPC HTTP client with custom headers, using defaults: {}", e);
+            HttpSender::new(url.to_string())
+        }
+    };
+    RpcClient::new_sender(TracingSender { inner }, RpcClientConfig::with_commitment(commitment))
+}
+
+struct TracingSender {
+    inner: HttpSender,
+}
+
+#[async_trait]
+impl RpcSender for TracingSender {
+    async fn send(&self, request: RpcRequest, params: serde_json::Value) -> ClientResult<serde_json::Value> {
+        let method = request.to_string();
+        let tracer = global::tracer(TRACER);
+        let mut span = tracer
+            .span_builder(format!("rpc {}", method))
+            .with_attributes(vec![
+                KeyValue::new("rpc.system", "jsonrpc"),
+                KeyValue::new("rpc.method", method),
+                KeyValue::new("server.address", redact_url(&self.inner.url())),
+            ])
+            .start_with_context(&tracer, &Context::current());
+
+        let result = self.inner.send(request, params).await;
+        if let Err(e) = &result {
+            span.set_status(Status::error(e.to_string()));
+        }
+        span.end();
+        result
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+use spl_token_2022::{extension::ExtensionType, state::Account};
+use token_ops_core::{
+    pausable,
+    tlv::{mint_extensions, PAUSABLE},
+};
+
+pub use token_ops_core::mint::{is_token_program, unpack_token_account, MintInfo};
+
+use crate::TokenOperations;
+
+impl TokenOperations {
+    pub fn fetch_mint_info(&self, mint_pubkey: &Pubkey) -> Result<MintInfo> {
+        token_ops_core::rpc::fetch_mint_info(&self.client, mint_pubkey)
+    }
+}
+
+/// Size of a new token account for the mint, including the account
+/// extensions Token-2022 adds for the mint's extensions and, when asked
+/// (always for ATAs), ImmutableOwner.
+pub fn token_account_len(mint_info: &MintInfo, immutable_owner: bool) -> Result<usize> {
+    if !mint_info.is_token_2022() {
+        return Ok(spl_token::state::Account::LEN);
+    }
+    let entries = mint_extensions(&mint_info.data)?;
+    let known: Vec<ExtensionType> = entries
+        .iter()
+        .filter_map(|(extension, _)| ExtensionType::try_from(*extension).ok())
+        .collect();
+    let mut account_extensions = ExtensionType::get_required_init_account_extensions(&known);
+    if immutable_owner {
+        account_extensions.push(ExtensionType::ImmutableOwner);
+    }
+    // PausableAccount is newer than the pinned spl-token-2022, so it is sized here.
+    let pausable = entries.iter().any(|(extension, _)| *extension == PAUSABLE);
+    if !pausable {
+        return ExtensionType::try_calculate_account_len::<Account>(&account_extensions)
+            .map_err(|e| anyhow!("Failed to size token account: {}", e));
+    }
+    let len = if account_extensions.is_empty() {
+        // The account type byte that precedes any extension.
+        Account::LEN + 1
+    } else {
+        ExtensionType::try_calculate_account_len::<Account>(&account_extensions)
+            .map_err(|e| anyhow!("Failed to size token account: {}", e))?
+    };
+    Ok(len + pausable::account_tlv_len())
+}
@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::{
+    instruction::{burn_checked, transfer_checked},
+    state::{Account, AccountState},
+};
+use std::str::FromStr;
+use token_ops_core::{
+    instructions::{memo as memo_instruction, transfer_to_wallet},
+    rpc::transfer_memo,
+};
+
+use crate::{
+    holders::{format_amount, parse_amount},
+    idempotency::{IdempotencyKey, IDEMPOTENCY_MEMO_UNITS},
+    prompt::confirm,
+    token_program::{unpack_token_account, MintInfo},
+    TokenOperations,
+};
+
+/// Estimated compute units for an idempotent ATA creation, a memo and TransferChecked.
+const TRANSFER_UNITS: u32 = 70_000;
+
+/// Estimated compute units for a BurnChecked instruction.
+const BURN_UNITS: u32 = 10_000;
+
+impl TokenOperations {
+    /// Send tokens from the wallet's ATA to `to`'s, creating it if needed.
+    /// When the destination requires memos and none is given, a default
+    /// memo is added so the transfer is not rejected.
+    pub fn transfer(
+        &self,
+        mint_address: &str,
+        to: &str,
+        amount: &str,
+        memo: Option<&str>,
+        idempotency: Option<&IdempotencyKey>,
+        assume_yes: bool,
+    ) -> Result<()> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let recipient = Pubkey::from_str(to).map_err(|e| anyhow!("Invalid destination address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint)?;
+        let decimals = mint_info.base()?.decimals;
+        let amount = parse_amount(amount, decimals)?;
+        if amount == 0 {
+            return Err(anyhow!("Amount must be greater than zero"));
+        }
+
+        let memo = transfer_memo(&self.client, &mint_info, &recipient, memo)?;
+        let mut summary = vec![
+            format!("Transfer {} of {}", format_amount(amount, decimals), mint),
+            format!("To:   {} ({})", recipient, mint_info.associated_token_address(&recipient)),
+        ];
+        if let Some(memo) = memo {
+            summary.push(format!("Memo: {}", memo));
+        }
+        confirm(&summary, assume_yes)?;
+
+        let request = format!("transfer {} of {} to {}", format_amount(amount, decimals), mint, recipient);
+        let mut instructions = Vec::new();
+        let mut units = TRANSFER_UNITS;
+        if let Some(key) = idempotency {
+            instructions.push(self.begin_idempotent(key, &request)?);
+            units += IDEMPOTENCY_MEMO_UNITS;
+        }
+        instructions.extend(transfer_to_wallet(&mint_info, &self.wallet.pubkey(), &recipient, amount, decimals, memo)?);
+        let signature = self
+            .send_instructions(instructions, units)
+            .map_err(|e| anyhow!("Failed to transfer: {}", e))?;
+        info!("Transfer completed! Signature: {}", signature);
+        if let Some(key) = idempotency {
+            key.complete(&request, &signature)?;
+        }
+        Ok(())
+    }
+}
+
+impl TokenOperations {
+    /// A token account of `mint` that the wallet may spend `amount` from as
+    /// its approved delegate, with the mint and the account's state.
+    fn delegated_source(&self, mint_address: &str, from: &str, amount: &str) -> Result<(MintInfo, Pubkey, Account, u64)> {
+        let mint = Pubkey::from_str(mint_address).map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let source = Pubkey::from_str(from).map_err(|e| anyhow!("Invalid source account: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint)?;
+        let decimals = mint_info.base()?.decimals;
+
+        let account = self.client.get_account(&source)
+            .map_err(|e| anyhow!("Failed to get source account {}: {}", source, e))?;
+        if account.owner != mint_info.program_id {
+            return Err(anyhow!("{} is not a token account of {}", source, mint));
+        }
+        let state = unpack_token_account(&account.data)?;
+        if state.mint != mint {
+            return Err(anyhow!("{} holds {}, not {}", source, state.mint, mint));
+        }
+        if Option::<Pubkey>::from(state.delegate) != Some(self.wallet.pubkey()) {
+            return Err(anyhow!("Loaded wallet is not the approved delegate of {}", source));
+        }
+        if state.state == AccountState::Frozen {
+            return Err(anyhow!("{} is frozen", source));
+        }
+
+        let amount = parse_amount(amount, decimals)?;
+        if amount == 0 {
+            return Err(anyhow!("Amount must be greater than zero"));
+        }
+        if amount > state.delegated_amount {
+            return Err(anyhow!(
+                "Remaining allowance is {}, less than {}",
+                format_amount(state.delegated_amount, decimals),
+                format_amount(amount, decimals)
+            ));
+        }
+        if amount > state.amount {
+            return Err(anyhow!("{} only holds {}", source, format_amount(state.amount, decimals)));
+        }
+        Ok((mint_info, source, state, amount))
+    }
+
+    /// Transfer from someone else's token account `from` to `to`'s ATA,
+    /// spending the allowance the owner approved for the wallet.
+    pub fn transfer_from(
+        &self,
+        mint_address: &str,
+        from: &str,
+        to: &str,
+        amount: &str,
+        memo: Option<&str>,
+        assume_yes: bool,
+    ) -> Result<()> {
+        let (mint_info, source, state, amount) = self.delegated_source(mint_address, from, amount)?;
+        let decimals = mint_info.base()?.decimals;
+        let recipient = Pubkey::from_str(to).map_err(|e| anyhow!("Invalid destination address: {}", e))?;
+        let destination = mint_info.associated_token_address(&recipient);
+        let memo = transfer_memo(&self.client, &mint_info, &recipient, memo)?;
+
+        let mut summary = vec![
+            format!("Transfer {} of {} as delegate", format_amount(amount, decimals), mint_info.address),
+            format!("From:      {} (owner {})", source, state.owner),
+            format!("To:        {} ({})", recipient, destination),
+            format!("Allowance: {} -> {}", format_amount(state.delegated_amount, decimals), format_amount(state.delegated_amount - amount, decimals)),
+        ];
+        if let Some(memo) = memo {
+            summary.push(format!("Memo:      {}", memo));
+        }
+        confirm(&summary, assume_yes)?;
+
+        let wallet = self.wallet.pubkey();
+        let mut instructions =
+            vec![create_associated_token_account_idempotent(&wallet, &recipient, &mint_info.address, &mint_info.program_id)];
+        if let Some(memo) = memo {
+            instructions.push(memo_instruction(memo, &wallet));
+        }
+        instructions.push(transfer_checked(
+            &mint_info.program_id,
+            &source,
+            &mint_info.address,
+            &destination,
+            &wallet,
+            &[],
+            amount,
+            decimals,
+        )?);
+        let signature = self
+            .send_instructions(instructions, TRANSFER_UNITS)
+            .map_err(|e| anyhow!("Failed to transfer from {}: {}", source, e))?;
+        info!("Delegated transfer completed! Signature: {}", signature);
+        Ok(())
+    }
+
+    /// Burn from someone else's token account `from`, spending the
+    /// allowance the owner approved for the wallet.
+    pub fn burn_from(&self, mint_address: &str, from: &str, amount: &str, assume_yes: bool) -> Result<()> {
+        let (mint_info, source, state, amount) = self.delegated_source(mint_address, from, amount)?;
+        let decimals = mint_info.base()?.decimals;
+
+        confirm(
+            &[
+                format!("BURN {} of {} as delegate", format_amount(amount, decimals), mint_info.address),
+                format!("From:      {} (owner {})", source, state.owner),
+                format!("Allowance: {} -> {}", format_amount(state.delegated_amount, decimals), format_amount(state.delegated_amount - amount, decimals)),
+            ],
+            assume_yes,
+        )?;
+
+        let instruction = burn_checked(
+            &mint_info.program_id,
+            &source,
+            &mint_info.address,
+            &self.wallet.pubkey(),
+            &[],
+            amount,
+            decimals,
+        )?;
+        let signature = self
+            .send_instructions(vec![instruction], BURN_UNITS)
+            .map_err(|e| anyhow!("Failed to burn from {}: {}", source, e))?;
+        info!("Delegated burn completed! Signature: {}", signature);
+        Ok(())
+    }
+}
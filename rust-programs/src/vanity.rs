@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use solana_sdk::signature::{Keypair, Signer};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Keypairs each worker generates between checks of the shared counters.
+const ATTEMPTS_PER_ROUND: u64 = 1_000;
+
+/// How often grinding progress is logged.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Prefixes longer than this take hours or more on typical hardware.
+const LONG_PREFIX: usize = 5;
+
+/// Address prefix to grind for.
+pub struct VanityPattern {
+    prefix: String,
+    ignore_case: bool,
+}
+
+impl VanityPattern {
+    pub fn new(prefix: &str, ignore_case: bool) -> Result<Self> {
+        if prefix.is_empty() {
+            return Err(anyhow!("Vanity prefix cannot be empty"));
+        }
+        if let Some(invalid) = prefix.chars().find(|c| !BASE58_ALPHABET.contains(*c)) {
+            return Err(anyhow!(
+                "'{}' never appears in a Solana address (base58 excludes 0, O, I and l)",
+                invalid
+            ));
+        }
+
+        Ok(Self {
+            prefix: if ignore_case { prefix.to_ascii_lowercase() } else { prefix.to_string() },
+            ignore_case,
+        })
+    }
+
+    fn matches(&self, address: &str) -> bool {
+        let Some(head) = address.get(..self.prefix.len()) else {
+            return false;
+        };
+        if self.ignore_case {
+            head.eq_ignore_ascii_case(&self.prefix)
+        } else {
+            head == self.prefix
+        }
+    }
+
+    /// Average number of keypairs needed to find a match.
+    pub fn expected_attempts(&self) -> f64 {
+        self.prefix
+            .chars()
+            .map(|c| {
+                let matching = BASE58_ALPHABET
+                    .chars()
+                    .filter(|candidate| if self.ignore_case { candidate.eq_ignore_ascii_case(&c) } else { *candidate == c })
+                    .count();
+                BASE58_ALPHABET.len() as f64 / matching as f64
+            })
+            .product()
+    }
+}
+
+/// Generate keypairs on every core until one's address starts with the
+/// pattern, logging the rate and expected time as it goes.
+pub fn grind(pattern: &VanityPattern) -> Keypair {
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let expected = pattern.expected_attempts();
+
+    if pattern.prefix.len() > LONG_PREFIX {
+        warn!("A {}-character prefix may take a very long time to find", pattern.prefix.len());
+    }
+    info!("Grinding for prefix '{}' on {} thread(s), ~{:.0} attempts expected", pattern.prefix, threads, expected);
+
+    let found = AtomicBool::new(false);
+    let attempts = AtomicU64::new(0);
+    let result: Mutex<Option<Keypair>> = Mutex::new(None);
+    let started = Instant::now();
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                while !found.load(Ordering::Relaxed) {
+                    for _ in 0..ATTEMPTS_PER_ROUND {
+                        let keypair = Keypair::new();
+                        if pattern.matches(&keypair.pubkey().to_string()) {
+                            found.store(true, Ordering::Relaxed);
+                            result.lock().unwrap().get_or_insert(keypair);
+                            break;
+                        }
+                    }
+                    attempts.fetch_add(ATTEMPTS_PER_ROUND, Ordering::Relaxed);
+                }
+            });
+        }
+
+        let mut last_report = Instant::now();
+        while !found.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(100));
+            if last_report.elapsed() < PROGRESS_INTERVAL {
+                continue;
+            }
+            last_report = Instant::now();
+
+            let tried = attempts.load(Ordering::Relaxed) as f64;
+            let rate = tried / started.elapsed().as_secs_f64();
+            // Each attempt is independent, so the expected wait never shrinks;
+            // report it against the current rate instead of counting down.
+            info!("  {:.0} keypairs tried ({:.0}/s), ~{:.0}s expected per match", tried, rate, expected / rate);
+        }
+    });
+
+    let keypair = result
+        .into_inner()
+        .unwrap()
+        .expect("grinding stops only once a keypair is found");
+    info!("Found {} after {:.1}s", keypair.pubkey(), started.elapsed().as_secs_f64());
+    keypair
+}
@@ -0,0 +1,186 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use serde::Serialize;
+use sha2::Sha256;
+use solana_sdk::pubkey::Pubkey;
+use std::{thread, time::Duration};
+
+use crate::{
+    events::{extract_events, warn_if_untracked, TokenEvent},
+    index::Index,
+    TokenOperations,
+};
+
+/// Webhook request timeout.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Attempts per delivery, with the delay doubling from `RETRY_DELAY`.
+const MAX_ATTEMPTS: u32 = 4;
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Header carrying `sha256=<hex HMAC>` of `<timestamp>.<body>` when a secret is set.
+pub const SIGNATURE_HEADER: &str = "X-Token-Ops-Signature";
+
+/// Header carrying the unix time the delivery was signed at.
+pub const TIMESTAMP_HEADER: &str = "X-Token-Ops-Timestamp";
+
+/// The `--webhook` URLs events and alerts are POSTed to as JSON.
+///
+/// With a `--webhook-secret`, every request is signed so receivers can
+/// reject forged or replayed deliveries: recompute the HMAC-SHA256 of the
+/// timestamp header, a dot and the raw body, and compare it with the
+/// signature header. Failed deliveries are retried with backoff on network
+/// errors, 429 and 5xx; a URL that still fails is logged and skipped.
+#[derive(Debug)]
+pub struct Webhooks {
+    http: reqwest::blocking::Client,
+    urls: Vec<String>,
+    secret: Option<String>,
+}
+
+/// One observed token event, as delivered by watch-events.
+#[derive(Debug, Serialize)]
+struct EventDelivery<'a> {
+    mint: String,
+    #[serde(flatten)]
+    event: &'a TokenEvent,
+}
+
+impl Webhooks {
+    pub fn new(urls: Vec<String>, secret: Option<String>) -> Result<Self> {
+        let http = reqwest::blocking::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|e| anyhow!("Failed to create HTTP client: {}", e))?;
+        Ok(Self { http, urls, secret })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+
+    fn signature(&self, timestamp: i64, body: &[u8]) -> Option<String> {
+        let secret = self.secret.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body);
+        let digest: String = mac.finalize().into_bytes().iter().map(|byte| format!("{:02x}", byte)).collect();
+        Some(format!("sha256={}", digest))
+    }
+
+    /// POST `payload` to every URL. Returns whether all of them accepted it.
+    pub fn deliver<T: Serialize>(&self, payload: &T) -> bool {
+        let body = match serde_json::to_vec(payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to encode webhook payload: {}", e);
+                return false;
+            }
+        };
+        // Every URL gets the event, even after one of them fails.
+        let failed = self.urls.iter().filter(|url| !self.deliver_to(url, &body)).count();
+        failed == 0
+    }
+
+    fn deliver_to(&self, url: &str, body: &[u8]) -> bool {
+        let mut delay = RETRY_DELAY;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let timestamp = Utc::now().timestamp();
+            let mut request = self.http
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .body(body.to_vec());
+            if let Some(signature) = self.signature(timestamp, body) {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+
+            let retryable = match request.send() {
+                Ok(response) if response.status().is_success() => return true,
+                Ok(response) => {
+                    let status = response.status();
+                    warn!("Webhook {} answered {} (attempt {}/{})", url, status, attempt, MAX_ATTEMPTS);
+                    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(e) => {
+                    warn!("Webhook {} failed: {} (attempt {}/{})", url, e, attempt, MAX_ATTEMPTS);
+                    true
+                }
+            };
+            if !retryable {
+                break;
+            }
+            if attempt < MAX_ATTEMPTS {
+                thread::sleep(delay);
+                delay *= 2;
+            }
+        }
+        warn!("Giving up on webhook delivery to {}", url);
+        false
+    }
+}
+
+impl TokenOperations {
+    /// Poll the mints every `interval` seconds and deliver each new
+    /// mint, burn and transfer event to the webhooks. Starts from the
+    /// newest transaction of each mint and tracked account, so past history
+    /// is not replayed.
+    ///
+    /// Like index-events, the mint's own signatures are walked together
+    /// with the token accounts tracked in the index, which is where plain
+    /// Transfer instructions show up; keep it current with index-sync.
+    /// Events are stored in the index before delivery, so each is
+    /// delivered once even when a poll fails and is retried.
+    pub fn watch_events(&self, mints: &[Pubkey], interval: u64, webhooks: &Webhooks, index_path: &str) -> Result<()> {
+        if webhooks.is_empty() {
+            return Err(anyhow!("No webhooks to deliver to; pass --webhook URL"));
+        }
+
+        let mut index = Index::open(index_path)?;
+        for mint in mints {
+            warn_if_untracked(&index, &mint.to_string())?;
+            self.seed_event_cursors(&index, &mint.to_string())?;
+        }
+        info!("Watching events of {} mint(s) every {}s", mints.len(), interval);
+
+        loop {
+            thread::sleep(Duration::from_secs(interval));
+
+            for mint in mints {
+                if let Err(e) = self.deliver_new_events(&mut index, mint, webhooks) {
+                    warn!("Poll of {} failed, retrying: {}", mint, e);
+                }
+            }
+        }
+    }
+
+    fn deliver_new_events(&self, index: &mut Index, mint: &Pubkey, webhooks: &Webhooks) -> Result<()> {
+        let mint_address = mint.to_string();
+        // Accounts tracked since the last poll start from their newest transaction too.
+        self.seed_event_cursors(index, &mint_address)?;
+        let progress = self.pending_events(index, &mint_address)?;
+
+        for (signature, _) in &progress.pending {
+            let transaction = self.fetch_transaction(signature)?;
+            if transaction.failed() {
+                continue;
+            }
+            for event in extract_events(&transaction, mint) {
+                if index.record_events(&mint_address, std::slice::from_ref(&event))? == 0 {
+                    continue;
+                }
+                info!("{} {} of {} in {}", event.kind.as_str(), event.amount, mint, event.signature);
+                webhooks.deliver(&EventDelivery { mint: mint_address.clone(), event: &event });
+            }
+        }
+
+        // Cursors only advance once every transaction has been delivered.
+        for (address, signature) in &progress.cursors {
+            index.set_event_cursor(address, &mint_address, signature)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{program_option::COption, program_pack::Pack, pubkey::Pubkey, signature::Signer};
+use spl_token_2022::{
+    extension::default_account_state::instruction::update_default_account_state,
+    instruction::thaw_account,
+    state::AccountState,
+};
+use std::str::FromStr;
+
+use crate::{
+    output::{write_output, OutputFormat},
+    token_program::{unpack_token_account, MintInfo},
+    TokenOperations,
+};
+
+/// Byte offset of the `state` field in the base token account layout.
+const ACCOUNT_STATE_OFFSET: usize = 108;
+
+/// Estimated compute units for an idempotent ATA creation.
+const CREATE_ATA_UNITS: u32 = 40_000;
+
+/// Estimated compute units for one ThawAccount instruction.
+const THAW_UNITS: u32 = 5_000;
+
+/// A frozen token account waiting for approval.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingHolder {
+    pub token_account: String,
+    pub owner: String,
+    pub amount: u64,
+}
+
+impl TokenOperations {
+    fn whitelist_mint(&self, mint_address: &str) -> Result<MintInfo> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+
+        if mint_info.base()?.freeze_authority != COption::Some(self.wallet.pubkey()) {
+            return Err(anyhow!("Loaded wallet is not the freeze authority of this mint"));
+        }
+
+        match mint_info.default_account_state()? {
+            Some(AccountState::Frozen) => {}
+            Some(_) => warn!("DefaultAccountState is not Frozen; new holders do not need approval"),
+            None => warn!("Mint has no DefaultAccountState extension; new accounts are not frozen by default"),
+        }
+
+        Ok(mint_info)
+    }
+
+    /// Thaw a wallet's ATA so it can hold and move the token, creating the ATA first if needed.
+    pub fn approve_holder(&self, mint_address: &str, owner_address: &str) -> Result<()> {
+        let mint_info = self.whitelist_mint(mint_address)?;
+        let owner = Pubkey::from_str(owner_address)
+            .map_err(|e| anyhow!("Invalid owner address: {}", e))?;
+        let token_account = mint_info.associated_token_address(&owner);
+
+        info!("Approving holder {} (token account {})", owner, token_account);
+
+        let existing = self.client
+            .get_account_with_commitment(&token_account, self.client.commitment())
+            .map_err(|e| anyhow!("Failed to get token account: {}", e))?
+            .value;
+
+        let mut instructions = Vec::new();
+        let mut units = THAW_UNITS;
+
+        let frozen = match existing {
+            Some(account) => unpack_token_account(&account.data)?.state == AccountState::Frozen,
+            None => {
+                info!("Creating associated token account for {}", owner);
+                instructions.push(
+                    spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                        &self.wallet.pubkey(),
+                        &owner,
+                        &mint_info.address,
+                        &mint_info.program_id,
+                    ),
+                );
+                units += CREATE_ATA_UNITS;
+                mint_info.default_account_state()? == Some(AccountState::Frozen)
+            }
+        };
+
+        if frozen {
+            instructions.push(thaw_account(
+                &mint_info.program_id,
+                &token_account,
+                &mint_info.address,
+                &self.wallet.pubkey(),
+                &[],
+            )?);
+        } else if instructions.is_empty() {
+            info!("Token account {} is already approved", token_account);
+            return Ok(());
+        }
+
+        match self.send_instructions(instructions, units) {
+            Ok(signature) => {
+                info!("Holder {} approved! Signature: {}", owner, signature);
+                Ok(())
+            }
+            Err(e) => Err(anyhow!("Failed to approve holder: {}", e)),
+        }
+    }
+
+    /// List frozen token accounts for the mint, i.e. holders awaiting approval.
+    pub fn pending_holders(&self, mint_address: &str, output: Option<(&str, OutputFormat)>) -> Result<()> {
+        let mint_info = self.whitelist_mint(mint_address)?;
+
+        // Token-2022 accounts vary in size with their extensions, so match on
+        // the mint and the frozen state byte rather than the data length.
+        let mut filters = vec![
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, mint_info.address.as_ref())),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(ACCOUNT_STATE_OFFSET, &[AccountState::Frozen as u8])),
+        ];
+        if !mint_info.is_token_2022() {
+            filters.push(RpcFilterType::DataSize(spl_token::state::Account::LEN as u64));
+        }
+
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let accounts = self.client.get_program_accounts_with_config(&mint_info.program_id, config)
+            .map_err(|e| anyhow!("Failed to get frozen token accounts: {}", e))?;
+
+        let pending: Vec<PendingHolder> = accounts
+            .iter()
+            .filter_map(|(address, account)| {
+                let token_account = unpack_token_account(&account.data).ok()?;
+                (token_account.state == AccountState::Frozen).then(|| PendingHolder {
+                    token_account: address.to_string(),
+                    owner: token_account.owner.to_string(),
+                    amount: token_account.amount,
+                })
+            })
+            .collect();
+
+        info!("Found {} pending holder(s):", pending.len());
+        for (i, holder) in pending.iter().enumerate() {
+            info!("  {}. Owner: {} (account {}, balance {})", i + 1, holder.owner, holder.token_account, holder.amount);
+        }
+
+        write_output(output, &pending, &pending)
+    }
+
+    /// Switch the state new token accounts start in (Token-2022 DefaultAccountState).
+    pub fn set_default_account_state(&self, mint_address: &str, frozen: bool) -> Result<()> {
+        let mint_pubkey = Pubkey::from_str(mint_address)
+            .map_err(|e| anyhow!("Invalid mint address: {}", e))?;
+        let mint_info = self.fetch_mint_info(&mint_pubkey)?;
+
+        if mint_info.default_account_state()?.is_none() {
+            return Err(anyhow!("Mint was not created with the DefaultAccountState extension"));
+        }
+
+        let state = if frozen { AccountState::Frozen } else { AccountState::Initialized };
+        info!("Setting default account state to {:?}", state);
+
+        let instruction = update_default_account_state(
+            &mint_info.program_id,
+            &mint_info.address,
+            &self.wallet.pubkey(),
+            &[],
+            &state,
+        )?;
+
+        match self.send_instructions(vec![instruction], THAW_UNITS) {
+            Ok(signature) => {
+                info!("Default account state updated! Signature: {}", signature);
+                Ok(())
+            }
+            Err(e) => Err(anyhow!("Failed to update default account state: {}", e)),
+        }
+    }
+}
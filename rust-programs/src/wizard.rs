@@ -0,0 +1,188 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Deserialize;
+use serde_json::json;
+use solana_sdk::signature::Signer;
+use std::{fs, path::Path, time::Duration};
+
+use crate::{
+    apply::{SpecMetadata, TokenSpec},
+    holders::parse_amount,
+    prompt::{ask, ask_parsed, ask_yes_no, confirm},
+    TokenOperations,
+};
+
+/// Upload request timeout; images can be a few megabytes.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Longest name and symbol accepted, matching Metaplex limits so the token
+/// displays the same everywhere.
+const MAX_NAME_LEN: usize = 32;
+const MAX_SYMBOL_LEN: usize = 10;
+
+#[derive(Debug, Deserialize)]
+struct IpfsAddResponse {
+    #[serde(rename = "Hash")]
+    hash: String,
+}
+
+/// Add a file through an IPFS HTTP API (`/api/v0/add`) and return its `ipfs://` URI.
+fn ipfs_add(api: &str, file_name: &str, data: Vec<u8>) -> Result<String> {
+    let http = reqwest::blocking::Client::builder()
+        .timeout(UPLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| anyhow!("Failed to create HTTP client: {}", e))?;
+    let form = reqwest::blocking::multipart::Form::new()
+        .part("file", reqwest::blocking::multipart::Part::bytes(data).file_name(file_name.to_string()));
+
+    let response: IpfsAddResponse = http
+        .post(format!("{}/api/v0/add?pin=true", api.trim_end_matches('/')))
+        .multipart(form)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.json())
+        .map_err(|e| anyhow!("Failed to upload {} to IPFS: {}", file_name, e))?;
+
+    Ok(format!("ipfs://{}", response.hash))
+}
+
+fn is_uri(value: &str) -> bool {
+    ["https://", "http://", "ipfs://", "ar://"].iter().any(|scheme| value.starts_with(scheme))
+}
+
+impl TokenOperations {
+    /// Walk through creating a Token-2022 mint with embedded metadata, then
+    /// create it, mint the supply and set the authorities in one flow.
+    ///
+    /// Local images and the metadata JSON are uploaded through `ipfs_api`
+    /// when given; otherwise the JSON is written to the working directory for the
+    /// operator to host, and its URI is asked for.
+    pub fn create_interactive(&self, mint_keypair_path: &str, ipfs_api: Option<&str>) -> Result<()> {
+        eprintln!("This creates a Token-2022 token paid for by {}.", self.wallet.pubkey());
+        eprintln!("Press Enter to accept the value in brackets.");
+        eprintln!();
+
+        let name = ask_parsed::<String>("Token name", None, |name| {
+            if name.len() > MAX_NAME_LEN {
+                return Err(anyhow!("Use at most {} characters", MAX_NAME_LEN));
+            }
+            Ok(())
+        })?;
+        let symbol = ask_parsed::<String>("Symbol (ticker)", None, |symbol| {
+            if symbol.len() > MAX_SYMBOL_LEN || symbol.contains(char::is_whitespace) {
+                return Err(anyhow!("Use at most {} characters without spaces", MAX_SYMBOL_LEN));
+            }
+            Ok(())
+        })?
+        .to_uppercase();
+        let description = ask("Description", Some(""))?;
+
+        let decimals = ask_parsed::<u8>("Decimals (how finely a token can be divided)", Some("6"), |decimals| {
+            if *decimals > 9 {
+                return Err(anyhow!("Use 0 to 9 decimals"));
+            }
+            Ok(())
+        })?;
+        let supply = ask_parsed::<String>("Total supply, in whole tokens", None, |supply| {
+            parse_amount(supply, decimals).map(|_| ())
+        })?;
+
+        let image = loop {
+            let image = ask("Image file or URL", None)?;
+            if is_uri(&image) {
+                break image;
+            }
+            if !Path::new(&image).is_file() {
+                eprintln!("  {} is neither a URL nor a file", image);
+                continue;
+            }
+            let Some(api) = ipfs_api else {
+                eprintln!("  Uploading a file needs --ipfs-api; host the image and enter its URL instead");
+                continue;
+            };
+            let file_name = Path::new(&image).file_name().and_then(|name| name.to_str()).unwrap_or("image");
+            match fs::read(&image).map_err(anyhow::Error::from).and_then(|data| ipfs_add(api, file_name, data)) {
+                Ok(uri) => {
+                    eprintln!("  Uploaded image: {}", uri);
+                    break uri;
+                }
+                Err(e) => eprintln!("  {}", e),
+            }
+        };
+
+        let mut extensions = Vec::new();
+        if ask_yes_no("Charge a fee on every transfer?", false)? {
+            let basis_points = ask_parsed::<u16>("Fee in basis points (100 = 1%)", Some("100"), |bps| {
+                if *bps > 10_000 {
+                    return Err(anyhow!("Use at most 10000 basis points"));
+                }
+                Ok(())
+            })?;
+            let maximum = ask_parsed::<String>("Maximum fee per transfer, in tokens", Some(&supply), |maximum| {
+                parse_amount(maximum, decimals).map(|_| ())
+            })?;
+            extensions.push(format!("transfer-fee={}:{}", basis_points, maximum));
+        }
+
+        let keep_mint = ask_yes_no("Keep the ability to mint more tokens later?", false)?;
+        let keep_freeze = ask_yes_no("Keep the ability to freeze holders' accounts?", false)?;
+
+        let offchain = json!({
+            "name": name,
+            "symbol": symbol,
+            "description": description,
+            "image": image,
+        });
+        let offchain = serde_json::to_vec_pretty(&offchain)?;
+        let uri = match ipfs_api {
+            Some(api) => {
+                let uri = ipfs_add(api, "metadata.json", offchain)?;
+                eprintln!("  Uploaded metadata: {}", uri);
+                uri
+            }
+            None => {
+                let path = format!("{}-metadata.json", symbol.to_lowercase());
+                fs::write(&path, offchain).map_err(|e| anyhow!("Failed to write {}: {}", path, e))?;
+                eprintln!("Metadata JSON written to {}; host it and enter its URL.", path);
+                ask_parsed::<String>("Metadata URL", None, |uri| {
+                    if !is_uri(uri) {
+                        return Err(anyhow!("Enter an https://, ipfs:// or ar:// URL"));
+                    }
+                    Ok(())
+                })?
+            }
+        };
+
+        let authority = |keep: bool| Some(if keep { "wallet" } else { "none" }.to_string());
+        let spec = TokenSpec {
+            mint_keypair: mint_keypair_path.to_string(),
+            token_2022: true,
+            decimals,
+            extensions,
+            metadata: Some(SpecMetadata { name: name.clone(), symbol: symbol.clone(), uri: uri.clone() }),
+            supply: Some(supply.clone()),
+            mint_authority: authority(keep_mint),
+            freeze_authority: authority(keep_freeze),
+        };
+
+        let mut summary = vec![
+            format!("Name:        {}", name),
+            format!("Symbol:      {}", symbol),
+            format!("Metadata:    {}", uri),
+            format!("Decimals:    {}", decimals),
+            format!("Supply:      {} (minted to {})", supply, self.wallet.pubkey()),
+        ];
+        if let Some(fee) = spec.extensions.first() {
+            summary.push(format!("Transfer fee: {}", fee.trim_start_matches("transfer-fee=").replace(':', " bps, max ")));
+        }
+        summary.push(format!("Mint authority:   {}", if keep_mint { "kept by the wallet" } else { "revoked (fixed supply)" }));
+        summary.push(format!("Freeze authority: {}", if keep_freeze { "kept by the wallet" } else { "revoked" }));
+        summary.push(format!("Mint keypair: {}", mint_keypair_path));
+        confirm(&summary, false)?;
+
+        // The summary above was the confirmation.
+        self.apply_token_spec(&spec, "the wizard answers", true)?;
+        info!("Token {} ({}) is ready", name, symbol);
+        Ok(())
+    }
+}